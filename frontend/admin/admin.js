@@ -4,6 +4,12 @@
 
 let currentPage = 1;
 let currentUser = null;
+let csrfToken = null;
+
+// Headers for state-changing admin requests, which must carry the CSRF token
+function csrfHeaders(headers = {}) {
+  return { ...headers, 'X-CSRF-Token': csrfToken };
+}
 
 // Authentication
 export async function checkAuth() {
@@ -12,6 +18,7 @@ export async function checkAuth() {
     const result = await response.json();
     if (result.success) {
       currentUser = result.data;
+      csrfToken = result.data.csrf_token;
       return result.data;
     }
   } catch (e) {
@@ -79,7 +86,9 @@ function renderSubmissionsTable(data) {
     approved: 'Goedgekeurd',
     rejected: 'Afgewezen',
     forwarded: 'Doorgestuurd',
-    completed: 'Afgerond'
+    completed: 'Afgerond',
+    withdrawn: 'Ingetrokken',
+    changes_requested: 'Aanvulling gevraagd'
   };
 
   if (data.items.length === 0) {
@@ -96,6 +105,7 @@ function renderSubmissionsTable(data) {
           <th>Organisatie</th>
           <th>Status</th>
           <th>Documenten</th>
+          <th>Opmerkingen</th>
           <th>Datum</th>
           <th>Acties</th>
         </tr>
@@ -108,6 +118,7 @@ function renderSubmissionsTable(data) {
             <td>${escapeHtml(sub.organization)}</td>
             <td><span class="status-badge status-${sub.status}">${statusLabels[sub.status] || sub.status}</span></td>
             <td>${sub.documents?.length || 0}</td>
+            <td>${sub.comment_count || 0}</td>
             <td>${new Date(sub.created_at).toLocaleDateString('nl-NL')}</td>
             <td>
               <button class="action-btn" onclick="openSubmissionModal('${sub.id}')">Bekijken</button>
@@ -167,7 +178,8 @@ function renderSubmissionDetail(sub) {
   const body = document.getElementById('modal-body');
   const statusLabels = {
     draft: 'Concept', submitted: 'Ingediend', under_review: 'In behandeling',
-    approved: 'Goedgekeurd', rejected: 'Afgewezen', forwarded: 'Doorgestuurd', completed: 'Afgerond'
+    approved: 'Goedgekeurd', rejected: 'Afgewezen', forwarded: 'Doorgestuurd', completed: 'Afgerond',
+    withdrawn: 'Ingetrokken', changes_requested: 'Aanvulling gevraagd'
   };
   const categoryLabels = {
     formal_law: 'Formele wet', circular: 'Circulaire',
@@ -212,7 +224,7 @@ function renderSubmissionDetail(sub) {
               <div class="document-name">
                 ${doc.external_url
                   ? `<a href="${escapeHtml(doc.external_url)}" target="_blank">${escapeHtml(doc.external_title || doc.external_url)}</a>`
-                  : escapeHtml(doc.filename) || 'Document'}
+                  : `<a href="/api/admin/submissions/${sub.id}/documents/${doc.id}">${escapeHtml(doc.filename) || 'Document'}</a>`}
               </div>
               <div class="document-meta">
                 ${categoryLabels[doc.category] || doc.category} |
@@ -224,6 +236,15 @@ function renderSubmissionDetail(sub) {
       `).join('') : '<p>Geen documenten</p>'}
     </div>
 
+    <div class="detail-section">
+      <h3>Opmerkingen</h3>
+      <div id="comment-list"><p>Laden...</p></div>
+      <div style="display: flex; gap: 12px; margin-top: 12px; align-items: flex-start;">
+        <textarea id="comment-body" rows="3" style="flex: 1;" placeholder="Alleen zichtbaar voor beoordelaars"></textarea>
+        <rr-button variant="secondary" onclick="addComment('${sub.id}')">Plaatsen</rr-button>
+      </div>
+    </div>
+
     <div class="detail-section">
       <h3>Acties</h3>
       <div style="display: flex; gap: 12px; flex-wrap: wrap;">
@@ -238,6 +259,8 @@ function renderSubmissionDetail(sub) {
         </rr-select-field>
         <rr-button variant="primary" onclick="updateStatus('${sub.id}')">Status bijwerken</rr-button>
         <rr-button variant="secondary" onclick="forwardSubmission('${sub.id}')">Doorsturen naar team</rr-button>
+        <rr-button variant="secondary" onclick="requestChanges('${sub.id}')">Aanvulling vragen</rr-button>
+        <rr-button variant="secondary" onclick="rotateSlug('${sub.id}')">Nieuwe referentiecode</rr-button>
         <rr-button variant="danger" onclick="deleteSubmission('${sub.id}')">Verwijderen</rr-button>
       </div>
     </div>
@@ -250,6 +273,59 @@ function renderSubmissionDetail(sub) {
       </div>
     </div>
   `;
+
+  loadComments(sub.id);
+}
+
+async function loadComments(id) {
+  const list = document.getElementById('comment-list');
+  try {
+    const response = await fetch(`/api/admin/submissions/${id}/comments`, { credentials: 'include' });
+    const result = await response.json();
+    if (!result.success) {
+      list.innerHTML = '<p>Kon opmerkingen niet laden.</p>';
+      return;
+    }
+    list.innerHTML = result.data.length ? result.data.map(comment => `
+      <div class="document-item" style="margin-bottom: 8px;">
+        <div>
+          <div class="document-meta">
+            ${escapeHtml(comment.author_name) || 'Verwijderde beoordelaar'} |
+            ${new Date(comment.created_at).toLocaleString('nl-NL')}
+          </div>
+          <div style="white-space: pre-wrap;">${escapeHtml(comment.body)}</div>
+        </div>
+      </div>
+    `).join('') : '<p>Nog geen opmerkingen</p>';
+  } catch (e) {
+    list.innerHTML = '<p>Fout bij laden van opmerkingen.</p>';
+  }
+}
+
+export async function addComment(id) {
+  const input = document.getElementById('comment-body');
+  const body = input.value.trim();
+  if (!body) return;
+
+  try {
+    const response = await fetch(`/api/admin/submissions/${id}/comments`, {
+      method: 'POST',
+      headers: csrfHeaders({ 'Content-Type': 'application/json' }),
+      credentials: 'include',
+      body: JSON.stringify({ body })
+    });
+
+    const result = await response.json();
+    if (result.success) {
+      input.value = '';
+      loadComments(id);
+      loadSubmissions(currentPage);
+    } else {
+      alert(result.error || 'Kon opmerking niet plaatsen.');
+    }
+  } catch (e) {
+    alert('Fout bij plaatsen van opmerking.');
+  }
 }
 
 export function closeModal() {
@@ -262,7 +338,7 @@ export async function updateStatus(id) {
   try {
     const response = await fetch(`/api/admin/submissions/${id}/status`, {
       method: 'PUT',
-      headers: { 'Content-Type': 'application/json' },
+      headers: csrfHeaders({ 'Content-Type': 'application/json' }),
       credentials: 'include',
       body: JSON.stringify({ status })
     });
@@ -287,6 +363,7 @@ export async function deleteSubmission(id) {
   try {
     const response = await fetch(`/api/admin/submissions/${id}`, {
       method: 'DELETE',
+      headers: csrfHeaders(),
       credentials: 'include'
     });
 
@@ -309,7 +386,7 @@ export async function forwardSubmission(id) {
   try {
     const response = await fetch(`/api/admin/submissions/${id}/forward`, {
       method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
+      headers: csrfHeaders({ 'Content-Type': 'application/json' }),
       credentials: 'include',
       body: JSON.stringify({ forward_to: forwardTo })
     });
@@ -326,6 +403,53 @@ export async function forwardSubmission(id) {
   }
 }
 
+export async function requestChanges(id) {
+  const message = prompt('Welke aanvulling is nodig? De indiener ontvangt dit bericht.');
+  if (!message || !message.trim()) return;
+
+  try {
+    const response = await fetch(`/api/admin/submissions/${id}/request-changes`, {
+      method: 'POST',
+      headers: csrfHeaders({ 'Content-Type': 'application/json' }),
+      credentials: 'include',
+      body: JSON.stringify({ message })
+    });
+
+    const result = await response.json();
+    if (result.success) {
+      closeModal();
+      loadSubmissions(currentPage);
+    } else {
+      alert(result.error || 'Kon geen aanvulling vragen.');
+    }
+  } catch (e) {
+    alert('Fout bij aanvulling vragen.');
+  }
+}
+
+export async function rotateSlug(id) {
+  if (!confirm('Nieuwe referentiecode toekennen? De oude code werkt daarna niet meer en de indiener wordt uitgelogd.')) return;
+
+  try {
+    const response = await fetch(`/api/admin/submissions/${id}/rotate-slug`, {
+      method: 'POST',
+      headers: csrfHeaders(),
+      credentials: 'include'
+    });
+
+    const result = await response.json();
+    if (result.success) {
+      alert(`Nieuwe referentiecode: ${result.data.slug}`);
+      closeModal();
+      loadSubmissions(currentPage);
+    } else {
+      alert(result.error || 'Kon geen nieuwe referentiecode toekennen.');
+    }
+  } catch (e) {
+    alert('Fout bij toekennen referentiecode.');
+  }
+}
+
 // Calendar
 export async function loadCalendarSlots() {
   try {
@@ -353,14 +477,18 @@ function renderCalendarSlots(slots) {
     const end = new Date(slot.slot_end);
     const dateStr = start.toLocaleDateString('nl-NL', { weekday: 'long', day: 'numeric', month: 'long', year: 'numeric' });
     const timeStr = `${start.toLocaleTimeString('nl-NL', { hour: '2-digit', minute: '2-digit' })} - ${end.toLocaleTimeString('nl-NL', { hour: '2-digit', minute: '2-digit' })}`;
-    const isBooked = slot.booked_by_submission !== null;
+    const isBooked = slot.bookings.length > 0;
+    const status = slot.capacity > 1
+      ? `${slot.bookings.length} van ${slot.capacity} plaatsen geboekt`
+      : (isBooked ? 'Geboekt' : 'Beschikbaar');
 
     return `
-      <div class="slot-item ${isBooked ? 'booked' : ''}">
+      <div class="slot-item ${slot.is_available ? '' : 'booked'}">
         <div class="slot-info">
           <span class="slot-date">${dateStr}</span>
           <span class="slot-time">${timeStr}</span>
-          <span class="slot-status">${isBooked ? 'Geboekt' : 'Beschikbaar'}</span>
+          <span class="slot-status">${status}</span>
+          ${slot.bookings.map(booking => `<span class="slot-status">${escapeHtml(booking.submitter_name)} (${escapeHtml(booking.organization)}) – ${escapeHtml(booking.submission_slug)}</span>`).join('')}
           ${slot.notes ? `<span class="slot-status">${escapeHtml(slot.notes)}</span>` : ''}
         </div>
         ${!isBooked ? `<button class="action-btn danger" onclick="deleteSlot('${slot.id}')">Verwijderen</button>` : ''}
@@ -374,6 +502,7 @@ export async function addCalendarSlot() {
   const startTime = document.getElementById('slot-start').value;
   const endTime = document.getElementById('slot-end').value;
   const notes = document.getElementById('slot-notes').value;
+  const capacity = parseInt(document.getElementById('slot-capacity').value, 10) || 1;
 
   if (!date || !startTime || !endTime) {
     alert('Vul datum en tijden in.');
@@ -386,12 +515,13 @@ export async function addCalendarSlot() {
   try {
     const response = await fetch('/api/admin/calendar/slots', {
       method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
+      headers: csrfHeaders({ 'Content-Type': 'application/json' }),
       credentials: 'include',
       body: JSON.stringify([{
         slot_start: slotStart.toISOString(),
         slot_end: slotEnd.toISOString(),
-        notes: notes || null
+        notes: notes || null,
+        capacity
       }])
     });
 
@@ -413,6 +543,7 @@ export async function deleteSlot(id) {
   try {
     const response = await fetch(`/api/admin/calendar/slots/${id}`, {
       method: 'DELETE',
+      headers: csrfHeaders(),
       credentials: 'include'
     });
 
@@ -82,7 +82,9 @@ function renderSubmissionStatus(sub) {
     approved: 'Goedgekeurd',
     rejected: 'Afgewezen',
     forwarded: 'Doorgestuurd naar team',
-    completed: 'Afgerond'
+    completed: 'Afgerond',
+    withdrawn: 'Ingetrokken',
+    changes_requested: 'Aanvulling gevraagd'
   };
 
   const categoryLabels = {
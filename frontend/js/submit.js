@@ -160,7 +160,7 @@ function handleUploadModeChange(e) {
   document.getElementById('mode-document').style.display = mode === 'document' ? 'block' : 'none';
 }
 
-function handleAddLink() {
+async function handleAddLink() {
   const urlField = document.getElementById('link_url');
   const titleField = document.getElementById('link_title');
   const url = urlField.value.trim();
@@ -182,11 +182,12 @@ function handleAddLink() {
   // Auto-detect type based on URL
   const isFormal = url.startsWith('https://wetten.overheid.nl/');
   const source = isFormal ? 'formal' : 'other';
+  const suggestedTitle = !title && isFormal ? await lookupLawTitle(url) : null;
 
   const link = {
     id: `law_${++stagedIdCounter}`,
     url,
-    title: title || extractTitleFromUrl(url),
+    title: title || suggestedTitle || extractTitleFromUrl(url),
     source
   };
 
@@ -204,6 +205,17 @@ function handleAddLink() {
   }
 }
 
+async function lookupLawTitle(url) {
+  // Official title from wetten.overheid.nl; null when it cannot be fetched
+  try {
+    const response = await fetch(`/api/formal-law/lookup?url=${encodeURIComponent(url)}`);
+    const result = await response.json();
+    return result.success ? result.data.title : null;
+  } catch {
+    return null;
+  }
+}
+
 function extractTitleFromUrl(url) {
   // Try to extract a readable title from the URL
   const bwbr = url.match(/BWBR\d+/);
@@ -318,7 +330,7 @@ function renderStagedDocuments() {
               <div class="document-meta">Link: ${escapeHtml(doc.url)}</div>
             </div>
           </div>
-          <button type="button" class="delete-btn" onclick="confirmDeleteItem('${doc.id}', 'law', this)">
+          <button type="button" class="delete-btn" data-id="${doc.id}" data-type="law">
             Verwijderen
           </button>
         </div>
@@ -334,13 +346,18 @@ function renderStagedDocuments() {
               </div>
             </div>
           </div>
-          <button type="button" class="delete-btn" onclick="confirmDeleteItem('${doc.id}', 'document', this)">
+          <button type="button" class="delete-btn" data-id="${doc.id}" data-type="document">
             Verwijderen
           </button>
         </div>
       `;
     }
   }).join('');
+
+  // Handlers are attached here rather than inline, which the CSP forbids
+  container.querySelectorAll('.delete-btn').forEach(button => {
+    button.onclick = () => confirmDeleteItem(button.dataset.id, button.dataset.type, button);
+  });
 }
 
 // Two-step delete: first click shows confirm, second click deletes
@@ -401,28 +418,35 @@ async function loadAvailableSlots() {
     const response = await fetch('/api/calendar/available');
     const result = await response.json();
 
-    if (result.success && result.data.length > 0) {
-      container.innerHTML = `
-        <div class="slots-grid">
-          ${result.data.map(slot => {
-            const start = new Date(slot.slot_start);
-            const end = new Date(slot.slot_end);
-            const dateStr = start.toLocaleDateString('nl-NL', { weekday: 'long', day: 'numeric', month: 'long' });
-            const timeStr = `${start.toLocaleTimeString('nl-NL', { hour: '2-digit', minute: '2-digit' })} - ${end.toLocaleTimeString('nl-NL', { hour: '2-digit', minute: '2-digit' })}`;
-            const isSelected = stagedData.selectedSlot?.id === slot.id;
-
-            return `
-              <div class="slot-option ${isSelected ? 'selected' : ''}" onclick="selectSlot('${slot.id}', '${slot.slot_start}', '${slot.slot_end}', this)">
-                <input type="radio" name="slot" value="${slot.id}" ${isSelected ? 'checked' : ''}>
-                <div class="slot-details">
-                  <div class="slot-date">${dateStr}</div>
-                  <div class="slot-time">${timeStr}</div>
+    if (result.success && result.data.days.length > 0) {
+      // Dates and times are in the Amsterdam time zone, whatever the browser's
+      const localTime = (iso) => iso.slice(11, 16);
+      container.innerHTML = result.data.days.map(day => {
+        const dateStr = new Date(`${day.date}T12:00:00Z`).toLocaleDateString('nl-NL', { weekday: 'long', day: 'numeric', month: 'long', timeZone: 'UTC' });
+        return `
+          <div class="slot-date">${dateStr}</div>
+          <div class="slots-grid">
+            ${day.slots.map(slot => {
+              const timeStr = `${localTime(slot.local_start)} - ${localTime(slot.local_end)}`;
+              const isSelected = stagedData.selectedSlot?.id === slot.id;
+
+              return `
+                <div class="slot-option ${isSelected ? 'selected' : ''}" data-id="${slot.id}" data-start="${slot.slot_start}" data-end="${slot.slot_end}">
+                  <input type="radio" name="slot" value="${slot.id}" ${isSelected ? 'checked' : ''}>
+                  <div class="slot-details">
+                    <div class="slot-time">${timeStr}</div>
+                  </div>
                 </div>
-              </div>
-            `;
-          }).join('')}
-        </div>
-      `;
+              `;
+            }).join('')}
+          </div>
+        `;
+      }).join('');
+      container.querySelectorAll('.slot-option').forEach(option => {
+        option.addEventListener('click', () => {
+          selectSlot(option.dataset.id, option.dataset.start, option.dataset.end, option);
+        });
+      });
     } else {
       container.innerHTML = `
         <div class="info-box">
@@ -580,10 +604,15 @@ async function handleFinalSubmit() {
   }
 
   try {
-    // STEP 1: Create submission with contact info
+    // STEP 1: Create submission with contact info. The key stays the same
+    // when the user retries, so a lost response does not leave a second draft.
+    stagedData.idempotencyKey = stagedData.idempotencyKey || crypto.randomUUID();
     const submissionResponse = await fetch('/api/submissions', {
       method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
+      headers: {
+        'Content-Type': 'application/json',
+        'Idempotency-Key': stagedData.idempotencyKey
+      },
       body: JSON.stringify({
         submitter_name: stagedData.contact.name,
         submitter_email: stagedData.contact.email,
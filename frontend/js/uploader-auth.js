@@ -15,7 +15,9 @@ const statusLabels = {
   approved: 'Goedgekeurd',
   rejected: 'Afgewezen',
   forwarded: 'Doorgestuurd',
-  completed: 'Afgerond'
+  completed: 'Afgerond',
+  withdrawn: 'Ingetrokken',
+  changes_requested: 'Aanvulling gevraagd'
 };
 
 const categoryLabels = {
@@ -129,11 +131,15 @@ function renderDocumentList() {
           </div>
         </div>
       </div>
-      <button onclick="deleteDocument('${doc.id}')" style="background: none; border: none; color: #dc2626; cursor: pointer; padding: 8px;">
+      <button class="delete-doc-btn" data-id="${doc.id}" style="background: none; border: none; color: #dc2626; cursor: pointer; padding: 8px;">
         Verwijderen
       </button>
     </div>
   `).join('');
+
+  container.querySelectorAll('.delete-doc-btn').forEach(button => {
+    button.addEventListener('click', () => deleteDocument(button.dataset.id));
+  });
 }
 
 /**
@@ -16,7 +16,7 @@ export class RRFileUpload extends RRLocalBase {
   }
 
   get files() { return this._files; }
-  get accept() { return this.getAttribute('accept') || '.pdf,.doc,.docx,.odt,.txt,.md,.markdown,.xls,.xlsx,.ppt,.pptx,.csv,.rtf'; }
+  get accept() { return this.getAttribute('accept') || '.pdf,.doc,.docx,.odt,.ods,.odp,.txt,.md,.markdown,.xls,.xlsx,.ppt,.pptx,.csv,.rtf'; }
   get disabled() { return this.getBooleanAttribute('disabled'); }
   get multiple() { return this.getBooleanAttribute('multiple'); }
 
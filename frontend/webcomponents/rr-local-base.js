@@ -41,6 +41,8 @@ export class RRLocalBase extends HTMLElement {
       this.shadowRoot.adoptedStyleSheets = styles;
     } else {
       const styleEl = document.createElement('style');
+      // Without adoptedStyleSheets the style element needs the page's CSP nonce
+      styleEl.nonce = document.querySelector('meta[property="csp-nonce"]')?.nonce || '';
       for (const sheet of styles) {
         if (sheet.cssRules) {
           for (const rule of sheet.cssRules) {
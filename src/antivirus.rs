@@ -0,0 +1,196 @@
+//! Malware scanning of uploads through clamd
+//!
+//! Staged uploads are streamed to a clamd daemon over TCP with the INSTREAM
+//! command before they are moved into storage. Scanning is optional: without
+//! `CLAMAV_ADDR` uploads are stored unscanned, and when clamd cannot be
+//! reached the caller logs a warning and stores the file anyway.
+
+use std::path::Path;
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Bytes sent per INSTREAM chunk
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Time allowed for connecting and for the whole scan
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const SCAN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Longest reply accepted from clamd
+const MAX_REPLY_LEN: usize = 4096;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScanError {
+    #[error("I/O error talking to clamd: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("clamd did not answer in time")]
+    Timeout,
+    #[error("clamd reported an error: {0}")]
+    Clamd(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanResult {
+    Clean,
+    /// Name of the matched signature
+    Infected(String),
+}
+
+/// Client for a clamd daemon listening on TCP
+pub struct VirusScanner {
+    addr: String,
+}
+
+impl VirusScanner {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self { addr: addr.into() }
+    }
+
+    /// Scan a local file
+    pub async fn scan_file(&self, path: &Path) -> Result<ScanResult, ScanError> {
+        tokio::time::timeout(SCAN_TIMEOUT, self.scan(path))
+            .await
+            .map_err(|_| ScanError::Timeout)?
+    }
+
+    async fn scan(&self, path: &Path) -> Result<ScanResult, ScanError> {
+        let mut file = File::open(path).await?;
+        let mut stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&self.addr))
+            .await
+            .map_err(|_| ScanError::Timeout)??;
+
+        // The 'z' prefix makes clamd expect and send NUL-terminated messages
+        stream.write_all(b"zINSTREAM\0").await?;
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        loop {
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            stream.write_all(&(n as u32).to_be_bytes()).await?;
+            stream.write_all(&buf[..n]).await?;
+        }
+        stream.write_all(&0u32.to_be_bytes()).await?;
+
+        let mut reply = Vec::new();
+        (&mut stream)
+            .take(MAX_REPLY_LEN as u64)
+            .read_to_end(&mut reply)
+            .await?;
+        parse_reply(&String::from_utf8_lossy(&reply))
+    }
+}
+
+/// Interpret a clamd reply such as `stream: OK` or
+/// `stream: Eicar-Signature FOUND`
+fn parse_reply(reply: &str) -> Result<ScanResult, ScanError> {
+    let reply = reply.trim_end_matches(['\0', '\n']).trim();
+    let result = reply.strip_prefix("stream:").unwrap_or(reply).trim();
+
+    if result == "OK" {
+        Ok(ScanResult::Clean)
+    } else if let Some(signature) = result.strip_suffix(" FOUND") {
+        Ok(ScanResult::Infected(signature.to_string()))
+    } else {
+        Err(ScanError::Clamd(reply.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// The standard antivirus test file; every scanner reports it
+    const EICAR: &[u8] = br"X5O!P%@AP[4\PZX54(P^)7CC)7}$EICAR-STANDARD-ANTIVIRUS-TEST-FILE!$H+H*";
+
+    /// Minimal clamd: reads one INSTREAM request and reports EICAR
+    async fn mock_clamd() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut command = [0u8; 10];
+                    socket.read_exact(&mut command).await.unwrap();
+                    assert_eq!(&command, b"zINSTREAM\0");
+
+                    let mut data = Vec::new();
+                    loop {
+                        let len = socket.read_u32().await.unwrap() as usize;
+                        if len == 0 {
+                            break;
+                        }
+                        let start = data.len();
+                        data.resize(start + len, 0);
+                        socket.read_exact(&mut data[start..]).await.unwrap();
+                    }
+
+                    let infected = data.windows(EICAR.len()).any(|w| w == EICAR);
+                    let reply: &[u8] = if infected {
+                        b"stream: Eicar-Signature FOUND\0"
+                    } else {
+                        b"stream: OK\0"
+                    };
+                    socket.write_all(reply).await.unwrap();
+                });
+            }
+        });
+        addr
+    }
+
+    async fn temp_file(contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("scan-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, contents).await.unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_scan_detects_eicar() {
+        let scanner = VirusScanner::new(mock_clamd().await);
+
+        let infected = temp_file(EICAR).await;
+        assert_eq!(
+            scanner.scan_file(&infected).await.unwrap(),
+            ScanResult::Infected("Eicar-Signature".to_string())
+        );
+
+        // Large enough to span several chunks
+        let clean = temp_file(&vec![b'a'; 3 * CHUNK_SIZE + 17]).await;
+        assert_eq!(scanner.scan_file(&clean).await.unwrap(), ScanResult::Clean);
+
+        let _ = tokio::fs::remove_file(&infected).await;
+        let _ = tokio::fs::remove_file(&clean).await;
+    }
+
+    #[tokio::test]
+    async fn test_scan_unreachable_clamd_is_an_error() {
+        // Bind and drop to get a port nothing listens on
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let scanner = VirusScanner::new(addr.to_string());
+
+        let path = temp_file(b"%PDF-1.7").await;
+        assert!(scanner.scan_file(&path).await.is_err());
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(parse_reply("stream: OK\0").unwrap(), ScanResult::Clean);
+        assert_eq!(
+            parse_reply("stream: Win.Test.EICAR_HDB-1 FOUND\0").unwrap(),
+            ScanResult::Infected("Win.Test.EICAR_HDB-1".to_string())
+        );
+        assert!(matches!(
+            parse_reply("INSTREAM size limit exceeded. ERROR\0"),
+            Err(ScanError::Clamd(_))
+        ));
+    }
+}
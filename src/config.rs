@@ -2,7 +2,23 @@
 //!
 //! Loads configuration from environment variables with sensible defaults.
 
+use crate::encryption::FileKey;
+use crate::models::{DocumentCategory, DocumentClassification, EffectiveConfig};
+use crate::share_links::LinkSecret;
+use crate::thumbnails;
+use crate::totp::TotpKey;
+use crate::validation::{is_dangerous_mime_type, DEFAULT_ALLOWED_MIME_TYPES};
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use std::collections::HashMap;
 use std::env;
+use std::time::Duration;
+
+/// Longest admin session lifetime accepted in production
+const MAX_PRODUCTION_SESSION_HOURS: u64 = 72;
+
+/// Shortest MAX_FILENAME_LENGTH accepted, leaving room for a stem and an extension
+const MIN_FILENAME_LENGTH: usize = 16;
 
 /// Application configuration
 #[derive(Debug, Clone)]
@@ -14,12 +30,44 @@ pub struct Config {
     pub port: u16,
     /// Database connection URL
     pub database_url: String,
+    /// Which environment variables `database_url` was built from
+    pub database_url_source: DatabaseUrlSource,
+    /// Connection pool sizing and timeouts
+    pub db_pool: DbPoolConfig,
     /// Upload directory path
     pub upload_dir: String,
     /// Frontend assets directory
     pub frontend_dir: String,
-    /// Session expiration in hours
+    /// Admin session idle window in hours; active sessions slide forward by this much
     pub session_expiry_hours: u64,
+    /// Absolute admin session lifetime in hours, regardless of activity
+    pub session_max_hours: u64,
+    /// Uploader session lifetime in hours
+    pub uploader_session_hours: u64,
+    /// Consecutive failed admin logins that lock the account
+    pub login_max_failures: i32,
+    /// How long a locked admin account stays locked, in minutes
+    pub login_lockout_minutes: i64,
+    /// Argon2 cost for newly hashed admin passwords; existing hashes keep their own
+    pub argon2_params: argon2::Params,
+    /// Booking actions (book, cancel, reschedule) allowed per client IP per window, per action
+    pub book_slot_max_attempts: i64,
+    /// Formal law links that may be added, and titles looked up, per client IP per window
+    pub formal_law_max_attempts: i64,
+    /// Public status checks allowed per client IP per window
+    pub status_check_max_attempts: i64,
+    /// Admin logins and password changes allowed per client IP per window
+    pub rate_limit_login: i64,
+    /// Submissions that may be created per client IP per window
+    pub rate_limit_submission: i64,
+    /// Uploader logins and reference code recoveries allowed per client IP per window
+    pub rate_limit_uploader_login: i64,
+    /// Window over which rate-limited attempts are counted, in seconds
+    pub rate_limit_window_secs: u64,
+    /// Where formal law titles are looked up
+    pub wetten_base_url: String,
+    /// How long a looked-up formal law title is cached, in hours
+    pub formal_law_title_cache_hours: u64,
     /// Maximum upload file size in bytes
     pub max_upload_size: usize,
     /// CORS allowed origins
@@ -29,6 +77,68 @@ pub struct Config {
     /// Trusted proxy IP prefixes (e.g., ["10.0.0.", "172.16."])
     /// Only trust X-Forwarded-For headers from these IPs
     pub trusted_proxies: Vec<String>,
+    /// Age in minutes after which unsubmitted drafts are cleaned up
+    pub draft_max_age_minutes: i64,
+    /// Months a new submission is kept before its data is purged
+    pub retention_months: u32,
+    /// Months an uploader's retention consent adds to the expiry date
+    pub retention_extension_months: u32,
+    /// Longest total retention in months, counted from submission creation
+    pub retention_max_months: u32,
+    /// Only log which expired submissions the daily purge would delete
+    pub retention_purge_dry_run: bool,
+    /// Allowed upload file extensions (e.g., ["pdf", "docx"]); empty disables the allowlist
+    pub allowed_extensions: Vec<String>,
+    /// MIME types accepted for uploads; HTML, XML and scripts are never accepted
+    pub allowed_mime_types: Vec<String>,
+    /// Where document files are stored (filesystem or S3-compatible object storage)
+    pub storage_backend: StorageBackend,
+    /// Object storage settings, present when `storage_backend` is S3
+    pub s3: Option<S3Config>,
+    /// How document files of a submission are grouped below the storage root
+    pub upload_layout: UploadLayout,
+    /// Maximum number of files accepted in one batch upload request
+    pub max_files_per_upload: usize,
+    /// Maximum length of the sanitized filename used in storage keys
+    pub max_filename_length: usize,
+    /// Maximum number of formal law links on a single submission
+    pub max_formal_laws_per_submission: i64,
+    /// Reject formal law links outside wetten.overheid.nl (default on in production)
+    pub strict_formal_law_domain: bool,
+    /// Classification applied per document category when an upload omits one
+    pub default_classifications: HashMap<DocumentCategory, DocumentClassification>,
+    /// Send status change emails to submitters (off by default so dev stays quiet)
+    pub notify_enabled: bool,
+    /// SMTP settings, present when `notify_enabled` is set
+    pub smtp: Option<SmtpConfig>,
+    /// Key for encrypting admin TOTP secrets; two-factor enrollment is unavailable without it
+    pub totp_key: Option<TotpKey>,
+    /// clamd address (host:port) for scanning uploads; scanning is off when unset
+    pub clamav_addr: Option<String>,
+    /// Parse uploaded PDFs and reject files without a valid trailer or any page
+    pub strict_pdf_validation: bool,
+    /// Outbound webhook for submission events, present when WEBHOOK_URL is set
+    pub webhook: Option<WebhookConfig>,
+    /// Refuse to start when the database has applied migrations unknown to this build
+    pub strict_migrations: bool,
+    /// Secret for signing document download links; a random one is used when unset
+    pub download_link_secret: Option<LinkSecret>,
+    /// Default lifetime of a document download link, in hours
+    pub download_link_expiry_hours: u64,
+    /// Key for encrypting document files at rest; new uploads are stored plaintext without it
+    pub file_encryption_key: Option<FileKey>,
+    /// Admin account created at startup when it does not exist yet
+    pub admin_seed: Option<AdminSeed>,
+    /// Requests arrive through a reverse proxy, so TRUSTED_PROXIES must be set in production
+    pub behind_proxy: bool,
+    /// Command rendering PDF thumbnails; the thumbnail endpoint is off when unset
+    pub thumbnail_renderer: Option<String>,
+    /// Longest side of a rendered thumbnail, in pixels
+    pub thumbnail_size: u32,
+    /// Weekdays and hours within which admins may create slots; unrestricted when unset
+    pub business_hours: Option<BusinessHours>,
+    /// Sources and inline script handling of the Content-Security-Policy
+    pub csp: CspConfig,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -37,6 +147,535 @@ pub enum Environment {
     Production,
 }
 
+/// Environment variables the database URL was taken from, first match wins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DatabaseUrlSource {
+    /// `DATABASE_URL`
+    DatabaseUrl,
+    /// `DATABASE_SERVER_FULL`
+    DatabaseServerFull,
+    /// Host, port, user, password and database name set separately
+    Components,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageBackend {
+    /// Files under `upload_dir`
+    Fs,
+    /// Objects in an S3/MinIO bucket
+    S3,
+}
+
+/// Grouping of stored files, selected by `UPLOAD_LAYOUT`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UploadLayout {
+    /// `<submission id>/...` directly below the storage root
+    Flat,
+    /// `YYYY/MM/<submission id>/...` by the submission's creation month (UTC),
+    /// so no single directory collects every submission
+    Date,
+}
+
+/// S3-compatible object storage settings
+#[derive(Clone)]
+pub struct S3Config {
+    pub bucket: String,
+    /// Custom endpoint for MinIO or other S3-compatible services
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Use `endpoint/bucket/key` URLs instead of `bucket.endpoint/key` (needed for MinIO)
+    pub force_path_style: bool,
+}
+
+// Keep credentials out of logs
+impl std::fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Config")
+            .field("bucket", &self.bucket)
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field("force_path_style", &self.force_path_style)
+            .finish()
+    }
+}
+
+/// How the SMTP connection is secured
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS (port 587)
+    StartTls,
+    /// TLS from the first byte (port 465)
+    Tls,
+    /// Unencrypted, for local test servers such as MailHog
+    None,
+}
+
+/// Outgoing mail server settings
+#[derive(Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    /// Sender address, e.g. "RegelRecht <noreply@example.nl>"
+    pub from: String,
+    pub tls: SmtpTls,
+    /// Address of the portal, for login links in emails
+    pub public_url: Option<String>,
+}
+
+// Keep credentials out of logs
+impl std::fmt::Debug for SmtpConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("user", &self.user)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .field("from", &self.from)
+            .field("tls", &self.tls)
+            .field("public_url", &self.public_url)
+            .finish()
+    }
+}
+
+impl SmtpConfig {
+    fn from_env() -> Result<Self, ConfigError> {
+        let required = |name: &str| {
+            env::var(name)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| {
+                    ConfigError::Missing(format!("{} is required when NOTIFY_ENABLED=true", name))
+                })
+        };
+        let optional = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+
+        let public_url = match optional("PUBLIC_URL") {
+            Some(url) => match url::Url::parse(&url) {
+                Ok(parsed) if matches!(parsed.scheme(), "https" | "http") => {
+                    Some(url.trim_end_matches('/').to_string())
+                }
+                _ => {
+                    return Err(ConfigError::Invalid(format!(
+                        "PUBLIC_URL must be an http(s) URL, got '{}'",
+                        url
+                    )))
+                }
+            },
+            None => None,
+        };
+
+        let tls = match env::var("SMTP_TLS")
+            .unwrap_or_else(|_| "starttls".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "starttls" => SmtpTls::StartTls,
+            "tls" => SmtpTls::Tls,
+            "none" => SmtpTls::None,
+            other => {
+                return Err(ConfigError::Invalid(format!(
+                    "SMTP_TLS must be 'starttls', 'tls' or 'none', got '{}'",
+                    other
+                )))
+            }
+        };
+
+        Ok(SmtpConfig {
+            host: required("SMTP_HOST")?,
+            port: env::var("SMTP_PORT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(match tls {
+                    SmtpTls::Tls => 465,
+                    SmtpTls::StartTls | SmtpTls::None => 587,
+                }),
+            user: optional("SMTP_USER"),
+            password: optional("SMTP_PASSWORD"),
+            from: required("SMTP_FROM")?,
+            tls,
+            public_url,
+        })
+    }
+}
+
+/// Outbound webhook settings
+#[derive(Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Shared secret for the HMAC-SHA256 body signature
+    pub secret: String,
+}
+
+// Keep the shared secret out of logs
+impl std::fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookConfig")
+            .field("url", &self.url)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+impl WebhookConfig {
+    /// Read WEBHOOK_URL and WEBHOOK_SECRET; `None` when no URL is set
+    fn from_env() -> Result<Option<Self>, ConfigError> {
+        let Some(url) = env::var("WEBHOOK_URL").ok().filter(|v| !v.is_empty()) else {
+            return Ok(None);
+        };
+        match url::Url::parse(&url) {
+            Ok(parsed) if matches!(parsed.scheme(), "https" | "http") => {}
+            _ => {
+                return Err(ConfigError::Invalid(format!(
+                    "WEBHOOK_URL must be an http(s) URL, got '{}'",
+                    url
+                )))
+            }
+        }
+        let secret = env::var("WEBHOOK_SECRET")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| {
+                ConfigError::Missing("WEBHOOK_SECRET is required when WEBHOOK_URL is set".into())
+            })?;
+        Ok(Some(Self { url, secret }))
+    }
+}
+
+/// Admin account seeded from ADMIN_USERNAME, ADMIN_EMAIL and a password
+#[derive(Clone)]
+pub struct AdminSeed {
+    pub username: String,
+    pub email: String,
+    pub password: AdminPassword,
+}
+
+#[derive(Clone)]
+pub enum AdminPassword {
+    /// ADMIN_PASSWORD_HASH, an Argon2 PHC string
+    Hash(String),
+    /// ADMIN_PASSWORD, for development only
+    Plain(String),
+}
+
+// Keep the password out of logs
+impl std::fmt::Debug for AdminSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let password = match self.password {
+            AdminPassword::Hash(_) => "<hash>",
+            AdminPassword::Plain(_) => "<plain text>",
+        };
+        f.debug_struct("AdminSeed")
+            .field("username", &self.username)
+            .field("email", &self.email)
+            .field("password", &password)
+            .finish()
+    }
+}
+
+impl AdminSeed {
+    /// Read the ADMIN_* variables; `None` unless username, email and a password are set
+    ///
+    /// ADMIN_PASSWORD_HASH is preferred over ADMIN_PASSWORD.
+    fn from_env() -> Result<Option<Self>, ConfigError> {
+        let var = |name| env::var(name).ok().filter(|v: &String| !v.is_empty());
+        let (Some(username), Some(email)) = (var("ADMIN_USERNAME"), var("ADMIN_EMAIL")) else {
+            return Ok(None);
+        };
+
+        let password = match (var("ADMIN_PASSWORD_HASH"), var("ADMIN_PASSWORD")) {
+            (Some(hash), _) if hash.starts_with("$argon2") => AdminPassword::Hash(hash),
+            (Some(_), _) => {
+                return Err(ConfigError::Invalid(
+                    "ADMIN_PASSWORD_HASH must be an Argon2 hash (starting with '$argon2')".into(),
+                ))
+            }
+            (None, Some(password)) => AdminPassword::Plain(password),
+            (None, None) => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            username,
+            email,
+            password,
+        }))
+    }
+}
+
+/// Database connection pool settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbPoolConfig {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// How long a request waits for a free connection
+    pub acquire_timeout: Duration,
+    /// Idle connections above `min_connections` are closed after this long
+    pub idle_timeout: Duration,
+}
+
+impl Default for DbPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            min_connections: 1,
+            acquire_timeout: Duration::from_secs(10),
+            idle_timeout: Duration::from_secs(600),
+        }
+    }
+}
+
+impl DbPoolConfig {
+    fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Read DB_MAX_CONNECTIONS, DB_MIN_CONNECTIONS, DB_ACQUIRE_TIMEOUT_SECS and
+    /// DB_IDLE_TIMEOUT_SECS through `var`, keeping defaults for unset values
+    fn from_lookup(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        let number = |name: &str| var(name).and_then(|v| v.trim().parse::<u64>().ok());
+
+        let max_connections = number("DB_MAX_CONNECTIONS")
+            .filter(|n| *n > 0)
+            .map_or(defaults.max_connections, |n| n.min(u32::MAX as u64) as u32);
+        let min_connections = number("DB_MIN_CONNECTIONS")
+            .map_or(defaults.min_connections, |n| n.min(u32::MAX as u64) as u32);
+        if min_connections > max_connections {
+            return Err(ConfigError::Invalid(format!(
+                "DB_MIN_CONNECTIONS ({}) must not exceed DB_MAX_CONNECTIONS ({})",
+                min_connections, max_connections
+            )));
+        }
+
+        Ok(Self {
+            max_connections,
+            min_connections,
+            acquire_timeout: number("DB_ACQUIRE_TIMEOUT_SECS")
+                .filter(|s| *s > 0)
+                .map_or(defaults.acquire_timeout, Duration::from_secs),
+            idle_timeout: number("DB_IDLE_TIMEOUT_SECS")
+                .filter(|s| *s > 0)
+                .map_or(defaults.idle_timeout, Duration::from_secs),
+        })
+    }
+}
+
+/// Content-Security-Policy settings
+///
+/// The source lists are added to `'self'` in the matching directive. With
+/// `nonce` set, inline scripts and style elements need the nonce of the
+/// response instead of `'unsafe-inline'`; pages below `relaxed_paths` keep
+/// `'unsafe-inline'` because they still use inline event handlers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CspConfig {
+    pub script_src: Vec<String>,
+    pub style_src: Vec<String>,
+    pub img_src: Vec<String>,
+    pub connect_src: Vec<String>,
+    pub nonce: bool,
+    pub relaxed_paths: Vec<String>,
+}
+
+impl Default for CspConfig {
+    fn default() -> Self {
+        Self {
+            script_src: Vec::new(),
+            style_src: Vec::new(),
+            img_src: Vec::new(),
+            connect_src: Vec::new(),
+            nonce: true,
+            relaxed_paths: vec!["/admin".to_string()],
+        }
+    }
+}
+
+impl CspConfig {
+    fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Read CSP_SCRIPT_SRC, CSP_STYLE_SRC, CSP_IMG_SRC and CSP_CONNECT_SRC
+    /// (sources separated by spaces or commas), CSP_NONCE and
+    /// CSP_RELAXED_PATHS through `var`, keeping defaults for unset values
+    fn from_lookup(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        let list = |name: &str| -> Result<Option<Vec<String>>, ConfigError> {
+            let Some(value) = var(name) else {
+                return Ok(None);
+            };
+            let items: Vec<String> = value
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect();
+            if let Some(bad) = items
+                .iter()
+                .find(|s| s.contains(';') || !s.chars().all(|c| c.is_ascii_graphic()))
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "{} has an invalid entry '{}'",
+                    name, bad
+                )));
+            }
+            Ok(Some(items))
+        };
+
+        Ok(Self {
+            script_src: list("CSP_SCRIPT_SRC")?.unwrap_or(defaults.script_src),
+            style_src: list("CSP_STYLE_SRC")?.unwrap_or(defaults.style_src),
+            img_src: list("CSP_IMG_SRC")?.unwrap_or(defaults.img_src),
+            connect_src: list("CSP_CONNECT_SRC")?.unwrap_or(defaults.connect_src),
+            nonce: var("CSP_NONCE")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(defaults.nonce),
+            relaxed_paths: list("CSP_RELAXED_PATHS")?.unwrap_or(defaults.relaxed_paths),
+        })
+    }
+}
+
+/// Weekdays and local time window within which calendar slots may be created
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusinessHours {
+    pub weekdays: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    /// Zone in which the weekday and times of a slot are compared
+    pub timezone: Tz,
+}
+
+impl BusinessHours {
+    fn from_env() -> Result<Option<Self>, ConfigError> {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Read SLOT_BUSINESS_HOURS ("09:00-17:00"), SLOT_BUSINESS_DAYS ("mon-fri"
+    /// or "mon,wed,fri") and SLOT_BUSINESS_TIMEZONE through `var`; there is
+    /// no constraint unless SLOT_BUSINESS_HOURS is set
+    fn from_lookup(var: impl Fn(&str) -> Option<String>) -> Result<Option<Self>, ConfigError> {
+        let Some(hours) = var("SLOT_BUSINESS_HOURS").filter(|v| !v.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let invalid = |name: &str, value: &str| {
+            ConfigError::Invalid(format!("{} has an invalid value '{}'", name, value))
+        };
+
+        let (start, end) = hours
+            .split_once('-')
+            .and_then(|(start, end)| {
+                let time = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok();
+                Some((time(start)?, time(end)?))
+            })
+            .ok_or_else(|| invalid("SLOT_BUSINESS_HOURS", &hours))?;
+        if end <= start {
+            return Err(ConfigError::Invalid(format!(
+                "SLOT_BUSINESS_HOURS must end after it starts, got '{}'",
+                hours
+            )));
+        }
+
+        let days = var("SLOT_BUSINESS_DAYS").unwrap_or_else(|| "mon-fri".to_string());
+        let weekdays = parse_weekdays(&days).ok_or_else(|| invalid("SLOT_BUSINESS_DAYS", &days))?;
+
+        let timezone = match var("SLOT_BUSINESS_TIMEZONE").filter(|v| !v.trim().is_empty()) {
+            Some(name) => name
+                .trim()
+                .parse::<Tz>()
+                .map_err(|_| invalid("SLOT_BUSINESS_TIMEZONE", &name))?,
+            None => chrono_tz::Europe::Amsterdam,
+        };
+
+        Ok(Some(Self {
+            weekdays,
+            start,
+            end,
+            timezone,
+        }))
+    }
+
+    /// Why a slot from `start` to `end` falls outside these hours, if it does
+    pub fn violation(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<String> {
+        let local_start = start.with_timezone(&self.timezone);
+        let local_end = end.with_timezone(&self.timezone);
+
+        if !self.weekdays.contains(&local_start.weekday()) {
+            let days: Vec<String> = self.weekdays.iter().map(Weekday::to_string).collect();
+            return Some(format!(
+                "Slots may only be created on business days ({}, {}), not on {}",
+                days.join(", "),
+                self.timezone.name(),
+                local_start.weekday()
+            ));
+        }
+        if local_start.time() < self.start
+            || local_end.date_naive() != local_start.date_naive()
+            || local_end.time() > self.end
+        {
+            return Some(format!(
+                "Slots must fall within business hours ({}-{}, {}), got {}-{}",
+                self.start.format("%H:%M"),
+                self.end.format("%H:%M"),
+                self.timezone.name(),
+                local_start.format("%H:%M"),
+                local_end.format("%H:%M")
+            ));
+        }
+        None
+    }
+}
+
+/// Weekdays from a list such as "mon-fri" or "mon,wed,fri", in week order
+fn parse_weekdays(value: &str) -> Option<Vec<Weekday>> {
+    let mut weekdays = Vec::new();
+    for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let mut day = first.trim().parse::<Weekday>().ok()?;
+                let last = last.trim().parse::<Weekday>().ok()?;
+                weekdays.push(day);
+                while day != last {
+                    day = day.succ();
+                    weekdays.push(day);
+                }
+            }
+            None => weekdays.push(part.parse::<Weekday>().ok()?),
+        }
+    }
+    weekdays.sort_by_key(Weekday::num_days_from_monday);
+    weekdays.dedup();
+    (!weekdays.is_empty()).then_some(weekdays)
+}
+
+impl S3Config {
+    fn from_env() -> Result<Self, ConfigError> {
+        let required = |name: &str| {
+            env::var(name)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| {
+                    ConfigError::Missing(format!("{} is required when STORAGE_BACKEND=s3", name))
+                })
+        };
+
+        let endpoint = env::var("S3_ENDPOINT").ok().filter(|e| !e.is_empty());
+        Ok(S3Config {
+            bucket: required("S3_BUCKET")?,
+            region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            access_key_id: required("S3_ACCESS_KEY_ID")?,
+            secret_access_key: required("S3_SECRET_ACCESS_KEY")?,
+            // Path-style addressing by default for custom endpoints (MinIO)
+            force_path_style: env::var("S3_FORCE_PATH_STYLE")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(endpoint.is_some()),
+            endpoint,
+        })
+    }
+}
+
 impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> Result<Self, ConfigError> {
@@ -51,12 +690,22 @@ impl Config {
             _ => Environment::Development,
         };
 
+        // Formal law links may point anywhere in development, only to
+        // wetten.overheid.nl in production unless configured otherwise
+        let strict_formal_law_domain = env::var("STRICT_FORMAL_LAW_DOMAIN")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(environment == Environment::Production);
+
         // Build DATABASE_URL from various env var formats:
         // 1. DATABASE_URL (standard)
         // 2. DATABASE_SERVER_FULL (platform alias)
         // 3. Individual components: DATABASE_SERVER_HOST, DATABASE_SERVER_PORT, DATABASE_USER, DATABASE_PASSWORD, DATABASE_DB
-        let database_url = env::var("DATABASE_URL")
-            .or_else(|_| env::var("DATABASE_SERVER_FULL"))
+        let (database_url, database_url_source) = env::var("DATABASE_URL")
+            .map(|url| (url, DatabaseUrlSource::DatabaseUrl))
+            .or_else(|_| {
+                env::var("DATABASE_SERVER_FULL")
+                    .map(|url| (url, DatabaseUrlSource::DatabaseServerFull))
+            })
             .or_else(|_| {
                 let host = env::var("DATABASE_SERVER_HOST")
                     .or_else(|_| env::var("APP_DATABASE_SERVER"))
@@ -73,9 +722,9 @@ impl Config {
                 let db = env::var("DATABASE_DB")
                     .or_else(|_| env::var("APP_DATABASE_DB"))
                     .map_err(|_| env::VarError::NotPresent)?;
-                Ok(format!(
-                    "postgres://{}:{}@{}:{}/{}",
-                    user, password, host, port, db
+                Ok((
+                    format!("postgres://{}:{}@{}:{}/{}", user, password, host, port, db),
+                    DatabaseUrlSource::Components,
                 ))
             })
             .map_err(|_: env::VarError| {
@@ -84,6 +733,115 @@ impl Config {
                 )
             })?;
 
+        let storage_backend = match env::var("STORAGE_BACKEND")
+            .unwrap_or_else(|_| "fs".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "fs" => StorageBackend::Fs,
+            "s3" => StorageBackend::S3,
+            other => {
+                return Err(ConfigError::Invalid(format!(
+                    "STORAGE_BACKEND must be 'fs' or 's3', got '{}'",
+                    other
+                )))
+            }
+        };
+        let upload_layout = match env::var("UPLOAD_LAYOUT")
+            .unwrap_or_else(|_| "flat".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "flat" => UploadLayout::Flat,
+            "date" => UploadLayout::Date,
+            other => {
+                return Err(ConfigError::Invalid(format!(
+                    "UPLOAD_LAYOUT must be 'flat' or 'date', got '{}'",
+                    other
+                )))
+            }
+        };
+        let s3 = match storage_backend {
+            StorageBackend::S3 => Some(S3Config::from_env()?),
+            StorageBackend::Fs => None,
+        };
+
+        let default_classifications = match env::var("DEFAULT_CLASSIFICATIONS") {
+            Ok(value) => parse_default_classifications(&value)?,
+            Err(_) => builtin_default_classifications(),
+        };
+
+        let notify_enabled = env::var("NOTIFY_ENABLED")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        let smtp = if notify_enabled {
+            Some(SmtpConfig::from_env()?)
+        } else {
+            None
+        };
+
+        let totp_key = match env::var("TOTP_ENCRYPTION_KEY") {
+            Ok(value) if !value.is_empty() => Some(
+                value
+                    .parse()
+                    .map_err(|e| ConfigError::Invalid(format!("TOTP_ENCRYPTION_KEY: {}", e)))?,
+            ),
+            _ => None,
+        };
+
+        let file_encryption_key = match env::var("FILE_ENCRYPTION_KEY") {
+            Ok(value) if !value.is_empty() => Some(
+                value
+                    .parse()
+                    .map_err(|e| ConfigError::Invalid(format!("FILE_ENCRYPTION_KEY: {}", e)))?,
+            ),
+            _ => None,
+        };
+
+        let download_link_secret = match env::var("DOWNLOAD_LINK_SECRET") {
+            Ok(value) if !value.is_empty() => Some(
+                value
+                    .parse()
+                    .map_err(|e| ConfigError::Invalid(format!("DOWNLOAD_LINK_SECRET {}", e)))?,
+            ),
+            _ => None,
+        };
+
+        let thumbnail_renderer = match env::var("THUMBNAIL_RENDERER") {
+            Ok(command) if !command.trim().is_empty() => {
+                if !command.contains("{input}") || !command.contains("{output}") {
+                    return Err(ConfigError::Invalid(
+                        "THUMBNAIL_RENDERER must contain {input} and {output}".into(),
+                    ));
+                }
+                Some(command.trim().to_string())
+            }
+            _ => None,
+        };
+
+        let allowed_mime_types = match env::var("ALLOWED_MIME_TYPES") {
+            Ok(value) => parse_allowed_mime_types(&value)?,
+            Err(_) => DEFAULT_ALLOWED_MIME_TYPES
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
+        };
+
+        let argon2_cost = |name: &str, default: u32| {
+            env::var(name)
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &u32| *n > 0)
+                .unwrap_or(default)
+        };
+        let argon2_params = argon2::Params::new(
+            argon2_cost("ARGON2_MEMORY_KIB", argon2::Params::DEFAULT_M_COST),
+            argon2_cost("ARGON2_ITERATIONS", argon2::Params::DEFAULT_T_COST),
+            argon2_cost("ARGON2_PARALLELISM", argon2::Params::DEFAULT_P_COST),
+            None,
+        )
+        .map_err(|e| ConfigError::Invalid(format!("Argon2 parameters: {}", e)))?;
+
         Ok(Config {
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             port: env::var("PORT")
@@ -91,6 +849,8 @@ impl Config {
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(8080),
             database_url,
+            database_url_source,
+            db_pool: DbPoolConfig::from_env()?,
             upload_dir: env::var("UPLOAD_DIR")
                 .or_else(|_| {
                     env::var("DATA_PATH").map(|p| format!("{}/uploads", p.trim_end_matches('/')))
@@ -100,7 +860,71 @@ impl Config {
             session_expiry_hours: env::var("SESSION_EXPIRY_HOURS")
                 .ok()
                 .and_then(|h| h.parse().ok())
+                .filter(|h: &u64| *h > 0)
                 .unwrap_or(8),
+            session_max_hours: env::var("SESSION_MAX_HOURS")
+                .ok()
+                .and_then(|h| h.parse().ok())
+                .filter(|h: &u64| *h > 0)
+                .unwrap_or(24),
+            uploader_session_hours: env::var("UPLOADER_SESSION_HOURS")
+                .ok()
+                .and_then(|h| h.parse().ok())
+                .filter(|h: &u64| *h > 0)
+                .unwrap_or(4),
+            login_max_failures: env::var("LOGIN_MAX_FAILURES")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &i32| *n > 0)
+                .unwrap_or(5),
+            login_lockout_minutes: env::var("LOGIN_LOCKOUT_MINUTES")
+                .ok()
+                .and_then(|m| m.parse().ok())
+                .filter(|m: &i64| *m > 0)
+                .unwrap_or(15),
+            argon2_params,
+            book_slot_max_attempts: env::var("BOOK_SLOT_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &i64| *n > 0)
+                .unwrap_or(10),
+            formal_law_max_attempts: env::var("FORMAL_LAW_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &i64| *n > 0)
+                .unwrap_or(30),
+            status_check_max_attempts: env::var("STATUS_CHECK_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &i64| *n > 0)
+                .unwrap_or(30),
+            rate_limit_login: env::var("RATE_LIMIT_LOGIN")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &i64| *n > 0)
+                .unwrap_or(10),
+            rate_limit_submission: env::var("RATE_LIMIT_SUBMISSION")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &i64| *n > 0)
+                .unwrap_or(20),
+            rate_limit_uploader_login: env::var("RATE_LIMIT_UPLOADER_LOGIN")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &i64| *n > 0)
+                .unwrap_or(10),
+            rate_limit_window_secs: env::var("RATE_LIMIT_WINDOW_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|s: &u64| *s > 0)
+                .unwrap_or(3600),
+            wetten_base_url: env::var("WETTEN_BASE_URL")
+                .unwrap_or_else(|_| "https://wetten.overheid.nl".to_string()),
+            formal_law_title_cache_hours: env::var("FORMAL_LAW_TITLE_CACHE_HOURS")
+                .ok()
+                .and_then(|h| h.parse().ok())
+                .filter(|h: &u64| *h > 0)
+                .unwrap_or(24),
             max_upload_size: env::var("MAX_UPLOAD_SIZE")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -119,9 +943,164 @@ impl Config {
                         .collect()
                 })
                 .unwrap_or_default(),
+            draft_max_age_minutes: env::var("DRAFT_MAX_AGE_MINUTES")
+                .ok()
+                .and_then(|m| m.parse().ok())
+                .filter(|m: &i64| *m > 0)
+                .unwrap_or(60),
+            retention_months: env::var("RETENTION_MONTHS")
+                .ok()
+                .and_then(|m| m.parse().ok())
+                .filter(|m: &u32| *m > 0)
+                .unwrap_or(12),
+            retention_extension_months: env::var("RETENTION_EXTENSION_MONTHS")
+                .ok()
+                .and_then(|m| m.parse().ok())
+                .filter(|m: &u32| *m > 0)
+                .unwrap_or(12),
+            retention_max_months: env::var("RETENTION_MAX_MONTHS")
+                .ok()
+                .and_then(|m| m.parse().ok())
+                .filter(|m: &u32| *m > 0)
+                .unwrap_or(36),
+            retention_purge_dry_run: env::var("RETENTION_PURGE_DRY_RUN")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            allowed_extensions: env::var("ALLOWED_EXTENSIONS")
+                .map(|s| {
+                    s.split(',')
+                        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                        .filter(|e| !e.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            allowed_mime_types,
+            storage_backend,
+            upload_layout,
+            s3,
+            max_files_per_upload: env::var("MAX_FILES_PER_UPLOAD")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &usize| *n > 0)
+                .unwrap_or(10),
+            max_filename_length: env::var("MAX_FILENAME_LENGTH")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &usize| *n >= MIN_FILENAME_LENGTH)
+                .unwrap_or(150),
+            max_formal_laws_per_submission: env::var("MAX_FORMAL_LAWS_PER_SUBMISSION")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &i64| *n > 0)
+                .unwrap_or(50),
+            strict_formal_law_domain,
+            default_classifications,
+            notify_enabled,
+            smtp,
+            totp_key,
+            clamav_addr: env::var("CLAMAV_ADDR")
+                .ok()
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty()),
+            strict_pdf_validation: env::var("STRICT_PDF_VALIDATION")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            webhook: WebhookConfig::from_env()?,
+            strict_migrations: env::var("STRICT_MIGRATIONS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            download_link_secret,
+            download_link_expiry_hours: env::var("DOWNLOAD_LINK_EXPIRY_HOURS")
+                .ok()
+                .and_then(|h| h.parse().ok())
+                .filter(|h: &u64| *h > 0)
+                .unwrap_or(24),
+            file_encryption_key,
+            admin_seed: AdminSeed::from_env()?,
+            behind_proxy: env::var("BEHIND_PROXY")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            thumbnail_renderer,
+            thumbnail_size: env::var("THUMBNAIL_SIZE")
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .filter(|n: &u32| (16..=thumbnails::MAX_SIZE).contains(n))
+                .unwrap_or(thumbnails::DEFAULT_SIZE),
+            business_hours: BusinessHours::from_env()?,
+            csp: CspConfig::from_env()?,
         })
     }
 
+    /// Refuse settings that are unsafe in production
+    ///
+    /// The defaults suit local development; in production they would leave
+    /// CORS open to localhost, trust no proxy or keep sessions alive for
+    /// too long. Development configurations are always accepted.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.is_production() {
+            return Ok(());
+        }
+
+        for origin in &self.cors_origins {
+            let host = url::Url::parse(origin)
+                .ok()
+                .filter(|u| u.scheme() == "https")
+                .and_then(|u| u.host_str().map(str::to_string));
+            match host.as_deref() {
+                None => {
+                    return Err(ConfigError::Invalid(format!(
+                        "CORS_ORIGINS entry '{}' must be an https origin",
+                        origin
+                    )))
+                }
+                Some("localhost" | "127.0.0.1" | "[::1]") => {
+                    return Err(ConfigError::Invalid(format!(
+                        "CORS_ORIGINS must list the public origin of the portal, not '{}'",
+                        origin
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+        if self.cors_origins.is_empty() {
+            return Err(ConfigError::Missing(
+                "CORS_ORIGINS is required in production".into(),
+            ));
+        }
+
+        if self.behind_proxy && self.trusted_proxies.is_empty() {
+            return Err(ConfigError::Missing(
+                "TRUSTED_PROXIES is required when BEHIND_PROXY=true".into(),
+            ));
+        }
+
+        if let Some(AdminSeed {
+            password: AdminPassword::Plain(_),
+            ..
+        }) = &self.admin_seed
+        {
+            return Err(ConfigError::Invalid(
+                "ADMIN_PASSWORD is for development only, set ADMIN_PASSWORD_HASH in production"
+                    .into(),
+            ));
+        }
+
+        if self.session_expiry_hours > self.session_max_hours {
+            return Err(ConfigError::Invalid(format!(
+                "SESSION_EXPIRY_HOURS ({}) must not exceed SESSION_MAX_HOURS ({})",
+                self.session_expiry_hours, self.session_max_hours
+            )));
+        }
+        if self.session_max_hours > MAX_PRODUCTION_SESSION_HOURS {
+            return Err(ConfigError::Invalid(format!(
+                "SESSION_MAX_HOURS must be at most {} in production, got {}",
+                MAX_PRODUCTION_SESSION_HOURS, self.session_max_hours
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Check if running in production
     pub fn is_production(&self) -> bool {
         self.environment == Environment::Production
@@ -131,6 +1110,116 @@ impl Config {
     pub fn server_addr(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
+
+    /// The settings admins may see, without the database URL or any secret
+    pub fn effective(&self) -> EffectiveConfig {
+        EffectiveConfig {
+            environment: match self.environment {
+                Environment::Development => "development",
+                Environment::Production => "production",
+            }
+            .to_string(),
+            database_url_source: match self.database_url_source {
+                DatabaseUrlSource::DatabaseUrl => "database_url",
+                DatabaseUrlSource::DatabaseServerFull => "database_server_full",
+                DatabaseUrlSource::Components => "components",
+            }
+            .to_string(),
+            max_upload_size: self.max_upload_size,
+            max_files_per_upload: self.max_files_per_upload,
+            storage_backend: match self.storage_backend {
+                StorageBackend::Fs => "fs",
+                StorageBackend::S3 => "s3",
+            }
+            .to_string(),
+            upload_dir: self.upload_dir.clone(),
+            upload_layout: match self.upload_layout {
+                UploadLayout::Flat => "flat",
+                UploadLayout::Date => "date",
+            }
+            .to_string(),
+            cors_origins: self.cors_origins.clone(),
+            trusted_proxies: self.trusted_proxies.clone(),
+            behind_proxy: self.behind_proxy,
+            session_expiry_hours: self.session_expiry_hours,
+            session_max_hours: self.session_max_hours,
+            uploader_session_hours: self.uploader_session_hours,
+            retention_months: self.retention_months,
+            notify_enabled: self.notify_enabled,
+            virus_scanning_enabled: self.clamav_addr.is_some(),
+            webhook_configured: self.webhook.is_some(),
+            file_encryption_enabled: self.file_encryption_key.is_some(),
+            totp_enabled: self.totp_key.is_some(),
+        }
+    }
+}
+
+/// Defaults used when DEFAULT_CLASSIFICATIONS is not set
+fn builtin_default_classifications() -> HashMap<DocumentCategory, DocumentClassification> {
+    HashMap::from([
+        (DocumentCategory::FormalLaw, DocumentClassification::Public),
+        (DocumentCategory::Circular, DocumentClassification::Public),
+    ])
+}
+
+/// Parse a comma-separated list of MIME types, e.g. "application/pdf,text/csv"
+///
+/// Types that could carry scripts are dropped with a warning rather than
+/// failing startup; a list left empty is an error.
+fn parse_allowed_mime_types(value: &str) -> Result<Vec<String>, ConfigError> {
+    let mut types: Vec<String> = Vec::new();
+    for mime_type in value.split(',').map(|t| t.trim().to_ascii_lowercase()) {
+        if mime_type.is_empty() || types.contains(&mime_type) {
+            continue;
+        }
+        if is_dangerous_mime_type(&mime_type) {
+            tracing::warn!(
+                "Ignoring {} in ALLOWED_MIME_TYPES: HTML, XML and scripts are never accepted",
+                mime_type
+            );
+            continue;
+        }
+        types.push(mime_type);
+    }
+
+    if types.is_empty() {
+        return Err(ConfigError::Invalid(
+            "ALLOWED_MIME_TYPES contains no acceptable type".into(),
+        ));
+    }
+    Ok(types)
+}
+
+/// Parse `category=classification` pairs, e.g. "circular=public,work_instruction=claude_allowed"
+///
+/// The listed pairs are applied on top of the built-in defaults.
+fn parse_default_classifications(
+    value: &str,
+) -> Result<HashMap<DocumentCategory, DocumentClassification>, ConfigError> {
+    let mut defaults = builtin_default_classifications();
+
+    for pair in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let invalid = |reason: String| {
+            ConfigError::Invalid(format!(
+                "DEFAULT_CLASSIFICATIONS entry '{}': {}",
+                pair, reason
+            ))
+        };
+        let (category, classification) = pair
+            .split_once('=')
+            .ok_or_else(|| invalid("expected category=classification".to_string()))?;
+        let category: DocumentCategory = category.trim().parse().map_err(invalid)?;
+        let classification: DocumentClassification =
+            classification.trim().parse().map_err(invalid)?;
+
+        // Restricted documents may never be uploaded, so they cannot be a default
+        if classification == DocumentClassification::Restricted {
+            return Err(invalid("restricted cannot be a default".to_string()));
+        }
+        defaults.insert(category, classification);
+    }
+
+    Ok(defaults)
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -141,3 +1230,223 @@ pub enum ConfigError {
     #[error("Invalid configuration: {0}")]
     Invalid(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_classifications() {
+        let defaults = parse_default_classifications(
+            "work_instruction=claude_allowed, circular=claude_allowed",
+        )
+        .unwrap();
+
+        assert_eq!(
+            defaults.get(&DocumentCategory::WorkInstruction),
+            Some(&DocumentClassification::ClaudeAllowed)
+        );
+        // Overrides a built-in default
+        assert_eq!(
+            defaults.get(&DocumentCategory::Circular),
+            Some(&DocumentClassification::ClaudeAllowed)
+        );
+        // Built-in default kept
+        assert_eq!(
+            defaults.get(&DocumentCategory::FormalLaw),
+            Some(&DocumentClassification::Public)
+        );
+        assert_eq!(defaults.get(&DocumentCategory::ImplementationPolicy), None);
+    }
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_db_pool_config_from_env() {
+        assert_eq!(
+            DbPoolConfig::from_lookup(lookup(&[])).unwrap(),
+            DbPoolConfig::default()
+        );
+
+        let pool = DbPoolConfig::from_lookup(lookup(&[
+            ("DB_MAX_CONNECTIONS", "25"),
+            ("DB_MIN_CONNECTIONS", "0"),
+            ("DB_ACQUIRE_TIMEOUT_SECS", "3"),
+            ("DB_IDLE_TIMEOUT_SECS", "not a number"),
+        ]))
+        .unwrap();
+        assert_eq!(pool.max_connections, 25);
+        assert_eq!(pool.min_connections, 0);
+        assert_eq!(pool.acquire_timeout, Duration::from_secs(3));
+        assert_eq!(pool.idle_timeout, DbPoolConfig::default().idle_timeout);
+    }
+
+    #[test]
+    fn test_db_pool_config_rejects_min_above_max() {
+        let err = DbPoolConfig::from_lookup(lookup(&[
+            ("DB_MAX_CONNECTIONS", "4"),
+            ("DB_MIN_CONNECTIONS", "5"),
+        ]))
+        .unwrap_err();
+        assert!(err.to_string().contains("DB_MIN_CONNECTIONS (5)"));
+
+        // The default maximum applies when only the minimum is raised
+        assert!(DbPoolConfig::from_lookup(lookup(&[("DB_MIN_CONNECTIONS", "11")])).is_err());
+        assert!(DbPoolConfig::from_lookup(lookup(&[("DB_MIN_CONNECTIONS", "10")])).is_ok());
+    }
+
+    #[test]
+    fn test_csp_config_from_env() {
+        assert_eq!(
+            CspConfig::from_lookup(lookup(&[])).unwrap(),
+            CspConfig::default()
+        );
+
+        let csp = CspConfig::from_lookup(lookup(&[
+            (
+                "CSP_CONNECT_SRC",
+                "https://api.example.org, wss://live.example.org",
+            ),
+            ("CSP_IMG_SRC", "https://cdn.example.org"),
+            ("CSP_NONCE", "false"),
+            ("CSP_RELAXED_PATHS", ""),
+        ]))
+        .unwrap();
+        assert_eq!(
+            csp.connect_src,
+            ["https://api.example.org", "wss://live.example.org"]
+        );
+        assert_eq!(csp.img_src, ["https://cdn.example.org"]);
+        assert!(csp.script_src.is_empty());
+        assert!(!csp.nonce);
+        assert!(csp.relaxed_paths.is_empty());
+
+        // A source cannot smuggle in another directive
+        assert!(CspConfig::from_lookup(lookup(&[(
+            "CSP_SCRIPT_SRC",
+            "https://cdn.example.org;script-src *"
+        )]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_business_hours_from_env() {
+        assert_eq!(BusinessHours::from_lookup(lookup(&[])).unwrap(), None);
+
+        let hours = BusinessHours::from_lookup(lookup(&[("SLOT_BUSINESS_HOURS", "09:00-17:00")]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            hours.weekdays,
+            [
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri
+            ]
+        );
+        assert_eq!(hours.start, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(hours.end, NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+        assert_eq!(hours.timezone, chrono_tz::Europe::Amsterdam);
+
+        let hours = BusinessHours::from_lookup(lookup(&[
+            ("SLOT_BUSINESS_HOURS", "10:00 - 12:30"),
+            ("SLOT_BUSINESS_DAYS", "fri, tue-wed"),
+            ("SLOT_BUSINESS_TIMEZONE", "UTC"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(hours.weekdays, [Weekday::Tue, Weekday::Wed, Weekday::Fri]);
+        assert_eq!(hours.end, NaiveTime::from_hms_opt(12, 30, 0).unwrap());
+        assert_eq!(hours.timezone, chrono_tz::UTC);
+
+        for vars in [
+            [("SLOT_BUSINESS_HOURS", "17:00-09:00"), ("", "")],
+            [("SLOT_BUSINESS_HOURS", "9-17"), ("", "")],
+            [
+                ("SLOT_BUSINESS_HOURS", "09:00-17:00"),
+                ("SLOT_BUSINESS_DAYS", "weekdays"),
+            ],
+            [
+                ("SLOT_BUSINESS_HOURS", "09:00-17:00"),
+                ("SLOT_BUSINESS_TIMEZONE", "Europe/Nowhere"),
+            ],
+        ] {
+            assert!(
+                BusinessHours::from_lookup(lookup(&vars)).is_err(),
+                "{:?}",
+                vars
+            );
+        }
+    }
+
+    #[test]
+    fn test_business_hours_violation() {
+        let hours = BusinessHours {
+            weekdays: parse_weekdays("mon-fri").unwrap(),
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            timezone: chrono_tz::Europe::Amsterdam,
+        };
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+
+        // Tuesday 9 June 2026, 09:00-10:00 and 16:00-17:00 in Amsterdam (UTC+2)
+        assert_eq!(
+            hours.violation(at("2026-06-09T07:00:00Z"), at("2026-06-09T08:00:00Z")),
+            None
+        );
+        assert_eq!(
+            hours.violation(at("2026-06-09T14:00:00Z"), at("2026-06-09T15:00:00Z")),
+            None
+        );
+
+        // Saturday 13 June
+        let weekend = hours
+            .violation(at("2026-06-13T08:00:00Z"), at("2026-06-13T09:00:00Z"))
+            .unwrap();
+        assert!(weekend.contains("business days"), "{}", weekend);
+        assert!(weekend.contains("Sat"), "{}", weekend);
+
+        // Tuesday 08:30-09:30 local starts before opening
+        let early = hours
+            .violation(at("2026-06-09T06:30:00Z"), at("2026-06-09T07:30:00Z"))
+            .unwrap();
+        assert!(early.contains("business hours (09:00-17:00"), "{}", early);
+
+        // Tuesday 16:30-17:30 local runs past closing
+        assert!(hours
+            .violation(at("2026-06-09T14:30:00Z"), at("2026-06-09T15:30:00Z"))
+            .is_some());
+    }
+
+    #[test]
+    fn test_parse_default_classifications_rejects_invalid_entries() {
+        assert!(parse_default_classifications("circular").is_err());
+        assert!(parse_default_classifications("memo=public").is_err());
+        assert!(parse_default_classifications("circular=secret").is_err());
+        assert!(parse_default_classifications("circular=restricted").is_err());
+    }
+
+    #[test]
+    fn test_parse_allowed_mime_types_drops_dangerous_types() {
+        let types = parse_allowed_mime_types(
+            "application/pdf, Application/VND.oasis.opendocument.spreadsheet,text/html,,application/pdf",
+        )
+        .unwrap();
+        assert_eq!(
+            types,
+            [
+                "application/pdf",
+                "application/vnd.oasis.opendocument.spreadsheet"
+            ]
+        );
+        assert!(parse_allowed_mime_types("text/html,image/svg+xml").is_err());
+    }
+}
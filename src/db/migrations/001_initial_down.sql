@@ -0,0 +1,19 @@
+-- Reverts 001_initial: drops the whole schema, including all data
+-- The uuid-ossp extension is left installed
+
+DROP TABLE IF EXISTS rate_limit_attempts;
+DROP TABLE IF EXISTS audit_log;
+DROP TABLE IF EXISTS calendar_slots;
+DROP TABLE IF EXISTS admin_sessions;
+DROP TABLE IF EXISTS admin_users;
+DROP TABLE IF EXISTS documents;
+DROP TABLE IF EXISTS submissions;
+
+DROP FUNCTION IF EXISTS generate_submission_slug();
+DROP FUNCTION IF EXISTS update_updated_at_column();
+DROP FUNCTION IF EXISTS cleanup_rate_limits();
+
+DROP TYPE IF EXISTS audit_action;
+DROP TYPE IF EXISTS document_classification;
+DROP TYPE IF EXISTS document_category;
+DROP TYPE IF EXISTS submission_status;
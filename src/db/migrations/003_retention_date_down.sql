@@ -0,0 +1,3 @@
+-- Reverts 003_retention_date
+DROP INDEX IF EXISTS idx_submissions_retention_expiry;
+ALTER TABLE submissions DROP COLUMN IF EXISTS retention_expiry_date;
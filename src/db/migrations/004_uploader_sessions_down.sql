@@ -0,0 +1,5 @@
+-- Reverts 004_uploader_sessions
+-- PostgreSQL cannot drop enum values, so the uploader audit actions stay
+
+DROP INDEX IF EXISTS idx_submissions_email;
+DROP TABLE IF EXISTS uploader_sessions;
@@ -0,0 +1,10 @@
+-- Optional TOTP two-factor authentication for admin accounts
+-- Secrets are stored encrypted (AES-256-GCM, hex encoded) by the application
+
+-- Active secret, login requires a valid code when set
+ALTER TABLE admin_users ADD COLUMN totp_secret TEXT;
+
+-- Secret handed out by enrollment, activated once a code has been confirmed
+ALTER TABLE admin_users ADD COLUMN totp_pending_secret TEXT;
+
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'admin_2fa_enrolled';
@@ -0,0 +1,5 @@
+-- Reverts 007_admin_totp, removing every admin's two-factor enrollment
+-- PostgreSQL cannot drop enum values, so 'admin_2fa_enrolled' stays
+
+ALTER TABLE admin_users DROP COLUMN IF EXISTS totp_pending_secret;
+ALTER TABLE admin_users DROP COLUMN IF EXISTS totp_secret;
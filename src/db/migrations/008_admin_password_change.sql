@@ -0,0 +1,3 @@
+-- Self-service password change for admin accounts
+
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'admin_password_changed';
@@ -0,0 +1,2 @@
+-- Reverts 008_admin_password_change
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
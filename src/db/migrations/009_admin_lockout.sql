@@ -0,0 +1,10 @@
+-- Lock admin accounts after repeated failed logins
+-- Complements the per-IP rate limit, which an attacker can evade by rotating IPs
+
+-- Consecutive failed attempts since the last successful login or lockout
+ALTER TABLE admin_users ADD COLUMN failed_login_attempts INTEGER NOT NULL DEFAULT 0;
+
+-- Logins are refused until this time
+ALTER TABLE admin_users ADD COLUMN locked_until TIMESTAMPTZ;
+
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'admin_account_locked';
@@ -0,0 +1,5 @@
+-- Reverts 009_admin_lockout
+-- PostgreSQL cannot drop enum values, so 'admin_account_locked' stays
+
+ALTER TABLE admin_users DROP COLUMN IF EXISTS locked_until;
+ALTER TABLE admin_users DROP COLUMN IF EXISTS failed_login_attempts;
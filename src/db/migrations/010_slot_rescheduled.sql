@@ -0,0 +1,2 @@
+-- Audit action for applicants moving their booking to another slot
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'slot_rescheduled';
@@ -0,0 +1,2 @@
+-- Reverts 010_slot_rescheduled
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
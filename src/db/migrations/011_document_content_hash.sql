@@ -0,0 +1,8 @@
+-- Content hash of uploaded files, used to reject re-uploads of the same file
+-- Formal law links have no file and keep a NULL hash
+
+ALTER TABLE documents ADD COLUMN content_sha256 VARCHAR(64);
+
+CREATE UNIQUE INDEX idx_documents_submission_content_sha256
+    ON documents(submission_id, content_sha256)
+    WHERE content_sha256 IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Reverts 011_document_content_hash
+DROP INDEX IF EXISTS idx_documents_submission_content_sha256;
+ALTER TABLE documents DROP COLUMN IF EXISTS content_sha256;
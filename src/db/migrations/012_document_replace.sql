@@ -0,0 +1,9 @@
+-- Allow uploaders to replace a document's file while keeping its id
+
+-- Last time the file was replaced (equals created_at for untouched documents)
+ALTER TABLE documents ADD COLUMN updated_at TIMESTAMPTZ;
+UPDATE documents SET updated_at = created_at;
+ALTER TABLE documents ALTER COLUMN updated_at SET NOT NULL;
+ALTER TABLE documents ALTER COLUMN updated_at SET DEFAULT NOW();
+
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'document_replaced';
@@ -0,0 +1,4 @@
+-- Reverts 012_document_replace
+-- PostgreSQL cannot drop enum values, so 'document_replaced' stays
+
+ALTER TABLE documents DROP COLUMN IF EXISTS updated_at;
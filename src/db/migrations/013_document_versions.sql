@@ -0,0 +1,24 @@
+-- Every file ever stored for a document, so reviewers can see what was replaced
+-- Files of old versions stay in storage until the submission is deleted
+
+CREATE TABLE document_versions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    version_no INTEGER NOT NULL,
+    file_path VARCHAR(1024) NOT NULL,
+    file_size BIGINT,
+    mime_type VARCHAR(127),
+    original_filename VARCHAR(255),
+    content_sha256 VARCHAR(64),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (document_id, version_no)
+);
+
+-- Existing files become version 1
+INSERT INTO document_versions (
+    document_id, version_no, file_path, file_size, mime_type,
+    original_filename, content_sha256, created_at
+)
+SELECT id, 1, file_path, file_size, mime_type, original_filename, content_sha256, updated_at
+FROM documents
+WHERE file_path IS NOT NULL;
@@ -0,0 +1,4 @@
+-- Reverts 013_document_versions
+-- Files of replaced versions are no longer referenced and stay in storage
+
+DROP TABLE IF EXISTS document_versions;
@@ -0,0 +1,2 @@
+-- Admins can end their own sessions on other devices
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'admin_session_revoked';
@@ -0,0 +1,2 @@
+-- Reverts 014_admin_session_revoked
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
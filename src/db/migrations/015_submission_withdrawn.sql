@@ -0,0 +1,3 @@
+-- Uploaders can withdraw a submission that has not been forwarded yet
+ALTER TYPE submission_status ADD VALUE IF NOT EXISTS 'withdrawn';
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'submission_withdrawn';
@@ -0,0 +1,3 @@
+-- Reverts 015_submission_withdrawn
+-- PostgreSQL cannot drop enum values, so the 'withdrawn' status stays usable
+-- Move withdrawn submissions to another status by hand if that matters
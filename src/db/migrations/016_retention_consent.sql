@@ -0,0 +1,6 @@
+-- Uploaders can consent to keeping their submission longer than 12 months
+
+-- When the uploader last consented to an extended retention period
+ALTER TABLE submissions ADD COLUMN retention_consent_at TIMESTAMPTZ;
+
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'retention_extended';
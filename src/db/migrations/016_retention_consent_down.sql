@@ -0,0 +1,4 @@
+-- Reverts 016_retention_consent
+-- PostgreSQL cannot drop enum values, so 'retention_extended' stays
+
+ALTER TABLE submissions DROP COLUMN IF EXISTS retention_consent_at;
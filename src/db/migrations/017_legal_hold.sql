@@ -0,0 +1,5 @@
+-- Submissions under a legal hold are exempt from automatic deletion
+ALTER TABLE submissions ADD COLUMN legal_hold BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'legal_hold_set';
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'legal_hold_cleared';
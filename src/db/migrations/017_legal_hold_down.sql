@@ -0,0 +1,4 @@
+-- Reverts 017_legal_hold, lifting every legal hold
+-- PostgreSQL cannot drop enum values, so the legal hold audit actions stay
+
+ALTER TABLE submissions DROP COLUMN IF EXISTS legal_hold;
@@ -0,0 +1,18 @@
+-- Full-text search over submission notes and document descriptions.
+-- Dutch stemming matches the language reviewers write in.
+ALTER TABLE submissions ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    to_tsvector('dutch',
+        coalesce(submitter_name, '') || ' ' ||
+        coalesce(organization, '') || ' ' ||
+        coalesce(notes, ''))
+) STORED;
+
+CREATE INDEX idx_submissions_search_vector ON submissions USING GIN (search_vector);
+
+ALTER TABLE documents ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    to_tsvector('dutch',
+        coalesce(external_title, '') || ' ' ||
+        coalesce(description, ''))
+) STORED;
+
+CREATE INDEX idx_documents_search_vector ON documents USING GIN (search_vector);
@@ -0,0 +1,6 @@
+-- Reverts 018_full_text_search
+DROP INDEX IF EXISTS idx_documents_search_vector;
+ALTER TABLE documents DROP COLUMN IF EXISTS search_vector;
+
+DROP INDEX IF EXISTS idx_submissions_search_vector;
+ALTER TABLE submissions DROP COLUMN IF EXISTS search_vector;
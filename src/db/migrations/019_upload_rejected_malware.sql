@@ -0,0 +1,2 @@
+-- Uploads that clamd flagged as malware are rejected and audited
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'upload_rejected_malware';
@@ -0,0 +1,2 @@
+-- Reverts 019_upload_rejected_malware
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
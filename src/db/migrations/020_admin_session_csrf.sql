@@ -0,0 +1,3 @@
+-- Hash of the CSRF token issued with each admin session
+-- Sessions from before this migration have none and are checked against the derived token
+ALTER TABLE admin_sessions ADD COLUMN csrf_token_hash VARCHAR(255);
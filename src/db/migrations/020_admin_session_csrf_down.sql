@@ -0,0 +1,2 @@
+-- Reverts 020_admin_session_csrf
+ALTER TABLE admin_sessions DROP COLUMN IF EXISTS csrf_token_hash;
@@ -0,0 +1,2 @@
+-- Admins can share a single document through a signed, expiring download link
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'download_link_created';
@@ -0,0 +1,2 @@
+-- Reverts 021_download_link_created
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
@@ -0,0 +1,4 @@
+-- Document files can be stored encrypted at rest. Files stored before
+-- FILE_ENCRYPTION_KEY was set stay plaintext, so the flag is per file.
+ALTER TABLE documents ADD COLUMN encrypted BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE document_versions ADD COLUMN encrypted BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+-- Reverts 022_document_encryption
+ALTER TABLE document_versions DROP COLUMN IF EXISTS encrypted;
+ALTER TABLE documents DROP COLUMN IF EXISTS encrypted;
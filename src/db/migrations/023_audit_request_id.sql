@@ -0,0 +1,4 @@
+-- Audit entries written while handling a request record its X-Request-Id, so
+-- a request id from a support ticket leads to the matching entries and logs
+ALTER TABLE audit_log ADD COLUMN request_id UUID;
+CREATE INDEX idx_audit_log_request_id ON audit_log(request_id) WHERE request_id IS NOT NULL;
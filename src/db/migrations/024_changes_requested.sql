@@ -0,0 +1,3 @@
+-- Reviewers can send a submission back to the uploader for more documents
+ALTER TYPE submission_status ADD VALUE IF NOT EXISTS 'changes_requested';
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'changes_requested';
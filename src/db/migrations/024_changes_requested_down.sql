@@ -0,0 +1,3 @@
+-- Reverts 024_changes_requested
+-- PostgreSQL cannot drop enum values, so the 'changes_requested' status stays usable
+-- Move such submissions back to under_review by hand if that matters
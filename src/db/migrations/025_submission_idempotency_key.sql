@@ -0,0 +1,6 @@
+-- Clients may send an Idempotency-Key when creating a submission, so a retried
+-- request returns the submission it already created instead of a second draft.
+-- The periodic cleanup clears keys once their replay window has passed.
+ALTER TABLE submissions ADD COLUMN idempotency_key VARCHAR(255);
+CREATE UNIQUE INDEX idx_submissions_idempotency_key ON submissions(idempotency_key)
+    WHERE idempotency_key IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Reverts 025_submission_idempotency_key
+DROP INDEX IF EXISTS idx_submissions_idempotency_key;
+ALTER TABLE submissions DROP COLUMN IF EXISTS idempotency_key;
@@ -0,0 +1,23 @@
+-- A slot can take several bookings, for group review sessions. Bookings move
+-- to their own table and a slot stays available while it has fewer than
+-- `capacity` of them.
+ALTER TABLE calendar_slots ADD COLUMN capacity INTEGER NOT NULL DEFAULT 1
+    CONSTRAINT valid_capacity CHECK (capacity >= 1);
+
+CREATE TABLE slot_bookings (
+    slot_id UUID NOT NULL REFERENCES calendar_slots(id) ON DELETE CASCADE,
+    submission_id UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (slot_id, submission_id)
+);
+
+-- A submission books at most one slot
+CREATE UNIQUE INDEX idx_slot_bookings_submission ON slot_bookings(submission_id);
+
+INSERT INTO slot_bookings (slot_id, submission_id)
+SELECT id, booked_by_submission FROM calendar_slots WHERE booked_by_submission IS NOT NULL;
+
+DROP INDEX IF EXISTS idx_calendar_slots_available;
+ALTER TABLE calendar_slots DROP COLUMN booked_by_submission;
+ALTER TABLE calendar_slots DROP COLUMN is_available;
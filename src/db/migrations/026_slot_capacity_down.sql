@@ -0,0 +1,17 @@
+-- Reverts 026_slot_capacity
+-- Each slot keeps its earliest booking; further bookings are lost
+ALTER TABLE calendar_slots ADD COLUMN is_available BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE calendar_slots ADD COLUMN booked_by_submission UUID REFERENCES submissions(id) ON DELETE SET NULL;
+
+UPDATE calendar_slots c
+SET is_available = false, booked_by_submission = b.submission_id
+FROM (
+    SELECT DISTINCT ON (slot_id) slot_id, submission_id
+    FROM slot_bookings
+    ORDER BY slot_id, created_at
+) b
+WHERE b.slot_id = c.id;
+
+CREATE INDEX idx_calendar_slots_available ON calendar_slots(is_available, slot_start);
+DROP TABLE IF EXISTS slot_bookings;
+ALTER TABLE calendar_slots DROP COLUMN IF EXISTS capacity;
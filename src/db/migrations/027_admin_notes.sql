@@ -0,0 +1,15 @@
+-- Reviewer notes get their own column so they are never shown to the
+-- applicant, while `notes` stays the applicant's own. Admin search covers both.
+ALTER TABLE submissions ADD COLUMN admin_notes TEXT;
+
+DROP INDEX IF EXISTS idx_submissions_search_vector;
+ALTER TABLE submissions DROP COLUMN search_vector;
+ALTER TABLE submissions ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    to_tsvector('dutch',
+        coalesce(submitter_name, '') || ' ' ||
+        coalesce(organization, '') || ' ' ||
+        coalesce(notes, '') || ' ' ||
+        coalesce(admin_notes, ''))
+) STORED;
+
+CREATE INDEX idx_submissions_search_vector ON submissions USING GIN (search_vector);
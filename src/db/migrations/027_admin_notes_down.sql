@@ -0,0 +1,13 @@
+-- Reverts 027_admin_notes
+DROP INDEX IF EXISTS idx_submissions_search_vector;
+ALTER TABLE submissions DROP COLUMN IF EXISTS search_vector;
+ALTER TABLE submissions ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    to_tsvector('dutch',
+        coalesce(submitter_name, '') || ' ' ||
+        coalesce(organization, '') || ' ' ||
+        coalesce(notes, ''))
+) STORED;
+
+CREATE INDEX idx_submissions_search_vector ON submissions USING GIN (search_vector);
+
+ALTER TABLE submissions DROP COLUMN IF EXISTS admin_notes;
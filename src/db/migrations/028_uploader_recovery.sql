@@ -0,0 +1,2 @@
+-- Uploaders who lost their reference code can have it emailed to them
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'uploader_recovery_requested';
@@ -0,0 +1,2 @@
+-- Reverts 028_uploader_recovery
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
@@ -0,0 +1,2 @@
+-- Admins can give a submission a new slug when the old one was shared too widely
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'slug_rotated';
@@ -0,0 +1,2 @@
+-- Reverts 029_slug_rotated
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
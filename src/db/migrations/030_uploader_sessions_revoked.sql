@@ -0,0 +1,2 @@
+-- Admins can end all uploader sessions of a submission
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'uploader_sessions_revoked';
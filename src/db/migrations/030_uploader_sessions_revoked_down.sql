@@ -0,0 +1,2 @@
+-- Reverts 030_uploader_sessions_revoked
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
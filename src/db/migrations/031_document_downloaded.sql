@@ -0,0 +1,2 @@
+-- Admin downloads of single documents are audited
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'document_downloaded';
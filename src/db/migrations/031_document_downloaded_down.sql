@@ -0,0 +1,2 @@
+-- Reverts 031_document_downloaded
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
@@ -0,0 +1,2 @@
+-- Admins opening the uploader view of a submission are audited
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'uploader_view_accessed';
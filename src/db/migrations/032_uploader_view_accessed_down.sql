@@ -0,0 +1,2 @@
+-- Reverts 032_uploader_view_accessed
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
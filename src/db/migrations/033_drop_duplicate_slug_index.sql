@@ -0,0 +1,4 @@
+-- The UNIQUE constraint on submissions.slug already has an index that slug
+-- lookups use, so this second one only slows down writes. The other hot
+-- lookups are covered since 001 and 004, see test_hot_queries_use_indexes.
+DROP INDEX IF EXISTS idx_submissions_slug;
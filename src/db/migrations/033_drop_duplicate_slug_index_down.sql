@@ -0,0 +1,2 @@
+-- Reverts 033_drop_duplicate_slug_index
+CREATE INDEX IF NOT EXISTS idx_submissions_slug ON submissions(slug);
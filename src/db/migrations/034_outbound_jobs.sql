@@ -0,0 +1,22 @@
+-- Emails and webhook deliveries are queued here instead of being sent from a
+-- spawned task, so they survive a restart and are retried with backoff.
+-- Finished jobs are removed by the periodic cleanup after a week.
+
+CREATE TYPE outbound_job_kind AS ENUM ('email', 'webhook');
+CREATE TYPE outbound_job_status AS ENUM ('pending', 'done', 'failed');
+
+CREATE TABLE outbound_jobs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    kind outbound_job_kind NOT NULL,
+    payload JSONB NOT NULL,
+    status outbound_job_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for the worker picking up due jobs
+CREATE INDEX idx_outbound_jobs_due ON outbound_jobs(next_attempt_at)
+WHERE status = 'pending';
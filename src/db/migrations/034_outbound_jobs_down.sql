@@ -0,0 +1,6 @@
+-- Reverts 034_outbound_jobs
+-- Jobs still pending are dropped unsent
+
+DROP TABLE IF EXISTS outbound_jobs;
+DROP TYPE IF EXISTS outbound_job_status;
+DROP TYPE IF EXISTS outbound_job_kind;
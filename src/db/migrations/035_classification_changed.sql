@@ -0,0 +1,2 @@
+-- Uploaders can swap a document between public and claude_allowed
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'classification_changed';
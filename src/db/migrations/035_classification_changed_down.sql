@@ -0,0 +1,2 @@
+-- Reverts 035_classification_changed
+-- PostgreSQL cannot drop enum values, so there is nothing to undo here
@@ -0,0 +1,15 @@
+-- Reviewers discuss a submission in a thread of comments. Comments are for
+-- admins only and never shown to the uploader.
+
+CREATE TABLE submission_comments (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    submission_id UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES admin_users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for listing the thread of a submission, newest first
+CREATE INDEX idx_submission_comments_submission ON submission_comments(submission_id, created_at DESC);
+
+ALTER TYPE audit_action ADD VALUE IF NOT EXISTS 'comment_added';
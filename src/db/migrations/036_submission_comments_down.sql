@@ -0,0 +1,4 @@
+-- Reverts 036_submission_comments
+-- PostgreSQL cannot drop enum values, so 'comment_added' stays
+
+DROP TABLE IF EXISTS submission_comments;
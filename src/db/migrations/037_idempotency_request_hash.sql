@@ -0,0 +1,3 @@
+-- Hash of the request body an Idempotency-Key was first used with, so a
+-- reused key with a different body is refused instead of replayed.
+ALTER TABLE submissions ADD COLUMN idempotency_request_hash VARCHAR(64);
@@ -0,0 +1,2 @@
+-- Reverts 037_idempotency_request_hash
+ALTER TABLE submissions DROP COLUMN IF EXISTS idempotency_request_hash;
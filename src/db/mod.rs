@@ -1,5 +1,7 @@
 //! Database module
 
 pub mod pool;
+pub mod upload_dirs;
 
 pub use pool::*;
+pub use upload_dirs::*;
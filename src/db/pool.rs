@@ -1,11 +1,21 @@
 //! Database connection pool
 
-use sqlx::postgres::{PgPool, PgPoolOptions};
+use sha2::{Digest, Sha256};
+use sqlx::postgres::{PgConnection, PgPool, PgPoolOptions};
 use std::time::Duration;
 
+use super::upload_dirs::DATA_MIGRATIONS;
+use crate::config::DbPoolConfig;
+
 /// Create a new database connection pool
-pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
-    tracing::info!("Creating database pool...");
+pub async fn create_pool(database_url: &str, config: &DbPoolConfig) -> Result<PgPool, sqlx::Error> {
+    tracing::info!(
+        "Creating database pool (max {} connections, min {}, acquire timeout {}s, idle timeout {}s)",
+        config.max_connections,
+        config.min_connections,
+        config.acquire_timeout.as_secs(),
+        config.idle_timeout.as_secs()
+    );
 
     // Retry connection with backoff
     let mut attempts = 0;
@@ -16,10 +26,10 @@ pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
         tracing::info!("Database connection attempt {}/{}", attempts, max_attempts);
 
         let result = PgPoolOptions::new()
-            .max_connections(10)
-            .min_connections(1)
-            .acquire_timeout(Duration::from_secs(10))
-            .idle_timeout(Duration::from_secs(600))
+            .max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .acquire_timeout(config.acquire_timeout)
+            .idle_timeout(config.idle_timeout)
             .connect(database_url)
             .await;
 
@@ -96,17 +106,146 @@ fn has_sql_content(s: &str) -> bool {
     })
 }
 
+/// A schema migration and the SQL that reverts it
+pub struct Migration {
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+/// Migration from `migrations/<name>.sql` with its `<name>_down.sql`
+macro_rules! migration {
+    ($name:literal) => {
+        Migration {
+            name: $name,
+            up: include_str!(concat!("migrations/", $name, ".sql")),
+            down: include_str!(concat!("migrations/", $name, "_down.sql")),
+        }
+    };
+}
+
+/// All migrations in order
+///
+/// There never was a released 002, and 005 and 006 are the file layout
+/// migrations in `upload_dirs`. Any other name in `_migrations` means the
+/// database ran a different history, see `check_migration_history`.
+pub const MIGRATIONS: &[Migration] = &[
+    migration!("001_initial"),
+    migration!("003_retention_date"),
+    migration!("004_uploader_sessions"),
+    migration!("007_admin_totp"),
+    migration!("008_admin_password_change"),
+    migration!("009_admin_lockout"),
+    migration!("010_slot_rescheduled"),
+    migration!("011_document_content_hash"),
+    migration!("012_document_replace"),
+    migration!("013_document_versions"),
+    migration!("014_admin_session_revoked"),
+    migration!("015_submission_withdrawn"),
+    migration!("016_retention_consent"),
+    migration!("017_legal_hold"),
+    migration!("018_full_text_search"),
+    migration!("019_upload_rejected_malware"),
+    migration!("020_admin_session_csrf"),
+    migration!("021_download_link_created"),
+    migration!("022_document_encryption"),
+    migration!("023_audit_request_id"),
+    migration!("024_changes_requested"),
+    migration!("025_submission_idempotency_key"),
+    migration!("026_slot_capacity"),
+    migration!("027_admin_notes"),
+    migration!("028_uploader_recovery"),
+    migration!("029_slug_rotated"),
+    migration!("030_uploader_sessions_revoked"),
+    migration!("031_document_downloaded"),
+    migration!("032_uploader_view_accessed"),
+    migration!("033_drop_duplicate_slug_index"),
+    migration!("034_outbound_jobs"),
+    migration!("035_classification_changed"),
+    migration!("036_submission_comments"),
+    migration!("037_idempotency_request_hash"),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error(
+        "Migration {name} was edited after it was applied (recorded checksum {recorded}, \
+         file now has {current}); restore the file and add a new migration instead"
+    )]
+    ChecksumMismatch {
+        name: String,
+        recorded: String,
+        current: String,
+    },
+    #[error("Unknown migration: {0}")]
+    Unknown(String),
+    #[error("Migration {0} is not applied")]
+    NotApplied(String),
+    #[error("Migration {name} is not the latest applied migration, roll back {latest} first")]
+    NotLatest { name: String, latest: String },
+    #[error(
+        "Database has applied migrations this build does not know: {}; \
+         it has diverged from this migration history",
+        .0.join(", ")
+    )]
+    UnknownApplied(Vec<String>),
+}
+
+/// Hex SHA-256 of a migration's SQL, recorded when it is applied
+fn migration_checksum(sql: &str) -> String {
+    hex::encode(Sha256::digest(sql.as_bytes()))
+}
+
+/// Key of the advisory lock held while migrations are checked and applied
+const MIGRATION_LOCK_KEY: i64 = 0x7272_6d69_6772;
+
 /// Run database migrations with tracking
-pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
+///
+/// With `strict`, a database that has applied migrations unknown to this
+/// build is refused instead of only logged. Replicas starting at the same
+/// time wait for each other on an advisory lock, so a migration is never
+/// applied twice. Everything runs on the connection holding the lock, so a
+/// pool of a single connection is enough.
+pub async fn run_migrations(pool: &PgPool, strict: bool) -> Result<(), MigrationError> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    let result = migrate(&mut conn, strict).await;
+
+    let unlocked = sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await;
+    if unlocked.is_err() {
+        // Close the connection rather than return it to the pool still
+        // holding the lock; closing it releases the lock
+        drop(conn.detach());
+    }
+    result?;
+    unlocked?;
+    Ok(())
+}
+
+async fn migrate(conn: &mut PgConnection, strict: bool) -> Result<(), MigrationError> {
     // Create migrations tracking table
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS _migrations (
             name TEXT PRIMARY KEY,
-            applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            checksum TEXT
         )",
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
+    // Tracking tables from before checksums were recorded
+    sqlx::query("ALTER TABLE _migrations ADD COLUMN IF NOT EXISTS checksum TEXT")
+        .execute(&mut *conn)
+        .await?;
 
     // Handle legacy databases: if schema exists but wasn't tracked, mark as applied
     // This prevents re-running 001_initial on servers where it already ran
@@ -114,7 +253,7 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
         "SELECT table_name::text FROM information_schema.tables
          WHERE table_schema = 'public' AND table_name = 'submissions'",
     )
-    .fetch_optional(pool)
+    .fetch_optional(&mut *conn)
     .await?;
 
     if submissions_exists.is_some() {
@@ -123,52 +262,113 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
             "INSERT INTO _migrations (name) VALUES ('001_initial')
              ON CONFLICT (name) DO NOTHING",
         )
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
         tracing::info!("Legacy schema detected, marked 001_initial as applied");
     }
 
-    // Define all migrations in order
-    let migrations = [
-        ("001_initial", include_str!("migrations/001_initial.sql")),
-        (
-            "003_retention_date",
-            include_str!("migrations/003_retention_date.sql"),
-        ),
-        (
-            "004_uploader_sessions",
-            include_str!("migrations/004_uploader_sessions.sql"),
-        ),
-    ];
-
-    for (name, sql) in migrations {
-        // Check if already applied
-        let already_applied: Option<(String,)> =
-            sqlx::query_as("SELECT name FROM _migrations WHERE name = $1")
+    let known: Vec<&str> = MIGRATIONS
+        .iter()
+        .map(|m| m.name)
+        .chain(DATA_MIGRATIONS.iter().copied())
+        .collect();
+    check_migration_history(conn, &known, strict).await?;
+
+    apply_migrations(conn, MIGRATIONS).await
+}
+
+/// Compare `_migrations` against the known migration names
+///
+/// Unknown entries, such as a `002_*` from an unreleased branch, mean this
+/// database was migrated along a different history than this build. They
+/// are logged, or returned as an error when `strict` is set. Returns the
+/// unknown names.
+async fn check_migration_history(
+    conn: &mut PgConnection,
+    known: &[&str],
+    strict: bool,
+) -> Result<Vec<String>, MigrationError> {
+    let unknown: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM _migrations WHERE NOT (name = ANY($1)) ORDER BY name")
+            .bind(known)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    if !unknown.is_empty() {
+        if strict {
+            return Err(MigrationError::UnknownApplied(unknown));
+        }
+        tracing::warn!(
+            "Database has applied migrations this build does not know: {}. \
+             Its schema may differ from what this build expects.",
+            unknown.join(", ")
+        );
+    }
+    Ok(unknown)
+}
+
+/// Apply the migrations that are not yet recorded, verifying the others
+///
+/// An applied migration whose SQL no longer matches its recorded checksum
+/// aborts the run before anything later is applied. Migrations recorded
+/// without a checksum get the current one.
+async fn apply_migrations(
+    conn: &mut PgConnection,
+    migrations: &[Migration],
+) -> Result<(), MigrationError> {
+    for migration in migrations {
+        let name = migration.name;
+        let checksum = migration_checksum(migration.up);
+
+        let recorded: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT checksum FROM _migrations WHERE name = $1")
                 .bind(name)
-                .fetch_optional(pool)
+                .fetch_optional(&mut *conn)
                 .await?;
 
-        if already_applied.is_some() {
-            tracing::debug!("Migration {} already applied, skipping", name);
-            continue;
+        match recorded {
+            Some((Some(recorded),)) if recorded != checksum => {
+                return Err(MigrationError::ChecksumMismatch {
+                    name: name.to_string(),
+                    recorded,
+                    current: checksum,
+                });
+            }
+            Some((Some(_),)) => {
+                tracing::debug!("Migration {} already applied, skipping", name);
+                continue;
+            }
+            Some((None,)) => {
+                sqlx::query("UPDATE _migrations SET checksum = $1 WHERE name = $2")
+                    .bind(&checksum)
+                    .bind(name)
+                    .execute(&mut *conn)
+                    .await?;
+                tracing::info!("Recorded checksum of previously applied migration {}", name);
+                continue;
+            }
+            None => {}
         }
 
         tracing::info!("Applying migration: {}", name);
 
         // Split and execute statements
-        let statements = split_sql_statements(sql);
+        let statements = split_sql_statements(migration.up);
         for statement in &statements {
-            sqlx::query(statement).execute(pool).await.map_err(|e| {
-                tracing::error!("Migration {} failed: {}", name, e);
-                e
-            })?;
+            sqlx::query(statement)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Migration {} failed: {}", name, e);
+                    e
+                })?;
         }
 
         // Record as applied
-        sqlx::query("INSERT INTO _migrations (name) VALUES ($1)")
+        sqlx::query("INSERT INTO _migrations (name, checksum) VALUES ($1, $2)")
             .bind(name)
-            .execute(pool)
+            .bind(&checksum)
+            .execute(&mut *conn)
             .await?;
 
         tracing::info!("Migration {} applied successfully", name);
@@ -176,3 +376,338 @@ pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
 
     Ok(())
 }
+
+/// Revert the most recently applied migration by running its down SQL
+///
+/// Only the latest applied migration can be rolled back, so migrations are
+/// undone in reverse order. Startup applies pending migrations again, so
+/// this is meant to be followed by deploying a build without the migration.
+pub async fn rollback_migration(pool: &PgPool, name: &str) -> Result<(), MigrationError> {
+    rollback(pool, MIGRATIONS, name).await
+}
+
+async fn rollback(
+    pool: &PgPool,
+    migrations: &[Migration],
+    name: &str,
+) -> Result<(), MigrationError> {
+    let migration = migrations
+        .iter()
+        .find(|m| m.name == name)
+        .ok_or_else(|| MigrationError::Unknown(name.to_string()))?;
+
+    let names: Vec<&str> = migrations.iter().map(|m| m.name).collect();
+    let applied: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM _migrations WHERE name = ANY($1)")
+            .bind(&names)
+            .fetch_all(pool)
+            .await?;
+    let latest = migrations
+        .iter()
+        .rev()
+        .find(|m| applied.iter().any(|a| a == m.name))
+        .ok_or_else(|| MigrationError::NotApplied(name.to_string()))?;
+    if !applied.iter().any(|a| a == name) {
+        return Err(MigrationError::NotApplied(name.to_string()));
+    }
+    if latest.name != name {
+        return Err(MigrationError::NotLatest {
+            name: name.to_string(),
+            latest: latest.name.to_string(),
+        });
+    }
+
+    tracing::info!("Rolling back migration: {}", name);
+
+    let mut tx = pool.begin().await?;
+    for statement in split_sql_statements(migration.down) {
+        sqlx::query(&statement)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("Rollback of {} failed: {}", name, e);
+                e
+            })?;
+    }
+    sqlx::query("DELETE FROM _migrations WHERE name = $1")
+        .bind(name)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!("Migration {} rolled back", name);
+    Ok(())
+}
+
+/// Pool for tests that need PostgreSQL
+///
+/// These tests are skipped unless `TEST_DATABASE_URL` points at a scratch
+/// database; migrations are applied to it first.
+#[cfg(test)]
+pub async fn test_pool() -> Option<PgPool> {
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    let pool = create_pool(&url, &DbPoolConfig::default()).await.unwrap();
+    run_migrations(&pool, false).await.unwrap();
+    Some(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Migration creating and dropping its own table, under a unique name
+    fn table_migration(table: &str, column_type: &str) -> Migration {
+        let leak = |s: String| -> &'static str { Box::leak(s.into_boxed_str()) };
+        Migration {
+            name: leak(format!("test_{}", table)),
+            up: leak(format!("CREATE TABLE {} (id {});", table, column_type)),
+            down: leak(format!("DROP TABLE {};", table)),
+        }
+    }
+
+    async fn table_exists(pool: &PgPool, table: &str) -> bool {
+        sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+            .bind(table)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_migrations_wait_for_the_lock() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+
+        // Another instance is migrating
+        let mut other = pool.acquire().await.unwrap();
+        sqlx::query("SELECT pg_advisory_lock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .execute(&mut *other)
+            .await
+            .unwrap();
+
+        let waiting = tokio::spawn({
+            let pool = pool.clone();
+            async move { run_migrations(&pool, false).await }
+        });
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(!waiting.is_finished());
+
+        sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .execute(&mut *other)
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(10), waiting)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+
+        // The lock is released afterwards
+        let free: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .fetch_one(&mut *other)
+            .await
+            .unwrap();
+        assert!(free);
+        sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .execute(&mut *other)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_migrations_run_with_a_single_connection() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        drop(pool);
+
+        // DB_MAX_CONNECTIONS=1 is a valid setting
+        let url = std::env::var("TEST_DATABASE_URL").unwrap();
+        let config = DbPoolConfig {
+            max_connections: 1,
+            acquire_timeout: Duration::from_secs(2),
+            ..DbPoolConfig::default()
+        };
+        let pool = create_pool(&url, &config).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(10), run_migrations(&pool, false))
+            .await
+            .expect("migrations waited for a second connection")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_edited_migration_is_detected() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let mut conn = pool.acquire().await.unwrap();
+
+        let table = format!("migration_test_{}", uuid::Uuid::new_v4().simple());
+        let original = table_migration(&table, "INTEGER");
+        apply_migrations(&mut conn, std::slice::from_ref(&original))
+            .await
+            .unwrap();
+        assert!(table_exists(&pool, &table).await);
+
+        // Unchanged SQL passes verification
+        apply_migrations(&mut conn, std::slice::from_ref(&original))
+            .await
+            .unwrap();
+
+        let edited = Migration {
+            name: original.name,
+            ..table_migration(&table, "BIGINT")
+        };
+        let err = apply_migrations(&mut conn, &[edited]).await.unwrap_err();
+        match err {
+            MigrationError::ChecksumMismatch {
+                name,
+                recorded,
+                current,
+            } => {
+                assert_eq!(name, original.name);
+                assert_eq!(recorded, migration_checksum(original.up));
+                assert_ne!(recorded, current);
+            }
+            other => panic!("expected a checksum mismatch, got {:?}", other),
+        }
+
+        rollback(&pool, std::slice::from_ref(&original), original.name)
+            .await
+            .unwrap();
+        assert!(!table_exists(&pool, &table).await);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_only_latest_applied_migration() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let mut conn = pool.acquire().await.unwrap();
+
+        let tag = uuid::Uuid::new_v4().simple().to_string();
+        let migrations = [
+            table_migration(&format!("migration_first_{}", tag), "INTEGER"),
+            table_migration(&format!("migration_second_{}", tag), "INTEGER"),
+        ];
+        apply_migrations(&mut conn, &migrations).await.unwrap();
+
+        assert!(matches!(
+            rollback(&pool, &migrations, migrations[0].name).await,
+            Err(MigrationError::NotLatest { .. })
+        ));
+        assert!(matches!(
+            rollback(&pool, &migrations, "test_missing").await,
+            Err(MigrationError::Unknown(_))
+        ));
+
+        rollback(&pool, &migrations, migrations[1].name)
+            .await
+            .unwrap();
+        assert!(matches!(
+            rollback(&pool, &migrations, migrations[1].name).await,
+            Err(MigrationError::NotApplied(_))
+        ));
+        rollback(&pool, &migrations, migrations[0].name)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_hot_queries_use_indexes() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+
+        // Queries run on (nearly) every request, and the index each relies on
+        let queries = [
+            (
+                "SELECT * FROM submissions WHERE slug = 'rr-20260101-abcde'",
+                "submissions_slug_key",
+            ),
+            (
+                "SELECT * FROM documents
+                 WHERE submission_id = ANY(ARRAY['00000000-0000-0000-0000-000000000000'::uuid])",
+                "idx_documents_submission",
+            ),
+            (
+                "SELECT * FROM admin_sessions WHERE token_hash = 'x' AND expires_at > NOW()",
+                "idx_admin_sessions_token",
+            ),
+            (
+                "SELECT * FROM uploader_sessions WHERE token_hash = 'x' AND expires_at > NOW()",
+                "idx_uploader_sessions_token",
+            ),
+            (
+                "SELECT * FROM rate_limit_attempts
+                 WHERE ip_address = '192.0.2.1' AND endpoint = 'login'
+                 AND attempted_at > NOW() - INTERVAL '1 hour'",
+                "idx_rate_limit_ip_endpoint",
+            ),
+        ];
+
+        // The test tables are tiny, so rule out the sequential scan the
+        // planner would otherwise prefer and see which index it reaches for
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("SET LOCAL enable_seqscan = off")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        for (query, index) in queries {
+            let plan: Vec<String> = sqlx::query_scalar(&format!("EXPLAIN {}", query))
+                .fetch_all(&mut *tx)
+                .await
+                .unwrap();
+            let plan = plan.join("\n");
+            assert!(
+                plan.contains(&format!(" {}", index)),
+                "{} does not use {}:\n{}",
+                query,
+                index,
+                plan
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stray_applied_migration_is_reported() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let mut conn = pool.acquire().await.unwrap();
+
+        // As if the database once ran a 002 that was later dropped
+        let stray = format!("002_dropped_{}", uuid::Uuid::new_v4().simple());
+        sqlx::query("INSERT INTO _migrations (name) VALUES ($1)")
+            .bind(&stray)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let known: Vec<&str> = MIGRATIONS
+            .iter()
+            .map(|m| m.name)
+            .chain(DATA_MIGRATIONS.iter().copied())
+            .collect();
+        let unknown = check_migration_history(&mut conn, &known, false)
+            .await
+            .unwrap();
+        assert!(unknown.contains(&stray));
+
+        match check_migration_history(&mut conn, &known, true).await {
+            Err(MigrationError::UnknownApplied(names)) => assert!(names.contains(&stray)),
+            other => panic!("expected unknown migrations, got {:?}", other),
+        }
+
+        sqlx::query("DELETE FROM _migrations WHERE name = $1")
+            .bind(&stray)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
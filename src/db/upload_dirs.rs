@@ -0,0 +1,141 @@
+//! One-off migrations of the upload directory layout
+//!
+//! Files used to be stored under `upload_dir/<slug>/`. They now live under
+//! `upload_dir/<submission id>/`, so a slug change can never orphan them.
+//! The first step moves existing directories and rewrites the stored
+//! `file_path` of their documents. The second turns those absolute paths into
+//! storage keys relative to `upload_dir`, as used by the `storage` backends.
+//! Both are tracked in `_migrations` like the SQL migrations, so they only
+//! run once per database.
+
+use sqlx::PgPool;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Name under which the relocation is recorded in `_migrations`
+const RELOCATION_NAME: &str = "005_upload_dirs_by_id";
+
+/// Name under which the storage key conversion is recorded in `_migrations`
+const STORAGE_KEYS_NAME: &str = "006_file_path_storage_keys";
+
+/// `_migrations` entries written by this module rather than by SQL migrations
+pub const DATA_MIGRATIONS: &[&str] = &[RELOCATION_NAME, STORAGE_KEYS_NAME];
+
+async fn is_applied(pool: &PgPool, name: &str) -> Result<bool, sqlx::Error> {
+    let applied: Option<(String,)> = sqlx::query_as("SELECT name FROM _migrations WHERE name = $1")
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+    Ok(applied.is_some())
+}
+
+/// Move `upload_dir/<slug>/` directories to `upload_dir/<id>/`
+pub async fn relocate_slug_upload_dirs(
+    pool: &PgPool,
+    upload_dir: &Path,
+) -> Result<(), sqlx::Error> {
+    if is_applied(pool, RELOCATION_NAME).await? {
+        tracing::debug!("Upload directory relocation already applied, skipping");
+        return Ok(());
+    }
+
+    tracing::info!("Relocating slug-based upload directories to submission ids...");
+
+    let submissions: Vec<(Uuid, String)> = sqlx::query_as("SELECT id, slug FROM submissions")
+        .fetch_all(pool)
+        .await?;
+
+    let mut relocated = 0;
+    for (id, slug) in submissions {
+        let old_dir = upload_dir.join(&slug);
+        let new_dir = upload_dir.join(id.to_string());
+
+        if !old_dir.is_dir() || new_dir.exists() {
+            continue;
+        }
+
+        if let Err(e) = tokio::fs::rename(&old_dir, &new_dir).await {
+            tracing::error!(
+                "Failed to move upload directory {:?} to {:?}: {}",
+                old_dir,
+                new_dir,
+                e
+            );
+            continue;
+        }
+
+        // file_path stays authoritative: rewrite the directory prefix in place
+        let old_prefix = format!("{}/", old_dir.to_string_lossy());
+        let new_prefix = format!("{}/", new_dir.to_string_lossy());
+        let result = sqlx::query(
+            r#"
+            UPDATE documents
+            SET file_path = $1 || substring(file_path FROM length($2) + 1)
+            WHERE submission_id = $3 AND starts_with(file_path, $2)
+            "#,
+        )
+        .bind(&new_prefix)
+        .bind(&old_prefix)
+        .bind(id)
+        .execute(pool)
+        .await;
+
+        if let Err(e) = result {
+            // Put the directory back so the stored paths remain valid
+            tracing::error!("Failed to update file paths for submission {}: {}", id, e);
+            if let Err(e) = tokio::fs::rename(&new_dir, &old_dir).await {
+                tracing::error!("Failed to restore upload directory {:?}: {}", old_dir, e);
+            }
+            return Err(e);
+        }
+
+        relocated += 1;
+    }
+
+    sqlx::query("INSERT INTO _migrations (name) VALUES ($1)")
+        .bind(RELOCATION_NAME)
+        .execute(pool)
+        .await?;
+
+    tracing::info!("Relocated {} upload directories", relocated);
+
+    Ok(())
+}
+
+/// Rewrite absolute `upload_dir/<key>` document paths to plain storage keys
+pub async fn convert_file_paths_to_storage_keys(
+    pool: &PgPool,
+    upload_dir: &Path,
+) -> Result<(), sqlx::Error> {
+    if is_applied(pool, STORAGE_KEYS_NAME).await? {
+        tracing::debug!("Storage key conversion already applied, skipping");
+        return Ok(());
+    }
+
+    let prefix = format!("{}/", upload_dir.to_string_lossy().trim_end_matches('/'));
+
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query(
+        r#"
+        UPDATE documents
+        SET file_path = substring(file_path FROM length($1) + 1)
+        WHERE starts_with(file_path, $1)
+        "#,
+    )
+    .bind(&prefix)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO _migrations (name) VALUES ($1)")
+        .bind(STORAGE_KEYS_NAME)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        "Converted {} document paths to storage keys",
+        result.rows_affected()
+    );
+
+    Ok(())
+}
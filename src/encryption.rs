@@ -0,0 +1,438 @@
+//! Encryption at rest for document files
+//!
+//! With `FILE_ENCRYPTION_KEY` set, uploads are encrypted with AES-256-GCM
+//! before they are handed to storage. Files are encrypted as a STREAM: a
+//! random nonce prefix, then chunks of [`CHUNK_LEN`] bytes that are each
+//! authenticated with their own nonce, the last one marked as last. Readers
+//! can decrypt chunk by chunk, and a truncated or reordered file fails to
+//! decrypt. The `encrypted` flag on documents and versions tells readers
+//! which files to decrypt, so files stored before the key was configured
+//! remain readable.
+
+use aes_gcm::{
+    aead::{
+        generic_array::GenericArray,
+        stream::{DecryptorBE32, EncryptorBE32},
+        KeyInit,
+    },
+    Aes256Gcm,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use rand::RngCore;
+use std::io;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+use crate::storage::FileReader;
+
+/// Length of the nonce prefix on every encrypted file
+const NONCE_LEN: usize = 7;
+
+/// Plaintext bytes per encrypted chunk
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// Length of the authentication tag after every chunk
+const TAG_LEN: usize = 16;
+
+/// Ciphertext bytes of every chunk but the last
+const SEALED_CHUNK_LEN: usize = CHUNK_LEN + TAG_LEN;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FileCryptoError {
+    #[error("Failed to encrypt file")]
+    Encrypt,
+    #[error("Encrypted file is damaged or was tampered with")]
+    Decrypt,
+    #[error("File is encrypted but FILE_ENCRYPTION_KEY is not set")]
+    MissingKey,
+}
+
+impl From<FileCryptoError> for io::Error {
+    fn from(e: FileCryptoError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// AES-256 data key for document files
+#[derive(Clone)]
+pub struct FileKey([u8; 32]);
+
+// Keep the key out of logs
+impl std::fmt::Debug for FileKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FileKey(<redacted>)")
+    }
+}
+
+impl FromStr for FileKey {
+    type Err = String;
+
+    /// Parse a key given as 32 base64-encoded bytes
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = STANDARD.decode(s.trim()).map_err(|e| e.to_string())?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| "expected 32 bytes of base64".to_string())?;
+        Ok(Self(key))
+    }
+}
+
+impl FileKey {
+    fn encryptor(&self) -> ([u8; NONCE_LEN], EncryptorBE32<Aes256Gcm>) {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let cipher = Aes256Gcm::new((&self.0).into());
+        let encryptor = EncryptorBE32::from_aead(cipher, GenericArray::from_slice(&nonce));
+        (nonce, encryptor)
+    }
+
+    fn decryptor(&self, nonce: &[u8]) -> DecryptorBE32<Aes256Gcm> {
+        let cipher = Aes256Gcm::new((&self.0).into());
+        DecryptorBE32::from_aead(cipher, GenericArray::from_slice(nonce))
+    }
+
+    /// Encrypt file contents held in memory
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, FileCryptoError> {
+        let (nonce, mut encryptor) = self.encryptor();
+        let mut out = nonce.to_vec();
+        // The last chunk is a full one when the length is a multiple of CHUNK_LEN
+        let last_start = plaintext.len().saturating_sub(1) / CHUNK_LEN * CHUNK_LEN;
+        let (body, last) = plaintext.split_at(last_start);
+        for chunk in body.chunks(CHUNK_LEN) {
+            let sealed = encryptor
+                .encrypt_next(chunk)
+                .map_err(|_| FileCryptoError::Encrypt)?;
+            out.extend_from_slice(&sealed);
+        }
+        let sealed = encryptor
+            .encrypt_last(last)
+            .map_err(|_| FileCryptoError::Encrypt)?;
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    /// Decrypt contents produced by [`FileKey::encrypt`] or [`FileKey::encrypt_to`]
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, FileCryptoError> {
+        if data.len() < NONCE_LEN {
+            return Err(FileCryptoError::Decrypt);
+        }
+        let (nonce, mut rest) = data.split_at(NONCE_LEN);
+        let mut decryptor = self.decryptor(nonce);
+        let mut out = Vec::with_capacity(rest.len());
+        while rest.len() > SEALED_CHUNK_LEN {
+            let (chunk, tail) = rest.split_at(SEALED_CHUNK_LEN);
+            let plain = decryptor
+                .decrypt_next(chunk)
+                .map_err(|_| FileCryptoError::Decrypt)?;
+            out.extend_from_slice(&plain);
+            rest = tail;
+        }
+        let plain = decryptor
+            .decrypt_last(rest)
+            .map_err(|_| FileCryptoError::Decrypt)?;
+        out.extend_from_slice(&plain);
+        Ok(out)
+    }
+
+    /// Encrypt everything `reader` yields into `writer`, one chunk at a time
+    pub async fn encrypt_to(
+        &self,
+        mut reader: impl AsyncRead + Unpin,
+        mut writer: impl AsyncWrite + Unpin,
+    ) -> io::Result<()> {
+        let (nonce, mut encryptor) = self.encryptor();
+        writer.write_all(&nonce).await?;
+
+        // One byte past a chunk tells whether that chunk is the last
+        let mut buf = vec![0u8; CHUNK_LEN + 1];
+        let mut filled = 0;
+        loop {
+            let n = reader.read(&mut buf[filled..]).await?;
+            filled += n;
+            if filled > CHUNK_LEN {
+                let sealed = encryptor
+                    .encrypt_next(&buf[..CHUNK_LEN])
+                    .map_err(|_| FileCryptoError::Encrypt)?;
+                writer.write_all(&sealed).await?;
+                buf.copy_within(CHUNK_LEN..filled, 0);
+                filled -= CHUNK_LEN;
+            } else if n == 0 {
+                break;
+            }
+        }
+        let sealed = encryptor
+            .encrypt_last(&buf[..filled])
+            .map_err(|_| FileCryptoError::Encrypt)?;
+        writer.write_all(&sealed).await?;
+        writer.flush().await
+    }
+
+    /// Reader over the plaintext of an encrypted `reader`
+    ///
+    /// Nothing of a chunk is returned before the chunk is authenticated; a
+    /// damaged chunk fails the read with `InvalidData`.
+    pub fn decrypt_reader<R: AsyncRead + Unpin>(&self, reader: R) -> DecryptReader<R> {
+        DecryptReader {
+            key: self.clone(),
+            inner: reader,
+            decryptor: None,
+            input: vec![0u8; SEALED_CHUNK_LEN + 1],
+            filled: 0,
+            output: Vec::new(),
+            read: 0,
+            eof: false,
+            done: false,
+        }
+    }
+}
+
+/// Plaintext of an encrypted file, decrypted chunk by chunk as it is read
+pub struct DecryptReader<R> {
+    key: FileKey,
+    inner: R,
+    decryptor: Option<DecryptorBE32<Aes256Gcm>>,
+    /// Ciphertext read ahead, up to one byte past a chunk
+    input: Vec<u8>,
+    filled: usize,
+    /// Decrypted chunk and how much of it was read
+    output: Vec<u8>,
+    read: usize,
+    eof: bool,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for DecryptReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.read < this.output.len() {
+                let n = buf.remaining().min(this.output.len() - this.read);
+                buf.put_slice(&this.output[this.read..this.read + n]);
+                this.read += n;
+                return Poll::Ready(Ok(()));
+            }
+            if this.done {
+                return Poll::Ready(Ok(()));
+            }
+
+            let wanted = match this.decryptor {
+                None => NONCE_LEN,
+                Some(_) => SEALED_CHUNK_LEN + 1,
+            };
+            if this.filled < wanted && !this.eof {
+                let mut read_buf = ReadBuf::new(&mut this.input[this.filled..wanted]);
+                ready!(Pin::new(&mut this.inner).poll_read(cx, &mut read_buf))?;
+                let n = read_buf.filled().len();
+                this.filled += n;
+                this.eof = n == 0;
+                continue;
+            }
+
+            let Some(decryptor) = this.decryptor.as_mut() else {
+                if this.filled < NONCE_LEN {
+                    return Poll::Ready(Err(FileCryptoError::Decrypt.into()));
+                }
+                this.decryptor = Some(this.key.decryptor(&this.input[..NONCE_LEN]));
+                this.input.copy_within(NONCE_LEN..this.filled, 0);
+                this.filled -= NONCE_LEN;
+                continue;
+            };
+            let plain = if this.filled > SEALED_CHUNK_LEN {
+                let plain = decryptor.decrypt_next(&this.input[..SEALED_CHUNK_LEN]);
+                this.input.copy_within(SEALED_CHUNK_LEN..this.filled, 0);
+                this.filled -= SEALED_CHUNK_LEN;
+                plain
+            } else {
+                this.done = true;
+                let decryptor = this.decryptor.take().expect("checked above");
+                decryptor.decrypt_last(&this.input[..this.filled])
+            };
+            this.output = plain.map_err(|_| io::Error::from(FileCryptoError::Decrypt))?;
+            this.read = 0;
+        }
+    }
+}
+
+/// Plaintext of a file read from storage, decrypting it when `encrypted`
+pub fn decrypt_stored(
+    key: Option<&FileKey>,
+    data: Bytes,
+    encrypted: bool,
+) -> Result<Bytes, FileCryptoError> {
+    if !encrypted {
+        return Ok(data);
+    }
+    let key = key.ok_or(FileCryptoError::MissingKey)?;
+    key.decrypt(&data).map(Bytes::from)
+}
+
+/// Reader over the plaintext of a file opened from storage, decrypting it
+/// when `encrypted`
+pub fn decrypt_stored_reader(
+    key: Option<&FileKey>,
+    reader: FileReader,
+    encrypted: bool,
+) -> Result<FileReader, FileCryptoError> {
+    if !encrypted {
+        return Ok(reader);
+    }
+    let key = key.ok_or(FileCryptoError::MissingKey)?;
+    Ok(Box::new(key.decrypt_reader(reader)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> FileKey {
+        STANDARD.encode([7u8; 32]).parse().unwrap()
+    }
+
+    /// Plaintexts around the chunk boundaries
+    fn plaintexts() -> Vec<Vec<u8>> {
+        [
+            0,
+            1,
+            CHUNK_LEN - 1,
+            CHUNK_LEN,
+            CHUNK_LEN + 1,
+            2 * CHUNK_LEN + 5,
+        ]
+        .into_iter()
+        .map(|len| (0..len).map(|i| (i % 251) as u8).collect())
+        .collect()
+    }
+
+    async fn encrypt_streamed(key: &FileKey, plaintext: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        key.encrypt_to(plaintext, &mut out).await.unwrap();
+        out
+    }
+
+    async fn decrypt_streamed(key: &FileKey, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        key.decrypt_reader(data).read_to_end(&mut out).await?;
+        Ok(out)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key = key();
+        let plaintext = b"%PDF-1.7 werkinstructie".to_vec();
+        let encrypted = key.encrypt(&plaintext).unwrap();
+
+        assert_ne!(&encrypted[NONCE_LEN..], plaintext.as_slice());
+        assert_eq!(key.decrypt(&encrypted).unwrap(), plaintext);
+        // A fresh nonce per file
+        assert_ne!(key.encrypt(&plaintext).unwrap(), encrypted);
+
+        assert_eq!(key.decrypt(&key.encrypt(b"").unwrap()).unwrap(), b"");
+    }
+
+    #[tokio::test]
+    async fn test_streamed_and_buffered_formats_agree() {
+        let key = key();
+        for plaintext in plaintexts() {
+            let streamed = encrypt_streamed(&key, &plaintext).await;
+            let buffered = key.encrypt(&plaintext).unwrap();
+            assert_eq!(streamed.len(), buffered.len(), "{} bytes", plaintext.len());
+
+            assert_eq!(key.decrypt(&streamed).unwrap(), plaintext);
+            assert_eq!(decrypt_streamed(&key, &buffered).await.unwrap(), plaintext);
+            assert_eq!(decrypt_streamed(&key, &streamed).await.unwrap(), plaintext);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_truncation_at_a_chunk_boundary_is_detected() {
+        let key = key();
+        let plaintext = vec![1u8; 2 * CHUNK_LEN + 5];
+        let encrypted = key.encrypt(&plaintext).unwrap();
+
+        // Dropping the last chunk leaves a file that ends on a full chunk
+        let truncated = &encrypted[..NONCE_LEN + 2 * SEALED_CHUNK_LEN];
+        assert!(matches!(
+            key.decrypt(truncated),
+            Err(FileCryptoError::Decrypt)
+        ));
+        let err = decrypt_streamed(&key, truncated).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // Swapping two chunks breaks their nonces
+        let mut swapped = encrypted.clone();
+        let (first, second) = swapped[NONCE_LEN..].split_at_mut(SEALED_CHUNK_LEN);
+        first.swap_with_slice(&mut second[..SEALED_CHUNK_LEN]);
+        assert!(key.decrypt(&swapped).is_err());
+        assert!(decrypt_streamed(&key, &swapped).await.is_err());
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let key = key();
+        let encrypted = key.encrypt(b"%PDF-1.7 werkinstructie").unwrap();
+
+        let mut flipped = encrypted.clone();
+        flipped[NONCE_LEN + 3] ^= 1;
+        assert!(matches!(
+            key.decrypt(&flipped),
+            Err(FileCryptoError::Decrypt)
+        ));
+
+        let mut other_nonce = encrypted.clone();
+        other_nonce[0] ^= 1;
+        assert!(matches!(
+            key.decrypt(&other_nonce),
+            Err(FileCryptoError::Decrypt)
+        ));
+
+        assert!(matches!(
+            key.decrypt(&encrypted[..encrypted.len() - 1]),
+            Err(FileCryptoError::Decrypt)
+        ));
+        assert!(matches!(
+            key.decrypt(b"kort"),
+            Err(FileCryptoError::Decrypt)
+        ));
+
+        let other_key: FileKey = STANDARD.encode([8u8; 32]).parse().unwrap();
+        assert!(matches!(
+            other_key.decrypt(&encrypted),
+            Err(FileCryptoError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn test_decrypt_stored_passes_plaintext_through() {
+        let key = key();
+        let plain = Bytes::from_static(b"oud bestand");
+        assert_eq!(decrypt_stored(None, plain.clone(), false).unwrap(), plain);
+        assert_eq!(
+            decrypt_stored(Some(&key), plain.clone(), false).unwrap(),
+            plain
+        );
+
+        let encrypted = Bytes::from(key.encrypt(&plain).unwrap());
+        assert_eq!(
+            decrypt_stored(Some(&key), encrypted.clone(), true).unwrap(),
+            plain
+        );
+        assert!(matches!(
+            decrypt_stored(None, encrypted, true),
+            Err(FileCryptoError::MissingKey)
+        ));
+    }
+
+    #[test]
+    fn test_key_must_be_32_bytes() {
+        assert!(STANDARD.encode([1u8; 16]).parse::<FileKey>().is_err());
+        assert!("geen base64!".parse::<FileKey>().is_err());
+    }
+}
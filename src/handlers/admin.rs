@@ -1,6 +1,16 @@
 //! Admin portal handlers
 
+use crate::encryption::{decrypt_stored, decrypt_stored_reader, FileCryptoError, FileKey};
+use crate::handlers::audit::{self, AuditEntry};
+use crate::handlers::error::AppError;
+use crate::handlers::middleware::current_request_id;
+use crate::handlers::uploader_auth::uploader_view;
+use crate::i18n::ErrorKey;
 use crate::models::*;
+use crate::notify::{notify_changes_requested, notify_status_change};
+use crate::storage::{delete_submission_files, FileStore, StorageError};
+use crate::webhooks::{self, WebhookEvent};
+use crate::workflow::is_valid_transition;
 use axum::{
     body::Body,
     extract::{Path, Query, State},
@@ -8,35 +18,191 @@ use axum::{
     response::{IntoResponse, Response},
     Extension, Json,
 };
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, SubsecRound, Utc};
 use serde::{Deserialize, Serialize};
-use std::io::{Cursor, Write};
+use sqlx::{FromRow, Postgres, QueryBuilder};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Write};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::io::{ReaderStream, SyncIoBridge};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
-use zip::write::FileOptions;
-use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
 
 use super::AppState;
 
+/// Bytes buffered between an export writer and the response body
+const EXPORT_STREAM_BUFFER: usize = 64 * 1024;
+
+/// Submissions loaded per query while streaming the bulk export
+const BULK_EXPORT_BATCH: i64 = 200;
+
+/// Longest message a reviewer can send with a change request, sent as `max`
+/// when exceeded
+const MAX_CHANGE_REQUEST_LENGTH: usize = 5000;
+
+/// Longest comment a reviewer can add to a submission's thread, sent as `max`
+/// when exceeded
+const MAX_COMMENT_LENGTH: usize = 5000;
+
+/// Most submissions one bulk status update may change, sent as `max` when
+/// exceeded
+const MAX_BULK_STATUS_IDS: usize = 200;
+
+/// Longest lifetime an admin can give a shared download link, in hours, sent
+/// as `max` when exceeded
+const MAX_SHARE_LINK_HOURS: i64 = 7 * 24;
+
+/// Default and largest window of the expiring-submissions list, in days
+const DEFAULT_EXPIRING_WITHIN_DAYS: i64 = 30;
+const MAX_EXPIRING_WITHIN_DAYS: i64 = 365;
+
 // =============================================================================
 // Query Parameters
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ListSubmissionsQuery {
     pub page: Option<i64>,
     pub per_page: Option<i64>,
     pub status: Option<SubmissionStatus>,
+    /// Matches name, organization and slug; terms of four or more characters
+    /// also search notes and document text, ordered by relevance
     pub search: Option<String>,
+    /// Only submissions created at or after this time (RFC 3339)
+    pub created_from: Option<DateTime<Utc>>,
+    /// Only submissions created before this time (RFC 3339)
+    pub created_to: Option<DateTime<Utc>>,
+    /// Only submissions with at least one document of this classification
+    pub classification: Option<DocumentClassification>,
+    /// `next_cursor` from a previous response; continues after that row
+    /// and takes precedence over `page`
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExpiringSubmissionsQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// Days ahead to look, up to 365 (default 30)
+    pub within_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListAuditLogQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub entity_id: Option<Uuid>,
+    pub action: Option<String>,
+    /// Only entries at or after this time (RFC 3339)
+    pub from: Option<DateTime<Utc>>,
+    /// Only entries before this time (RFC 3339)
+    pub to: Option<DateTime<Utc>>,
+    /// Only entries written while handling this request
+    pub request_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BulkExportQuery {
+    pub status: Option<SubmissionStatus>,
+    /// Only submissions updated at or after this time (RFC 3339), for
+    /// incremental exports
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DashboardQuery {
+    /// Number of days covered by the trends, up to 365 (default 30)
+    pub days: Option<i64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateStatusRequest {
     pub status: SubmissionStatus,
+    /// Reviewer notes, stored as `admin_notes` and not shown to the applicant
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RequestChangesRequest {
+    /// What the uploader should add or correct; stored as the submission notes
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkStatusRequest {
+    pub ids: Vec<Uuid>,
+    pub status: SubmissionStatus,
+    /// Reviewer notes, stored as `admin_notes` on every submission
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkStatusResponse {
+    /// Submissions now in the requested status, in request order
+    pub updated: Vec<Uuid>,
+}
+
+/// Submission nearing its retention date
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExpiringSubmissionResponse {
+    #[serde(flatten)]
+    pub submission: AdminSubmissionResponse,
+    /// Meeting the submitter booked, if any
+    pub meeting: Option<BookedMeeting>,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct BookedMeeting {
+    #[serde(skip)]
+    pub submission_id: Uuid,
+    pub slot_id: Uuid,
+    pub slot_start: DateTime<Utc>,
+    pub slot_end: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RotateSlugResponse {
+    /// The submission's new slug; the old one no longer works
+    pub slug: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LegalHoldRequest {
+    pub legal_hold: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCommentRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ShareLinkRequest {
+    /// Link lifetime in hours, up to a week
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareLinkResponse {
+    /// Path of the download, relative to the server origin
+    #[schema(example = "/api/download/AbC123.XyZ789")]
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ForwardSubmissionRequest {
     pub forward_to: String,
+    /// Reviewer notes, stored as `admin_notes`
     pub notes: Option<String>,
 }
 
@@ -45,6 +211,18 @@ pub struct ForwardSubmissionRequest {
 // =============================================================================
 
 /// List all submissions (admin)
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions",
+    tag = "admin",
+    params(ListSubmissionsQuery),
+    responses(
+        (status = 200, description = "Page of submissions", body = ApiResponse<PaginatedResponse<AdminSubmissionResponse>>),
+        (status = 400, description = "Invalid cursor, or a cursor with a full-text search", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn list_submissions(
     State(state): State<AppState>,
     Extension(admin): Extension<AdminUser>,
@@ -52,150 +230,423 @@ pub async fn list_submissions(
 ) -> impl IntoResponse {
     let page = query.page.unwrap_or(1).max(1);
     let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
-    let offset = (page - 1) * per_page;
-
-    // Build query based on filters
-    let (submissions, total): (Vec<Submission>, i64) = if let Some(status) = query.status {
-        let subs = sqlx::query_as::<_, Submission>(
-            r#"
-            SELECT * FROM submissions
-            WHERE status = $1
-            ORDER BY created_at DESC
-            LIMIT $2 OFFSET $3
-            "#,
-        )
-        .bind(status)
-        .bind(per_page)
-        .bind(offset)
-        .fetch_all(&state.pool)
-        .await
-        .unwrap_or_default();
 
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM submissions WHERE status = $1")
-            .bind(status)
-            .fetch_one(&state.pool)
-            .await
-            .unwrap_or(0);
+    let cursor = match query.after.as_deref().map(SubmissionCursor::decode) {
+        None => None,
+        Some(Some(cursor)) => Some(cursor),
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(ErrorKey::InvalidCursor)),
+            );
+        }
+    };
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        (page - 1) * per_page
+    };
 
-        (subs, count)
-    } else if let Some(ref search) = query.search {
-        let search_pattern = format!("%{}%", search);
-        let subs = sqlx::query_as::<_, Submission>(
-            r#"
-            SELECT * FROM submissions
-            WHERE submitter_name ILIKE $1
-               OR organization ILIKE $1
-               OR slug ILIKE $1
-            ORDER BY created_at DESC
-            LIMIT $2 OFFSET $3
-            "#,
-        )
-        .bind(&search_pattern)
-        .bind(per_page)
-        .bind(offset)
-        .fetch_all(&state.pool)
-        .await
-        .unwrap_or_default();
+    let search = query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let full_text = search.filter(|s| uses_full_text(s));
+    if full_text.is_some() && cursor.is_some() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(ErrorKey::CursorWithFullTextSearch)),
+        );
+    }
 
-        let count: i64 = sqlx::query_scalar(
-            r#"
-            SELECT COUNT(*) FROM submissions
-            WHERE submitter_name ILIKE $1
-               OR organization ILIKE $1
-               OR slug ILIKE $1
-            "#,
-        )
-        .bind(&search_pattern)
-        .fetch_one(&state.pool)
-        .await
-        .unwrap_or(0);
+    let search_pattern = search.map(|search| format!("%{}%", search));
+    let filter = SubmissionFilter {
+        status: query.status,
+        search_pattern: search_pattern.as_deref(),
+        full_text,
+        created_from: query.created_from,
+        created_to: query.created_to,
+        classification: query.classification,
+    };
 
-        (subs, count)
-    } else {
-        let subs = sqlx::query_as::<_, Submission>(
-            "SELECT * FROM submissions ORDER BY created_at DESC LIMIT $1 OFFSET $2",
-        )
-        .bind(per_page)
-        .bind(offset)
-        .fetch_all(&state.pool)
-        .await
-        .unwrap_or_default();
+    // One extra row tells whether there is a next page
+    let submissions =
+        fetch_submission_page(&state.pool, &filter, cursor.as_ref(), per_page + 1, offset).await;
+    let total = count_submissions(&state.pool, &filter).await;
 
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM submissions")
-            .fetch_one(&state.pool)
-            .await
-            .unwrap_or(0);
+    let (mut submissions, total) = match (submissions, total) {
+        (Ok(submissions), Ok(total)) => (submissions, total),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("Failed to list submissions: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::LoadSubmissionsFailed)),
+            );
+        }
+    };
 
-        (subs, count)
+    // Relevance-ordered results have no stable position to resume from
+    let has_next = submissions.len() as i64 > per_page;
+    submissions.truncate(per_page as usize);
+    let next_cursor = if has_next && full_text.is_none() {
+        submissions
+            .last()
+            .map(|sub| SubmissionCursor::from(sub).encode())
+    } else {
+        None
     };
 
-    // Batch fetch documents for all submissions (avoid N+1 query)
     let submission_ids: Vec<Uuid> = submissions.iter().map(|s| s.id).collect();
+    let mut docs_by_submission = documents_by_submission(&state.pool, &submission_ids).await;
+    let comment_counts: HashMap<Uuid, i64> = sqlx::query_as::<_, (Uuid, i64)>(
+        r#"
+        SELECT submission_id, COUNT(*) FROM submission_comments
+        WHERE submission_id = ANY($1)
+        GROUP BY submission_id
+        "#,
+    )
+    .bind(&submission_ids)
+    .fetch_all(&state.pool)
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .collect();
+
+    let mut responses = Vec::new();
+    for sub in submissions {
+        let documents = docs_by_submission.remove(&sub.id).unwrap_or_default();
+        let comment_count = comment_counts.get(&sub.id).copied().unwrap_or(0);
+
+        let mut response = AdminSubmissionResponse::new(
+            sub,
+            documents.into_iter().map(DocumentResponse::from).collect(),
+        );
+        response.comment_count = Some(comment_count);
+        responses.push(response);
+    }
+
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    tracing::info!(
+        "Admin {} listed submissions (page {}, {} results)",
+        admin.username,
+        page,
+        responses.len()
+    );
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(PaginatedResponse {
+            items: responses,
+            total,
+            page,
+            per_page,
+            total_pages,
+            next_cursor,
+        })),
+    )
+}
+
+/// Documents of several submissions, grouped by submission
+///
+/// One query for the whole page instead of one per submission. A failed
+/// query leaves the submissions without documents rather than failing the list.
+async fn documents_by_submission(
+    pool: &sqlx::PgPool,
+    submission_ids: &[Uuid],
+) -> HashMap<Uuid, Vec<Document>> {
     let all_documents = if submission_ids.is_empty() {
         vec![]
     } else {
         sqlx::query_as::<_, Document>(
             "SELECT * FROM documents WHERE submission_id = ANY($1) ORDER BY created_at",
         )
-        .bind(&submission_ids)
-        .fetch_all(&state.pool)
+        .bind(submission_ids)
+        .fetch_all(pool)
         .await
         .unwrap_or_default()
     };
 
-    // Group documents by submission_id
-    let mut docs_by_submission: std::collections::HashMap<Uuid, Vec<Document>> =
-        std::collections::HashMap::new();
+    let mut docs_by_submission: HashMap<Uuid, Vec<Document>> = HashMap::new();
     for doc in all_documents {
         docs_by_submission
             .entry(doc.submission_id)
             .or_default()
             .push(doc);
     }
+    docs_by_submission
+}
 
-    let mut responses = Vec::new();
-    for sub in submissions {
-        let documents = docs_by_submission.remove(&sub.id).unwrap_or_default();
+/// List submissions whose retention ends soon, soonest first (admin)
+///
+/// Covers everything the retention purge would delete within the window,
+/// including submissions already past their date; those under legal hold
+/// are left out because the purge skips them.
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/expiring",
+    tag = "admin",
+    params(ExpiringSubmissionsQuery),
+    responses(
+        (status = 200, description = "Page of submissions, soonest expiry first", body = ApiResponse<PaginatedResponse<ExpiringSubmissionResponse>>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn list_expiring_submissions(
+    State(state): State<AppState>,
+    Query(query): Query<ExpiringSubmissionsQuery>,
+) -> impl IntoResponse {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    let within_days = query
+        .within_days
+        .unwrap_or(DEFAULT_EXPIRING_WITHIN_DAYS)
+        .clamp(1, MAX_EXPIRING_WITHIN_DAYS);
+    let expires_before = Utc::now() + chrono::Duration::days(within_days);
 
-        responses.push(SubmissionResponse {
-            id: sub.id,
-            slug: sub.slug,
-            submitter_name: sub.submitter_name,
-            submitter_email: sub.submitter_email,
-            organization: sub.organization,
-            organization_department: sub.organization_department,
-            status: sub.status,
-            notes: sub.notes,
-            created_at: sub.created_at,
-            updated_at: sub.updated_at,
-            submitted_at: sub.submitted_at,
-            retention_expiry_date: sub.retention_expiry_date,
-            documents: documents.into_iter().map(DocumentResponse::from).collect(),
-        });
-    }
+    let submissions = sqlx::query_as::<_, Submission>(
+        r#"
+        SELECT * FROM submissions
+        WHERE retention_expiry_date < $1 AND NOT legal_hold
+        ORDER BY retention_expiry_date, id
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(expires_before)
+    .bind(per_page)
+    .bind((page - 1) * per_page)
+    .fetch_all(&state.pool)
+    .await;
+    let total: Result<i64, sqlx::Error> = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM submissions WHERE retention_expiry_date < $1 AND NOT legal_hold",
+    )
+    .bind(expires_before)
+    .fetch_one(&state.pool)
+    .await;
 
-    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+    let (submissions, total) = match (submissions, total) {
+        (Ok(submissions), Ok(total)) => (submissions, total),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("Failed to list expiring submissions: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::LoadSubmissionsFailed)),
+            );
+        }
+    };
 
-    tracing::info!(
-        "Admin {} listed submissions (page {}, {} results)",
-        admin.username,
-        page,
-        responses.len()
-    );
+    let submission_ids: Vec<Uuid> = submissions.iter().map(|s| s.id).collect();
+    let mut docs_by_submission = documents_by_submission(&state.pool, &submission_ids).await;
+    let meetings = sqlx::query_as::<_, BookedMeeting>(
+        r#"
+        SELECT sb.submission_id, c.id AS slot_id, c.slot_start, c.slot_end
+        FROM slot_bookings sb
+        JOIN calendar_slots c ON c.id = sb.slot_id
+        WHERE sb.submission_id = ANY($1)
+        "#,
+    )
+    .bind(&submission_ids)
+    .fetch_all(&state.pool)
+    .await
+    .unwrap_or_default();
+    let mut meetings: HashMap<Uuid, BookedMeeting> = meetings
+        .into_iter()
+        .map(|meeting| (meeting.submission_id, meeting))
+        .collect();
+
+    let items = submissions
+        .into_iter()
+        .map(|sub| {
+            let documents = docs_by_submission.remove(&sub.id).unwrap_or_default();
+            let meeting = meetings.remove(&sub.id);
+            ExpiringSubmissionResponse {
+                submission: AdminSubmissionResponse::new(
+                    sub,
+                    documents.into_iter().map(DocumentResponse::from).collect(),
+                ),
+                meeting,
+            }
+        })
+        .collect();
 
     (
         StatusCode::OK,
         Json(ApiResponse::success(PaginatedResponse {
-            items: responses,
+            items,
             total,
             page,
             per_page,
-            total_pages,
+            total_pages: (total as f64 / per_page as f64).ceil() as i64,
+            next_cursor: None,
         })),
     )
 }
 
+/// Shortest single-word search term that uses full-text search; shorter
+/// terms are usually name or slug prefixes, which only ILIKE matches
+const MIN_FULL_TEXT_CHARS: usize = 4;
+
+/// Whether a (trimmed) search term is matched with full-text search
+fn uses_full_text(term: &str) -> bool {
+    term.split_whitespace().nth(1).is_some() || term.chars().count() >= MIN_FULL_TEXT_CHARS
+}
+
+/// Position in the submission list, ordered newest first
+#[derive(Debug, Clone, PartialEq)]
+struct SubmissionCursor {
+    created_at: DateTime<Utc>,
+    id: Uuid,
+}
+
+impl From<&Submission> for SubmissionCursor {
+    fn from(sub: &Submission) -> Self {
+        Self {
+            created_at: sub.created_at,
+            id: sub.id,
+        }
+    }
+}
+
+impl SubmissionCursor {
+    /// Base64 of `created_at,id`; clients should treat it as opaque
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{},{}", self.created_at.to_rfc3339(), self.id))
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        let (created_at, id) = raw.split_once(',')?;
+        Some(Self {
+            created_at: DateTime::parse_from_rfc3339(created_at).ok()?.to_utc(),
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Filters for the admin submission list; unset fields match every row
+#[derive(Default)]
+struct SubmissionFilter<'a> {
+    status: Option<SubmissionStatus>,
+    search_pattern: Option<&'a str>,
+    /// Search term for full-text matching of notes and document text, on
+    /// top of `search_pattern`; results are then ordered by relevance
+    full_text: Option<&'a str>,
+    created_from: Option<DateTime<Utc>>,
+    created_to: Option<DateTime<Utc>>,
+    classification: Option<DocumentClassification>,
+}
+
+impl<'a> SubmissionFilter<'a> {
+    /// Append a WHERE clause for the set filters to `query`
+    fn push_where(&self, query: &mut QueryBuilder<'a, Postgres>) {
+        query.push(" WHERE TRUE");
+        if let Some(status) = self.status {
+            query.push(" AND status = ").push_bind(status);
+        }
+        if let Some(pattern) = self.search_pattern {
+            query
+                .push(" AND (submitter_name ILIKE ")
+                .push_bind(pattern)
+                .push(" OR organization ILIKE ")
+                .push_bind(pattern)
+                .push(" OR slug ILIKE ")
+                .push_bind(pattern);
+            if let Some(term) = self.full_text {
+                query
+                    .push(" OR search_vector @@ plainto_tsquery('dutch', ")
+                    .push_bind(term)
+                    .push(
+                        ") OR EXISTS (SELECT 1 FROM documents d \
+                         WHERE d.submission_id = submissions.id \
+                         AND d.search_vector @@ plainto_tsquery('dutch', ",
+                    )
+                    .push_bind(term)
+                    .push("))");
+            }
+            query.push(")");
+        }
+        if let Some(from) = self.created_from {
+            query.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = self.created_to {
+            query.push(" AND created_at < ").push_bind(to);
+        }
+        if let Some(classification) = self.classification {
+            query
+                .push(
+                    " AND EXISTS (SELECT 1 FROM documents d \
+                     WHERE d.submission_id = submissions.id AND d.classification = ",
+                )
+                .push_bind(classification)
+                .push(")");
+        }
+    }
+}
+
+/// Fetch submissions newest first, either after `cursor` or at `offset`
+///
+/// `id` breaks ties between equal `created_at` values so the order is total
+/// and a cursor never skips or repeats a row. Full-text searches are ordered
+/// by relevance first, counting the best matching document.
+async fn fetch_submission_page(
+    pool: &sqlx::PgPool,
+    filter: &SubmissionFilter<'_>,
+    cursor: Option<&SubmissionCursor>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Submission>, sqlx::Error> {
+    let mut query = QueryBuilder::new("SELECT * FROM submissions");
+    filter.push_where(&mut query);
+    if let Some(cursor) = cursor {
+        query
+            .push(" AND (created_at, id) < (")
+            .push_bind(cursor.created_at)
+            .push(", ")
+            .push_bind(cursor.id)
+            .push(")");
+    }
+    query.push(" ORDER BY ");
+    if let Some(term) = filter.full_text {
+        query
+            .push("ts_rank(search_vector, plainto_tsquery('dutch', ")
+            .push_bind(term)
+            .push(")) + COALESCE((SELECT MAX(ts_rank(d.search_vector, plainto_tsquery('dutch', ")
+            .push_bind(term)
+            .push("))) FROM documents d WHERE d.submission_id = submissions.id), 0) DESC, ");
+    }
+    query
+        .push("created_at DESC, id DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    query.build_query_as::<Submission>().fetch_all(pool).await
+}
+
+async fn count_submissions(
+    pool: &sqlx::PgPool,
+    filter: &SubmissionFilter<'_>,
+) -> Result<i64, sqlx::Error> {
+    let mut query = QueryBuilder::new("SELECT COUNT(*) FROM submissions");
+    filter.push_where(&mut query);
+    query.build_query_scalar().fetch_one(pool).await
+}
+
 /// Get submission details (admin)
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "Submission with its documents", body = ApiResponse<AdminSubmissionResponse>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn get_submission_admin(
     State(state): State<AppState>,
     Extension(_admin): Extension<AdminUser>,
@@ -216,56 +667,92 @@ pub async fn get_submission_admin(
             .await
             .unwrap_or_default();
 
-            let response = SubmissionResponse {
-                id: sub.id,
-                slug: sub.slug,
-                submitter_name: sub.submitter_name,
-                submitter_email: sub.submitter_email,
-                organization: sub.organization,
-                organization_department: sub.organization_department,
-                status: sub.status,
-                notes: sub.notes,
-                created_at: sub.created_at,
-                updated_at: sub.updated_at,
-                submitted_at: sub.submitted_at,
-                retention_expiry_date: sub.retention_expiry_date,
-                documents: documents.into_iter().map(DocumentResponse::from).collect(),
-            };
+            let response = AdminSubmissionResponse::new(
+                sub,
+                documents.into_iter().map(DocumentResponse::from).collect(),
+            );
 
             (StatusCode::OK, Json(ApiResponse::success(response)))
         }
         Ok(None) => (
             StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Submission not found")),
+            Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
         ),
         Err(e) => {
             tracing::error!("Database error: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Database error")),
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
             )
         }
     }
 }
 
 /// Update submission status (admin)
+#[utoipa::path(
+    put,
+    path = "/api/admin/submissions/{id}/status",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    request_body = UpdateStatusRequest,
+    responses(
+        (status = 200, description = "Status changed", body = ApiResponse<Submission>),
+        (status = 400, description = "Transition not allowed", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Status changed concurrently", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn update_submission_status(
     State(state): State<AppState>,
     Extension(admin): Extension<AdminUser>,
     Path(id): Path<Uuid>,
     Json(input): Json<UpdateStatusRequest>,
 ) -> impl IntoResponse {
+    let current =
+        sqlx::query_scalar::<_, SubmissionStatus>("SELECT status FROM submissions WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&state.pool)
+            .await;
+
+    let current = match current {
+        Ok(Some(status)) => status,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
+            )
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
+            );
+        }
+    };
+
+    if !is_valid_transition(current, input.status) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(ErrorKey::InvalidStatusTransition)),
+        );
+    }
+
+    // Only update if the status is still the one we validated against
     let result = sqlx::query_as::<_, Submission>(
         r#"
         UPDATE submissions
-        SET status = $1, notes = COALESCE($2, notes)
-        WHERE id = $3
+        SET status = $1, admin_notes = COALESCE($2, admin_notes)
+        WHERE id = $3 AND status = $4
         RETURNING *
         "#,
     )
     .bind(input.status)
     .bind(&input.notes)
     .bind(id)
+    .bind(current)
     .fetch_optional(&state.pool)
     .await;
 
@@ -274,16 +761,18 @@ pub async fn update_submission_status(
             // Log audit event
             let _ = sqlx::query(
                 r#"
-                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details)
-                VALUES ('submission_status_changed'::audit_action, 'submission', $1, 'admin', $2, $3)
+                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+                VALUES ('submission_status_changed'::audit_action, 'submission', $1, 'admin', $2, $3, $4)
                 "#,
             )
             .bind(id)
             .bind(admin.id)
             .bind(serde_json::json!({
+                "old_status": current,
                 "new_status": input.status,
                 "notes": input.notes
             }))
+            .bind(current_request_id())
             .execute(&state.pool)
             .await;
 
@@ -294,217 +783,2036 @@ pub async fn update_submission_status(
                 input.status
             );
 
+            notify_status_change(&state.pool, state.notifier.as_ref(), &submission).await;
+            if let Some(event) = WebhookEvent::for_status(submission.status) {
+                webhooks::dispatch(
+                    &state.pool,
+                    state.webhooks.as_ref(),
+                    event,
+                    &submission,
+                    None,
+                )
+                .await;
+            }
+
             (StatusCode::OK, Json(ApiResponse::success(submission)))
         }
         Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Submission not found")),
+            StatusCode::CONFLICT,
+            Json(ApiResponse::error(ErrorKey::StatusChangedConcurrently)),
         ),
         Err(e) => {
             tracing::error!("Failed to update status: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Failed to update status")),
+                Json(ApiResponse::error(ErrorKey::UpdateStatusFailed)),
             )
         }
     }
 }
 
-/// Forward submission to RegelRecht team (admin)
-pub async fn forward_submission(
+/// Send a submission back to the uploader for more documents (admin)
+///
+/// The uploader is emailed the message and logs in as usual to add files;
+/// once they have, a reviewer moves the submission back to `under_review`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/submissions/{id}/request-changes",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    request_body = RequestChangesRequest,
+    responses(
+        (status = 200, description = "Changes requested", body = ApiResponse<Submission>),
+        (status = 400, description = "Message missing or transition not allowed", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Status changed concurrently", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn request_changes(
     State(state): State<AppState>,
     Extension(admin): Extension<AdminUser>,
     Path(id): Path<Uuid>,
-    Json(input): Json<ForwardSubmissionRequest>,
+    Json(input): Json<RequestChangesRequest>,
 ) -> impl IntoResponse {
-    // Update status to forwarded
+    let message = input.message.trim();
+    if message.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(ErrorKey::ChangeRequestMessageRequired)),
+        );
+    }
+    if message.chars().count() > MAX_CHANGE_REQUEST_LENGTH {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::invalid(FieldError {
+                max: Some(MAX_CHANGE_REQUEST_LENGTH as i64),
+                ..FieldError::new("message", "too_long", ErrorKey::ChangeRequestMessageTooLong)
+            })),
+        );
+    }
+
+    let current =
+        sqlx::query_scalar::<_, SubmissionStatus>("SELECT status FROM submissions WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&state.pool)
+            .await;
+    let current = match current {
+        Ok(Some(status)) => status,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
+            )
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
+            );
+        }
+    };
+
+    if !is_valid_transition(current, SubmissionStatus::ChangesRequested) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(ErrorKey::InvalidStatusTransition)),
+        );
+    }
+
     let result = sqlx::query_as::<_, Submission>(
         r#"
         UPDATE submissions
-        SET status = 'forwarded', notes = COALESCE($1, notes)
-        WHERE id = $2 AND status IN ('submitted', 'under_review', 'approved')
+        SET status = 'changes_requested', notes = $1
+        WHERE id = $2 AND status = $3
         RETURNING *
         "#,
     )
-    .bind(&input.notes)
+    .bind(message)
     .bind(id)
+    .bind(current)
     .fetch_optional(&state.pool)
     .await;
 
-    match result {
-        Ok(Some(submission)) => {
-            // Log audit event with forward details
-            let _ = sqlx::query(
-                r#"
-                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details)
-                VALUES ('submission_status_changed'::audit_action, 'submission', $1, 'admin', $2, $3)
-                "#,
+    let submission = match result {
+        Ok(Some(submission)) => submission,
+        Ok(None) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::error(ErrorKey::StatusChangedConcurrently)),
+            )
+        }
+        Err(e) => {
+            tracing::error!("Failed to request changes: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::UpdateStatusFailed)),
+            );
+        }
+    };
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+        VALUES ('changes_requested'::audit_action, 'submission', $1, 'admin', $2, $3, $4)
+        "#,
+    )
+    .bind(id)
+    .bind(admin.id)
+    .bind(serde_json::json!({
+        "old_status": current,
+        "message": message,
+    }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    tracing::info!(
+        "Admin {} requested changes to submission {}",
+        admin.username,
+        id
+    );
+
+    notify_changes_requested(&state.pool, state.notifier.as_ref(), &submission, message).await;
+
+    (StatusCode::OK, Json(ApiResponse::success(submission)))
+}
+
+/// Change the status of several submissions at once (admin)
+///
+/// Either every submission moves to the new status or none does: one unknown
+/// id or one transition the workflow does not allow rejects the whole batch.
+#[utoipa::path(
+    post,
+    path = "/api/admin/submissions/bulk-status",
+    tag = "admin",
+    request_body = BulkStatusRequest,
+    responses(
+        (status = 200, description = "All statuses changed", body = ApiResponse<BulkStatusResponse>),
+        (status = 400, description = "Batch empty, too large or containing a disallowed transition", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "A submission in the batch does not exist", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn bulk_update_submission_status(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Json(input): Json<BulkStatusRequest>,
+) -> impl IntoResponse {
+    let mut seen = HashSet::new();
+    let ids: Vec<Uuid> = input
+        .ids
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .collect();
+    if ids.is_empty() || ids.len() > MAX_BULK_STATUS_IDS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::invalid(FieldError {
+                min: Some(1),
+                max: Some(MAX_BULK_STATUS_IDS as i64),
+                ..FieldError::new("ids", "out_of_range", ErrorKey::BulkSelectionSize)
+            })),
+        );
+    }
+
+    let result = apply_bulk_status(
+        &state.pool,
+        admin.id,
+        &ids,
+        input.status,
+        input.notes.as_deref(),
+    )
+    .await;
+
+    let submissions = match result {
+        Ok(submissions) => submissions,
+        Err(BulkStatusError::NotFound(id)) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse {
+                    submission_id: Some(id),
+                    ..ApiResponse::error(ErrorKey::SubmissionNotFound)
+                }),
+            );
+        }
+        Err(BulkStatusError::InvalidTransition(id)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    submission_id: Some(id),
+                    ..ApiResponse::error(ErrorKey::InvalidStatusTransition)
+                }),
+            );
+        }
+        Err(BulkStatusError::Database(e)) => {
+            tracing::error!("Failed to bulk update status: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::UpdateStatusFailed)),
+            );
+        }
+    };
+
+    tracing::info!(
+        "Admin {} changed the status of {} submissions to {:?}",
+        admin.username,
+        submissions.len(),
+        input.status
+    );
+
+    for submission in &submissions {
+        notify_status_change(&state.pool, state.notifier.as_ref(), submission).await;
+        if let Some(event) = WebhookEvent::for_status(submission.status) {
+            webhooks::dispatch(
+                &state.pool,
+                state.webhooks.as_ref(),
+                event,
+                submission,
+                None,
             )
-            .bind(id)
-            .bind(admin.id)
-            .bind(serde_json::json!({
-                "action": "forwarded",
-                "forward_to": input.forward_to,
-                "notes": input.notes
-            }))
-            .execute(&state.pool)
             .await;
+        }
+    }
 
-            tracing::info!(
-                "Admin {} forwarded submission {} to {}",
-                admin.username,
-                id,
-                input.forward_to
+    let updated = submissions.iter().map(|s| s.id).collect();
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(BulkStatusResponse { updated })),
+    )
+}
+
+/// Why a bulk status update was refused as a whole
+#[derive(Debug)]
+enum BulkStatusError {
+    NotFound(Uuid),
+    InvalidTransition(Uuid),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for BulkStatusError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+/// Move all `ids` to `status` in one transaction, auditing each change
+///
+/// The rows are locked before the transitions are checked, so a concurrent
+/// change cannot slip in between check and update.
+async fn apply_bulk_status(
+    pool: &sqlx::PgPool,
+    admin_id: Uuid,
+    ids: &[Uuid],
+    status: SubmissionStatus,
+    notes: Option<&str>,
+) -> Result<Vec<Submission>, BulkStatusError> {
+    let mut tx = pool.begin().await?;
+
+    let current: HashMap<Uuid, SubmissionStatus> = sqlx::query_as::<_, (Uuid, SubmissionStatus)>(
+        "SELECT id, status FROM submissions WHERE id = ANY($1) FOR UPDATE",
+    )
+    .bind(ids)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .collect();
+
+    for id in ids {
+        let Some(&from) = current.get(id) else {
+            return Err(BulkStatusError::NotFound(*id));
+        };
+        if !is_valid_transition(from, status) {
+            return Err(BulkStatusError::InvalidTransition(*id));
+        }
+    }
+
+    let mut updated = Vec::with_capacity(ids.len());
+    for id in ids {
+        let submission = sqlx::query_as::<_, Submission>(
+            "UPDATE submissions SET status = $1, admin_notes = COALESCE($2, admin_notes) WHERE id = $3 RETURNING *",
+        )
+        .bind(status)
+        .bind(notes)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+            VALUES ('submission_status_changed'::audit_action, 'submission', $1, 'admin', $2, $3, $4)
+            "#,
+        )
+        .bind(id)
+        .bind(admin_id)
+        .bind(serde_json::json!({
+            "old_status": current[id],
+            "new_status": status,
+            "notes": notes,
+            "bulk": true,
+        }))
+        .bind(current_request_id())
+        .execute(&mut *tx)
+        .await?;
+
+        updated.push(submission);
+    }
+
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Place or lift a legal hold on a submission (admin)
+///
+/// A held submission is never removed by the draft cleanup or the retention
+/// purge. Setting the current value again is accepted without an audit entry.
+#[utoipa::path(
+    put,
+    path = "/api/admin/submissions/{id}/legal-hold",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    request_body = LegalHoldRequest,
+    responses(
+        (status = 200, description = "Legal hold updated", body = ApiResponse<Submission>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn set_legal_hold(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<LegalHoldRequest>,
+) -> impl IntoResponse {
+    let changed = sqlx::query_as::<_, Submission>(
+        "UPDATE submissions SET legal_hold = $1 WHERE id = $2 AND legal_hold <> $1 RETURNING *",
+    )
+    .bind(input.legal_hold)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await;
+
+    let submission = match changed {
+        Ok(Some(submission)) => submission,
+        Ok(None) => {
+            // Either unknown or already in the requested state
+            return match sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&state.pool)
+                .await
+            {
+                Ok(Some(submission)) => (StatusCode::OK, Json(ApiResponse::success(submission))),
+                Ok(None) => (
+                    StatusCode::NOT_FOUND,
+                    Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
+                ),
+                Err(e) => {
+                    tracing::error!("Database error: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(ErrorKey::DatabaseError)),
+                    )
+                }
+            };
+        }
+        Err(e) => {
+            tracing::error!("Failed to update legal hold: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::UpdateLegalHoldFailed)),
+            );
+        }
+    };
+
+    let action = if submission.legal_hold {
+        "legal_hold_set"
+    } else {
+        "legal_hold_cleared"
+    };
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+        VALUES ($1::audit_action, 'submission', $2, 'admin', $3, $4, $5)
+        "#,
+    )
+    .bind(action)
+    .bind(id)
+    .bind(admin.id)
+    .bind(serde_json::json!({ "slug": submission.slug }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    tracing::info!(
+        "Admin {} {} legal hold on submission {}",
+        admin.username,
+        if submission.legal_hold {
+            "placed"
+        } else {
+            "lifted"
+        },
+        submission.slug
+    );
+
+    (StatusCode::OK, Json(ApiResponse::success(submission)))
+}
+
+/// Give a submission a new slug, for when the old one was shared too widely (admin)
+///
+/// Uploader sessions opened with the old slug end at once. Files are stored
+/// by submission id, so they stay where they are. The uploader can have the
+/// new slug mailed to them through reference code recovery.
+#[utoipa::path(
+    post,
+    path = "/api/admin/submissions/{id}/rotate-slug",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "New slug assigned", body = ApiResponse<RotateSlugResponse>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn rotate_slug(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let rotated = match rotate_submission_slug(&state.pool, id).await {
+        Ok(Some(rotated)) => rotated,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Failed to rotate slug of submission {}: {}", id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::RotateSlugFailed)),
+            );
+        }
+    };
+    audit::record(
+        &state.pool,
+        AuditEntry {
+            action: "slug_rotated",
+            entity_type: "submission",
+            entity_id: Some(id),
+            actor_type: "admin",
+            actor_id: Some(admin.id),
+            details: Some(serde_json::json!({
+                "old_slug": rotated.old_slug,
+                "new_slug": rotated.new_slug,
+                "ended_uploader_sessions": rotated.ended_sessions,
+            })),
+        },
+    )
+    .await;
+
+    tracing::info!(
+        "Admin {} replaced slug {} of submission {} with {}",
+        admin.username,
+        rotated.old_slug,
+        id,
+        rotated.new_slug
+    );
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(RotateSlugResponse {
+            slug: rotated.new_slug,
+        })),
+    )
+}
+
+struct RotatedSlug {
+    old_slug: String,
+    new_slug: String,
+    ended_sessions: u64,
+}
+
+/// Replace the slug of a submission and end its uploader sessions
+///
+/// Both changes commit together; `None` for an unknown submission. Stored
+/// files are keyed by submission id, so none of them move.
+async fn rotate_submission_slug(
+    pool: &sqlx::PgPool,
+    id: Uuid,
+) -> Result<Option<RotatedSlug>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let Some(old_slug) =
+        sqlx::query_scalar::<_, String>("SELECT slug FROM submissions WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+    else {
+        return Ok(None);
+    };
+    let new_slug: String = sqlx::query_scalar(
+        "UPDATE submissions SET slug = generate_submission_slug() WHERE id = $1 RETURNING slug",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let ended_sessions = sqlx::query("DELETE FROM uploader_sessions WHERE submission_id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    tx.commit().await?;
+    Ok(Some(RotatedSlug {
+        old_slug,
+        new_slug,
+        ended_sessions,
+    }))
+}
+
+/// List the uploader sessions open for a submission (admin)
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/uploader-sessions",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "Active uploader sessions, newest first", body = ApiResponse<Vec<UploaderSessionSummary>>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn list_uploader_sessions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match submission_exists(&state.pool, id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Failed to load submission {}: {}", id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::ListSessionsFailed)),
+            );
+        }
+    }
+
+    let sessions = sqlx::query_as::<_, UploaderSession>(
+        r#"
+        SELECT * FROM uploader_sessions
+        WHERE submission_id = $1 AND expires_at > NOW()
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await;
+
+    match sessions {
+        Ok(sessions) => {
+            let sessions: Vec<UploaderSessionSummary> =
+                sessions.into_iter().map(Into::into).collect();
+            (StatusCode::OK, Json(ApiResponse::success(sessions)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to list uploader sessions of {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::ListSessionsFailed)),
+            )
+        }
+    }
+}
+
+/// End every uploader session of a submission (admin)
+///
+/// The uploader can log in again with the slug and email, so pair this with
+/// [`rotate_slug`] when the slug itself leaked.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/submissions/{id}/uploader-sessions",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "Number of sessions revoked", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn revoke_uploader_sessions(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match submission_exists(&state.pool, id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Failed to load submission {}: {}", id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::RevokeSessionsFailed)),
+            );
+        }
+    }
+
+    let revoked = sqlx::query("DELETE FROM uploader_sessions WHERE submission_id = $1")
+        .bind(id)
+        .execute(&state.pool)
+        .await;
+    let revoked = match revoked {
+        Ok(result) => result.rows_affected(),
+        Err(e) => {
+            tracing::error!("Failed to revoke uploader sessions of {}: {}", id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::RevokeSessionsFailed)),
+            );
+        }
+    };
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+        VALUES ('uploader_sessions_revoked'::audit_action, 'submission', $1, 'admin', $2, $3, $4)
+        "#,
+    )
+    .bind(id)
+    .bind(admin.id)
+    .bind(serde_json::json!({ "sessions_revoked": revoked }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    tracing::info!(
+        "Admin {} revoked {} uploader sessions of submission {}",
+        admin.username,
+        revoked,
+        id
+    );
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            serde_json::json!({ "sessions_revoked": revoked }),
+        )),
+    )
+}
+
+/// The submission as its uploader sees it, to reproduce an applicant's problem (admin)
+///
+/// Read-only: no uploader session is created. `session_expires_at` is the end
+/// of the uploader's latest open session, or now when none is open.
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/uploader-view",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "What the uploader sees", body = ApiResponse<UploaderSessionResponse>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn get_uploader_view(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<UploaderSessionResponse>>, AppError> {
+    let submission = sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(AppError::db(ErrorKey::DatabaseError))?
+        .ok_or(AppError::NotFound(ErrorKey::SubmissionNotFound))?;
+
+    let session_expires_at: DateTime<Utc> = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(MAX(expires_at), NOW()) FROM uploader_sessions
+        WHERE submission_id = $1 AND expires_at > NOW()
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::DatabaseError))?;
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, request_id)
+        VALUES ('uploader_view_accessed'::audit_action, 'submission', $1, 'admin', $2, $3)
+        "#,
+    )
+    .bind(id)
+    .bind(admin.id)
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    let view = uploader_view(&state.pool, submission, session_expires_at).await;
+    Ok(Json(ApiResponse::success(view)))
+}
+
+/// Columns of a comment with its author's name, for `SELECT ... FROM submission_comments c`
+const COMMENT_COLUMNS: &str =
+    "c.id, c.author_id, COALESCE(a.display_name, a.username) AS author_name, c.body, c.created_at";
+
+/// Comments on a submission, newest first (admin)
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/comments",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "Comments, newest first", body = ApiResponse<Vec<SubmissionComment>>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn list_comments(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<SubmissionComment>>>, AppError> {
+    if !submission_exists(&state.pool, id).await? {
+        return Err(AppError::NotFound(ErrorKey::SubmissionNotFound));
+    }
+
+    let comments = sqlx::query_as::<_, SubmissionComment>(&format!(
+        r#"
+        SELECT {} FROM submission_comments c
+        LEFT JOIN admin_users a ON a.id = c.author_id
+        WHERE c.submission_id = $1
+        ORDER BY c.created_at DESC, c.id
+        "#,
+        COMMENT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::LoadCommentsFailed))?;
+
+    Ok(Json(ApiResponse::success(comments)))
+}
+
+/// Add a comment to a submission's thread (admin)
+///
+/// Comments are only seen by reviewers; the uploader gets no notification.
+#[utoipa::path(
+    post,
+    path = "/api/admin/submissions/{id}/comments",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    request_body = CreateCommentRequest,
+    responses(
+        (status = 200, description = "Comment added", body = ApiResponse<SubmissionComment>),
+        (status = 400, description = "Empty or too long comment", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn add_comment(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<CreateCommentRequest>,
+) -> Result<Json<ApiResponse<SubmissionComment>>, AppError> {
+    let body = input.body.trim();
+    if body.is_empty() {
+        return Err(AppError::Validation(FieldError::new(
+            "body",
+            "required",
+            ErrorKey::CommentRequired,
+        )));
+    }
+    if body.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(AppError::Validation(FieldError {
+            max: Some(MAX_COMMENT_LENGTH as i64),
+            ..FieldError::new("body", "too_long", ErrorKey::CommentTooLong)
+        }));
+    }
+    if !submission_exists(&state.pool, id).await? {
+        return Err(AppError::NotFound(ErrorKey::SubmissionNotFound));
+    }
+
+    let comment = sqlx::query_as::<_, SubmissionComment>(&format!(
+        r#"
+        WITH c AS (
+            INSERT INTO submission_comments (submission_id, author_id, body)
+            VALUES ($1, $2, $3)
+            RETURNING *
+        )
+        SELECT {} FROM c
+        LEFT JOIN admin_users a ON a.id = c.author_id
+        "#,
+        COMMENT_COLUMNS
+    ))
+    .bind(id)
+    .bind(admin.id)
+    .bind(body)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::AddCommentFailed))?;
+
+    // The audit entry points at the comment; the text stays in the thread
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+        VALUES ('comment_added'::audit_action, 'submission', $1, 'admin', $2, $3, $4)
+        "#,
+    )
+    .bind(id)
+    .bind(admin.id)
+    .bind(serde_json::json!({ "comment_id": comment.id }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    tracing::info!("Admin {} commented on submission {}", admin.username, id);
+    Ok(Json(ApiResponse::success(comment)))
+}
+
+async fn submission_exists(pool: &sqlx::PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM submissions WHERE id = $1)")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Forward submission to RegelRecht team (admin)
+#[utoipa::path(
+    post,
+    path = "/api/admin/submissions/{id}/forward",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    request_body = ForwardSubmissionRequest,
+    responses(
+        (status = 200, description = "Submission forwarded", body = ApiResponse<Submission>),
+        (status = 400, description = "Transition not allowed", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Status changed concurrently", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn forward_submission(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<ForwardSubmissionRequest>,
+) -> impl IntoResponse {
+    // Update status to forwarded
+    let result = sqlx::query_as::<_, Submission>(
+        r#"
+        UPDATE submissions
+        SET status = 'forwarded', admin_notes = COALESCE($1, admin_notes)
+        WHERE id = $2 AND status IN ('submitted', 'under_review', 'approved')
+        RETURNING *
+        "#,
+    )
+    .bind(&input.notes)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await;
+
+    match result {
+        Ok(Some(submission)) => {
+            // Log audit event with forward details
+            let _ = sqlx::query(
+                r#"
+                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+                VALUES ('submission_status_changed'::audit_action, 'submission', $1, 'admin', $2, $3, $4)
+                "#,
+            )
+            .bind(id)
+            .bind(admin.id)
+            .bind(serde_json::json!({
+                "action": "forwarded",
+                "forward_to": input.forward_to,
+                "notes": input.notes
+            }))
+            .bind(current_request_id())
+            .execute(&state.pool)
+            .await;
+
+            tracing::info!(
+                "Admin {} forwarded submission {} to {}",
+                admin.username,
+                id,
+                input.forward_to
+            );
+
+            notify_status_change(&state.pool, state.notifier.as_ref(), &submission).await;
+            webhooks::dispatch(
+                &state.pool,
+                state.webhooks.as_ref(),
+                WebhookEvent::SubmissionForwarded,
+                &submission,
+                None,
+            )
+            .await;
+
+            (StatusCode::OK, Json(ApiResponse::success(submission)))
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(ErrorKey::NotForwardable)),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to forward submission: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::ForwardFailed)),
+            )
+        }
+    }
+}
+
+/// Delete a submission (admin)
+#[utoipa::path(
+    delete,
+    path = "/api/admin/submissions/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "Submission and its files deleted", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn delete_submission(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    // 1. Fetch the submission to locate its files for cleanup
+    let submission = sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await;
+
+    match submission {
+        Ok(Some(sub)) => {
+            // 2. Delete files from storage before database cascade
+            if let Err(e) =
+                delete_submission_files(state.store.as_ref(), sub.id, sub.created_at).await
+            {
+                tracing::warn!("Failed to remove files of submission {}: {}", sub.id, e);
+                // Continue with database deletion even if file cleanup fails
+            }
+
+            // 3. Delete from database (CASCADE handles documents + uploader_sessions)
+            let delete_result = sqlx::query("DELETE FROM submissions WHERE id = $1")
+                .bind(id)
+                .execute(&state.pool)
+                .await;
+
+            match delete_result {
+                Ok(_) => {
+                    // 4. Log audit event
+                    let _ = sqlx::query(
+                        r#"
+                        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+                        VALUES ('data_deleted'::audit_action, 'submission', $1, 'admin', $2, $3, $4)
+                        "#,
+                    )
+                    .bind(id)
+                    .bind(admin.id)
+                    .bind(serde_json::json!({
+                        "slug": sub.slug,
+                        "submitter_name": sub.submitter_name,
+                        "organization": sub.organization,
+                        "deleted_by": admin.username
+                    }))
+                    .bind(current_request_id())
+                    .execute(&state.pool)
+                    .await;
+
+                    tracing::info!(
+                        "Admin {} deleted submission {} ({})",
+                        admin.username,
+                        id,
+                        sub.slug
+                    );
+
+                    (
+                        StatusCode::OK,
+                        Json(ApiResponse::success(serde_json::json!({
+                            "deleted": true,
+                            "id": id,
+                            "slug": sub.slug
+                        }))),
+                    )
+                }
+                Err(e) => {
+                    tracing::error!("Failed to delete submission: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::error(ErrorKey::DeleteSubmissionFailed)),
+                    )
+                }
+            }
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
+        ),
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct CategoryCount {
+    pub category: DocumentCategory,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ClassificationCount {
+    pub classification: DocumentClassification,
+    pub count: i64,
+}
+
+/// Size summary of a submission, to check before exporting it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SubmissionStats {
+    pub submission_id: Uuid,
+    /// Uploaded files and formal law links together
+    pub document_count: i64,
+    /// Sum of the uploaded files' sizes in bytes
+    pub total_file_bytes: i64,
+    pub formal_law_count: i64,
+    pub by_category: Vec<CategoryCount>,
+    pub by_classification: Vec<ClassificationCount>,
+    pub has_meeting_booked: bool,
+    pub retention_expiry_date: DateTime<Utc>,
+    pub legal_hold: bool,
+}
+
+/// Document counts and total file size of a submission (admin)
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/stats",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "Document counts, total bytes, meeting and retention state", body = ApiResponse<SubmissionStats>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn get_submission_stats(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<SubmissionStats>>, AppError> {
+    let (
+        document_count,
+        total_file_bytes,
+        formal_law_count,
+        has_meeting_booked,
+        retention_expiry_date,
+        legal_hold,
+    ) = sqlx::query_as::<_, (i64, i64, i64, bool, DateTime<Utc>, bool)>(
+        r#"
+            SELECT d.document_count, d.total_file_bytes, d.formal_law_count,
+                EXISTS (SELECT 1 FROM slot_bookings b WHERE b.submission_id = s.id),
+                s.retention_expiry_date, s.legal_hold
+            FROM submissions s
+            CROSS JOIN LATERAL (
+                SELECT COUNT(*) AS document_count,
+                    COALESCE(SUM(file_size), 0)::BIGINT AS total_file_bytes,
+                    COUNT(*) FILTER (WHERE category = 'formal_law') AS formal_law_count
+                FROM documents
+                WHERE submission_id = s.id
+            ) d
+            WHERE s.id = $1
+            "#,
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::LoadSubmissionStatsFailed))?
+    .ok_or(AppError::NotFound(ErrorKey::SubmissionNotFound))?;
+
+    let by_category = sqlx::query_as::<_, CategoryCount>(
+        r#"
+        SELECT category, COUNT(*) AS count FROM documents
+        WHERE submission_id = $1
+        GROUP BY category
+        ORDER BY category
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::LoadSubmissionStatsFailed))?;
+
+    let by_classification = sqlx::query_as::<_, ClassificationCount>(
+        r#"
+        SELECT classification, COUNT(*) AS count FROM documents
+        WHERE submission_id = $1
+        GROUP BY classification
+        ORDER BY classification
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::LoadSubmissionStatsFailed))?;
+
+    Ok(Json(ApiResponse::success(SubmissionStats {
+        submission_id: id,
+        document_count,
+        total_file_bytes,
+        formal_law_count,
+        by_category,
+        by_classification,
+        has_meeting_booked,
+        retention_expiry_date,
+        legal_hold,
+    })))
+}
+
+/// Settings the server is running with (admin)
+///
+/// Meant for checking a deployment: the database URL and all passwords and
+/// keys are left out, secrets are only reported as set or not.
+#[utoipa::path(
+    get,
+    path = "/api/admin/config",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Effective non-secret configuration", body = ApiResponse<EffectiveConfig>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn get_effective_config(
+    State(state): State<AppState>,
+) -> Json<ApiResponse<EffectiveConfig>> {
+    Json(ApiResponse::success(
+        state.effective_config.as_ref().clone(),
+    ))
+}
+
+/// Longest trend window the dashboard accepts
+const MAX_TREND_DAYS: i64 = 365;
+
+/// Number of organizations in the dashboard's most-active list
+const TOP_ORGANIZATIONS: i64 = 10;
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct DailySubmissionCount {
+    pub day: chrono::NaiveDate,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct OrganizationSubmissionCount {
+    pub organization: String,
+    pub count: i64,
+}
+
+/// Get admin dashboard statistics
+///
+/// Besides the totals this returns submissions per day (UTC) for the last
+/// `days` days, including days without submissions, and the organizations
+/// with the most submissions in that period.
+#[utoipa::path(
+    get,
+    path = "/api/admin/dashboard",
+    tag = "admin",
+    params(DashboardQuery),
+    responses(
+        (status = 200, description = "Submission counts per status, document and slot totals, daily volume and most active organizations", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn get_dashboard_stats(
+    State(state): State<AppState>,
+    Extension(_admin): Extension<AdminUser>,
+    Query(query): Query<DashboardQuery>,
+) -> impl IntoResponse {
+    let days = query.days.unwrap_or(30).clamp(1, MAX_TREND_DAYS);
+    let last_day = Utc::now().date_naive();
+    let first_day = last_day - chrono::Duration::days(days - 1);
+
+    // Get counts by status
+    let stats = sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT status::text, COUNT(*) as count
+        FROM submissions
+        GROUP BY status
+        "#,
+    )
+    .fetch_all(&state.pool)
+    .await
+    .unwrap_or_default();
+
+    let total_documents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM documents")
+        .fetch_one(&state.pool)
+        .await
+        .unwrap_or(0);
+
+    let pending_slots: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM calendar_slots c
+        WHERE c.slot_start > NOW()
+          AND (SELECT COUNT(*) FROM slot_bookings b WHERE b.slot_id = c.id) < c.capacity
+        "#,
+    )
+    .fetch_one(&state.pool)
+    .await
+    .unwrap_or(0);
+
+    let per_day = submissions_per_day(&state.pool, first_day, last_day)
+        .await
+        .unwrap_or_default();
+    let top_organizations =
+        most_active_organizations(&state.pool, first_day, last_day, TOP_ORGANIZATIONS)
+            .await
+            .unwrap_or_default();
+
+    let stats_map: std::collections::HashMap<String, i64> = stats.into_iter().collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(serde_json::json!({
+            "submissions_by_status": stats_map,
+            "total_documents": total_documents,
+            "available_meeting_slots": pending_slots,
+            "trend_days": days,
+            "submissions_per_day": per_day,
+            "top_organizations": top_organizations
+        }))),
+    )
+}
+
+/// Submissions created on each UTC day from `first_day` to `last_day`
+/// inclusive, with a zero count for days without any
+async fn submissions_per_day(
+    pool: &sqlx::PgPool,
+    first_day: chrono::NaiveDate,
+    last_day: chrono::NaiveDate,
+) -> Result<Vec<DailySubmissionCount>, sqlx::Error> {
+    sqlx::query_as::<_, DailySubmissionCount>(
+        r#"
+        SELECT day::date AS day, COUNT(s.id) AS count
+        FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS day
+        LEFT JOIN submissions s
+            ON date_trunc('day', s.created_at AT TIME ZONE 'UTC') = day
+        GROUP BY day
+        ORDER BY day
+        "#,
+    )
+    .bind(first_day)
+    .bind(last_day)
+    .fetch_all(pool)
+    .await
+}
+
+/// Organizations with the most submissions created between `first_day` and
+/// `last_day` (UTC, inclusive), busiest first
+async fn most_active_organizations(
+    pool: &sqlx::PgPool,
+    first_day: chrono::NaiveDate,
+    last_day: chrono::NaiveDate,
+    limit: i64,
+) -> Result<Vec<OrganizationSubmissionCount>, sqlx::Error> {
+    sqlx::query_as::<_, OrganizationSubmissionCount>(
+        r#"
+        SELECT organization, COUNT(*) AS count
+        FROM submissions
+        WHERE created_at AT TIME ZONE 'UTC' >= $1::date
+          AND created_at AT TIME ZONE 'UTC' < $2::date + 1
+        GROUP BY organization
+        ORDER BY count DESC, organization
+        LIMIT $3
+        "#,
+    )
+    .bind(first_day)
+    .bind(last_day)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+// =============================================================================
+// Document Versions
+// =============================================================================
+
+/// List every stored version of a document, newest first (admin)
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/documents/{doc_id}/versions",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id"), ("doc_id" = Uuid, Path, description = "Document id")),
+    responses(
+        (status = 200, description = "Versions, newest first", body = ApiResponse<Vec<DocumentVersionResponse>>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Document not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn list_document_versions(
+    State(state): State<AppState>,
+    Extension(_admin): Extension<AdminUser>,
+    Path((id, doc_id)): Path<(Uuid, Uuid)>,
+) -> impl IntoResponse {
+    let exists: Result<bool, sqlx::Error> = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM documents WHERE id = $1 AND submission_id = $2)",
+    )
+    .bind(doc_id)
+    .bind(id)
+    .fetch_one(&state.pool)
+    .await;
+
+    match exists {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<Vec<DocumentVersionResponse>>::error(
+                    ErrorKey::DocumentNotFound,
+                )),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
+            );
+        }
+    }
+
+    let versions = sqlx::query_as::<_, DocumentVersion>(
+        "SELECT * FROM document_versions WHERE document_id = $1 ORDER BY version_no DESC",
+    )
+    .bind(doc_id)
+    .fetch_all(&state.pool)
+    .await;
+
+    match versions {
+        Ok(versions) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                versions
+                    .into_iter()
+                    .map(DocumentVersionResponse::from)
+                    .collect(),
+            )),
+        ),
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
+            )
+        }
+    }
+}
+
+/// Download the file of a specific document version (admin)
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/documents/{doc_id}/versions/{version_no}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id"), ("doc_id" = Uuid, Path, description = "Document id"), ("version_no" = i32, Path, description = "Version number, starting at 1")),
+    responses(
+        (status = 200, description = "File of the version", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Version not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn download_document_version(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path((id, doc_id, version_no)): Path<(Uuid, Uuid, i32)>,
+) -> Response {
+    let version = sqlx::query_as::<_, DocumentVersion>(
+        r#"
+        SELECT v.* FROM document_versions v
+        JOIN documents d ON d.id = v.document_id
+        WHERE v.document_id = $1 AND d.submission_id = $2 AND v.version_no = $3
+        "#,
+    )
+    .bind(doc_id)
+    .bind(id)
+    .bind(version_no)
+    .fetch_optional(&state.pool)
+    .await;
+
+    let version = match version {
+        Ok(Some(version)) => version,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(ErrorKey::DocumentVersionNotFound)),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(ErrorKey::DatabaseError)),
+            )
+                .into_response();
+        }
+    };
+
+    let body = match document_body(&state, &version.file_path, version.encrypted).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+
+    tracing::info!(
+        "Admin {} downloaded version {} of document {}",
+        admin.username,
+        version_no,
+        doc_id
+    );
+
+    let filename = attachment_filename(version.original_filename.as_deref().unwrap_or("document"));
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            header::CONTENT_TYPE,
+            version
+                .mime_type
+                .as_deref()
+                .unwrap_or("application/octet-stream"),
+        )
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(body)
+        .unwrap()
+}
+
+/// Download the current file of a document (admin)
+///
+/// Needs no uploader session, so every download is audited with the admin as
+/// actor. Formal law links have no file and give 404.
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/documents/{doc_id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id"), ("doc_id" = Uuid, Path, description = "Document id")),
+    responses(
+        (status = 200, description = "File of the document", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Document not found or has no file", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn download_document(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path((id, doc_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let document = sqlx::query_as::<_, Document>(
+        "SELECT * FROM documents WHERE id = $1 AND submission_id = $2",
+    )
+    .bind(doc_id)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await;
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(ErrorKey::DocumentNotFound)),
+        )
+            .into_response()
+    };
+    let (document, file_path) = match document {
+        Ok(Some(document)) => match document.file_path.clone() {
+            Some(file_path) => (document, file_path),
+            None => return not_found(),
+        },
+        Ok(None) => return not_found(),
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(ErrorKey::DatabaseError)),
+            )
+                .into_response();
+        }
+    };
+
+    let body = match document_body(&state, &file_path, document.encrypted).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+
+    audit::record(
+        &state.pool,
+        AuditEntry {
+            action: "document_downloaded",
+            entity_type: "document",
+            entity_id: Some(doc_id),
+            actor_type: "admin",
+            actor_id: Some(admin.id),
+            details: Some(serde_json::json!({
+                "submission_id": id,
+                "filename": document.original_filename,
+            })),
+        },
+    )
+    .await;
+
+    tracing::info!("Admin {} downloaded document {}", admin.username, doc_id);
+
+    document_attachment(&document, body)
+}
+
+/// Render the first page of a PDF document as a PNG thumbnail (admin)
+///
+/// Thumbnails are cached per document, so only the first request after an
+/// upload or replacement runs the renderer.
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/documents/{doc_id}/thumbnail",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id"), ("doc_id" = Uuid, Path, description = "Document id")),
+    responses(
+        (status = 200, description = "PNG of the first page", body = Vec<u8>, content_type = "image/png"),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Document not found or has no file", body = ApiResponse<serde_json::Value>),
+        (status = 415, description = "Document is not a PDF", body = ApiResponse<serde_json::Value>),
+        (status = 503, description = "THUMBNAIL_RENDERER is not set", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn get_document_thumbnail(
+    State(state): State<AppState>,
+    Path((id, doc_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let error = |status: StatusCode, key: ErrorKey| {
+        (status, Json(ApiResponse::<()>::error(key))).into_response()
+    };
+    let Some(thumbnailer) = &state.thumbnailer else {
+        return error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            ErrorKey::ThumbnailsNotConfigured,
+        );
+    };
+
+    let document = sqlx::query_as::<_, Document>(
+        "SELECT * FROM documents WHERE id = $1 AND submission_id = $2",
+    )
+    .bind(doc_id)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await;
+
+    let document = match document {
+        Ok(Some(document)) => document,
+        Ok(None) => return error(StatusCode::NOT_FOUND, ErrorKey::DocumentNotFound),
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return error(StatusCode::INTERNAL_SERVER_ERROR, ErrorKey::DatabaseError);
+        }
+    };
+    // Formal law links have no file to render
+    let Some(file_path) = document.file_path.as_deref() else {
+        return error(StatusCode::NOT_FOUND, ErrorKey::DocumentNotFound);
+    };
+    if document.mime_type.as_deref() != Some("application/pdf") {
+        return error(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ErrorKey::ThumbnailPdfOnly,
+        );
+    }
+
+    let png = match thumbnailer.cached(doc_id, document.updated_at).await {
+        Some(png) => png,
+        None => {
+            let pdf = match read_document_file(&state, file_path, document.encrypted).await {
+                Ok(pdf) => pdf,
+                Err(response) => return response,
+            };
+            match thumbnailer.render(doc_id, &pdf).await {
+                Ok(png) => png,
+                Err(e) => {
+                    tracing::error!("Failed to render thumbnail of document {}: {}", doc_id, e);
+                    return error(StatusCode::INTERNAL_SERVER_ERROR, ErrorKey::ThumbnailFailed);
+                }
+            }
+        }
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "image/png")
+        .body(Body::from(png))
+        .unwrap()
+}
+
+/// Create a signed link for downloading one document without a session (admin)
+#[utoipa::path(
+    post,
+    path = "/api/admin/submissions/{id}/documents/{doc_id}/share-link",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id"), ("doc_id" = Uuid, Path, description = "Document id")),
+    request_body(content = Option<ShareLinkRequest>, description = "Optional lifetime; defaults to DOWNLOAD_LINK_EXPIRY_HOURS"),
+    responses(
+        (status = 201, description = "Link created", body = ApiResponse<ShareLinkResponse>),
+        (status = 400, description = "Invalid lifetime", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Document not found or has no file", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn create_share_link(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Path((id, doc_id)): Path<(Uuid, Uuid)>,
+    input: Option<Json<ShareLinkRequest>>,
+) -> impl IntoResponse {
+    let ttl = match input.and_then(|Json(input)| input.expires_in_hours) {
+        Some(hours) if !(1..=MAX_SHARE_LINK_HOURS).contains(&hours) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::invalid(FieldError {
+                    min: Some(1),
+                    max: Some(MAX_SHARE_LINK_HOURS),
+                    ..FieldError::new(
+                        "expires_in_hours",
+                        "out_of_range",
+                        ErrorKey::ShareLinkExpiryOutOfRange,
+                    )
+                })),
             );
+        }
+        Some(hours) => chrono::Duration::hours(hours),
+        None => state.download_links.default_ttl,
+    };
 
-            (StatusCode::OK, Json(ApiResponse::success(submission)))
+    // Formal law links have no file to share
+    let has_file: Result<Option<bool>, sqlx::Error> = sqlx::query_scalar(
+        "SELECT file_path IS NOT NULL FROM documents WHERE id = $1 AND submission_id = $2",
+    )
+    .bind(doc_id)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await;
+
+    match has_file {
+        Ok(Some(true)) => {}
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::DocumentNotFound)),
+            );
         }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error(
-                "Submission not found or not in a forwardable status",
-            )),
-        ),
         Err(e) => {
-            tracing::error!("Failed to forward submission: {}", e);
-            (
+            tracing::error!("Database error: {}", e);
+            return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Failed to forward submission")),
-            )
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
+            );
         }
     }
+
+    let expires_at = (Utc::now() + ttl).trunc_subsecs(0);
+    let token = state.download_links.sign(doc_id, expires_at);
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+        VALUES ('download_link_created'::audit_action, 'document', $1, 'admin', $2, $3, $4)
+        "#,
+    )
+    .bind(doc_id)
+    .bind(admin.id)
+    .bind(serde_json::json!({ "submission_id": id, "expires_at": expires_at }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    tracing::info!(
+        "Admin {} created a download link for document {}, valid until {}",
+        admin.username,
+        doc_id,
+        expires_at
+    );
+
+    (
+        StatusCode::CREATED,
+        Json(ApiResponse::success(ShareLinkResponse {
+            url: format!("/api/download/{}", token),
+            expires_at,
+        })),
+    )
 }
 
-/// Delete a submission (admin)
-pub async fn delete_submission(
+/// Download a document through a shared link
+#[utoipa::path(
+    get,
+    path = "/api/download/{token}",
+    tag = "documents",
+    params(("token" = String, Path, description = "Signed token from a share link")),
+    responses(
+        (status = 200, description = "Document file", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 403, description = "Link is invalid or has expired", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Document no longer exists", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn download_shared_document(
     State(state): State<AppState>,
-    Extension(admin): Extension<AdminUser>,
-    Path(id): Path<Uuid>,
-) -> impl IntoResponse {
-    // 1. Fetch the submission to get the slug for file cleanup
-    let submission = sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE id = $1")
-        .bind(id)
+    Path(token): Path<String>,
+) -> Response {
+    let doc_id = match state.download_links.verify(&token, Utc::now()) {
+        Ok(doc_id) => doc_id,
+        Err(e) => {
+            tracing::info!("Rejected download link: {}", e);
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::<()>::error(ErrorKey::InvalidDownloadLink)),
+            )
+                .into_response();
+        }
+    };
+
+    let document = sqlx::query_as::<_, Document>("SELECT * FROM documents WHERE id = $1")
+        .bind(doc_id)
         .fetch_optional(&state.pool)
         .await;
 
-    match submission {
-        Ok(Some(sub)) => {
-            // 2. Delete files from disk before database cascade
-            let submission_dir = state.upload_dir.join(&sub.slug);
-            if submission_dir.exists() {
-                if let Err(e) = tokio::fs::remove_dir_all(&submission_dir).await {
-                    tracing::warn!(
-                        "Failed to remove submission directory {:?}: {}",
-                        submission_dir,
-                        e
-                    );
-                    // Continue with database deletion even if file cleanup fails
-                }
+    let (document, file_path) = match document {
+        Ok(Some(document)) => match document.file_path.clone() {
+            Some(file_path) => (document, file_path),
+            None => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(ApiResponse::<()>::error(ErrorKey::DocumentNotFound)),
+                )
+                    .into_response();
             }
+        },
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(ErrorKey::DocumentNotFound)),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(ErrorKey::DatabaseError)),
+            )
+                .into_response();
+        }
+    };
 
-            // 3. Delete from database (CASCADE handles documents + uploader_sessions)
-            let delete_result = sqlx::query("DELETE FROM submissions WHERE id = $1")
-                .bind(id)
-                .execute(&state.pool)
-                .await;
+    let body = match document_body(&state, &file_path, document.encrypted).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
 
-            match delete_result {
-                Ok(_) => {
-                    // 4. Log audit event
-                    let _ = sqlx::query(
-                        r#"
-                        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details)
-                        VALUES ('data_deleted'::audit_action, 'submission', $1, 'admin', $2, $3)
-                        "#,
-                    )
-                    .bind(id)
-                    .bind(admin.id)
-                    .bind(serde_json::json!({
-                        "slug": sub.slug,
-                        "submitter_name": sub.submitter_name,
-                        "organization": sub.organization,
-                        "deleted_by": admin.username
-                    }))
-                    .execute(&state.pool)
-                    .await;
+    tracing::info!("Document {} downloaded through a shared link", doc_id);
 
-                    tracing::info!(
-                        "Admin {} deleted submission {} ({})",
-                        admin.username,
-                        id,
-                        sub.slug
-                    );
+    document_attachment(&document, body)
+}
 
-                    (
-                        StatusCode::OK,
-                        Json(ApiResponse::success(serde_json::json!({
-                            "deleted": true,
-                            "id": id,
-                            "slug": sub.slug
-                        }))),
-                    )
-                }
-                Err(e) => {
-                    tracing::error!("Failed to delete submission: {}", e);
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse::error("Failed to delete submission")),
-                    )
-                }
-            }
+/// Streamed body of a stored document file, decrypted chunk by chunk if needed
+///
+/// A chunk that fails authentication ends the stream with an error, so the
+/// client sees a broken download rather than altered contents.
+async fn document_body(
+    state: &AppState,
+    file_path: &str,
+    encrypted: bool,
+) -> Result<Body, Response> {
+    let reader = state
+        .store
+        .open(file_path)
+        .await
+        .map_err(|e| storage_error_response(file_path, e))?;
+    let reader = decrypt_stored_reader(state.file_key.as_ref(), reader, encrypted)
+        .map_err(|e| decrypt_error_response(file_path, e))?;
+    Ok(Body::from_stream(ReaderStream::new(reader)))
+}
+
+/// Response for a stored file that could not be read
+///
+/// A missing file is a 404. So is a stored path the store refuses because
+/// it would leave the upload directory, which is logged as an error.
+fn storage_error_response(file_path: &str, e: StorageError) -> Response {
+    let (status, key) = match &e {
+        StorageError::NotFound(_) => {
+            tracing::warn!("Document file {:?} is missing", file_path);
+            (StatusCode::NOT_FOUND, ErrorKey::DocumentNotFound)
         }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Submission not found")),
-        ),
-        Err(e) => {
-            tracing::error!("Database error: {}", e);
+        StorageError::InvalidKey(_) => {
+            tracing::error!(
+                "Refusing stored path {:?} outside the upload directory",
+                file_path
+            );
+            (StatusCode::NOT_FOUND, ErrorKey::DocumentNotFound)
+        }
+        _ => {
+            tracing::error!("Failed to read file {:?}: {}", file_path, e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Database error")),
+                ErrorKey::ReadDocumentFailed,
             )
         }
+    };
+    (status, Json(ApiResponse::<()>::error(key))).into_response()
+}
+
+/// Attachment response for the current file of a document
+fn document_attachment(document: &Document, body: Body) -> Response {
+    let filename = attachment_filename(document.original_filename.as_deref().unwrap_or("document"));
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            header::CONTENT_TYPE,
+            document
+                .mime_type
+                .as_deref()
+                .unwrap_or("application/octet-stream"),
+        )
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        );
+    if let Some(size) = document.file_size {
+        response = response.header(header::CONTENT_LENGTH, size);
     }
+    response.body(body).unwrap()
 }
 
-/// Get admin dashboard statistics
-pub async fn get_dashboard_stats(
+/// Read a stored document file whole, decrypting it if needed
+///
+/// Failures are logged and turned into an error response.
+async fn read_document_file(
+    state: &AppState,
+    file_path: &str,
+    encrypted: bool,
+) -> Result<bytes::Bytes, Response> {
+    let data = state
+        .store
+        .get(file_path)
+        .await
+        .map_err(|e| storage_error_response(file_path, e))?;
+    decrypt_stored(state.file_key.as_ref(), data, encrypted)
+        .map_err(|e| decrypt_error_response(file_path, e))
+}
+
+/// Response for a stored file that could not be decrypted
+fn decrypt_error_response(file_path: &str, e: FileCryptoError) -> Response {
+    tracing::error!("Failed to decrypt file {:?}: {}", file_path, e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ApiResponse::<()>::error(ErrorKey::ReadDocumentFailed)),
+    )
+        .into_response()
+}
+
+/// Strip characters that would break out of a quoted Content-Disposition filename
+fn attachment_filename(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect()
+}
+
+// =============================================================================
+// Audit Log
+// =============================================================================
+
+/// List audit log entries, newest first (admin)
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit",
+    tag = "admin",
+    params(ListAuditLogQuery),
+    responses(
+        (status = 200, description = "Page of audit entries, newest first", body = ApiResponse<PaginatedResponse<AuditLogResponse>>),
+        (status = 400, description = "Invalid filter", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn list_audit_log(
     State(state): State<AppState>,
-    Extension(_admin): Extension<AdminUser>,
+    Extension(admin): Extension<AdminUser>,
+    Query(query): Query<ListAuditLogQuery>,
 ) -> impl IntoResponse {
-    // Get counts by status
-    let stats = sqlx::query_as::<_, (String, i64)>(
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * per_page;
+
+    // Unset filters are passed as NULL and match every row
+    const FILTER: &str = r#"
+        WHERE ($1::uuid IS NULL OR entity_id = $1)
+          AND ($2::text IS NULL OR action::text = $2)
+          AND ($3::timestamptz IS NULL OR created_at >= $3)
+          AND ($4::timestamptz IS NULL OR created_at < $4)
+          AND ($5::uuid IS NULL OR request_id = $5)
+    "#;
+
+    let entries = sqlx::query_as::<_, AuditLogEntry>(&format!(
         r#"
-        SELECT status::text, COUNT(*) as count
-        FROM submissions
-        GROUP BY status
+        SELECT id, action::text AS action, entity_type, entity_id, actor_type,
+               actor_id, actor_ip, details, request_id, created_at
+        FROM audit_log
+        {}
+        ORDER BY created_at DESC, id
+        LIMIT $6 OFFSET $7
         "#,
-    )
+        FILTER
+    ))
+    .bind(query.entity_id)
+    .bind(&query.action)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(query.request_id)
+    .bind(per_page)
+    .bind(offset)
     .fetch_all(&state.pool)
-    .await
-    .unwrap_or_default();
+    .await;
 
-    let total_documents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM documents")
+    let total = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM audit_log {}", FILTER))
+        .bind(query.entity_id)
+        .bind(&query.action)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.request_id)
         .fetch_one(&state.pool)
-        .await
-        .unwrap_or(0);
+        .await;
 
-    let pending_slots: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM calendar_slots WHERE is_available = true AND slot_start > NOW()",
-    )
-    .fetch_one(&state.pool)
-    .await
-    .unwrap_or(0);
+    let (entries, total) = match (entries, total) {
+        (Ok(entries), Ok(total)) => (entries, total),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("Failed to list audit log: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::LoadAuditLogFailed)),
+            );
+        }
+    };
 
-    let stats_map: std::collections::HashMap<String, i64> = stats.into_iter().collect();
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    tracing::info!(
+        "Admin {} listed audit log (page {}, {} results)",
+        admin.username,
+        page,
+        entries.len()
+    );
 
     (
         StatusCode::OK,
-        Json(ApiResponse::success(serde_json::json!({
-            "submissions_by_status": stats_map,
-            "total_documents": total_documents,
-            "available_meeting_slots": pending_slots
-        }))),
+        Json(ApiResponse::success(PaginatedResponse {
+            items: entries
+                .into_iter()
+                .map(AuditLogResponse::from)
+                .collect::<Vec<_>>(),
+            total,
+            page,
+            per_page,
+            total_pages,
+            next_cursor: None,
+        })),
     )
 }
 
@@ -512,14 +2820,136 @@ pub async fn get_dashboard_stats(
 // Export Endpoints
 // =============================================================================
 
+/// One row of the submissions CSV export
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SubmissionCsvRow {
+    pub slug: String,
+    pub submitter_name: String,
+    pub organization: String,
+    #[serde(rename = "department")]
+    pub organization_department: Option<String>,
+    pub status: SubmissionStatus,
+    pub created_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub retention_expiry_date: DateTime<Utc>,
+    pub document_count: i64,
+}
+
+/// Serialize submission rows as CSV with a header line
+fn write_submissions_csv(rows: &[SubmissionCsvRow]) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
+
+/// Export the submission list as CSV (admin)
+///
+/// Takes the same `status` and `search` filters as the list endpoint, without
+/// pagination.
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/export.csv",
+    tag = "admin",
+    params(ListSubmissionsQuery),
+    responses(
+        (status = 200, description = "Submissions as CSV", body = String, content_type = "text/csv"),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn export_submissions_csv(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Query(query): Query<ListSubmissionsQuery>,
+) -> Response {
+    let search_pattern = query.search.as_ref().map(|s| format!("%{}%", s));
+
+    // Unset filters are passed as NULL and match every row
+    let rows = sqlx::query_as::<_, SubmissionCsvRow>(
+        r#"
+        SELECT s.slug, s.submitter_name, s.organization, s.organization_department,
+               s.status, s.created_at, s.submitted_at, s.retention_expiry_date,
+               (SELECT COUNT(*) FROM documents d WHERE d.submission_id = s.id) AS document_count
+        FROM submissions s
+        WHERE ($1::submission_status IS NULL OR s.status = $1)
+          AND ($2::text IS NULL
+               OR s.submitter_name ILIKE $2
+               OR s.organization ILIKE $2
+               OR s.slug ILIKE $2)
+        ORDER BY s.created_at DESC
+        "#,
+    )
+    .bind(query.status)
+    .bind(&search_pattern)
+    .fetch_all(&state.pool)
+    .await;
+
+    let rows = match rows {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to load submissions for CSV export: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(ErrorKey::DatabaseError)),
+            )
+                .into_response();
+        }
+    };
+
+    let csv_data = match write_submissions_csv(&rows) {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::error!("Failed to write submissions CSV: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(ErrorKey::ExportFailed)),
+            )
+                .into_response();
+        }
+    };
+
+    tracing::info!(
+        "Admin {} exported {} submissions as CSV",
+        admin.username,
+        rows.len()
+    );
+
+    let filename = format!("submissions_{}.csv", Utc::now().format("%Y%m%d"));
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(Body::from(csv_data))
+        .unwrap()
+}
+
 /// Export submission data as JSON
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SubmissionExport {
-    pub submission: SubmissionResponse,
+    pub submission: AdminSubmissionResponse,
     pub exported_at: chrono::DateTime<chrono::Utc>,
     pub exported_by: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/export",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "Submission export as a JSON attachment", body = SubmissionExport),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn export_submission_json(
     State(state): State<AppState>,
     Extension(admin): Extension<AdminUser>,
@@ -540,21 +2970,10 @@ pub async fn export_submission_json(
             .await
             .unwrap_or_default();
 
-            let response = SubmissionResponse {
-                id: sub.id,
-                slug: sub.slug.clone(),
-                submitter_name: sub.submitter_name,
-                submitter_email: sub.submitter_email,
-                organization: sub.organization,
-                organization_department: sub.organization_department,
-                status: sub.status,
-                notes: sub.notes,
-                created_at: sub.created_at,
-                updated_at: sub.updated_at,
-                submitted_at: sub.submitted_at,
-                retention_expiry_date: sub.retention_expiry_date,
-                documents: documents.into_iter().map(DocumentResponse::from).collect(),
-            };
+            let response = AdminSubmissionResponse::new(
+                sub.clone(),
+                documents.into_iter().map(DocumentResponse::from).collect(),
+            );
 
             let export = SubmissionExport {
                 submission: response,
@@ -581,27 +3000,179 @@ pub async fn export_submission_json(
                 .body(Body::from(json_data))
                 .unwrap()
         }
-        Ok(None) => Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(Body::from(
-                serde_json::to_string(&ApiResponse::<()>::error("Submission not found")).unwrap(),
-            ))
-            .unwrap(),
-        Err(e) => {
-            tracing::error!("Database error: {}", e);
-            Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(Body::from(
-                    serde_json::to_string(&ApiResponse::<()>::error("Database error")).unwrap(),
-                ))
-                .unwrap()
+        Ok(None) => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(&ApiResponse::<()>::error(ErrorKey::SubmissionNotFound))
+                    .unwrap(),
+            ))
+            .unwrap(),
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::to_string(&ApiResponse::<()>::error(ErrorKey::DatabaseError))
+                        .unwrap(),
+                ))
+                .unwrap()
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum BulkExportError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Serialization error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Write error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Export every submission as JSON Lines (admin)
+///
+/// One `SubmissionExport` object per line, documents embedded, oldest
+/// submission first. The body is streamed while submissions are read in
+/// batches, so the export size does not affect memory use.
+#[utoipa::path(
+    get,
+    path = "/api/admin/export/all.jsonl",
+    tag = "admin",
+    params(BulkExportQuery),
+    responses(
+        (status = 200, description = "One SubmissionExport per line", body = String, content_type = "application/x-ndjson"),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn export_all_submissions_jsonl(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Query(query): Query<BulkExportQuery>,
+) -> Response {
+    let (pipe_writer, pipe_reader) = tokio::io::duplex(EXPORT_STREAM_BUFFER);
+    let pool = state.pool.clone();
+    let username = admin.username.clone();
+    tokio::spawn(async move {
+        match write_jsonl_export(&pool, &query, &username, BULK_EXPORT_BATCH, pipe_writer).await {
+            Ok(count) => tracing::info!(
+                "Admin {} exported {} submissions as JSON Lines",
+                username,
+                count
+            ),
+            // Usually the client went away; the export is left truncated
+            Err(e) => tracing::warn!("JSON Lines export for {} aborted: {}", username, e),
+        }
+    });
+
+    let filename = format!("submissions_{}.jsonl", Utc::now().format("%Y%m%d"));
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(Body::from_stream(ReaderStream::new(pipe_reader)))
+        .unwrap()
+}
+
+/// Write matching submissions to `out` as JSON Lines, `batch_size` at a time
+///
+/// Batches are read by keyset on `(created_at, id)` so submissions created
+/// during the export neither shift nor repeat rows. Returns the number of
+/// lines written.
+async fn write_jsonl_export(
+    pool: &sqlx::PgPool,
+    query: &BulkExportQuery,
+    exported_by: &str,
+    batch_size: i64,
+    mut out: impl AsyncWrite + Unpin,
+) -> Result<usize, BulkExportError> {
+    let mut after: Option<(DateTime<Utc>, Uuid)> = None;
+    let mut count = 0;
+
+    loop {
+        let submissions = sqlx::query_as::<_, Submission>(
+            r#"
+            SELECT * FROM submissions
+            WHERE ($1::submission_status IS NULL OR status = $1)
+              AND ($2::timestamptz IS NULL OR updated_at >= $2)
+              AND ($3::timestamptz IS NULL OR (created_at, id) > ($3, $4::uuid))
+            ORDER BY created_at, id
+            LIMIT $5
+            "#,
+        )
+        .bind(query.status)
+        .bind(query.since)
+        .bind(after.map(|(created_at, _)| created_at))
+        .bind(after.map(|(_, id)| id))
+        .bind(batch_size)
+        .fetch_all(pool)
+        .await?;
+
+        let Some(last) = submissions.last() else {
+            break;
+        };
+        after = Some((last.created_at, last.id));
+        let is_last_batch = (submissions.len() as i64) < batch_size;
+
+        let submission_ids: Vec<Uuid> = submissions.iter().map(|s| s.id).collect();
+        let documents = sqlx::query_as::<_, Document>(
+            "SELECT * FROM documents WHERE submission_id = ANY($1) ORDER BY created_at",
+        )
+        .bind(&submission_ids)
+        .fetch_all(pool)
+        .await?;
+        let mut docs_by_submission: std::collections::HashMap<Uuid, Vec<DocumentResponse>> =
+            std::collections::HashMap::new();
+        for doc in documents {
+            docs_by_submission
+                .entry(doc.submission_id)
+                .or_default()
+                .push(DocumentResponse::from(doc));
+        }
+
+        let exported_at = Utc::now();
+        let mut lines = Vec::new();
+        for sub in submissions {
+            let documents = docs_by_submission.remove(&sub.id).unwrap_or_default();
+            let export = SubmissionExport {
+                submission: AdminSubmissionResponse::new(sub, documents),
+                exported_at,
+                exported_by: exported_by.to_string(),
+            };
+            serde_json::to_writer(&mut lines, &export)?;
+            lines.push(b'\n');
+            count += 1;
+        }
+        out.write_all(&lines).await?;
+
+        if is_last_batch {
+            break;
         }
     }
+
+    out.shutdown().await?;
+    Ok(count)
 }
 
 /// Export submission files as ZIP
+#[utoipa::path(
+    get,
+    path = "/api/admin/submissions/{id}/export/files",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "ZIP with metadata and document files", body = Vec<u8>, content_type = "application/zip"),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn export_submission_files(
     State(state): State<AppState>,
     Extension(admin): Extension<AdminUser>,
@@ -622,67 +3193,20 @@ pub async fn export_submission_files(
             .await
             .unwrap_or_default();
 
-            // Create ZIP file in memory
-            let mut zip_buffer = Cursor::new(Vec::new());
-            {
-                let mut zip = ZipWriter::new(&mut zip_buffer);
-                let options =
-                    FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
-
-                // Add submission metadata as JSON
-                let metadata = SubmissionExport {
-                    submission: SubmissionResponse {
-                        id: sub.id,
-                        slug: sub.slug.clone(),
-                        submitter_name: sub.submitter_name.clone(),
-                        submitter_email: sub.submitter_email.clone(),
-                        organization: sub.organization.clone(),
-                        organization_department: sub.organization_department.clone(),
-                        status: sub.status,
-                        notes: sub.notes.clone(),
-                        created_at: sub.created_at,
-                        updated_at: sub.updated_at,
-                        submitted_at: sub.submitted_at,
-                        retention_expiry_date: sub.retention_expiry_date,
-                        documents: documents
-                            .iter()
-                            .cloned()
-                            .map(DocumentResponse::from)
-                            .collect(),
-                    },
-                    exported_at: chrono::Utc::now(),
-                    exported_by: admin.username.clone(),
-                };
-
-                let metadata_json = serde_json::to_string_pretty(&metadata).unwrap_or_default();
-                if zip.start_file("metadata.json", options).is_ok() {
-                    let _ = zip.write_all(metadata_json.as_bytes());
-                }
-
-                // Add each document file
-                for doc in &documents {
-                    if let Some(ref file_path) = doc.file_path {
-                        let path = std::path::Path::new(file_path);
-                        if path.exists() {
-                            if let Ok(file_data) = tokio::fs::read(path).await {
-                                let fallback = doc
-                                    .filename
-                                    .clone()
-                                    .unwrap_or_else(|| "unknown".to_string());
-                                let filename = doc.original_filename.as_ref().unwrap_or(&fallback);
-                                if zip
-                                    .start_file(format!("files/{}", filename), options)
-                                    .is_ok()
-                                {
-                                    let _ = zip.write_all(&file_data);
-                                }
-                            }
-                        }
-                    }
-                }
-
-                let _ = zip.finish();
-            }
+            let metadata = SubmissionExport {
+                submission: AdminSubmissionResponse::new(
+                    sub.clone(),
+                    documents
+                        .iter()
+                        .cloned()
+                        .map(DocumentResponse::from)
+                        .collect(),
+                ),
+                exported_at: chrono::Utc::now(),
+                exported_by: admin.username.clone(),
+            };
+            let metadata_json = serde_json::to_string_pretty(&metadata).unwrap_or_default();
+            let body = stream_export_zip(&state, "metadata.json", metadata_json, documents, id);
 
             tracing::info!(
                 "Admin {} exported submission {} files as ZIP",
@@ -690,7 +3214,6 @@ pub async fn export_submission_files(
                 id
             );
 
-            let zip_data = zip_buffer.into_inner();
             let filename = format!("submission_{}_files.zip", sub.slug);
 
             Response::builder()
@@ -700,14 +3223,15 @@ pub async fn export_submission_files(
                     header::CONTENT_DISPOSITION,
                     format!("attachment; filename=\"{}\"", filename),
                 )
-                .body(Body::from(zip_data))
+                .body(body)
                 .unwrap()
         }
         Ok(None) => Response::builder()
             .status(StatusCode::NOT_FOUND)
             .header(header::CONTENT_TYPE, "application/json")
             .body(Body::from(
-                serde_json::to_string(&ApiResponse::<()>::error("Submission not found")).unwrap(),
+                serde_json::to_string(&ApiResponse::<()>::error(ErrorKey::SubmissionNotFound))
+                    .unwrap(),
             ))
             .unwrap(),
         Err(e) => {
@@ -716,36 +3240,147 @@ pub async fn export_submission_files(
                 .status(StatusCode::INTERNAL_SERVER_ERROR)
                 .header(header::CONTENT_TYPE, "application/json")
                 .body(Body::from(
-                    serde_json::to_string(&ApiResponse::<()>::error("Database error")).unwrap(),
+                    serde_json::to_string(&ApiResponse::<()>::error(ErrorKey::DatabaseError))
+                        .unwrap(),
                 ))
                 .unwrap()
         }
     }
 }
 
+/// Stream a ZIP of `documents` behind a JSON manifest named `manifest_name`
+///
+/// The archive is written on a blocking thread into one end of a pipe; the
+/// other end is streamed to the client as it fills.
+pub(crate) fn stream_export_zip(
+    state: &AppState,
+    manifest_name: &'static str,
+    manifest_json: String,
+    documents: Vec<Document>,
+    submission_id: Uuid,
+) -> Body {
+    let (pipe_writer, pipe_reader) = tokio::io::duplex(EXPORT_STREAM_BUFFER);
+    let store = state.store.clone();
+    let file_key = state.file_key.clone();
+    let handle = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        let out = SyncIoBridge::new_with_handle(pipe_writer, handle.clone());
+        if let Err(e) = write_export_zip(
+            &handle,
+            store.as_ref(),
+            file_key.as_ref(),
+            manifest_name,
+            &manifest_json,
+            &documents,
+            out,
+        ) {
+            // Usually the client went away; the archive is left truncated
+            tracing::warn!("ZIP export of submission {} aborted: {}", submission_id, e);
+        }
+    });
+    Body::from_stream(ReaderStream::new(pipe_reader))
+}
+
+/// Name for a file in the archive that no earlier entry uses
+///
+/// A second `Nota.pdf` becomes `Nota (2).pdf`.
+fn unique_entry_name(used: &mut HashSet<String>, filename: &str) -> String {
+    let mut name = format!("files/{}", filename);
+    let (stem, extension) = match filename.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (filename, String::new()),
+    };
+    let mut n = 2;
+    while !used.insert(name.clone()) {
+        name = format!("files/{} ({}){}", stem, n, extension);
+        n += 1;
+    }
+    name
+}
+
+/// Write an export archive to `out`: the manifest first, then every
+/// document file under `files/`
+///
+/// Runs on a blocking thread; files are copied from the store in chunks via
+/// `handle`. Files missing from the store are skipped with a warning.
+pub(crate) fn write_export_zip(
+    handle: &tokio::runtime::Handle,
+    store: &dyn FileStore,
+    file_key: Option<&FileKey>,
+    manifest_name: &str,
+    manifest_json: &str,
+    documents: &[Document],
+    out: impl Write,
+) -> zip::result::ZipResult<()> {
+    let mut zip = ZipWriter::new_stream(out);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(manifest_name, options)?;
+    zip.write_all(manifest_json.as_bytes())?;
+
+    let mut used_names = HashSet::new();
+    for doc in documents {
+        let Some(file_path) = &doc.file_path else {
+            continue;
+        };
+        let fallback = doc.filename.as_deref().unwrap_or("unknown");
+        let filename = doc.original_filename.as_deref().unwrap_or(fallback);
+
+        let reader = handle
+            .block_on(store.open(file_path))
+            .map_err(|e| e.to_string())
+            .and_then(|reader| {
+                decrypt_stored_reader(file_key, reader, doc.encrypted).map_err(|e| e.to_string())
+            });
+        let mut reader = match reader {
+            Ok(reader) => BufReader::new(SyncIoBridge::new_with_handle(reader, handle.clone())),
+            Err(e) => {
+                tracing::warn!("Skipping file {:?} in export: {}", file_path, e);
+                continue;
+            }
+        };
+        // The first chunk of an encrypted file is authenticated before its
+        // entry starts, so a damaged file is left out; damage further in
+        // aborts the archive
+        if let Err(e) = reader.fill_buf() {
+            tracing::warn!("Skipping file {:?} in export: {}", file_path, e);
+            continue;
+        }
+
+        zip.start_file(unique_entry_name(&mut used_names, filename), options)?;
+        std::io::copy(&mut reader, &mut zip)?;
+    }
+
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
 // =============================================================================
 // Maintenance Functions
 // =============================================================================
 
-/// Clean up abandoned draft submissions older than 1 hour
+/// Clean up abandoned draft submissions older than `max_age_minutes`
 ///
 /// This function is called periodically from the cleanup task in main.rs.
 /// It removes draft submissions that were never submitted, including their
 /// files from disk.
 pub async fn cleanup_abandoned_drafts(
     pool: &sqlx::PgPool,
-    upload_dir: &std::path::Path,
+    store: &dyn FileStore,
+    max_age_minutes: i64,
 ) -> Result<u64, sqlx::Error> {
-    // 1. Find and delete drafts older than 1 hour, returning the deleted rows
+    // 1. Find and delete drafts older than the threshold, returning the deleted rows
     //    This is atomic - no race condition between finding and deleting
     let deleted_drafts = sqlx::query_as::<_, Submission>(
         r#"
         DELETE FROM submissions
         WHERE status = 'draft'
-        AND created_at < NOW() - INTERVAL '1 hour'
+        AND NOT legal_hold
+        AND created_at < NOW() - make_interval(mins => $1::int)
         RETURNING *
         "#,
     )
+    .bind(max_age_minutes)
     .fetch_all(pool)
     .await?;
 
@@ -755,22 +3390,874 @@ pub async fn cleanup_abandoned_drafts(
 
     let count = deleted_drafts.len();
 
-    // 2. Delete files from disk for each deleted draft
+    // 2. Delete stored files for each deleted draft
     //    Safe because these drafts are already deleted from DB
     for draft in &deleted_drafts {
-        let draft_dir = upload_dir.join(&draft.slug);
-        if draft_dir.exists() {
-            if let Err(e) = tokio::fs::remove_dir_all(&draft_dir).await {
-                tracing::warn!(
-                    "Failed to remove abandoned draft directory {:?}: {}",
-                    draft_dir,
-                    e
-                );
+        if let Err(e) = delete_submission_files(store, draft.id, draft.created_at).await {
+            tracing::warn!(
+                "Failed to remove files of abandoned draft {}: {}",
+                draft.id,
+                e
+            );
+        }
+    }
+
+    Ok(count as u64)
+}
+
+/// Delete submissions whose `retention_expiry_date` has passed
+///
+/// Called once a day from the cleanup task in main.rs; submissions under a
+/// legal hold are skipped. Each deletion gets a `data_deleted` audit entry.
+///
+/// With `dry_run` set nothing is deleted, only logged.
+pub async fn purge_expired_submissions(
+    pool: &sqlx::PgPool,
+    store: &dyn FileStore,
+    dry_run: bool,
+) -> Result<u64, sqlx::Error> {
+    if dry_run {
+        let expired = sqlx::query_as::<_, Submission>(
+            "SELECT * FROM submissions WHERE retention_expiry_date < NOW() AND NOT legal_hold",
+        )
+        .fetch_all(pool)
+        .await?;
+        for submission in &expired {
+            tracing::info!(
+                "Retention purge (dry run): would delete submission {} (expired {})",
+                submission.slug,
+                submission.retention_expiry_date
+            );
+        }
+        return Ok(expired.len() as u64);
+    }
+
+    let purged = sqlx::query_as::<_, Submission>(
+        "DELETE FROM submissions WHERE retention_expiry_date < NOW() AND NOT legal_hold RETURNING *",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for submission in &purged {
+        if let Err(e) = delete_submission_files(store, submission.id, submission.created_at).await {
+            tracing::warn!(
+                "Failed to remove files of expired submission {}: {}",
+                submission.id,
+                e
+            );
+        }
+
+        let _ = sqlx::query(
+            r#"
+            INSERT INTO audit_log (action, entity_type, entity_id, actor_type, details, request_id)
+            VALUES ('data_deleted'::audit_action, 'submission', $1, 'system', $2, $3)
+            "#,
+        )
+        .bind(submission.id)
+        .bind(serde_json::json!({
+            "slug": submission.slug,
+            "reason": "retention_expired",
+            "retention_expiry_date": submission.retention_expiry_date,
+        }))
+        .bind(current_request_id())
+        .execute(pool)
+        .await;
+    }
+
+    Ok(purged.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv_row(organization: &str) -> SubmissionCsvRow {
+        let created_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        SubmissionCsvRow {
+            slug: "rr-20231114-abcde".to_string(),
+            submitter_name: "Vries, Jan de".to_string(),
+            organization: organization.to_string(),
+            organization_department: None,
+            status: SubmissionStatus::Submitted,
+            created_at,
+            submitted_at: Some(created_at),
+            retention_expiry_date: created_at + chrono::Duration::days(365),
+            document_count: 3,
+        }
+    }
+
+    #[test]
+    fn test_submissions_csv_round_trips_quotes_and_commas() {
+        let organization = r#"Stichting "Het Loket", afdeling Noord"#;
+        let data = write_submissions_csv(&[csv_row(organization)]).unwrap();
+
+        let mut reader = csv::Reader::from_reader(data.as_slice());
+        let headers = reader.headers().unwrap().clone();
+        assert_eq!(
+            headers.iter().collect::<Vec<_>>(),
+            [
+                "slug",
+                "submitter_name",
+                "organization",
+                "department",
+                "status",
+                "created_at",
+                "submitted_at",
+                "retention_expiry_date",
+                "document_count"
+            ]
+        );
+
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(&records[0][1], "Vries, Jan de");
+        assert_eq!(&records[0][2], organization);
+        assert_eq!(&records[0][3], "");
+        assert_eq!(&records[0][4], "submitted");
+        assert_eq!(&records[0][8], "3");
+    }
+
+    #[test]
+    fn test_attachment_filename_strips_quotes() {
+        assert_eq!(attachment_filename("beleid \"v2\".pdf"), "beleid v2.pdf");
+        assert_eq!(attachment_filename("a\r\nb\\c.pdf"), "abc.pdf");
+    }
+
+    /// Insert a submission whose retention period ended yesterday
+    async fn insert_expired_submission(pool: &sqlx::PgPool, legal_hold: bool) -> Uuid {
+        let slug = format!("rr-20240101-{}", &Uuid::new_v4().simple().to_string()[..5]);
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO submissions
+                (slug, submitter_name, organization, status, retention_expiry_date, legal_hold)
+            VALUES ($1, 'Jan', 'Gemeente', 'submitted', NOW() - INTERVAL '1 day', $2)
+            RETURNING id
+            "#,
+        )
+        .bind(&slug)
+        .bind(legal_hold)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_submission_cursor_round_trips() {
+        let cursor = SubmissionCursor {
+            created_at: DateTime::from_timestamp(1_700_000_000, 123_456_000).unwrap(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(SubmissionCursor::decode(&cursor.encode()), Some(cursor));
+
+        assert_eq!(SubmissionCursor::decode("not a cursor"), None);
+        assert_eq!(
+            SubmissionCursor::decode(&URL_SAFE_NO_PAD.encode("x,y")),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cursor_pagination_covers_rows_once_despite_inserts() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        // A unique organization keeps other tests' rows out of the listing
+        let organization = format!("Cursor {}", Uuid::new_v4());
+        let insert = |age_minutes: i64| {
+            let pool = pool.clone();
+            let organization = organization.clone();
+            async move {
+                let slug = format!("rr-20240101-{}", &Uuid::new_v4().simple().to_string()[..5]);
+                sqlx::query_scalar::<_, Uuid>(
+                    r#"
+                    INSERT INTO submissions (slug, submitter_name, organization, created_at)
+                    VALUES ($1, 'Jan', $2, date_trunc('minute', NOW()) - make_interval(mins => $3::int))
+                    RETURNING id
+                    "#,
+                )
+                .bind(&slug)
+                .bind(&organization)
+                .bind(age_minutes)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+            }
+        };
+
+        // Pairs of rows share a timestamp so the id tie-break is exercised
+        let mut expected = Vec::new();
+        for age in [10, 10, 20, 20, 30, 30, 40] {
+            expected.push(insert(age).await);
+        }
+
+        let pattern = format!("%{}%", organization);
+        let filter = SubmissionFilter {
+            search_pattern: Some(&pattern),
+            ..Default::default()
+        };
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = fetch_submission_page(&pool, &filter, cursor.as_ref(), 2, 0)
+                .await
+                .unwrap();
+            if page.is_empty() {
+                break;
             }
+            if seen.is_empty() {
+                // A newer row lands before the cursor and is not listed; an
+                // older one lands after it and is
+                insert(0).await;
+                expected.push(insert(50).await);
+            }
+            seen.extend(page.iter().map(|sub| sub.id));
+            cursor = page.last().map(SubmissionCursor::from);
         }
+
+        let mut unique = seen.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), seen.len(), "a row was listed twice");
+        expected.sort();
+        assert_eq!(unique, expected);
+
+        sqlx::query("DELETE FROM submissions WHERE organization = $1")
+            .bind(&organization)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    async fn listed_ids(pool: &sqlx::PgPool, filter: &SubmissionFilter<'_>) -> Vec<Uuid> {
+        let ids: Vec<Uuid> = fetch_submission_page(pool, filter, None, 100, 0)
+            .await
+            .unwrap()
+            .iter()
+            .map(|sub| sub.id)
+            .collect();
+        assert_eq!(
+            count_submissions(pool, filter).await.unwrap(),
+            ids.len() as i64
+        );
+        ids
     }
 
-    tracing::info!("Cleaned up {} abandoned draft submissions", count);
+    #[tokio::test]
+    async fn test_submission_filters_combine() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
 
-    Ok(count as u64)
+        let tag = Uuid::new_v4().simple().to_string();
+        let now = Utc::now();
+        let mut ids = Vec::new();
+        for (status, name, age_days) in [
+            ("submitted", "Jansen", 1),
+            ("submitted", "Jansen", 10),
+            ("submitted", "Pietersen", 1),
+            ("draft", "Jansen", 1),
+        ] {
+            let slug = format!("rr-20240101-{}", &Uuid::new_v4().simple().to_string()[..5]);
+            let id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO submissions (slug, submitter_name, organization, status, created_at)
+                VALUES ($1, $2, $3, $4::submission_status, $5)
+                RETURNING id
+                "#,
+            )
+            .bind(&slug)
+            .bind(format!("{} {}", name, tag))
+            .bind(format!("Gemeente {}", tag))
+            .bind(status)
+            .bind(now - chrono::Duration::days(age_days))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO documents (submission_id, category, classification, file_path)
+            VALUES ($1, 'circular', 'restricted', 'a.pdf'), ($2, 'circular', 'public', 'b.pdf')
+            "#,
+        )
+        .bind(ids[0])
+        .bind(ids[2])
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Each filter excludes one of the other rows: the draft by status,
+        // Pietersen by search and the older row by date
+        let jansen = format!("%Jansen {}%", tag);
+        let filter = SubmissionFilter {
+            status: Some(SubmissionStatus::Submitted),
+            search_pattern: Some(&jansen),
+            created_from: Some(now - chrono::Duration::days(5)),
+            created_to: Some(now),
+            ..Default::default()
+        };
+        assert_eq!(listed_ids(&pool, &filter).await, vec![ids[0]]);
+
+        let filter = SubmissionFilter {
+            created_from: None,
+            created_to: Some(now - chrono::Duration::days(5)),
+            ..filter
+        };
+        assert_eq!(listed_ids(&pool, &filter).await, vec![ids[1]]);
+
+        let organization = format!("%Gemeente {}%", tag);
+        let filter = SubmissionFilter {
+            search_pattern: Some(&organization),
+            classification: Some(DocumentClassification::Restricted),
+            ..Default::default()
+        };
+        assert_eq!(listed_ids(&pool, &filter).await, vec![ids[0]]);
+
+        sqlx::query("DELETE FROM submissions WHERE organization = $1")
+            .bind(format!("Gemeente {}", tag))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_export_writes_one_object_per_submission() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        let tag = Uuid::new_v4().simple().to_string();
+        let organization = format!("Gemeente {}", tag);
+        let since = Utc::now() - chrono::Duration::seconds(1);
+        let mut ids = Vec::new();
+        for status in ["draft", "draft", "draft", "submitted"] {
+            let slug = format!("rr-20240101-{}", &Uuid::new_v4().simple().to_string()[..5]);
+            let id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO submissions (slug, submitter_name, organization, status)
+                VALUES ($1, 'Jan', $2, $3::submission_status)
+                RETURNING id
+                "#,
+            )
+            .bind(&slug)
+            .bind(&organization)
+            .bind(status)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO documents (submission_id, category, classification, file_path)
+            VALUES ($1, 'circular', 'public', 'a.pdf'), ($1, 'circular', 'public', 'b.pdf')
+            "#,
+        )
+        .bind(ids[0])
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // A batch size of two makes the three drafts span several batches
+        let query = BulkExportQuery {
+            status: Some(SubmissionStatus::Draft),
+            since: Some(since),
+        };
+        let mut out = Vec::new();
+        let count = write_jsonl_export(&pool, &query, "beheerder", 2, &mut out)
+            .await
+            .unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.ends_with('\n'));
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), count);
+        assert!(lines.iter().all(|l| l["exported_by"] == "beheerder"));
+
+        let ours: Vec<&serde_json::Value> = lines
+            .iter()
+            .filter(|l| l["submission"]["organization"] == organization.as_str())
+            .collect();
+        let exported_ids: Vec<Uuid> = ours
+            .iter()
+            .map(|l| l["submission"]["id"].as_str().unwrap().parse().unwrap())
+            .collect();
+        assert_eq!(exported_ids, ids[..3]);
+        assert_eq!(
+            ours[0]["submission"]["documents"].as_array().unwrap().len(),
+            2
+        );
+        assert_eq!(
+            ours[1]["submission"]["documents"].as_array().unwrap().len(),
+            0
+        );
+
+        sqlx::query("DELETE FROM submissions WHERE organization = $1")
+            .bind(&organization)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_uses_full_text_for_words_not_short_prefixes() {
+        assert!(!uses_full_text("rr"));
+        assert!(!uses_full_text("Gem"));
+        assert!(uses_full_text("jeugdzorg"));
+        assert!(uses_full_text("ab cd"));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_status_rejects_mixed_batch_atomically() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        let organization = format!("Gemeente {}", Uuid::new_v4().simple());
+        let mut ids = Vec::new();
+        for status in ["submitted", "submitted", "draft"] {
+            let slug = format!("rr-20240101-{}", &Uuid::new_v4().simple().to_string()[..5]);
+            let id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO submissions (slug, submitter_name, organization, status)
+                VALUES ($1, 'Jan', $2, $3::submission_status)
+                RETURNING id
+                "#,
+            )
+            .bind(&slug)
+            .bind(&organization)
+            .bind(status)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        let admin_id = Uuid::new_v4();
+        let statuses = || async {
+            sqlx::query_scalar::<_, SubmissionStatus>(
+                "SELECT status FROM submissions WHERE organization = $1 ORDER BY created_at, id",
+            )
+            .bind(&organization)
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+        };
+        let audited = || async {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM audit_log WHERE actor_id = $1 AND action = 'submission_status_changed'",
+            )
+            .bind(admin_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+        };
+
+        // A draft cannot go under review, so nothing changes
+        let result =
+            apply_bulk_status(&pool, admin_id, &ids, SubmissionStatus::UnderReview, None).await;
+        match result {
+            Err(BulkStatusError::InvalidTransition(id)) => assert_eq!(id, ids[2]),
+            other => panic!("expected InvalidTransition, got {:?}", other),
+        }
+        use SubmissionStatus::{Draft, Submitted};
+        assert_eq!(statuses().await, [Submitted, Submitted, Draft]);
+        assert_eq!(audited().await, 0);
+
+        let missing = Uuid::new_v4();
+        let result = apply_bulk_status(
+            &pool,
+            admin_id,
+            &[ids[0], missing],
+            SubmissionStatus::UnderReview,
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(BulkStatusError::NotFound(id)) if id == missing));
+
+        let updated = apply_bulk_status(
+            &pool,
+            admin_id,
+            &ids[..2],
+            SubmissionStatus::UnderReview,
+            Some("Besproken in overleg"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.iter().map(|s| s.id).collect::<Vec<_>>(), ids[..2]);
+        assert_eq!(audited().await, 2);
+
+        sqlx::query("DELETE FROM submissions WHERE organization = $1")
+            .bind(&organization)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM audit_log WHERE actor_id = $1")
+            .bind(admin_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_full_text_search_matches_notes_and_documents() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        let tag = Uuid::new_v4().simple().to_string();
+        let organization = format!("Gemeente {}", tag);
+        let mut ids = Vec::new();
+        for notes in [
+            "Wachtlijst {tag}: vragen over de wachtlijst {tag} in de jeugdzorg",
+            "Alleen de wachtlijst {tag} genoemd",
+            "Niets relevants",
+        ] {
+            let slug = format!("rr-20240101-{}", &Uuid::new_v4().simple().to_string()[..5]);
+            let id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO submissions (slug, submitter_name, organization, notes)
+                VALUES ($1, 'Jan', $2, $3)
+                RETURNING id
+                "#,
+            )
+            .bind(&slug)
+            .bind(&organization)
+            .bind(notes.replace("{tag}", &tag))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO documents (submission_id, category, classification, file_path, description)
+            VALUES ($1, 'circular', 'public', 'c.pdf', $2)
+            "#,
+        )
+        .bind(ids[2])
+        .bind(format!("Beleidsregel {} voor bijstandsuitkeringen", tag))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // The note word is invisible to the name/organization/slug search
+        let term = format!("wachtlijst {}", tag);
+        let pattern = format!("%{}%", term);
+        let filter = SubmissionFilter {
+            search_pattern: Some(&pattern),
+            ..Default::default()
+        };
+        assert!(listed_ids(&pool, &filter).await.is_empty());
+
+        // The older submission mentions the term more often and ranks first
+        let filter = SubmissionFilter {
+            full_text: Some(&term),
+            ..filter
+        };
+        assert_eq!(listed_ids(&pool, &filter).await, vec![ids[0], ids[1]]);
+
+        // Stemmed words in a document description match too
+        let term = format!("{} bijstandsuitkering", tag);
+        let pattern = format!("%{}%", term);
+        let filter = SubmissionFilter {
+            search_pattern: Some(&pattern),
+            full_text: Some(&term),
+            ..Default::default()
+        };
+        assert_eq!(listed_ids(&pool, &filter).await, vec![ids[2]]);
+
+        sqlx::query("DELETE FROM submissions WHERE organization = $1")
+            .bind(&organization)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_trends_bucket_by_day() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        // A date long before any other test's rows keeps the counts exact
+        let tag = Uuid::new_v4().simple().to_string();
+        let busy = format!("Gemeente A {}", tag);
+        let quiet = format!("Gemeente B {}", tag);
+        for (organization, created_at) in [
+            (&busy, "2001-03-03T23:30:00Z"),
+            (&quiet, "2001-03-03T08:00:00Z"),
+            (&busy, "2001-03-04T00:30:00Z"),
+        ] {
+            let slug = format!("rr-20010303-{}", &Uuid::new_v4().simple().to_string()[..5]);
+            sqlx::query(
+                r#"
+                INSERT INTO submissions (slug, submitter_name, organization, status, created_at)
+                VALUES ($1, 'Jan', $2, 'submitted', $3::timestamptz)
+                "#,
+            )
+            .bind(&slug)
+            .bind(organization)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let day = |d: &str| d.parse::<chrono::NaiveDate>().unwrap();
+        let per_day = submissions_per_day(&pool, day("2001-03-02"), day("2001-03-04"))
+            .await
+            .unwrap();
+        let per_day: Vec<(String, i64)> = per_day
+            .iter()
+            .map(|c| (c.day.to_string(), c.count))
+            .collect();
+        assert_eq!(
+            per_day,
+            [
+                ("2001-03-02".to_string(), 0),
+                ("2001-03-03".to_string(), 2),
+                ("2001-03-04".to_string(), 1),
+            ]
+        );
+
+        let top = most_active_organizations(&pool, day("2001-03-03"), day("2001-03-04"), 10)
+            .await
+            .unwrap();
+        let top: Vec<(&str, i64)> = top
+            .iter()
+            .map(|c| (c.organization.as_str(), c.count))
+            .collect();
+        assert_eq!(top, [(busy.as_str(), 2), (quiet.as_str(), 1)]);
+
+        // The window ends at the end of its last day
+        let top = most_active_organizations(&pool, day("2001-03-04"), day("2001-03-04"), 10)
+            .await
+            .unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].organization, busy);
+
+        sqlx::query("DELETE FROM submissions WHERE organization IN ($1, $2)")
+            .bind(&busy)
+            .bind(&quiet)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    async fn submission_exists(pool: &sqlx::PgPool, id: Uuid) -> bool {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM submissions WHERE id = $1)")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Dry run, legal hold and real purge in one test: the purges would
+    /// otherwise race each other on the shared database
+    #[tokio::test]
+    async fn test_purge_expired_submissions() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+        let root = std::env::temp_dir().join(format!("purge-test-{}", Uuid::new_v4()));
+        let store = crate::storage::FsStore::new(&root);
+
+        let held = insert_expired_submission(&pool, true).await;
+        let unheld = insert_expired_submission(&pool, false).await;
+        let key = crate::storage::document_key(&unheld.to_string(), "doc_report.pdf");
+        store
+            .put(&key, bytes::Bytes::from_static(b"%PDF-1.7"))
+            .await
+            .unwrap();
+
+        // Dry run reports the expired submission but keeps it
+        let purged = purge_expired_submissions(&pool, &store, true)
+            .await
+            .unwrap();
+        assert!(purged >= 1);
+        assert!(submission_exists(&pool, unheld).await);
+        assert!(root.join(&key).exists());
+
+        // A real run removes it and its files, but not the held one
+        let purged = purge_expired_submissions(&pool, &store, false)
+            .await
+            .unwrap();
+        assert!(purged >= 1);
+        assert!(!submission_exists(&pool, unheld).await);
+        assert!(!root.join(&key).exists());
+        assert!(submission_exists(&pool, held).await);
+
+        let reason: String = sqlx::query_scalar(
+            "SELECT details->>'reason' FROM audit_log WHERE action = 'data_deleted' AND entity_id = $1",
+        )
+        .bind(unheld)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(reason, "retention_expired");
+
+        sqlx::query("DELETE FROM submissions WHERE id = $1")
+            .bind(held)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+
+    fn export_document(original_filename: &str, file_path: &str) -> Document {
+        let now = Utc::now();
+        Document {
+            id: Uuid::new_v4(),
+            submission_id: Uuid::new_v4(),
+            category: DocumentCategory::Circular,
+            classification: DocumentClassification::Public,
+            external_url: None,
+            external_title: None,
+            filename: Some(file_path.to_string()),
+            original_filename: Some(original_filename.to_string()),
+            file_path: Some(file_path.to_string()),
+            file_size: None,
+            mime_type: None,
+            description: None,
+            created_at: now,
+            content_sha256: None,
+            updated_at: now,
+            encrypted: false,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export_zip_streams_metadata_first_and_skips_missing_files() {
+        let root = std::env::temp_dir().join(format!("export-test-{}", Uuid::new_v4()));
+        let store = crate::storage::FsStore::new(&root);
+        let contents = vec![b'x'; 200 * 1024];
+        store
+            .put("sub/present.pdf", bytes::Bytes::from(contents.clone()))
+            .await
+            .unwrap();
+        let documents = vec![
+            export_document("Circulaire.pdf", "sub/present.pdf"),
+            export_document("Weg.pdf", "sub/missing.pdf"),
+        ];
+
+        let handle = tokio::runtime::Handle::current();
+        let data = tokio::task::spawn_blocking(move || {
+            let mut out = Vec::new();
+            write_export_zip(
+                &handle,
+                &store,
+                None,
+                "metadata.json",
+                r#"{"ok":true}"#,
+                &documents,
+                &mut out,
+            )
+            .unwrap();
+            out
+        })
+        .await
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+        let names: Vec<String> = archive.file_names().map(String::from).collect();
+        assert_eq!(archive.len(), 2, "{:?}", names);
+        assert_eq!(archive.by_index(0).unwrap().name(), "metadata.json");
+
+        let mut file = archive.by_name("files/Circulaire.pdf").unwrap();
+        let mut read_back = Vec::new();
+        std::io::Read::read_to_end(&mut file, &mut read_back).unwrap();
+        assert_eq!(read_back, contents);
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export_zip_decrypts_encrypted_files_next_to_plaintext() {
+        use base64::engine::general_purpose::STANDARD;
+
+        let root = std::env::temp_dir().join(format!("export-test-{}", Uuid::new_v4()));
+        let store = crate::storage::FsStore::new(&root);
+        let key: FileKey = STANDARD.encode([3u8; 32]).parse().unwrap();
+
+        // Large enough to span several encrypted chunks
+        let secret: Vec<u8> = (0..200 * 1024).map(|i| (i % 251) as u8).collect();
+        let encrypted = key.encrypt(&secret).unwrap();
+        let mut tampered = encrypted.clone();
+        tampered[10] ^= 1;
+        let mut tampered_late = encrypted.clone();
+        *tampered_late.last_mut().unwrap() ^= 1;
+        store
+            .put("sub/plain.pdf", bytes::Bytes::from_static(b"%PDF-1.7 oud"))
+            .await
+            .unwrap();
+        store
+            .put("sub/encrypted.pdf", bytes::Bytes::from(encrypted))
+            .await
+            .unwrap();
+        store
+            .put("sub/tampered.pdf", bytes::Bytes::from(tampered))
+            .await
+            .unwrap();
+        store
+            .put("sub/tampered-late.pdf", bytes::Bytes::from(tampered_late))
+            .await
+            .unwrap();
+
+        let documents = vec![
+            export_document("Oud.pdf", "sub/plain.pdf"),
+            Document {
+                encrypted: true,
+                ..export_document("Nieuw.pdf", "sub/encrypted.pdf")
+            },
+            Document {
+                encrypted: true,
+                ..export_document("Gewijzigd.pdf", "sub/tampered.pdf")
+            },
+        ];
+
+        let handle = tokio::runtime::Handle::current();
+        let (data, late) = tokio::task::spawn_blocking(move || {
+            let export = |documents: &[Document]| {
+                let mut out = Vec::new();
+                write_export_zip(
+                    &handle,
+                    &store,
+                    Some(&key),
+                    "metadata.json",
+                    "{}",
+                    documents,
+                    &mut out,
+                )
+                .map(|()| out)
+            };
+            let late = Document {
+                encrypted: true,
+                ..export_document("Later.pdf", "sub/tampered-late.pdf")
+            };
+            (export(&documents).unwrap(), export(&[late]))
+        })
+        .await
+        .unwrap();
+        // Damage past the first chunk is found mid-entry and aborts the archive
+        assert!(late.is_err());
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+        let read = |archive: &mut zip::ZipArchive<_>, name: &str| {
+            let mut file = archive.by_name(name).unwrap();
+            let mut contents = Vec::new();
+            std::io::Read::read_to_end(&mut file, &mut contents).unwrap();
+            contents
+        };
+        assert_eq!(read(&mut archive, "files/Oud.pdf"), b"%PDF-1.7 oud");
+        assert_eq!(read(&mut archive, "files/Nieuw.pdf"), secret);
+        // A file failing authentication is left out rather than exported garbled
+        assert!(archive.by_name("files/Gewijzigd.pdf").is_err());
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
 }
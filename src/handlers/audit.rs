@@ -0,0 +1,91 @@
+//! Writing audit log entries
+//!
+//! The audited action has already happened when its entry is written, so a
+//! failed insert does not fail the request. It is logged instead, so a gap
+//! in the audit trail can be noticed.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::handlers::middleware::current_request_id;
+
+/// One row for `audit_log`
+pub struct AuditEntry<'a> {
+    /// Value of the `audit_action` enum
+    pub action: &'a str,
+    pub entity_type: &'a str,
+    pub entity_id: Option<Uuid>,
+    /// `admin`, `applicant` or `system`
+    pub actor_type: &'a str,
+    pub actor_id: Option<Uuid>,
+    pub details: Option<serde_json::Value>,
+}
+
+/// Record `entry`, tagged with the id of the request being handled
+pub async fn record(pool: &PgPool, entry: AuditEntry<'_>) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+        VALUES ($1::audit_action, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(entry.action)
+    .bind(entry.entity_type)
+    .bind(entry.entity_id)
+    .bind(entry.actor_type)
+    .bind(entry.actor_id)
+    .bind(&entry.details)
+    .bind(current_request_id())
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!(
+            "Failed to write {} audit entry for {} {:?}: {}",
+            entry.action,
+            entry.entity_type,
+            entry.entity_id,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    #[tokio::test]
+    async fn test_record_writes_entry_and_survives_failure() {
+        let Some(pool) = db::test_pool().await else {
+            return;
+        };
+
+        let entity_id = Uuid::new_v4();
+        let entry = |action| AuditEntry {
+            action,
+            entity_type: "submission",
+            entity_id: Some(entity_id),
+            actor_type: "system",
+            actor_id: None,
+            details: Some(serde_json::json!({ "reason": "test" })),
+        };
+        record(&pool, entry("slug_rotated")).await;
+        // Not an `audit_action`; the insert fails without failing the caller
+        record(&pool, entry("no_such_action")).await;
+
+        let details: Vec<serde_json::Value> =
+            sqlx::query_scalar("SELECT details FROM audit_log WHERE entity_id = $1")
+                .bind(entity_id)
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(details, [serde_json::json!({ "reason": "test" })]);
+
+        sqlx::query("DELETE FROM audit_log WHERE entity_id = $1")
+            .bind(entity_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
@@ -1,17 +1,22 @@
 //! Authentication handlers
 
+use crate::config::{AdminPassword, AdminSeed};
+use crate::handlers::middleware::current_request_id;
+use crate::i18n::ErrorKey;
 use crate::models::*;
+use crate::totp;
+use crate::validation::validate_password;
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+    Algorithm, Argon2, Params, Version,
 };
 use axum::{
-    extract::State,
+    extract::{Extension, Path, State},
     http::{header, HeaderMap, StatusCode},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -20,17 +25,169 @@ use super::AppState;
 /// Session cookie name
 pub const SESSION_COOKIE: &str = "rr_admin_session";
 
-/// Rate limit: max attempts per IP per hour
-const MAX_LOGIN_ATTEMPTS: i64 = 10;
+/// Attempts allowed per client IP within the rate-limit window
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimits {
+    /// Admin logins and password changes
+    pub login: i64,
+    /// Submission creations
+    pub submission: i64,
+    /// Uploader logins and reference code recovery
+    pub uploader_login: i64,
+    /// Window over which attempts are counted, in seconds
+    pub window_secs: u64,
+}
+
+// =============================================================================
+// Account Lockout
+// =============================================================================
+
+/// Locks an admin account after repeated failed logins
+///
+/// Unlike the per-IP rate limit this follows the account, so rotating IPs
+/// does not buy an attacker more guesses.
+#[derive(Debug, Clone, Copy)]
+pub struct LockoutPolicy {
+    /// Consecutive failures that lock the account
+    pub max_failures: i32,
+    /// How long the account stays locked
+    pub duration: Duration,
+}
 
-/// Rate limit: max submission creations per IP per hour
-pub(crate) const MAX_SUBMISSION_ATTEMPTS: i64 = 20;
+/// Failed-login bookkeeping stored on `admin_users`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoginAttempts {
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+impl LockoutPolicy {
+    pub fn new(max_failures: i32, lockout_minutes: i64) -> Self {
+        Self {
+            max_failures,
+            duration: Duration::minutes(lockout_minutes),
+        }
+    }
+
+    /// State after one more failed login
+    ///
+    /// Reaching the threshold locks the account and starts a fresh count, so
+    /// after the lock expires the user has the full number of attempts again.
+    pub fn after_failure(&self, failed_login_attempts: i32, now: DateTime<Utc>) -> LoginAttempts {
+        let failed_login_attempts = failed_login_attempts + 1;
+        if failed_login_attempts >= self.max_failures {
+            LoginAttempts {
+                failed_login_attempts: 0,
+                locked_until: Some(now + self.duration),
+            }
+        } else {
+            LoginAttempts {
+                failed_login_attempts,
+                locked_until: None,
+            }
+        }
+    }
+}
+
+/// Whether an account locked until `locked_until` is still locked at `now`
+pub fn is_locked(locked_until: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    locked_until.is_some_and(|until| until > now)
+}
+
+/// Count a failed login for `user_id`, returning the lock time if it is now locked
+async fn record_failed_login(
+    pool: &PgPool,
+    user_id: Uuid,
+    policy: &LockoutPolicy,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    // Row lock so concurrent failures are all counted
+    let mut tx = pool.begin().await?;
+    let failed: i32 = sqlx::query_scalar(
+        "SELECT failed_login_attempts FROM admin_users WHERE id = $1 FOR UPDATE",
+    )
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let attempts = policy.after_failure(failed, Utc::now());
+    sqlx::query(
+        "UPDATE admin_users SET failed_login_attempts = $1, locked_until = $2 WHERE id = $3",
+    )
+    .bind(attempts.failed_login_attempts)
+    .bind(attempts.locked_until)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(attempts.locked_until)
+}
+
+/// Response for a wrong password or code: 423 once the failure locks the account
+async fn failed_login_response(
+    state: &AppState,
+    user: &AdminUser,
+    client_ip: &str,
+    body: ApiResponse<AdminUserResponse>,
+) -> (
+    StatusCode,
+    [(header::HeaderName, String); 1],
+    Json<ApiResponse<AdminUserResponse>>,
+) {
+    match record_failed_login(&state.pool, user.id, &state.login_lockout).await {
+        Ok(Some(locked_until)) => {
+            tracing::warn!(
+                "Admin account {} locked until {} after repeated failed logins",
+                user.username,
+                locked_until
+            );
+            let _ = sqlx::query(
+                r#"
+                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_ip, details, request_id)
+                VALUES ('admin_account_locked'::audit_action, 'admin_user', $1, 'system', $2, $3, $4)
+                "#,
+            )
+            .bind(user.id)
+            .bind(client_ip)
+            .bind(serde_json::json!({ "locked_until": locked_until }))
+            .bind(current_request_id())
+            .execute(&state.pool)
+            .await;
+
+            return (
+                StatusCode::LOCKED,
+                [(header::SET_COOKIE, "".to_string())],
+                Json(ApiResponse::error(ErrorKey::AccountLocked)),
+            );
+        }
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to record failed login for {}: {}", user.username, e),
+    }
+
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::SET_COOKIE, "".to_string())],
+        Json(body),
+    )
+}
 
 // =============================================================================
 // Login Endpoint
 // =============================================================================
 
 /// Admin login
+#[utoipa::path(
+    post,
+    path = "/api/admin/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Logged in; sets the admin session cookie", body = ApiResponse<AdminUserResponse>),
+        (status = 401, description = "Invalid credentials or authenticator code required", body = ApiResponse<serde_json::Value>),
+        (status = 423, description = "Account temporarily locked", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many login attempts", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+    ),
+)]
 pub async fn admin_login(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -39,12 +196,14 @@ pub async fn admin_login(
     let client_ip = get_client_ip(&headers, &state.trusted_proxies);
 
     // Check rate limit
-    if !check_rate_limit(&state.pool, &client_ip, "login").await {
+    if let Err(limited) =
+        check_rate_limit(&state, &client_ip, "login", state.rate_limits.login).await
+    {
         return (
             StatusCode::TOO_MANY_REQUESTS,
-            [(header::SET_COOKIE, "".to_string())],
+            [limited.retry_after()],
             Json(ApiResponse::<AdminUserResponse>::error(
-                "Too many login attempts. Please try again later.",
+                ErrorKey::TooManyLoginAttempts,
             )),
         );
     }
@@ -67,11 +226,20 @@ pub async fn admin_login(
             return (
                 StatusCode::UNAUTHORIZED,
                 [(header::SET_COOKIE, "".to_string())],
-                Json(ApiResponse::error("Invalid username or password")),
+                Json(ApiResponse::error(ErrorKey::InvalidCredentials)),
             );
         }
     };
 
+    // Locked accounts are refused before the password is even checked
+    if is_locked(user.locked_until, Utc::now()) {
+        return (
+            StatusCode::LOCKED,
+            [(header::SET_COOKIE, "".to_string())],
+            Json(ApiResponse::error(ErrorKey::AccountLocked)),
+        );
+    }
+
     // Verify password
     let parsed_hash = match PasswordHash::new(&user.password_hash) {
         Ok(h) => h,
@@ -83,26 +251,64 @@ pub async fn admin_login(
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 [(header::SET_COOKIE, "".to_string())],
-                Json(ApiResponse::error("Authentication error")),
+                Json(ApiResponse::error(ErrorKey::AuthenticationError)),
             );
         }
     };
 
-    if Argon2::default()
+    if password_hasher(&state.password_params)
         .verify_password(input.password.as_bytes(), &parsed_hash)
         .is_err()
     {
-        return (
-            StatusCode::UNAUTHORIZED,
-            [(header::SET_COOKIE, "".to_string())],
-            Json(ApiResponse::error("Invalid username or password")),
-        );
+        return failed_login_response(
+            &state,
+            &user,
+            &client_ip,
+            ApiResponse::error(ErrorKey::InvalidCredentials),
+        )
+        .await;
+    }
+
+    // Second factor: no session is issued until the code verifies
+    if let Some(encrypted) = &user.totp_secret {
+        let Some(code) = input.totp_code.as_deref().filter(|c| !c.trim().is_empty()) else {
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::SET_COOKIE, "".to_string())],
+                Json(ApiResponse::auth_required(
+                    AuthScheme::Totp,
+                    ErrorKey::TotpRequired,
+                )),
+            );
+        };
+
+        match verify_stored_totp(state.totp_key.as_ref(), encrypted, code) {
+            Ok(true) => {}
+            Ok(false) => {
+                // Wrong codes count too, or the 6 digits could be brute-forced
+                return failed_login_response(
+                    &state,
+                    &user,
+                    &client_ip,
+                    ApiResponse::auth_required(AuthScheme::Totp, ErrorKey::TotpInvalid),
+                )
+                .await;
+            }
+            Err(e) => {
+                tracing::error!("TOTP verification failed for user {}: {}", user.username, e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    [(header::SET_COOKIE, "".to_string())],
+                    Json(ApiResponse::error(ErrorKey::AuthenticationError)),
+                );
+            }
+        }
     }
 
     // Generate session token
     let token = generate_session_token();
     let token_hash = hash_token(&token);
-    let expires_at = Utc::now() + Duration::hours(8);
+    let expires_at = Utc::now() + Duration::hours(state.session_expiry_hours as i64);
 
     // Create session
     let user_agent = headers
@@ -110,10 +316,12 @@ pub async fn admin_login(
         .and_then(|v| v.to_str().ok())
         .map(|s| s.chars().take(500).collect::<String>());
 
+    let csrf = csrf_token(&token);
     let session_result = sqlx::query(
         r#"
-        INSERT INTO admin_sessions (admin_user_id, token_hash, expires_at, ip_address, user_agent)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO admin_sessions
+            (admin_user_id, token_hash, expires_at, ip_address, user_agent, csrf_token_hash)
+        VALUES ($1, $2, $3, $4, $5, $6)
         "#,
     )
     .bind(user.id)
@@ -121,6 +329,7 @@ pub async fn admin_login(
     .bind(expires_at)
     .bind(&client_ip)
     .bind(&user_agent)
+    .bind(hash_token(&csrf))
     .execute(&state.pool)
     .await;
 
@@ -128,46 +337,61 @@ pub async fn admin_login(
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
             [(header::SET_COOKIE, "".to_string())],
-            Json(ApiResponse::error("Failed to create session")),
+            Json(ApiResponse::error(ErrorKey::CreateSessionFailed)),
         );
     }
 
-    // Update last login
-    let _ = sqlx::query("UPDATE admin_users SET last_login_at = NOW() WHERE id = $1")
-        .bind(user.id)
-        .execute(&state.pool)
-        .await;
+    // Update last login and reset the failed-login count
+    let _ = sqlx::query(
+        r#"
+        UPDATE admin_users
+        SET last_login_at = NOW(), failed_login_attempts = 0, locked_until = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(user.id)
+    .execute(&state.pool)
+    .await;
 
     // Log audit event
     let _ = sqlx::query(
         r#"
-        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, actor_ip)
-        VALUES ('admin_login'::audit_action, 'admin_user', $1, 'admin', $1, $2)
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, actor_ip, request_id)
+        VALUES ('admin_login'::audit_action, 'admin_user', $1, 'admin', $1, $2, $3)
         "#,
     )
     .bind(user.id)
     .bind(&client_ip)
+    .bind(current_request_id())
     .execute(&state.pool)
     .await;
 
     // Set secure cookie
-    let secure_flag = if state.is_production { "; Secure" } else { "" };
-    let cookie = format!(
-        "{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}{}",
-        SESSION_COOKIE,
-        token,
-        8 * 3600, // 8 hours
-        secure_flag
+    let cookie = session_cookie(
+        &token,
+        state.session_expiry_hours as i64 * 3600,
+        state.is_production,
     );
 
     (
         StatusCode::OK,
         [(header::SET_COOKIE, cookie)],
-        Json(ApiResponse::success(AdminUserResponse::from(user))),
+        Json(ApiResponse::success(AdminUserResponse {
+            csrf_token: Some(csrf),
+            ..AdminUserResponse::from(user)
+        })),
     )
 }
 
 /// Admin logout
+#[utoipa::path(
+    post,
+    path = "/api/admin/logout",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Logged out", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn admin_logout(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
     let token = extract_session_token(&headers);
 
@@ -193,11 +417,12 @@ pub async fn admin_logout(State(state): State<AppState>, headers: HeaderMap) ->
         if let Some(session) = session {
             let _ = sqlx::query(
                 r#"
-                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id)
-                VALUES ('admin_logout'::audit_action, 'admin_user', $1, 'admin', $1)
+                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, request_id)
+                VALUES ('admin_logout'::audit_action, 'admin_user', $1, 'admin', $1, $2)
                 "#,
             )
             .bind(session.admin_user_id)
+            .bind(current_request_id())
             .execute(&state.pool)
             .await;
         }
@@ -218,6 +443,16 @@ pub async fn admin_logout(State(state): State<AppState>, headers: HeaderMap) ->
 }
 
 /// Get current admin user
+#[utoipa::path(
+    get,
+    path = "/api/admin/me",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Current admin", body = ApiResponse<AdminUserResponse>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn get_current_admin(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -225,15 +460,565 @@ pub async fn get_current_admin(
     match validate_admin_session(&state.pool, &headers).await {
         Some(user) => (
             StatusCode::OK,
-            Json(ApiResponse::success(AdminUserResponse::from(user))),
+            Json(ApiResponse::success(AdminUserResponse {
+                csrf_token: extract_session_token(&headers).map(|t| csrf_token(&t)),
+                ..AdminUserResponse::from(user)
+            })),
         ),
         None => (
             StatusCode::UNAUTHORIZED,
-            Json(ApiResponse::error("Not authenticated")),
+            Json(ApiResponse::auth_required(
+                AuthScheme::Admin,
+                ErrorKey::NotAuthenticated,
+            )),
         ),
     }
 }
 
+// =============================================================================
+// Two-Factor Authentication
+// =============================================================================
+
+/// Decrypt a stored TOTP secret and check `code` against it
+fn verify_stored_totp(
+    key: Option<&totp::TotpKey>,
+    encrypted: &str,
+    code: &str,
+) -> Result<bool, String> {
+    let key = key.ok_or("TOTP_ENCRYPTION_KEY is not configured")?;
+    let secret = key.decrypt(encrypted).map_err(|e| e.to_string())?;
+    totp::verify_code(&secret, code, totp::now()).map_err(|e| e.to_string())
+}
+
+/// Start two-factor enrollment for the current admin
+///
+/// Generates a new secret and returns it with an otpauth URI. The secret only
+/// takes effect after it is confirmed with a code, so an abandoned enrollment
+/// cannot lock the admin out.
+#[utoipa::path(
+    post,
+    path = "/api/admin/2fa/enroll",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Pending authenticator secret", body = ApiResponse<TotpEnrollmentResponse>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn enroll_totp(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+) -> impl IntoResponse {
+    let Some(key) = &state.totp_key else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::error(ErrorKey::TotpNotConfigured)),
+        );
+    };
+
+    if admin.totp_secret.is_some() {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::error(ErrorKey::TotpAlreadyEnabled)),
+        );
+    }
+
+    let secret = totp::generate_secret();
+    let enrollment = totp::otpauth_uri(&secret, &admin.username).and_then(|otpauth_uri| {
+        key.encrypt(&secret)
+            .map(|encrypted| (otpauth_uri, encrypted))
+    });
+    let (otpauth_uri, encrypted) = match enrollment {
+        Ok(e) => e,
+        Err(e) => {
+            tracing::error!("Failed to create TOTP secret: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::StartEnrollmentFailed)),
+            );
+        }
+    };
+
+    let result = sqlx::query("UPDATE admin_users SET totp_pending_secret = $1 WHERE id = $2")
+        .bind(&encrypted)
+        .bind(admin.id)
+        .execute(&state.pool)
+        .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to store pending TOTP secret: {}", e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(ErrorKey::StartEnrollmentFailed)),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(TotpEnrollmentResponse {
+            secret,
+            otpauth_uri,
+        })),
+    )
+}
+
+/// Confirm two-factor enrollment with a code from the authenticator app
+#[utoipa::path(
+    post,
+    path = "/api/admin/2fa/confirm",
+    tag = "auth",
+    request_body = TotpConfirmRequest,
+    responses(
+        (status = 200, description = "Two-factor authentication enabled", body = ApiResponse<AdminUserResponse>),
+        (status = 400, description = "Invalid code or no pending enrollment", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn confirm_totp(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    headers: HeaderMap,
+    Json(input): Json<TotpConfirmRequest>,
+) -> impl IntoResponse {
+    let Some(pending) = &admin.totp_pending_secret else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<AdminUserResponse>::error(
+                ErrorKey::NoEnrollment,
+            )),
+        );
+    };
+
+    match verify_stored_totp(state.totp_key.as_ref(), pending, &input.code) {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(ErrorKey::TotpInvalid)),
+            );
+        }
+        Err(e) => {
+            tracing::error!(
+                "TOTP verification failed for user {}: {}",
+                admin.username,
+                e
+            );
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::ConfirmEnrollmentFailed)),
+            );
+        }
+    }
+
+    let user = sqlx::query_as::<_, AdminUser>(
+        r#"
+        UPDATE admin_users
+        SET totp_secret = totp_pending_secret, totp_pending_secret = NULL
+        WHERE id = $1 AND totp_pending_secret = $2
+        RETURNING *
+        "#,
+    )
+    .bind(admin.id)
+    .bind(pending)
+    .fetch_optional(&state.pool)
+    .await;
+
+    let user = match user {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            // A newer enrollment replaced the secret this code was checked against
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::error(ErrorKey::EnrollmentChanged)),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Failed to activate TOTP secret: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::ConfirmEnrollmentFailed)),
+            );
+        }
+    };
+
+    let client_ip = get_client_ip(&headers, &state.trusted_proxies);
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, actor_ip, request_id)
+        VALUES ('admin_2fa_enrolled'::audit_action, 'admin_user', $1, 'admin', $1, $2, $3)
+        "#,
+    )
+    .bind(user.id)
+    .bind(&client_ip)
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(AdminUserResponse::from(user))),
+    )
+}
+
+// =============================================================================
+// Password Change
+// =============================================================================
+
+/// Check a password change request against the admin's current hash
+///
+/// Returns the status and message to report when the change is refused.
+fn check_password_change(
+    argon2: &Argon2,
+    password_hash: &str,
+    input: &ChangePasswordRequest,
+) -> Result<(), (StatusCode, String)> {
+    let parsed_hash = PasswordHash::new(password_hash).map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorKey::AuthenticationError.into(),
+        )
+    })?;
+
+    if argon2
+        .verify_password(input.current_password.as_bytes(), &parsed_hash)
+        .is_err()
+    {
+        return Err((
+            StatusCode::FORBIDDEN,
+            ErrorKey::CurrentPasswordIncorrect.into(),
+        ));
+    }
+
+    validate_password(&input.new_password).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    if input.new_password == input.current_password {
+        return Err((StatusCode::BAD_REQUEST, ErrorKey::PasswordUnchanged.into()));
+    }
+
+    Ok(())
+}
+
+/// Change the password of the current admin
+///
+/// All other sessions of the admin are ended, so a session obtained with the
+/// old password does not outlive the change. The session making the request
+/// stays logged in.
+#[utoipa::path(
+    post,
+    path = "/api/admin/change-password",
+    tag = "auth",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Password changed; other sessions revoked", body = ApiResponse<serde_json::Value>),
+        (status = 400, description = "New password does not meet the policy", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 403, description = "Current password is incorrect", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many attempts", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    headers: HeaderMap,
+    Json(input): Json<ChangePasswordRequest>,
+) -> Response {
+    let client_ip = get_client_ip(&headers, &state.trusted_proxies);
+
+    // Guards against guessing the current password with a hijacked session
+    if let Err(limited) = check_rate_limit(
+        &state,
+        &client_ip,
+        "change_password",
+        state.rate_limits.login,
+    )
+    .await
+    {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [limited.retry_after()],
+            Json(ApiResponse::<()>::error(ErrorKey::TooManyAttempts)),
+        )
+            .into_response();
+    }
+    record_attempt(&state.pool, &client_ip, "change_password").await;
+
+    let argon2 = password_hasher(&state.password_params);
+    if let Err((status, message)) = check_password_change(&argon2, &admin.password_hash, &input) {
+        if status == StatusCode::INTERNAL_SERVER_ERROR {
+            tracing::error!(
+                "Invalid password hash in database for user {}",
+                admin.username
+            );
+        }
+        return (status, Json(ApiResponse::<()>::error(message))).into_response();
+    }
+
+    let new_hash = match hash_password(&argon2, &input.new_password) {
+        Ok(h) => h,
+        Err(e) => {
+            tracing::error!("Failed to hash new password: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(ErrorKey::ChangePasswordFailed)),
+            )
+                .into_response();
+        }
+    };
+
+    let current_token_hash = extract_session_token(&headers)
+        .map(|token| hash_token(&token))
+        .unwrap_or_default();
+
+    let result = async {
+        let mut tx = state.pool.begin().await?;
+        sqlx::query("UPDATE admin_users SET password_hash = $1 WHERE id = $2")
+            .bind(&new_hash)
+            .bind(admin.id)
+            .execute(&mut *tx)
+            .await?;
+        let revoked =
+            sqlx::query("DELETE FROM admin_sessions WHERE admin_user_id = $1 AND token_hash <> $2")
+                .bind(admin.id)
+                .bind(&current_token_hash)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        tx.commit().await?;
+        Ok::<u64, sqlx::Error>(revoked)
+    }
+    .await;
+
+    let sessions_revoked = match result {
+        Ok(revoked) => revoked,
+        Err(e) => {
+            tracing::error!("Failed to change password for {}: {}", admin.username, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(ErrorKey::ChangePasswordFailed)),
+            )
+                .into_response();
+        }
+    };
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, actor_ip, details, request_id)
+        VALUES ('admin_password_changed'::audit_action, 'admin_user', $1, 'admin', $1, $2, $3, $4)
+        "#,
+    )
+    .bind(admin.id)
+    .bind(&client_ip)
+    .bind(serde_json::json!({ "sessions_revoked": sessions_revoked }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    tracing::info!(
+        "Admin {} changed their password ({} other sessions ended)",
+        admin.username,
+        sessions_revoked
+    );
+
+    (StatusCode::OK, Json(ApiResponse::success(()))).into_response()
+}
+
+// =============================================================================
+// Session Management
+// =============================================================================
+
+/// List the current admin's active sessions
+#[utoipa::path(
+    get,
+    path = "/api/admin/sessions",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Active sessions, newest first", body = ApiResponse<Vec<AdminSessionResponse>>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn list_admin_sessions(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let sessions = sqlx::query_as::<_, AdminSession>(
+        r#"
+        SELECT * FROM admin_sessions
+        WHERE admin_user_id = $1 AND expires_at > NOW()
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(admin.id)
+    .fetch_all(&state.pool)
+    .await;
+
+    let sessions = match sessions {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            tracing::error!("Failed to list sessions for {}: {}", admin.username, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::ListSessionsFailed)),
+            );
+        }
+    };
+
+    let current_token_hash = extract_session_token(&headers)
+        .map(|token| hash_token(&token))
+        .unwrap_or_default();
+    let sessions: Vec<AdminSessionResponse> = sessions
+        .into_iter()
+        .map(|session| AdminSessionResponse::new(session, &current_token_hash))
+        .collect();
+
+    (StatusCode::OK, Json(ApiResponse::success(sessions)))
+}
+
+/// End one of the current admin's sessions
+#[utoipa::path(
+    delete,
+    path = "/api/admin/sessions/{session_id}",
+    tag = "auth",
+    params(("session_id" = Uuid, Path, description = "Session id")),
+    responses(
+        (status = 200, description = "Session revoked", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "No such session for this admin", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn revoke_admin_session(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    headers: HeaderMap,
+    Path(session_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match delete_admin_session(&state.pool, admin.id, session_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(ErrorKey::SessionNotFound)),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Failed to revoke session {}: {}", session_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::RevokeSessionFailed)),
+            );
+        }
+    }
+
+    log_sessions_revoked(
+        &state,
+        &admin,
+        &headers,
+        serde_json::json!({ "session_id": session_id }),
+    )
+    .await;
+    tracing::info!("Admin {} revoked session {}", admin.username, session_id);
+
+    (StatusCode::OK, Json(ApiResponse::success(())))
+}
+
+/// End every session of the current admin except the one making the request
+#[utoipa::path(
+    post,
+    path = "/api/admin/sessions/revoke-all",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Number of sessions revoked", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn revoke_other_admin_sessions(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current_token_hash = extract_session_token(&headers)
+        .map(|token| hash_token(&token))
+        .unwrap_or_default();
+
+    let revoked =
+        sqlx::query("DELETE FROM admin_sessions WHERE admin_user_id = $1 AND token_hash <> $2")
+            .bind(admin.id)
+            .bind(&current_token_hash)
+            .execute(&state.pool)
+            .await;
+
+    let revoked = match revoked {
+        Ok(result) => result.rows_affected(),
+        Err(e) => {
+            tracing::error!("Failed to revoke sessions for {}: {}", admin.username, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::RevokeSessionsFailed)),
+            );
+        }
+    };
+
+    log_sessions_revoked(
+        &state,
+        &admin,
+        &headers,
+        serde_json::json!({ "sessions_revoked": revoked }),
+    )
+    .await;
+    tracing::info!(
+        "Admin {} revoked {} other sessions",
+        admin.username,
+        revoked
+    );
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            serde_json::json!({ "sessions_revoked": revoked }),
+        )),
+    )
+}
+
+/// Delete a session of `admin_user_id`, returning whether it existed
+async fn delete_admin_session(
+    pool: &PgPool,
+    admin_user_id: Uuid,
+    session_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM admin_sessions WHERE id = $1 AND admin_user_id = $2")
+        .bind(session_id)
+        .bind(admin_user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+async fn log_sessions_revoked(
+    state: &AppState,
+    admin: &AdminUser,
+    headers: &HeaderMap,
+    details: serde_json::Value,
+) {
+    let client_ip = get_client_ip(headers, &state.trusted_proxies);
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, actor_ip, details, request_id)
+        VALUES ('admin_session_revoked'::audit_action, 'admin_user', $1, 'admin', $1, $2, $3, $4)
+        "#,
+    )
+    .bind(admin.id)
+    .bind(&client_ip)
+    .bind(details)
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+}
+
 // =============================================================================
 // Session Validation
 // =============================================================================
@@ -285,67 +1070,39 @@ pub async fn validate_admin_session(pool: &PgPool, headers: &HeaderMap) -> Optio
 // Password Utilities
 // =============================================================================
 
+/// Argon2id hasher with the configured cost parameters
+///
+/// Verification reads the parameters from the stored PHC string, so hashes
+/// created with an older cost keep working; only new hashes use `params`.
+pub fn password_hasher(params: &Params) -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone())
+}
+
 /// Hash a password using Argon2
-pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+pub fn hash_password(
+    argon2: &Argon2,
+    password: &str,
+) -> Result<String, argon2::password_hash::Error> {
     let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
     let hash = argon2.hash_password(password.as_bytes(), &salt)?;
     Ok(hash.to_string())
 }
 
-/// Seed admin user from environment variables
-///
-/// Accepts either:
-/// - ADMIN_PASSWORD_HASH: Argon2 hash (recommended for production)
-/// - ADMIN_PASSWORD: plain text (for development only)
+/// Create the admin account from ADMIN_USERNAME, ADMIN_EMAIL and a password
 ///
-/// Required: ADMIN_USERNAME and ADMIN_EMAIL
-pub async fn seed_admin_user(pool: &PgPool) {
-    let username = match std::env::var("ADMIN_USERNAME") {
-        Ok(v) if !v.is_empty() => v,
-        _ => return,
-    };
-    let email = match std::env::var("ADMIN_EMAIL") {
-        Ok(v) if !v.is_empty() => v,
-        _ => return,
-    };
-
-    // Prefer pre-hashed password over plain text
-    let password_hash = if let Ok(hash) = std::env::var("ADMIN_PASSWORD_HASH") {
-        if !hash.is_empty() && hash.starts_with("$argon2") {
-            tracing::info!("Using ADMIN_PASSWORD_HASH for admin user");
-            hash
-        } else {
-            tracing::error!(
-                "ADMIN_PASSWORD_HASH is set but not a valid Argon2 hash \
-                (must start with '$argon2')"
-            );
-            return;
-        }
-    } else if let Ok(password) = std::env::var("ADMIN_PASSWORD") {
-        if !password.is_empty() {
-            tracing::warn!(
-                "Using ADMIN_PASSWORD (plain text). \
-                Consider using ADMIN_PASSWORD_HASH for production."
-            );
-            match hash_password(&password) {
-                Ok(h) => h,
-                Err(e) => {
-                    tracing::error!("Failed to hash admin password: {}", e);
-                    return;
-                }
-            }
-        } else {
-            return;
-        }
-    } else {
-        return;
-    };
+/// Production requires ADMIN_PASSWORD_HASH; a plain ADMIN_PASSWORD is only
+/// accepted in development. Does nothing when the username already exists.
+pub async fn seed_admin_user(pool: &PgPool, seed: &AdminSeed, params: &Params) {
+    let AdminSeed {
+        username,
+        email,
+        password,
+    } = seed;
 
     // Check if user already exists
     let existing: Option<(Uuid,)> =
         sqlx::query_as("SELECT id FROM admin_users WHERE username = $1")
-            .bind(&username)
+            .bind(username)
             .fetch_optional(pool)
             .await
             .unwrap_or(None);
@@ -355,22 +1112,15 @@ pub async fn seed_admin_user(pool: &PgPool) {
         return;
     }
 
-    // Create admin user with pre-computed hash
-    let result = sqlx::query_as::<_, AdminUser>(
-        r#"
-        INSERT INTO admin_users (username, email, password_hash, display_name)
-        VALUES ($1, $2, $3, $4)
-        RETURNING *
-        "#,
-    )
-    .bind(&username)
-    .bind(&email)
-    .bind(&password_hash)
-    .bind(&username)
-    .fetch_one(pool)
-    .await;
+    match password {
+        AdminPassword::Hash(_) => tracing::info!("Using ADMIN_PASSWORD_HASH for admin user"),
+        AdminPassword::Plain(_) => tracing::warn!(
+            "Using ADMIN_PASSWORD (plain text). \
+            Consider using ADMIN_PASSWORD_HASH for production."
+        ),
+    }
 
-    match result {
+    match create_admin_user(pool, username, email, password, Some(username), params).await {
         Ok(user) => {
             tracing::info!("Seeded admin user '{}' (id: {})", user.username, user.id);
         }
@@ -380,16 +1130,27 @@ pub async fn seed_admin_user(pool: &PgPool) {
     }
 }
 
-/// Create an admin user (utility function for setup)
+/// Whether any active admin account exists
+pub async fn active_admin_exists(pool: &PgPool) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM admin_users WHERE is_active = true)")
+        .fetch_one(pool)
+        .await
+}
+
+/// Create an admin user, hashing a plain password with `params`
 pub async fn create_admin_user(
     pool: &PgPool,
     username: &str,
     email: &str,
-    password: &str,
+    password: &AdminPassword,
     display_name: Option<&str>,
+    params: &Params,
 ) -> Result<AdminUser, sqlx::Error> {
-    let password_hash =
-        hash_password(password).map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+    let password_hash = match password {
+        AdminPassword::Hash(hash) => hash.clone(),
+        AdminPassword::Plain(password) => hash_password(&password_hasher(params), password)
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?,
+    };
 
     sqlx::query_as::<_, AdminUser>(
         r#"
@@ -410,6 +1171,15 @@ pub async fn create_admin_user(
 // Helper Functions
 // =============================================================================
 
+/// `Set-Cookie` value for an admin session token
+pub(crate) fn session_cookie(token: &str, max_age_secs: i64, is_production: bool) -> String {
+    let secure_flag = if is_production { "; Secure" } else { "" };
+    format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}{}",
+        SESSION_COOKIE, token, max_age_secs, secure_flag
+    )
+}
+
 pub(crate) fn extract_session_token(headers: &HeaderMap) -> Option<String> {
     let cookie_header = headers.get(header::COOKIE)?.to_str().ok()?;
 
@@ -430,6 +1200,14 @@ fn generate_session_token() -> String {
     hex::encode(bytes)
 }
 
+/// CSRF token belonging to an admin session token
+///
+/// Derived rather than random so `/api/admin/me` can hand it out again while
+/// only its hash is stored. It reveals nothing about the session token.
+pub(crate) fn csrf_token(session_token: &str) -> String {
+    hash_token(&format!("csrf:{}", session_token))
+}
+
 pub(crate) fn hash_token(token: &str) -> String {
     use sha2::{Digest, Sha256};
 
@@ -490,30 +1268,63 @@ pub(crate) fn get_client_ip(headers: &HeaderMap, trusted_proxies: &[String]) ->
     "unknown".to_string()
 }
 
-pub(crate) async fn check_rate_limit_with_max(
-    pool: &PgPool,
+/// A client ran out of attempts for an endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RateLimited {
+    /// Seconds until enough attempts have left the window to try again
+    pub retry_after_secs: u64,
+}
+
+impl RateLimited {
+    /// `Retry-After` header for the 429 response
+    pub fn retry_after(&self) -> (header::HeaderName, String) {
+        (header::RETRY_AFTER, self.retry_after_secs.to_string())
+    }
+}
+
+/// Check whether `ip` may make another attempt at `endpoint`
+///
+/// The client is limited while its `max_attempts`-th most recent attempt is
+/// still inside the configured window; once that attempt ages out it may try
+/// again. A failing query lets the request through.
+pub(crate) async fn check_rate_limit(
+    state: &AppState,
     ip: &str,
     endpoint: &str,
     max_attempts: i64,
-) -> bool {
-    let count: i64 = sqlx::query_scalar(
+) -> Result<(), RateLimited> {
+    let window_secs = state.rate_limits.window_secs;
+    if max_attempts <= 0 {
+        return Err(RateLimited {
+            retry_after_secs: window_secs,
+        });
+    }
+
+    let remaining: Option<f64> = sqlx::query_scalar(
         r#"
-        SELECT COUNT(*) FROM rate_limit_attempts
+        SELECT EXTRACT(EPOCH FROM attempted_at + make_interval(secs => $4) - NOW())::FLOAT8
+        FROM rate_limit_attempts
         WHERE ip_address = $1 AND endpoint = $2
-        AND attempted_at > NOW() - INTERVAL '1 hour'
+        AND attempted_at > NOW() - make_interval(secs => $4)
+        ORDER BY attempted_at DESC
+        OFFSET $3 - 1
+        LIMIT 1
         "#,
     )
     .bind(ip)
     .bind(endpoint)
-    .fetch_one(pool)
+    .bind(max_attempts)
+    .bind(window_secs as f64)
+    .fetch_optional(&state.pool)
     .await
-    .unwrap_or(0);
+    .unwrap_or(None);
 
-    count < max_attempts
-}
-
-pub(crate) async fn check_rate_limit(pool: &PgPool, ip: &str, endpoint: &str) -> bool {
-    check_rate_limit_with_max(pool, ip, endpoint, MAX_LOGIN_ATTEMPTS).await
+    match remaining {
+        Some(secs) => Err(RateLimited {
+            retry_after_secs: (secs.ceil() as u64).clamp(1, window_secs.max(1)),
+        }),
+        None => Ok(()),
+    }
 }
 
 pub(crate) async fn record_attempt(pool: &PgPool, ip: &str, endpoint: &str) {
@@ -568,7 +1379,7 @@ mod tests {
     #[test]
     fn test_hash_password_and_verify() {
         let password = "test-password-123!";
-        let hash = hash_password(password).unwrap();
+        let hash = hash_password(&Argon2::default(), password).unwrap();
 
         // Hash should be an Argon2 hash
         assert!(hash.starts_with("$argon2"));
@@ -582,13 +1393,194 @@ mod tests {
 
     #[test]
     fn test_hash_password_wrong_password() {
-        let hash = hash_password("correct-password").unwrap();
+        let hash = hash_password(&Argon2::default(), "correct-password").unwrap();
         let parsed = PasswordHash::new(&hash).unwrap();
         assert!(Argon2::default()
             .verify_password(b"wrong-password", &parsed)
             .is_err());
     }
 
+    #[tokio::test]
+    async fn test_revoked_session_no_longer_validates() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        let username = format!("revoke-{}", Uuid::new_v4());
+        let admin_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO admin_users (username, email, password_hash) VALUES ($1, $2, 'x') RETURNING id",
+        )
+        .bind(&username)
+        .bind(format!("{}@example.org", username))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let token = generate_session_token();
+        let session_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO admin_sessions (admin_user_id, token_hash, expires_at)
+            VALUES ($1, $2, NOW() + INTERVAL '1 hour')
+            RETURNING id
+            "#,
+        )
+        .bind(admin_id)
+        .bind(hash_token(&token))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("{}={}", SESSION_COOKIE, token).parse().unwrap(),
+        );
+        assert!(validate_admin_session(&pool, &headers).await.is_some());
+
+        // Another admin cannot revoke it
+        assert!(!delete_admin_session(&pool, Uuid::new_v4(), session_id)
+            .await
+            .unwrap());
+        assert!(validate_admin_session(&pool, &headers).await.is_some());
+
+        assert!(delete_admin_session(&pool, admin_id, session_id)
+            .await
+            .unwrap());
+        assert!(validate_admin_session(&pool, &headers).await.is_none());
+
+        sqlx::query("DELETE FROM admin_users WHERE id = $1")
+            .bind(admin_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_configured_params_round_trip_through_hash() {
+        let params = Params::new(32 * 1024, 3, 2, None).unwrap();
+        let hash = hash_password(&password_hasher(&params), "strong-password-1!").unwrap();
+
+        let parsed = PasswordHash::new(&hash).unwrap();
+        let stored = Params::try_from(&parsed).unwrap();
+        assert_eq!(stored.m_cost(), 32 * 1024);
+        assert_eq!(stored.t_cost(), 3);
+        assert_eq!(stored.p_cost(), 2);
+
+        // A hasher with other (lower) parameters still verifies the old hash
+        assert!(password_hasher(&Params::default())
+            .verify_password(b"strong-password-1!", &parsed)
+            .is_ok());
+        assert!(password_hasher(&Params::default())
+            .verify_password(b"wrong-password", &parsed)
+            .is_err());
+    }
+
+    fn minutes(m: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap() + Duration::minutes(m)
+    }
+
+    #[test]
+    fn test_lockout_after_max_failures() {
+        let policy = LockoutPolicy::new(3, 15);
+        let mut attempts = LoginAttempts {
+            failed_login_attempts: 0,
+            locked_until: None,
+        };
+
+        for _ in 0..2 {
+            attempts = policy.after_failure(attempts.failed_login_attempts, minutes(0));
+            assert!(!is_locked(attempts.locked_until, minutes(0)));
+        }
+
+        attempts = policy.after_failure(attempts.failed_login_attempts, minutes(0));
+        assert_eq!(
+            attempts,
+            LoginAttempts {
+                failed_login_attempts: 0,
+                locked_until: Some(minutes(15)),
+            }
+        );
+        assert!(is_locked(attempts.locked_until, minutes(0)));
+        assert!(is_locked(attempts.locked_until, minutes(14)));
+    }
+
+    #[test]
+    fn test_lockout_expires_after_window() {
+        let policy = LockoutPolicy::new(3, 15);
+        let locked = policy.after_failure(2, minutes(0));
+
+        assert!(!is_locked(locked.locked_until, minutes(15)));
+        assert!(!is_locked(locked.locked_until, minutes(60)));
+
+        // A fresh count after the lock: one more failure does not lock again
+        let next = policy.after_failure(locked.failed_login_attempts, minutes(16));
+        assert_eq!(next.failed_login_attempts, 1);
+        assert!(!is_locked(next.locked_until, minutes(16)));
+    }
+
+    #[test]
+    fn test_lockout_count_resets_on_success() {
+        let policy = LockoutPolicy::new(3, 15);
+        let almost = policy.after_failure(1, minutes(0));
+        assert_eq!(almost.failed_login_attempts, 2);
+
+        // A successful login stores a zero count, so the budget starts over
+        let after_success = policy.after_failure(0, minutes(1));
+        assert_eq!(after_success.failed_login_attempts, 1);
+        assert!(!is_locked(after_success.locked_until, minutes(1)));
+    }
+
+    fn change_request(current: &str, new: &str) -> ChangePasswordRequest {
+        ChangePasswordRequest {
+            current_password: current.to_string(),
+            new_password: new.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_check_password_change_rejects_wrong_current_password() {
+        let hash = hash_password(&Argon2::default(), "Old-Password-123").unwrap();
+        let result = check_password_change(
+            &Argon2::default(),
+            &hash,
+            &change_request("not-my-password", "New-Password-456"),
+        );
+        assert_eq!(
+            result,
+            Err((
+                StatusCode::FORBIDDEN,
+                ErrorKey::CurrentPasswordIncorrect.into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_check_password_change_enforces_policy() {
+        let hash = hash_password(&Argon2::default(), "Old-Password-123").unwrap();
+        assert!(check_password_change(
+            &Argon2::default(),
+            &hash,
+            &change_request("Old-Password-123", "New-Password-456")
+        )
+        .is_ok());
+
+        let (status, _) = check_password_change(
+            &Argon2::default(),
+            &hash,
+            &change_request("Old-Password-123", "short"),
+        )
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = check_password_change(
+            &Argon2::default(),
+            &hash,
+            &change_request("Old-Password-123", "Old-Password-123"),
+        )
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
     #[test]
     fn test_extract_session_token_from_cookie() {
         let mut headers = HeaderMap::new();
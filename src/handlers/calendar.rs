@@ -1,70 +1,162 @@
 //! Calendar and meeting scheduling handlers
 
+use crate::handlers::middleware::current_request_id;
+use crate::i18n::ErrorKey;
 use crate::models::*;
 use crate::validation::validate_slug;
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Extension, Json,
 };
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use serde::Deserialize;
+use sqlx::{PgConnection, PgExecutor};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use super::calendar_import::SLOT_TIMEZONE;
 use super::AppState;
 
+/// Most bookings a single slot can take, sent as `max` when exceeded
+pub const MAX_SLOT_CAPACITY: i32 = 50;
+
+/// Select list for a `CalendarSlot` from `calendar_slots c`, bookings counted
+const SLOT_COLUMNS: &str =
+    "c.*, (SELECT COUNT(*) FROM slot_bookings b WHERE b.slot_id = c.id) AS booked";
+
 // =============================================================================
 // Query Parameters
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AvailableSlotsQuery {
     pub from: Option<DateTime<Utc>>,
+    /// End of the window; defaults to 30 days after `from`
     pub to: Option<DateTime<Utc>>,
+    /// IANA time zone to group slots by date in; defaults to Europe/Amsterdam
+    pub tz: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct BookSlotRequest {
     pub slot_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RescheduleRequest {
+    pub new_slot_id: Uuid,
+}
+
 // =============================================================================
 // Public Calendar Endpoints
 // =============================================================================
 
 /// Get available meeting slots (public)
+///
+/// Slots in the window from `from` to `to` are grouped by the date they start
+/// on in `tz`. Request the next window with `from` set to `next_from`.
+#[utoipa::path(
+    get,
+    path = "/api/calendar/available",
+    tag = "calendar",
+    params(AvailableSlotsQuery),
+    responses(
+        (status = 200, description = "Bookable future slots by local date", body = ApiResponse<AvailableSlotsResponse>),
+        (status = 400, description = "Unknown time zone", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn get_available_slots(
     State(state): State<AppState>,
     Query(query): Query<AvailableSlotsQuery>,
 ) -> impl IntoResponse {
+    let tz = match query.tz.as_deref() {
+        None => SLOT_TIMEZONE,
+        Some(name) => match name.parse::<Tz>() {
+            Ok(tz) => tz,
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::error(ErrorKey::InvalidTimezone)),
+                );
+            }
+        },
+    };
     let from = query.from.unwrap_or_else(Utc::now);
     let to = query
         .to
         .unwrap_or_else(|| from + chrono::Duration::days(30));
 
-    let slots = sqlx::query_as::<_, CalendarSlot>(
+    let slots = sqlx::query_as::<_, CalendarSlot>(&format!(
         r#"
-        SELECT * FROM calendar_slots
-        WHERE is_available = true
-          AND slot_start >= $1
-          AND slot_start <= $2
-        ORDER BY slot_start ASC
-        "#,
-    )
+        SELECT {SLOT_COLUMNS} FROM calendar_slots c
+        WHERE c.slot_start >= $1
+          AND c.slot_start <= $2
+          AND (SELECT COUNT(*) FROM slot_bookings b WHERE b.slot_id = c.id) < c.capacity
+        ORDER BY c.slot_start ASC
+        "#
+    ))
     .bind(from)
     .bind(to)
     .fetch_all(&state.pool)
     .await
     .unwrap_or_default();
 
-    let responses: Vec<CalendarSlotResponse> =
-        slots.into_iter().map(CalendarSlotResponse::from).collect();
+    let response = AvailableSlotsResponse {
+        timezone: tz.name().to_string(),
+        days: group_by_local_date(slots, tz),
+        next_from: to,
+    };
+    (StatusCode::OK, Json(ApiResponse::success(response)))
+}
 
-    (StatusCode::OK, Json(ApiResponse::success(responses)))
+/// Slots, ordered by start, grouped by the date they start on in `tz`
+fn group_by_local_date(slots: Vec<CalendarSlot>, tz: Tz) -> Vec<AvailableSlotDay> {
+    let mut days: Vec<AvailableSlotDay> = Vec::new();
+    for slot in slots {
+        let local_start = slot.slot_start.with_timezone(&tz);
+        let local_end = slot.slot_end.with_timezone(&tz);
+        let date = local_start.date_naive();
+        let slot = CalendarSlotResponse::from(slot);
+        let local = LocalSlotResponse {
+            id: slot.id,
+            slot_start: slot.slot_start,
+            slot_end: slot.slot_end,
+            local_start: local_start.fixed_offset(),
+            local_end: local_end.fixed_offset(),
+            is_available: slot.is_available,
+            remaining_capacity: slot.remaining_capacity,
+            notes: slot.notes,
+        };
+        match days.last_mut() {
+            Some(day) if day.date == date => day.slots.push(local),
+            _ => days.push(AvailableSlotDay {
+                date,
+                slots: vec![local],
+            }),
+        }
+    }
+    days
 }
 
 /// Book a meeting slot for a submission
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/book-slot",
+    tag = "calendar",
+    params(("slug" = String, Path, description = "Submission slug")),
+    request_body = BookSlotRequest,
+    responses(
+        (status = 200, description = "Slot booked", body = ApiResponse<CalendarSlotResponse>),
+        (status = 400, description = "Slot unavailable or submission already has a booking", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many requests", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+    ),
+)]
 pub async fn book_slot(
     State(state): State<AppState>,
     Path(slug): Path<String>,
@@ -89,56 +181,41 @@ pub async fn book_slot(
         Ok(None) => {
             return (
                 StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Submission not found")),
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
             )
         }
         Err(e) => {
             tracing::error!("Database error: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Database error")),
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
             );
         }
     };
 
     // Check if submission already has a booked slot
-    let existing_booking = sqlx::query_as::<_, CalendarSlot>(
-        "SELECT * FROM calendar_slots WHERE booked_by_submission = $1",
-    )
-    .bind(submission.id)
-    .fetch_optional(&state.pool)
-    .await;
+    let existing_booking =
+        sqlx::query_scalar::<_, Uuid>("SELECT slot_id FROM slot_bookings WHERE submission_id = $1")
+            .bind(submission.id)
+            .fetch_optional(&state.pool)
+            .await;
 
     if let Ok(Some(_)) = existing_booking {
         return (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::error(
-                "This submission already has a meeting booked",
-            )),
+            Json(ApiResponse::error(ErrorKey::MeetingAlreadyBooked)),
         );
     }
 
-    // Try to book the slot (atomic operation)
-    let result = sqlx::query_as::<_, CalendarSlot>(
-        r#"
-        UPDATE calendar_slots
-        SET is_available = false, booked_by_submission = $1
-        WHERE id = $2 AND is_available = true AND slot_start > NOW()
-        RETURNING *
-        "#,
-    )
-    .bind(submission.id)
-    .bind(input.slot_id)
-    .fetch_optional(&state.pool)
-    .await;
+    let result = book_slot_for(&state.pool, input.slot_id, submission.id).await;
 
     match result {
         Ok(Some(slot)) => {
             // Log audit event
             let _ = sqlx::query(
                 r#"
-                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details)
-                VALUES ('slot_booked'::audit_action, 'calendar_slot', $1, 'applicant', $2, $3)
+                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+                VALUES ('slot_booked'::audit_action, 'calendar_slot', $1, 'applicant', $2, $3, $4)
                 "#,
             )
             .bind(slot.id)
@@ -148,6 +225,7 @@ pub async fn book_slot(
                 "slot_start": slot.slot_start,
                 "slot_end": slot.slot_end
             }))
+            .bind(current_request_id())
             .execute(&state.pool)
             .await;
 
@@ -160,21 +238,100 @@ pub async fn book_slot(
         }
         Ok(None) => (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::error(
-                "Slot not available or has already been booked",
-            )),
+            Json(ApiResponse::error(ErrorKey::SlotUnavailable)),
+        ),
+        // A concurrent request booked for this submission after the check
+        // above; the unique index on slot_bookings(submission_id) caught it
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(ErrorKey::MeetingAlreadyBooked)),
         ),
         Err(e) => {
             tracing::error!("Failed to book slot: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Failed to book slot")),
+                Json(ApiResponse::error(ErrorKey::BookSlotFailed)),
             )
         }
     }
 }
 
+/// Book a place on a slot that still has room
+///
+/// The slot row is locked while the bookings are counted, so concurrent
+/// requests cannot book it beyond its capacity. Returns `None` when the slot
+/// is full, in the past or does not exist.
+async fn book_slot_for(
+    pool: &sqlx::PgPool,
+    slot_id: Uuid,
+    submission_id: Uuid,
+) -> Result<Option<CalendarSlot>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let slot = book_in_transaction(&mut tx, slot_id, submission_id).await?;
+    if slot.is_some() {
+        tx.commit().await?;
+    }
+    Ok(slot)
+}
+
+async fn book_in_transaction(
+    conn: &mut PgConnection,
+    slot_id: Uuid,
+    submission_id: Uuid,
+) -> Result<Option<CalendarSlot>, sqlx::Error> {
+    let capacity = sqlx::query_scalar::<_, i32>(
+        "SELECT capacity FROM calendar_slots WHERE id = $1 AND slot_start > NOW() FOR UPDATE",
+    )
+    .bind(slot_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some(capacity) = capacity else {
+        return Ok(None);
+    };
+
+    // Counted after taking the lock, so bookings committed meanwhile are seen
+    let booked: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM slot_bookings WHERE slot_id = $1")
+        .bind(slot_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    if booked >= i64::from(capacity) {
+        return Ok(None);
+    }
+
+    sqlx::query("INSERT INTO slot_bookings (slot_id, submission_id) VALUES ($1, $2)")
+        .bind(slot_id)
+        .bind(submission_id)
+        .execute(&mut *conn)
+        .await?;
+
+    fetch_slot(&mut *conn, slot_id).await
+}
+
+/// A slot with its bookings counted
+async fn fetch_slot<'e>(
+    executor: impl PgExecutor<'e>,
+    slot_id: Uuid,
+) -> Result<Option<CalendarSlot>, sqlx::Error> {
+    sqlx::query_as::<_, CalendarSlot>(&format!(
+        "SELECT {SLOT_COLUMNS} FROM calendar_slots c WHERE c.id = $1"
+    ))
+    .bind(slot_id)
+    .fetch_optional(executor)
+    .await
+}
+
 /// Cancel a booking
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/cancel-booking",
+    tag = "calendar",
+    params(("slug" = String, Path, description = "Submission slug")),
+    responses(
+        (status = 200, description = "Booking cancelled", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission or booking not found", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many requests", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+    ),
+)]
 pub async fn cancel_booking(
     State(state): State<AppState>,
     Path(slug): Path<String>,
@@ -198,42 +355,38 @@ pub async fn cancel_booking(
         Ok(None) => {
             return (
                 StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Submission not found")),
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
             )
         }
         Err(e) => {
             tracing::error!("Database error: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Database error")),
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
             );
         }
     };
 
     // Find and cancel booking
-    let result = sqlx::query_as::<_, CalendarSlot>(
-        r#"
-        UPDATE calendar_slots
-        SET is_available = true, booked_by_submission = NULL
-        WHERE booked_by_submission = $1
-        RETURNING *
-        "#,
+    let result = sqlx::query_scalar::<_, Uuid>(
+        "DELETE FROM slot_bookings WHERE submission_id = $1 RETURNING slot_id",
     )
     .bind(submission.id)
     .fetch_optional(&state.pool)
     .await;
 
     match result {
-        Ok(Some(slot)) => {
+        Ok(Some(slot_id)) => {
             // Log audit event
             let _ = sqlx::query(
                 r#"
-                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id)
-                VALUES ('slot_cancelled'::audit_action, 'calendar_slot', $1, 'applicant', $2)
+                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, request_id)
+                VALUES ('slot_cancelled'::audit_action, 'calendar_slot', $1, 'applicant', $2, $3)
                 "#,
             )
-            .bind(slot.id)
+            .bind(slot_id)
             .bind(submission.id)
+            .bind(current_request_id())
             .execute(&state.pool)
             .await;
 
@@ -241,16 +394,300 @@ pub async fn cancel_booking(
         }
         Ok(None) => (
             StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("No booking found for this submission")),
+            Json(ApiResponse::error(ErrorKey::NoBooking)),
         ),
         Err(e) => {
             tracing::error!("Failed to cancel booking: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Failed to cancel booking")),
+                Json(ApiResponse::error(ErrorKey::CancelBookingFailed)),
+            )
+        }
+    }
+}
+
+/// Move a submission's booking to another slot (public)
+///
+/// The old slot is released and the new one booked in a single transaction,
+/// so the applicant either ends up with the new slot or keeps the old one.
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/reschedule",
+    tag = "calendar",
+    params(("slug" = String, Path, description = "Submission slug")),
+    request_body = RescheduleRequest,
+    responses(
+        (status = 200, description = "Booking moved to the new slot", body = ApiResponse<CalendarSlotResponse>),
+        (status = 400, description = "Target slot is in the past or already booked by this submission", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission, booking or slot not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Target slot was taken", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many requests", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+    ),
+)]
+pub async fn reschedule_booking(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(input): Json<RescheduleRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = validate_slug(&slug) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<CalendarSlotResponse>::error(e.to_string())),
+        );
+    }
+
+    let submission = sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE slug = $1")
+        .bind(&slug)
+        .fetch_optional(&state.pool)
+        .await;
+
+    let submission = match submission {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
             )
         }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
+            );
+        }
+    };
+
+    let outcome = match move_booking(&state, submission.id, input.new_slot_id).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            tracing::error!("Failed to reschedule booking: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::RescheduleFailed)),
+            );
+        }
+    };
+
+    let (old_slot_id, slot) = match outcome {
+        Ok(moved) => moved,
+        Err((status, message)) => return (status, Json(ApiResponse::error(message))),
+    };
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id, details, request_id)
+        VALUES ('slot_rescheduled'::audit_action, 'calendar_slot', $1, 'applicant', $2, $3, $4)
+        "#,
+    )
+    .bind(slot.id)
+    .bind(submission.id)
+    .bind(serde_json::json!({
+        "submission_slug": slug,
+        "old_slot_id": old_slot_id,
+        "new_slot_id": slot.id,
+        "slot_start": slot.slot_start,
+        "slot_end": slot.slot_end
+    }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    tracing::info!(
+        "Submission {} rescheduled from slot {} to {}",
+        slug,
+        old_slot_id,
+        slot.id
+    );
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(CalendarSlotResponse::from(slot))),
+    )
+}
+
+/// Release the current slot and book the new one; rolls back on rejection
+///
+/// Returns the id of the released slot and the newly booked slot.
+async fn move_booking(
+    state: &AppState,
+    submission_id: Uuid,
+    new_slot_id: Uuid,
+) -> Result<Result<(Uuid, CalendarSlot), (StatusCode, ErrorKey)>, sqlx::Error> {
+    let mut tx = state.pool.begin().await?;
+
+    let old_slot_id = sqlx::query_scalar::<_, Uuid>(
+        "DELETE FROM slot_bookings WHERE submission_id = $1 RETURNING slot_id",
+    )
+    .bind(submission_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let old_slot_id = match old_slot_id {
+        Some(slot_id) if slot_id == new_slot_id => {
+            tx.rollback().await?;
+            return Ok(Err((
+                StatusCode::BAD_REQUEST,
+                ErrorKey::SlotAlreadyBookedBySubmission,
+            )));
+        }
+        Some(slot_id) => slot_id,
+        None => {
+            tx.rollback().await?;
+            return Ok(Err((StatusCode::NOT_FOUND, ErrorKey::NoBooking)));
+        }
+    };
+
+    if let Some(slot) = book_in_transaction(&mut tx, new_slot_id, submission_id).await? {
+        tx.commit().await?;
+        return Ok(Ok((old_slot_id, slot)));
     }
+
+    let target = fetch_slot(&mut *tx, new_slot_id).await?;
+    tx.rollback().await?;
+
+    Ok(Err(reschedule_rejection(target.as_ref(), Utc::now())))
+}
+
+/// Explain why the target slot of a reschedule could not be booked
+fn reschedule_rejection(
+    target: Option<&CalendarSlot>,
+    now: DateTime<Utc>,
+) -> (StatusCode, ErrorKey) {
+    match target {
+        None => (StatusCode::NOT_FOUND, ErrorKey::SlotNotFound),
+        Some(slot) if slot.slot_start <= now => (StatusCode::BAD_REQUEST, ErrorKey::SlotInPast),
+        Some(_) => (StatusCode::CONFLICT, ErrorKey::SlotUnavailable),
+    }
+}
+
+/// Download the booked meeting of a submission as an iCalendar file
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{slug}/calendar.ics",
+    tag = "calendar",
+    params(("slug" = String, Path, description = "Submission slug")),
+    responses(
+        (status = 200, description = "iCalendar file for the booked meeting", body = String, content_type = "text/calendar"),
+        (status = 404, description = "No booking found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn get_booking_ics(State(state): State<AppState>, Path(slug): Path<String>) -> Response {
+    if let Err(e) = validate_slug(&slug) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(e.to_string())),
+        )
+            .into_response();
+    }
+
+    let slot = sqlx::query_as::<_, CalendarSlot>(&format!(
+        r#"
+        SELECT {SLOT_COLUMNS} FROM calendar_slots c
+        JOIN slot_bookings sb ON sb.slot_id = c.id
+        JOIN submissions s ON s.id = sb.submission_id
+        WHERE s.slug = $1
+        "#
+    ))
+    .bind(&slug)
+    .fetch_optional(&state.pool)
+    .await;
+
+    match slot {
+        Ok(Some(slot)) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .header(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"regelrecht-{}.ics\"", slug),
+            )
+            .body(booking_ics(&slot, Utc::now()).into())
+            .unwrap(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(ErrorKey::NoBooking)),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(ErrorKey::DatabaseError)),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Summary shown in the applicant's calendar
+const BOOKING_SUMMARY: &str = "RegelRecht bespreking";
+
+/// Render a booked slot as a VCALENDAR with a single VEVENT
+///
+/// The UID only depends on the slot, so importing the file again updates the
+/// existing calendar entry instead of duplicating it.
+fn booking_ics(slot: &CalendarSlot, now: DateTime<Utc>) -> String {
+    const ICS_TIME: &str = "%Y%m%dT%H%M%SZ";
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//RegelRecht//Upload Portal//NL".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}@regelrecht-upload", slot.id),
+        format!("DTSTAMP:{}", now.format(ICS_TIME)),
+        format!("DTSTART:{}", slot.slot_start.format(ICS_TIME)),
+        format!("DTEND:{}", slot.slot_end.format(ICS_TIME)),
+        format!("SUMMARY:{}", escape_ics_text(BOOKING_SUMMARY)),
+    ];
+    if let Some(notes) = slot.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        lines.push(format!("DESCRIPTION:{}", escape_ics_text(notes)));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_ics_line(line))
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+/// Escape a TEXT value (RFC 5545 section 3.3.11)
+fn escape_ics_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Fold a content line at 75 octets and terminate it with CRLF
+fn fold_ics_line(line: &str) -> String {
+    const MAX_OCTETS: usize = 75;
+
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space counts towards the next line
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
 }
 
 // =============================================================================
@@ -258,6 +695,20 @@ pub async fn cancel_booking(
 // =============================================================================
 
 /// List all slots (admin)
+///
+/// Booked slots name the submissions that booked them, so the calendar can
+/// show who is coming without looking each one up.
+#[utoipa::path(
+    get,
+    path = "/api/admin/calendar/slots",
+    tag = "admin-calendar",
+    params(AvailableSlotsQuery),
+    responses(
+        (status = 200, description = "All slots in the range", body = ApiResponse<Vec<AdminCalendarSlotResponse>>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn list_slots_admin(
     State(state): State<AppState>,
     Extension(_admin): Extension<AdminUser>,
@@ -270,47 +721,124 @@ pub async fn list_slots_admin(
         .to
         .unwrap_or_else(|| Utc::now() + chrono::Duration::days(60));
 
-    let slots = sqlx::query_as::<_, CalendarSlot>(
+    let slots = admin_slots(&state.pool, from, to).await.unwrap_or_default();
+
+    (StatusCode::OK, Json(ApiResponse::success(slots)))
+}
+
+/// Slots starting between `from` and `to`, with the booking submissions
+async fn admin_slots(
+    pool: &sqlx::PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<AdminCalendarSlotResponse>, sqlx::Error> {
+    let slots = sqlx::query_as::<_, CalendarSlot>(&format!(
         r#"
-        SELECT * FROM calendar_slots
-        WHERE slot_start >= $1 AND slot_start <= $2
-        ORDER BY slot_start ASC
-        "#,
-    )
+        SELECT {SLOT_COLUMNS} FROM calendar_slots c
+        WHERE c.slot_start >= $1 AND c.slot_start <= $2
+        ORDER BY c.slot_start ASC
+        "#
+    ))
     .bind(from)
     .bind(to)
-    .fetch_all(&state.pool)
-    .await
-    .unwrap_or_default();
+    .fetch_all(pool)
+    .await?;
+
+    let slot_ids: Vec<Uuid> = slots.iter().map(|slot| slot.id).collect();
+    let rows = sqlx::query_as::<_, (Uuid, Uuid, String, String, String)>(
+        r#"
+        SELECT sb.slot_id, s.id, s.slug, s.submitter_name, s.organization
+        FROM slot_bookings sb
+        JOIN submissions s ON s.id = sb.submission_id
+        WHERE sb.slot_id = ANY($1)
+        ORDER BY sb.created_at ASC
+        "#,
+    )
+    .bind(&slot_ids)
+    .fetch_all(pool)
+    .await?;
 
-    let responses: Vec<CalendarSlotResponse> =
-        slots.into_iter().map(CalendarSlotResponse::from).collect();
+    let mut bookings: HashMap<Uuid, Vec<SlotBooking>> = HashMap::new();
+    for (slot_id, submission_id, submission_slug, submitter_name, organization) in rows {
+        bookings.entry(slot_id).or_default().push(SlotBooking {
+            submission_id,
+            submission_slug,
+            submitter_name,
+            organization,
+        });
+    }
 
-    (StatusCode::OK, Json(ApiResponse::success(responses)))
+    Ok(slots
+        .into_iter()
+        .map(|slot| {
+            let remaining_capacity = slot.remaining_capacity();
+            AdminCalendarSlotResponse {
+                id: slot.id,
+                slot_start: slot.slot_start,
+                slot_end: slot.slot_end,
+                capacity: slot.capacity,
+                is_available: remaining_capacity > 0,
+                remaining_capacity,
+                notes: slot.notes,
+                bookings: bookings.remove(&slot.id).unwrap_or_default(),
+            }
+        })
+        .collect())
 }
 
 /// Create new calendar slot(s) (admin)
+#[utoipa::path(
+    post,
+    path = "/api/admin/calendar/slots",
+    tag = "admin-calendar",
+    request_body = Vec<CreateCalendarSlot>,
+    responses(
+        (status = 201, description = "Slots created", body = ApiResponse<Vec<CalendarSlotResponse>>),
+        (status = 400, description = "Invalid time range or outside business hours", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn create_slots(
     State(state): State<AppState>,
     Extension(admin): Extension<AdminUser>,
     Json(input): Json<Vec<CreateCalendarSlot>>,
 ) -> impl IntoResponse {
-    let mut created_slots = Vec::new();
-
-    for slot_input in input {
-        // Validate time range
+    // Validate every slot before creating any
+    for slot_input in &input {
         if slot_input.slot_end <= slot_input.slot_start {
             return (
                 StatusCode::BAD_REQUEST,
-                Json(ApiResponse::error("End time must be after start time")),
+                Json(ApiResponse::error(ErrorKey::EndBeforeStart)),
             );
         }
+        let capacity = slot_input.capacity.unwrap_or(1);
+        if !(1..=MAX_SLOT_CAPACITY).contains(&capacity) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::invalid(FieldError {
+                    min: Some(1),
+                    max: Some(MAX_SLOT_CAPACITY as i64),
+                    ..FieldError::new("capacity", "out_of_range", ErrorKey::SlotCapacityOutOfRange)
+                })),
+            );
+        }
+        if let Some(violation) = state
+            .business_hours
+            .as_ref()
+            .and_then(|hours| hours.violation(slot_input.slot_start, slot_input.slot_end))
+        {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(violation)));
+        }
+    }
 
-        // Create slot
+    let mut created_slots = Vec::new();
+    for slot_input in input {
+        let capacity = slot_input.capacity.unwrap_or(1);
         let result = sqlx::query_as::<_, CalendarSlot>(
             r#"
-            INSERT INTO calendar_slots (slot_start, slot_end, created_by, notes)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO calendar_slots (slot_start, slot_end, created_by, notes, capacity)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING *
             "#,
         )
@@ -318,6 +846,7 @@ pub async fn create_slots(
         .bind(slot_input.slot_end)
         .bind(admin.id)
         .bind(&slot_input.notes)
+        .bind(capacity)
         .fetch_one(&state.pool)
         .await;
 
@@ -329,7 +858,7 @@ pub async fn create_slots(
                 tracing::error!("Failed to create slot: {}", e);
                 return (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse::error("Failed to create slot")),
+                    Json(ApiResponse::error(ErrorKey::CreateSlotFailed)),
                 );
             }
         }
@@ -348,25 +877,33 @@ pub async fn create_slots(
 }
 
 /// Delete a calendar slot (admin)
+#[utoipa::path(
+    delete,
+    path = "/api/admin/calendar/slots/{slot_id}",
+    tag = "admin-calendar",
+    params(("slot_id" = Uuid, Path, description = "Slot id")),
+    responses(
+        (status = 200, description = "Slot deleted", body = ApiResponse<serde_json::Value>),
+        (status = 400, description = "Slot is booked", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Slot not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
 pub async fn delete_slot(
     State(state): State<AppState>,
     Extension(admin): Extension<AdminUser>,
     Path(slot_id): Path<Uuid>,
 ) -> impl IntoResponse {
     // Check if slot is booked
-    let slot = sqlx::query_as::<_, CalendarSlot>("SELECT * FROM calendar_slots WHERE id = $1")
-        .bind(slot_id)
-        .fetch_optional(&state.pool)
-        .await;
+    let slot = fetch_slot(&state.pool, slot_id).await;
 
     match slot {
         Ok(Some(slot)) => {
-            if slot.booked_by_submission.is_some() {
+            if slot.booked > 0 {
                 return (
                     StatusCode::BAD_REQUEST,
-                    Json(ApiResponse::error(
-                        "Cannot delete a booked slot. Cancel the booking first.",
-                    )),
+                    Json(ApiResponse::error(ErrorKey::SlotBooked)),
                 );
             }
 
@@ -381,14 +918,261 @@ pub async fn delete_slot(
         }
         Ok(None) => (
             StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Slot not found")),
+            Json(ApiResponse::error(ErrorKey::SlotNotFound)),
         ),
         Err(e) => {
             tracing::error!("Database error: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Database error")),
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
             )
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::calendar_import::parse_ics;
+    use super::*;
+
+    fn booked_slot(notes: Option<&str>) -> CalendarSlot {
+        let slot_start = DateTime::parse_from_rfc3339("2026-03-02T09:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        CalendarSlot {
+            id: Uuid::new_v4(),
+            slot_start,
+            slot_end: slot_start + chrono::Duration::hours(1),
+            capacity: 1,
+            created_by: None,
+            notes: notes.map(str::to_string),
+            created_at: slot_start,
+            booked: 1,
+        }
+    }
+
+    #[test]
+    fn test_booking_ics_parses_with_correct_times() {
+        let slot = booked_slot(Some("Teams-link volgt, neem je beleidsstukken mee"));
+        let ics = booking_ics(&slot, Utc::now());
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20260302T093000Z\r\n"));
+        assert!(ics.contains("DTEND:20260302T103000Z\r\n"));
+        assert!(ics.contains(&format!("UID:{}@regelrecht-upload\r\n", slot.id)));
+        assert!(ics.contains("DESCRIPTION:Teams-link volgt\\, neem"));
+
+        let parsed = parse_ics(&ics, SLOT_TIMEZONE);
+        assert_eq!(parsed.len(), 1);
+        let event = parsed[0].slot.as_ref().unwrap();
+        assert_eq!(event.slot_start, slot.slot_start);
+        assert_eq!(event.slot_end, slot.slot_end);
+        assert_eq!(event.notes.as_deref(), Some(BOOKING_SUMMARY));
+    }
+
+    #[test]
+    fn test_booking_ics_uid_is_stable() {
+        let slot = booked_slot(None);
+        let first = booking_ics(&slot, Utc::now());
+        let second = booking_ics(&slot, Utc::now() + chrono::Duration::minutes(5));
+        let uid = |ics: &str| {
+            ics.lines()
+                .find(|l| l.starts_with("UID:"))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(uid(&first), uid(&second));
+        assert!(!first.contains("DESCRIPTION"));
+    }
+
+    #[test]
+    fn test_reschedule_rejection() {
+        let now = Utc::now();
+        let mut slot = booked_slot(None);
+
+        slot.slot_start = now + chrono::Duration::days(1);
+        assert_eq!(
+            reschedule_rejection(Some(&slot), now).0,
+            StatusCode::CONFLICT
+        );
+
+        slot.slot_start = now - chrono::Duration::hours(1);
+        assert_eq!(
+            reschedule_rejection(Some(&slot), now).0,
+            StatusCode::BAD_REQUEST
+        );
+
+        assert_eq!(reschedule_rejection(None, now).0, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_slots_near_midnight_group_by_amsterdam_date() {
+        let slot_at = |start: &str| {
+            let mut slot = booked_slot(None);
+            slot.slot_start = DateTime::parse_from_rfc3339(start)
+                .unwrap()
+                .with_timezone(&Utc);
+            slot.slot_end = slot.slot_start + chrono::Duration::minutes(30);
+            slot
+        };
+        // Summer time starts 2026-03-29 and ends 2026-10-25, at 01:00 UTC
+        let slots = [
+            "2026-03-28T22:30:00Z",
+            "2026-03-28T23:30:00Z",
+            "2026-03-29T22:30:00Z",
+            "2026-10-24T22:30:00Z",
+            "2026-10-25T22:30:00Z",
+        ]
+        .map(slot_at);
+
+        let days = group_by_local_date(slots.to_vec(), SLOT_TIMEZONE);
+        let dates: Vec<String> = days.iter().map(|d| d.date.to_string()).collect();
+        assert_eq!(
+            dates,
+            ["2026-03-28", "2026-03-29", "2026-03-30", "2026-10-25"]
+        );
+        let local_starts: Vec<String> = days
+            .iter()
+            .flat_map(|d| &d.slots)
+            .map(|s| s.local_start.to_rfc3339())
+            .collect();
+        assert_eq!(
+            local_starts,
+            [
+                "2026-03-28T23:30:00+01:00",
+                "2026-03-29T00:30:00+01:00",
+                "2026-03-30T00:30:00+02:00",
+                "2026-10-25T00:30:00+02:00",
+                "2026-10-25T23:30:00+01:00",
+            ]
+        );
+        assert_eq!(days[3].slots[0].slot_start, slots[3].slot_start);
+
+        // The same instants fall on other dates in UTC
+        let days = group_by_local_date(slots.to_vec(), chrono_tz::UTC);
+        assert_eq!(days[0].slots.len(), 2);
+        assert_eq!(days[0].date.to_string(), "2026-03-28");
+    }
+
+    #[test]
+    fn test_fold_ics_line_limits_octets() {
+        let long = format!("DESCRIPTION:{}", "é".repeat(60));
+        let folded = fold_ics_line(&long);
+        for line in folded.split("\r\n").filter(|l| !l.is_empty()) {
+            assert!(line.len() <= 75, "{} octets", line.len());
+        }
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), long);
+    }
+
+    /// Insert a submission for booking tests, returning its id and slug
+    async fn insert_submission(pool: &sqlx::PgPool) -> (Uuid, String) {
+        sqlx::query_as(
+            "INSERT INTO submissions (slug, submitter_name, organization, status)
+             VALUES ($1, 'Jan', 'Gemeente', 'submitted') RETURNING id, slug",
+        )
+        .bind(format!("rr-{}", &Uuid::new_v4().simple().to_string()[..12]))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn insert_slot(pool: &sqlx::PgPool, start: DateTime<Utc>, capacity: i32) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO calendar_slots (slot_start, slot_end, capacity)
+             VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(start)
+        .bind(start + chrono::Duration::hours(1))
+        .bind(capacity)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_admin_slots_name_the_booking_submission() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        let (submission_id, slug) = insert_submission(&pool).await;
+        let start = Utc::now() + chrono::Duration::days(3);
+        let booked_id = insert_slot(&pool, start, 1).await;
+        let free_id = insert_slot(&pool, start, 1).await;
+        assert!(book_slot_for(&pool, booked_id, submission_id)
+            .await
+            .unwrap()
+            .is_some());
+
+        let slots = admin_slots(&pool, start, start).await.unwrap();
+        let slot = |id: Uuid| slots.iter().find(|s| s.id == id).unwrap();
+        let booked = slot(booked_id);
+        assert_eq!(booked.bookings.len(), 1);
+        assert_eq!(booked.bookings[0].submission_slug, slug);
+        assert_eq!(booked.bookings[0].submitter_name, "Jan");
+        assert_eq!(booked.bookings[0].organization, "Gemeente");
+        assert!(!booked.is_available);
+        let free = slot(free_id);
+        assert!(free.bookings.is_empty());
+        assert!(free.is_available);
+
+        sqlx::query("DELETE FROM calendar_slots WHERE id = ANY($1)")
+            .bind(vec![booked_id, free_id])
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM submissions WHERE id = $1")
+            .bind(submission_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_slot_takes_bookings_up_to_capacity() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        let slot_id = insert_slot(&pool, Utc::now() + chrono::Duration::days(3), 2).await;
+        let mut submission_ids = Vec::new();
+        for _ in 0..3 {
+            submission_ids.push(insert_submission(&pool).await.0);
+        }
+
+        let first = book_slot_for(&pool, slot_id, submission_ids[0])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.remaining_capacity(), 1);
+        let second = book_slot_for(&pool, slot_id, submission_ids[1])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.remaining_capacity(), 0);
+        assert!(!CalendarSlotResponse::from(second).is_available);
+        assert!(book_slot_for(&pool, slot_id, submission_ids[2])
+            .await
+            .unwrap()
+            .is_none());
+
+        let booked: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM slot_bookings WHERE slot_id = $1")
+                .bind(slot_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(booked, 2);
+
+        sqlx::query("DELETE FROM calendar_slots WHERE id = $1")
+            .bind(slot_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM submissions WHERE id = ANY($1)")
+            .bind(&submission_ids)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
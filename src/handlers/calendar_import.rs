@@ -0,0 +1,808 @@
+//! Bulk import of calendar slots from ICS or CSV files
+//!
+//! Admins keep the review schedule in Outlook; exporting it as .ics (or a
+//! simple CSV) and importing it here avoids re-entering every slot by hand.
+
+use crate::config::BusinessHours;
+use crate::i18n::ErrorKey;
+use crate::models::*;
+use axum::{
+    extract::{Multipart, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::AppState;
+
+/// Timezone used for floating (zone-less) times without configured business hours
+pub const SLOT_TIMEZONE: Tz = chrono_tz::Europe::Amsterdam;
+
+/// Maximum size of an import file
+const MAX_IMPORT_SIZE: usize = 1024 * 1024;
+
+/// Shortest and longest slot accepted by the importer
+pub(super) const MIN_SLOT_MINUTES: i64 = 15;
+pub(super) const MAX_SLOT_MINUTES: i64 = 4 * 60;
+
+// =============================================================================
+// Request / Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportSlotsQuery {
+    /// Validate and report without creating any slots
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotImportStatus {
+    Created,
+    WouldCreate,
+    Conflict,
+    Invalid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SlotImportResult {
+    /// 1-based event number (ICS) or line number (CSV) in the uploaded file
+    pub entry: usize,
+    pub status: SlotImportStatus,
+    pub slot_start: Option<DateTime<Utc>>,
+    pub slot_end: Option<DateTime<Utc>>,
+    pub slot_id: Option<Uuid>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SlotImportReport {
+    pub dry_run: bool,
+    pub created: usize,
+    pub rejected: usize,
+    pub results: Vec<SlotImportResult>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportFormat {
+    Ics,
+    Csv,
+}
+
+/// A single `NAME;PARAM=VALUE:content` line of an iCalendar file
+#[derive(Debug)]
+struct IcsProperty {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+/// A slot read from the import file, or the reason it could not be read
+#[derive(Debug)]
+pub(super) struct ParsedSlot {
+    pub(super) entry: usize,
+    pub(super) slot: Result<CreateCalendarSlot, String>,
+}
+
+// =============================================================================
+// Handler
+// =============================================================================
+
+/// Import calendar slots from an uploaded .ics or .csv file (admin)
+///
+/// Every entry is reported individually; valid, non-overlapping slots are
+/// created in a single transaction. With `?dry_run=true` nothing is written.
+#[utoipa::path(
+    post,
+    path = "/api/admin/calendar/slots/import",
+    tag = "admin-calendar",
+    params(ImportSlotsQuery),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Dry run report", body = ApiResponse<SlotImportReport>),
+        (status = 201, description = "Import report", body = ApiResponse<SlotImportReport>),
+        (status = 400, description = "Unreadable import file", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn import_slots(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Query(query): Query<ImportSlotsQuery>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let field = match multipart.next_field().await {
+        Ok(Some(field)) => field,
+        Ok(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<SlotImportReport>::error(
+                    ErrorKey::NoFileProvided,
+                )),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Multipart parsing error: {}", e);
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(ErrorKey::ProcessUploadFailed)),
+            );
+        }
+    };
+
+    let format = match detect_format(field.file_name(), field.content_type()) {
+        Some(format) => format,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(ErrorKey::UnsupportedImportFile)),
+            );
+        }
+    };
+
+    let data = match field.bytes().await {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::error!("Failed to read import file: {}", e);
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(ErrorKey::ReadUploadFailed)),
+            );
+        }
+    };
+
+    if data.len() > MAX_IMPORT_SIZE {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(ErrorKey::ImportTooLarge)),
+        );
+    }
+
+    let text = match std::str::from_utf8(&data) {
+        Ok(text) => text,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(ErrorKey::ImportNotUtf8)),
+            );
+        }
+    };
+
+    let tz = slot_timezone(state.business_hours.as_ref());
+    let parsed = match format {
+        ImportFormat::Ics => parse_ics(text, tz),
+        ImportFormat::Csv => match parse_csv(text, tz) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e)));
+            }
+        },
+    };
+
+    if parsed.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(ErrorKey::NoSlotsInImport)),
+        );
+    }
+
+    let report = match apply_import(&state, &admin, parsed, query.dry_run).await {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::error!("Failed to import calendar slots: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::ImportSlotsFailed)),
+            );
+        }
+    };
+
+    tracing::info!(
+        "Admin {} imported calendar slots (dry_run={}): {} created, {} rejected",
+        admin.username,
+        report.dry_run,
+        report.created,
+        report.rejected
+    );
+
+    let status = if query.dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    (status, Json(ApiResponse::success(report)))
+}
+
+/// Validate parsed slots against existing ones and create the acceptable ones
+async fn apply_import(
+    state: &AppState,
+    admin: &AdminUser,
+    parsed: Vec<ParsedSlot>,
+    dry_run: bool,
+) -> Result<SlotImportReport, sqlx::Error> {
+    let now = Utc::now();
+    let mut tx = state.pool.begin().await?;
+
+    // Keep concurrent slot creation from slipping in between the overlap
+    // checks and the inserts below
+    sqlx::query("LOCK TABLE calendar_slots IN SHARE ROW EXCLUSIVE MODE")
+        .execute(&mut *tx)
+        .await?;
+
+    let mut accepted: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    let mut results = Vec::with_capacity(parsed.len());
+
+    for ParsedSlot { entry, slot } in parsed {
+        let slot = match slot {
+            Ok(slot) => slot,
+            Err(reason) => {
+                results.push(SlotImportResult {
+                    entry,
+                    status: SlotImportStatus::Invalid,
+                    slot_start: None,
+                    slot_end: None,
+                    slot_id: None,
+                    reason: Some(reason),
+                });
+                continue;
+            }
+        };
+
+        let mut result = SlotImportResult {
+            entry,
+            status: SlotImportStatus::Invalid,
+            slot_start: Some(slot.slot_start),
+            slot_end: Some(slot.slot_end),
+            slot_id: None,
+            reason: None,
+        };
+
+        if let Err(reason) = validate_import_slot(
+            slot.slot_start,
+            slot.slot_end,
+            now,
+            state.business_hours.as_ref(),
+        ) {
+            result.reason = Some(reason);
+            results.push(result);
+            continue;
+        }
+
+        let overlaps_batch = accepted
+            .iter()
+            .any(|(start, end)| slot.slot_start < *end && slot.slot_end > *start);
+        if overlaps_batch {
+            result.status = SlotImportStatus::Conflict;
+            result.reason = Some("Overlaps another slot in this import".to_string());
+            results.push(result);
+            continue;
+        }
+
+        let overlaps_existing: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM calendar_slots
+                WHERE slot_start < $2 AND slot_end > $1
+            )
+            "#,
+        )
+        .bind(slot.slot_start)
+        .bind(slot.slot_end)
+        .fetch_one(&mut *tx)
+        .await?;
+        if overlaps_existing {
+            result.status = SlotImportStatus::Conflict;
+            result.reason = Some("Overlaps an existing slot".to_string());
+            results.push(result);
+            continue;
+        }
+
+        accepted.push((slot.slot_start, slot.slot_end));
+
+        if dry_run {
+            result.status = SlotImportStatus::WouldCreate;
+        } else {
+            let created = sqlx::query_as::<_, CalendarSlot>(
+                r#"
+                INSERT INTO calendar_slots (slot_start, slot_end, created_by, notes)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *
+                "#,
+            )
+            .bind(slot.slot_start)
+            .bind(slot.slot_end)
+            .bind(admin.id)
+            .bind(&slot.notes)
+            .fetch_one(&mut *tx)
+            .await?;
+            result.status = SlotImportStatus::Created;
+            result.slot_id = Some(created.id);
+        }
+        results.push(result);
+    }
+
+    if dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+
+    let created = accepted.len();
+    Ok(SlotImportReport {
+        dry_run,
+        created,
+        rejected: results.len() - created,
+        results,
+    })
+}
+
+// =============================================================================
+// Validation
+// =============================================================================
+
+/// Check an imported slot's duration and that it lies within the business
+/// hours admins are held to, when those are configured
+fn validate_import_slot(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    now: DateTime<Utc>,
+    hours: Option<&BusinessHours>,
+) -> Result<(), String> {
+    if end <= start {
+        return Err("End time must be after start time".to_string());
+    }
+    if start <= now {
+        return Err("Slot lies in the past".to_string());
+    }
+
+    let minutes = (end - start).num_minutes();
+    if !(MIN_SLOT_MINUTES..=MAX_SLOT_MINUTES).contains(&minutes) {
+        return Err(format!(
+            "Slot duration must be between {} and {} minutes",
+            MIN_SLOT_MINUTES, MAX_SLOT_MINUTES
+        ));
+    }
+
+    match hours.and_then(|hours| hours.violation(start, end)) {
+        Some(violation) => Err(violation),
+        None => Ok(()),
+    }
+}
+
+/// Zone in which local slot times are read: that of the business hours, if any
+pub(super) fn slot_timezone(hours: Option<&BusinessHours>) -> Tz {
+    hours.map_or(SLOT_TIMEZONE, |hours| hours.timezone)
+}
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+fn detect_format(file_name: Option<&str>, content_type: Option<&str>) -> Option<ImportFormat> {
+    let file_name = file_name.unwrap_or_default().to_lowercase();
+    let content_type = content_type.unwrap_or_default();
+    if file_name.ends_with(".ics") || content_type == "text/calendar" {
+        Some(ImportFormat::Ics)
+    } else if file_name.ends_with(".csv") || content_type == "text/csv" {
+        Some(ImportFormat::Csv)
+    } else {
+        None
+    }
+}
+
+/// Parse the VEVENTs of an iCalendar file, reading floating times in `tz`
+pub(super) fn parse_ics(text: &str, tz: Tz) -> Vec<ParsedSlot> {
+    let mut slots = Vec::new();
+    let mut event: Option<Vec<IcsProperty>> = None;
+
+    for line in unfold_ics_lines(text) {
+        let Some(property) = parse_ics_property(&line) else {
+            continue;
+        };
+        match (
+            property.name.as_str(),
+            property.value.to_ascii_uppercase().as_str(),
+        ) {
+            ("BEGIN", "VEVENT") => event = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(properties) = event.take() {
+                    slots.push(ParsedSlot {
+                        entry: slots.len() + 1,
+                        slot: ics_event_to_slot(&properties, tz),
+                    });
+                }
+            }
+            _ => {
+                if let Some(properties) = event.as_mut() {
+                    properties.push(property);
+                }
+            }
+        }
+    }
+
+    slots
+}
+
+/// Join folded continuation lines (RFC 5545 section 3.1)
+fn unfold_ics_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// Split `NAME;PARAM=VALUE:content` into its parts
+fn parse_ics_property(line: &str) -> Option<IcsProperty> {
+    // The value starts at the first colon outside a quoted parameter value
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| {
+            (
+                k.trim().to_ascii_uppercase(),
+                v.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+
+    Some(IcsProperty {
+        name,
+        params,
+        value: value.trim_end().to_string(),
+    })
+}
+
+fn ics_event_to_slot(properties: &[IcsProperty], tz: Tz) -> Result<CreateCalendarSlot, String> {
+    let find = |name: &str| properties.iter().find(|p| p.name == name);
+
+    if find("RRULE").is_some() {
+        return Err("Recurring events are not supported".to_string());
+    }
+    if find("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED")) {
+        return Err("Event is cancelled".to_string());
+    }
+
+    let start = find("DTSTART").ok_or("Event has no DTSTART")?;
+    let slot_start = parse_ics_datetime(&start.params, &start.value, tz)?;
+
+    let slot_end = if let Some(end) = find("DTEND") {
+        parse_ics_datetime(&end.params, &end.value, tz)?
+    } else if let Some(duration) = find("DURATION") {
+        slot_start + parse_ics_duration(&duration.value)?
+    } else {
+        return Err("Event has no DTEND or DURATION".to_string());
+    };
+
+    let notes = find("SUMMARY")
+        .map(|p| unescape_ics_text(&p.value))
+        .filter(|s| !s.is_empty());
+
+    Ok(CreateCalendarSlot {
+        slot_start,
+        slot_end,
+        notes,
+        capacity: None,
+    })
+}
+
+fn parse_ics_datetime(
+    params: &[(String, String)],
+    value: &str,
+    floating: Tz,
+) -> Result<DateTime<Utc>, String> {
+    let param = |key: &str| params.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+
+    if param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"))
+        || NaiveDate::parse_from_str(value, "%Y%m%d").is_ok()
+    {
+        return Err("All-day events are not supported".to_string());
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .map(|naive| naive.and_utc())
+            .map_err(|_| format!("Invalid date-time '{}'", value));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map_err(|_| format!("Invalid date-time '{}'", value))?;
+    let tz = match param("TZID") {
+        Some(tzid) => {
+            resolve_timezone(tzid).ok_or_else(|| format!("Unknown timezone '{}'", tzid))?
+        }
+        None => floating,
+    };
+    local_to_utc(tz, naive)
+}
+
+/// Resolve an IANA or (Outlook) Windows timezone name
+fn resolve_timezone(tzid: &str) -> Option<Tz> {
+    if let Ok(tz) = tzid.parse::<Tz>() {
+        return Some(tz);
+    }
+    match tzid {
+        "W. Europe Standard Time" => Some(chrono_tz::Europe::Amsterdam),
+        "Romance Standard Time" => Some(chrono_tz::Europe::Brussels),
+        "Central Europe Standard Time" => Some(chrono_tz::Europe::Budapest),
+        "GMT Standard Time" => Some(chrono_tz::Europe::London),
+        "UTC" | "Coordinated Universal Time" => Some(chrono_tz::UTC),
+        _ => None,
+    }
+}
+
+pub(super) fn local_to_utc(tz: Tz, naive: NaiveDateTime) -> Result<DateTime<Utc>, String> {
+    tz.from_local_datetime(&naive)
+        .single()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| format!("Ambiguous or non-existent local time '{}'", naive))
+}
+
+/// Parse the time part of an ICS duration, e.g. `PT1H30M`
+fn parse_ics_duration(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("Unsupported duration '{}'", value);
+    let rest = value.strip_prefix("PT").ok_or_else(invalid)?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in rest.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        total += match c {
+            'H' => Duration::hours(n),
+            'M' => Duration::minutes(n),
+            'S' => Duration::seconds(n),
+            _ => return Err(invalid()),
+        };
+    }
+
+    if !number.is_empty() || total.is_zero() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+fn unescape_ics_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Parse `slot_start,slot_end[,notes]` rows; a header row is optional
+///
+/// Times are RFC 3339 or `YYYY-MM-DD HH:MM` in `tz`. Rows are read line by
+/// line so results can refer to the line number in the file.
+fn parse_csv(text: &str, tz: Tz) -> Result<Vec<ParsedSlot>, String> {
+    let mut slots = Vec::new();
+    let mut seen_row = false;
+
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .trim(csv::Trim::All)
+            .from_reader(line.as_bytes());
+        let record = match reader.records().next() {
+            Some(record) => {
+                record.map_err(|e| format!("Invalid CSV on line {}: {}", index + 1, e))?
+            }
+            None => continue,
+        };
+        let first = record.get(0).unwrap_or_default();
+
+        // Skip a header row
+        let is_first_row = !seen_row;
+        seen_row = true;
+        if is_first_row && first.to_ascii_lowercase().contains("start") {
+            continue;
+        }
+
+        let slot = (|| {
+            let start = parse_csv_datetime(first, tz)?;
+            let end = parse_csv_datetime(record.get(1).ok_or("Missing slot_end column")?, tz)?;
+            let notes = record.get(2).filter(|s| !s.is_empty()).map(str::to_string);
+            Ok(CreateCalendarSlot {
+                slot_start: start,
+                slot_end: end,
+                notes,
+                capacity: None,
+            })
+        })();
+        slots.push(ParsedSlot {
+            entry: index + 1,
+            slot,
+        });
+    }
+
+    Ok(slots)
+}
+
+fn parse_csv_datetime(value: &str, tz: Tz) -> Result<DateTime<Utc>, String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+        .ok_or_else(|| format!("Invalid date-time '{}'", value))
+        .and_then(|naive| local_to_utc(tz, naive))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_ics_events() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20300114T090000Z\r\n\
+            DTEND:20300114T100000Z\r\n\
+            SUMMARY:Review\\, ronde 1\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;TZID=\"W. Europe Standard Time\":20300115T\r\n \
+            140000\r\n\
+            DURATION:PT1H30M\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20300116\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let slots = parse_ics(ics, SLOT_TIMEZONE);
+        assert_eq!(slots.len(), 3);
+
+        let first = slots[0].slot.as_ref().unwrap();
+        assert_eq!(first.slot_start, utc("2030-01-14T09:00:00Z"));
+        assert_eq!(first.slot_end, utc("2030-01-14T10:00:00Z"));
+        assert_eq!(first.notes.as_deref(), Some("Review, ronde 1"));
+
+        // Folded line, Windows timezone name (CET = UTC+1 in January)
+        let second = slots[1].slot.as_ref().unwrap();
+        assert_eq!(second.slot_start, utc("2030-01-15T13:00:00Z"));
+        assert_eq!(second.slot_end, utc("2030-01-15T14:30:00Z"));
+
+        assert_eq!(slots[2].entry, 3);
+        assert!(slots[2].slot.is_err());
+    }
+
+    #[test]
+    fn test_parse_ics_rejects_unknown_timezone_and_recurrence() {
+        let ics = "BEGIN:VEVENT\n\
+            DTSTART;TZID=Mars/Olympus:20300114T090000\n\
+            DTEND;TZID=Mars/Olympus:20300114T100000\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            DTSTART:20300114T090000Z\n\
+            DTEND:20300114T100000Z\n\
+            RRULE:FREQ=WEEKLY\n\
+            END:VEVENT\n";
+
+        let slots = parse_ics(ics, SLOT_TIMEZONE);
+        assert_eq!(slots.len(), 2);
+        assert!(slots.iter().all(|s| s.slot.is_err()));
+    }
+
+    #[test]
+    fn test_parse_csv_rows() {
+        let csv = "slot_start,slot_end,notes\n\
+            2030-06-03 10:00,2030-06-03 11:00,Zaal A\n\
+            2030-06-03T12:00:00Z,2030-06-03T13:00:00Z\n\
+            \n\
+            not a date,2030-06-03 11:00\n";
+
+        let slots = parse_csv(csv, SLOT_TIMEZONE).unwrap();
+        assert_eq!(slots.len(), 3);
+
+        // Local summer time (CEST = UTC+2)
+        let first = slots[0].slot.as_ref().unwrap();
+        assert_eq!(slots[0].entry, 2);
+        assert_eq!(first.slot_start, utc("2030-06-03T08:00:00Z"));
+        assert_eq!(first.notes.as_deref(), Some("Zaal A"));
+
+        let second = slots[1].slot.as_ref().unwrap();
+        assert_eq!(second.slot_start, utc("2030-06-03T12:00:00Z"));
+        assert!(second.notes.is_none());
+
+        assert_eq!(slots[2].entry, 5);
+        assert!(slots[2].slot.is_err());
+    }
+
+    #[test]
+    fn test_validate_import_slot() {
+        let now = utc("2030-01-01T00:00:00Z");
+        let hours = BusinessHours {
+            weekdays: vec![
+                chrono::Weekday::Mon,
+                chrono::Weekday::Tue,
+                chrono::Weekday::Wed,
+                chrono::Weekday::Thu,
+                chrono::Weekday::Fri,
+            ],
+            start: chrono::NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            end: chrono::NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            timezone: SLOT_TIMEZONE,
+        };
+        let check = |start: &str, end: &str, hours: Option<&BusinessHours>| {
+            validate_import_slot(utc(start), utc(end), now, hours)
+        };
+
+        // 10:00-11:00 local
+        assert!(check("2030-01-14T09:00:00Z", "2030-01-14T10:00:00Z", Some(&hours)).is_ok());
+        // Ends exactly at closing time
+        assert!(check("2030-01-14T16:00:00Z", "2030-01-14T17:00:00Z", Some(&hours)).is_ok());
+
+        // In the past
+        assert!(check("2029-12-01T09:00:00Z", "2029-12-01T10:00:00Z", None).is_err());
+        // Too short and too long
+        assert!(check("2030-01-14T09:00:00Z", "2030-01-14T09:10:00Z", None).is_err());
+        assert!(check("2030-01-14T08:00:00Z", "2030-01-14T13:00:00Z", None).is_err());
+
+        // Starts before business hours (06:30 local), ends after them
+        // (18:30 local) or falls on a Saturday
+        assert!(check("2030-01-14T05:30:00Z", "2030-01-14T06:30:00Z", Some(&hours)).is_err());
+        assert!(check("2030-01-14T16:30:00Z", "2030-01-14T17:30:00Z", Some(&hours)).is_err());
+        assert!(check("2030-01-19T09:00:00Z", "2030-01-19T10:00:00Z", Some(&hours)).is_err());
+        // Without configured business hours any time of day is accepted
+        assert!(check("2030-01-19T05:30:00Z", "2030-01-19T06:30:00Z", None).is_ok());
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            detect_format(Some("agenda.ICS"), None),
+            Some(ImportFormat::Ics)
+        );
+        assert_eq!(
+            detect_format(Some("slots"), Some("text/csv")),
+            Some(ImportFormat::Csv)
+        );
+        assert_eq!(detect_format(Some("slots.xlsx"), None), None);
+    }
+}
@@ -0,0 +1,346 @@
+//! Generation of recurring calendar slots
+//!
+//! Weekly review meetings follow a fixed pattern, so admins describe the
+//! pattern once and the server expands it into individual slots.
+
+use crate::i18n::ErrorKey;
+use crate::models::*;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::calendar_import::{local_to_utc, slot_timezone, MAX_SLOT_MINUTES, MIN_SLOT_MINUTES};
+use super::AppState;
+
+/// Upper bound on the number of slots a single request may generate
+const MAX_RECURRING_SLOTS: usize = 500;
+
+/// Start and end of a generated slot
+type SlotRange = (DateTime<Utc>, DateTime<Utc>);
+
+// =============================================================================
+// Request / Response Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceInterval {
+    Weekly,
+    Biweekly,
+}
+
+impl RecurrenceInterval {
+    fn weeks(self) -> i64 {
+        match self {
+            RecurrenceInterval::Weekly => 1,
+            RecurrenceInterval::Biweekly => 2,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateRecurringSlotsRequest {
+    /// First and last date (inclusive) on which slots may be generated
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Weekdays such as "mon" or "thursday"
+    #[schema(value_type = Vec<String>, example = json!(["tue", "thu"]))]
+    pub weekdays: Vec<Weekday>,
+    /// Local start time of each slot, in the zone of the business hours
+    pub start_time: NaiveTime,
+    pub duration_minutes: i64,
+    pub interval: RecurrenceInterval,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecurringSlotsReport {
+    pub created: usize,
+    /// Occurrences in the past or overlapping an existing slot
+    pub skipped: usize,
+    pub slots: Vec<CalendarSlotResponse>,
+}
+
+// =============================================================================
+// Handler
+// =============================================================================
+
+/// Expand a recurrence pattern into calendar slots (admin)
+///
+/// All slots are created in one transaction; occurrences that overlap an
+/// existing slot or already lie in the past are skipped.
+#[utoipa::path(
+    post,
+    path = "/api/admin/calendar/slots/recurring",
+    tag = "admin-calendar",
+    request_body = CreateRecurringSlotsRequest,
+    responses(
+        (status = 201, description = "Slots generated", body = ApiResponse<RecurringSlotsReport>),
+        (status = 400, description = "Invalid pattern, too many slots or outside business hours", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("admin_session" = [])),
+)]
+pub async fn create_recurring_slots(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AdminUser>,
+    Json(input): Json<CreateRecurringSlotsRequest>,
+) -> impl IntoResponse {
+    let tz = slot_timezone(state.business_hours.as_ref());
+    let occurrences = match expand_recurrence(&input, tz) {
+        Ok(occurrences) => occurrences,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e)));
+        }
+    };
+    if let Some(hours) = &state.business_hours {
+        if let Some(violation) = occurrences
+            .iter()
+            .find_map(|&(start, end)| hours.violation(start, end))
+        {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(violation)));
+        }
+    }
+
+    let report = match insert_occurrences(&state, &admin, &occurrences, &input.notes).await {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::error!("Failed to create recurring slots: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::CreateSlotsFailed)),
+            );
+        }
+    };
+
+    tracing::info!(
+        "Admin {} generated recurring calendar slots: {} created, {} skipped",
+        admin.username,
+        report.created,
+        report.skipped
+    );
+
+    (StatusCode::CREATED, Json(ApiResponse::success(report)))
+}
+
+async fn insert_occurrences(
+    state: &AppState,
+    admin: &AdminUser,
+    occurrences: &[SlotRange],
+    notes: &Option<String>,
+) -> Result<RecurringSlotsReport, sqlx::Error> {
+    let now = Utc::now();
+    let mut tx = state.pool.begin().await?;
+
+    // Same lock as the bulk import so concurrent slot creation cannot slip in
+    // between the overlap checks and the inserts
+    sqlx::query("LOCK TABLE calendar_slots IN SHARE ROW EXCLUSIVE MODE")
+        .execute(&mut *tx)
+        .await?;
+
+    let mut slots = Vec::new();
+    for &(slot_start, slot_end) in occurrences {
+        if slot_start <= now {
+            continue;
+        }
+
+        let overlaps_existing: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM calendar_slots
+                WHERE slot_start < $2 AND slot_end > $1
+            )
+            "#,
+        )
+        .bind(slot_start)
+        .bind(slot_end)
+        .fetch_one(&mut *tx)
+        .await?;
+        if overlaps_existing {
+            continue;
+        }
+
+        let slot = sqlx::query_as::<_, CalendarSlot>(
+            r#"
+            INSERT INTO calendar_slots (slot_start, slot_end, created_by, notes)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(slot_start)
+        .bind(slot_end)
+        .bind(admin.id)
+        .bind(notes)
+        .fetch_one(&mut *tx)
+        .await?;
+        slots.push(CalendarSlotResponse::from(slot));
+    }
+
+    tx.commit().await?;
+
+    Ok(RecurringSlotsReport {
+        created: slots.len(),
+        skipped: occurrences.len() - slots.len(),
+        slots,
+    })
+}
+
+// =============================================================================
+// Expansion
+// =============================================================================
+
+/// Expand a recurrence pattern into UTC slot ranges, in chronological order
+///
+/// Weeks are counted from the Monday of `start_date`, so a biweekly pattern
+/// uses that week and every second week after it.
+fn expand_recurrence(
+    input: &CreateRecurringSlotsRequest,
+    tz: Tz,
+) -> Result<Vec<SlotRange>, String> {
+    if input.end_date < input.start_date {
+        return Err("End date must not be before start date".to_string());
+    }
+    if input.weekdays.is_empty() {
+        return Err("At least one weekday is required".to_string());
+    }
+    if !(MIN_SLOT_MINUTES..=MAX_SLOT_MINUTES).contains(&input.duration_minutes) {
+        return Err(format!(
+            "Slot duration must be between {} and {} minutes",
+            MIN_SLOT_MINUTES, MAX_SLOT_MINUTES
+        ));
+    }
+
+    let duration = Duration::minutes(input.duration_minutes);
+
+    let first_monday = input.start_date
+        - Duration::days(i64::from(input.start_date.weekday().num_days_from_monday()));
+    let step = input.interval.weeks();
+
+    let mut occurrences = Vec::new();
+    for date in input.start_date.iter_days() {
+        if date > input.end_date {
+            break;
+        }
+        let week = (date - first_monday).num_days() / 7;
+        if week % step != 0 || !input.weekdays.contains(&date.weekday()) {
+            continue;
+        }
+        if occurrences.len() == MAX_RECURRING_SLOTS {
+            return Err(format!(
+                "Pattern generates more than {} slots",
+                MAX_RECURRING_SLOTS
+            ));
+        }
+
+        let start = local_to_utc(tz, date.and_time(input.start_time))?;
+        occurrences.push((start, start + duration));
+    }
+
+    Ok(occurrences)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::calendar_import::SLOT_TIMEZONE;
+
+    fn request(json: serde_json::Value) -> CreateRecurringSlotsRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn pattern(start: &str, end: &str, interval: &str) -> CreateRecurringSlotsRequest {
+        request(serde_json::json!({
+            "start_date": start,
+            "end_date": end,
+            "weekdays": ["tue", "Thursday"],
+            "start_time": "10:00:00",
+            "duration_minutes": 60,
+            "interval": interval,
+        }))
+    }
+
+    #[test]
+    fn test_expand_weekly_uses_local_time() {
+        // 2026-03-26 is the last Thursday before the switch to summer time
+        let occurrences = expand_recurrence(
+            &pattern("2026-03-24", "2026-04-02", "weekly"),
+            SLOT_TIMEZONE,
+        )
+        .unwrap();
+        let starts: Vec<String> = occurrences
+            .iter()
+            .map(|(start, _)| start.to_rfc3339())
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                "2026-03-24T09:00:00+00:00",
+                "2026-03-26T09:00:00+00:00",
+                "2026-03-31T08:00:00+00:00",
+                "2026-04-02T08:00:00+00:00",
+            ]
+        );
+        assert!(occurrences
+            .iter()
+            .all(|(start, end)| *end - *start == Duration::minutes(60)));
+    }
+
+    #[test]
+    fn test_expand_biweekly_skips_alternate_weeks() {
+        // Starts on a Thursday: the Tuesday of that week is before start_date
+        let occurrences = expand_recurrence(
+            &pattern("2026-03-05", "2026-03-31", "biweekly"),
+            SLOT_TIMEZONE,
+        )
+        .unwrap();
+        let dates: Vec<String> = occurrences
+            .iter()
+            .map(|(start, _)| start.date_naive().to_string())
+            .collect();
+        assert_eq!(
+            dates,
+            vec!["2026-03-05", "2026-03-17", "2026-03-19", "2026-03-31"]
+        );
+    }
+
+    #[test]
+    fn test_expand_rejects_invalid_patterns() {
+        assert!(expand_recurrence(
+            &pattern("2026-03-10", "2026-03-01", "weekly"),
+            SLOT_TIMEZONE
+        )
+        .is_err());
+
+        let mut too_long = pattern("2026-03-02", "2026-03-31", "weekly");
+        too_long.duration_minutes = MAX_SLOT_MINUTES + 15;
+        assert!(expand_recurrence(&too_long, SLOT_TIMEZONE).is_err());
+
+        let mut no_weekdays = pattern("2026-03-02", "2026-03-31", "weekly");
+        no_weekdays.weekdays.clear();
+        assert!(expand_recurrence(&no_weekdays, SLOT_TIMEZONE).is_err());
+    }
+
+    #[test]
+    fn test_expand_caps_slot_count() {
+        // Two slots a week for five years is well over the cap
+        let err = expand_recurrence(
+            &pattern("2026-01-01", "2030-12-31", "weekly"),
+            SLOT_TIMEZONE,
+        )
+        .unwrap_err();
+        assert!(err.contains("500"));
+
+        // A year of weekly slots stays under it
+        assert_eq!(
+            expand_recurrence(
+                &pattern("2026-01-05", "2027-01-03", "weekly"),
+                SLOT_TIMEZONE
+            )
+            .unwrap()
+            .len(),
+            104
+        );
+    }
+}
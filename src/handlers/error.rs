@@ -0,0 +1,255 @@
+//! Errors returned by handlers
+//!
+//! A handler returning `Result<Json<ApiResponse<T>>, AppError>` can use `?`
+//! on validation and queries; the error renders as the usual `ApiResponse`
+//! envelope with the status that fits it. Failed queries are logged here, so
+//! the handler only names the message the client sees.
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::handlers::auth::RateLimited;
+use crate::i18n::ErrorKey;
+use crate::models::{ApiResponse, AuthScheme, FieldError};
+use crate::validation::ValidationError;
+
+#[derive(Debug)]
+pub enum AppError {
+    /// 400 for a request that cannot be carried out as sent
+    BadRequest(String),
+    /// 400 for input that failed validation, naming the offending field
+    Validation(FieldError),
+    /// 401 for a request that needs an admin or uploader session
+    Unauthorized(AuthScheme, ErrorKey),
+    NotFound(ErrorKey),
+    /// 409 for a request that conflicts with the current state
+    Conflict(String),
+    /// 422 for a well-formed request that cannot be processed as sent
+    Unprocessable(ErrorKey),
+    /// 413 for a body over the upload limit, naming the limit
+    PayloadTooLarge {
+        max_bytes: usize,
+    },
+    /// 429, telling the client when to try again
+    RateLimited {
+        retry_after_secs: u64,
+        message: ErrorKey,
+    },
+    /// 500 for a failed query; the error is logged, the client sees `message`
+    Db {
+        message: ErrorKey,
+        error: sqlx::Error,
+    },
+    /// 500 for any other failure; `detail` is logged, the client sees `message`
+    Internal {
+        message: ErrorKey,
+        detail: String,
+    },
+}
+
+impl AppError {
+    /// Maps a failed query to a 500 with `message`, for use with `map_err`
+    pub fn db(message: ErrorKey) -> impl FnOnce(sqlx::Error) -> Self {
+        move |error| Self::Db { message, error }
+    }
+
+    /// 429 for a client over its limit, with `Retry-After` from the check
+    pub(crate) fn rate_limited(limited: RateLimited, message: ErrorKey) -> Self {
+        Self::RateLimited {
+            retry_after_secs: limited.retry_after_secs,
+            message,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::BadRequest(_) | Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(..) => StatusCode::UNAUTHORIZED,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Db { .. } | Self::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// A bare `?` on a query reports a generic database error
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Db {
+            message: ErrorKey::DatabaseError,
+            error,
+        }
+    }
+}
+
+impl From<ValidationError> for AppError {
+    fn from(e: ValidationError) -> Self {
+        Self::Validation(e.to_detail())
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let body = match self {
+            Self::BadRequest(message) | Self::Conflict(message) => {
+                ApiResponse::<()>::error(message)
+            }
+            Self::Validation(detail) => ApiResponse::invalid(detail),
+            Self::Unauthorized(scheme, message) => ApiResponse::auth_required(scheme, message),
+            Self::NotFound(message) | Self::Unprocessable(message) => ApiResponse::error(message),
+            Self::PayloadTooLarge { max_bytes } => ApiResponse::invalid(
+                ValidationError::FileTooLarge {
+                    max_mb: max_bytes / (1024 * 1024),
+                }
+                .to_detail(),
+            ),
+            Self::RateLimited {
+                retry_after_secs,
+                message,
+            } => {
+                return (
+                    status,
+                    [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                    Json(ApiResponse::<()>::error(message)),
+                )
+                    .into_response();
+            }
+            Self::Db { message, error } => {
+                tracing::error!("{}: {}", message.en(), error);
+                ApiResponse::error(message)
+            }
+            Self::Internal { message, detail } => {
+                tracing::error!("{}: {}", message.en(), detail);
+                ApiResponse::error(message)
+            }
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn render(error: AppError) -> (StatusCode, Option<String>, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .map(|v| v.to_str().unwrap().to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, retry_after, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_each_variant_renders_status_and_envelope() {
+        let cases = [
+            (
+                AppError::BadRequest("Ongeldig".to_string()),
+                StatusCode::BAD_REQUEST,
+                "Ongeldig",
+            ),
+            (
+                AppError::NotFound(ErrorKey::SubmissionNotFound),
+                StatusCode::NOT_FOUND,
+                "Inzending niet gevonden",
+            ),
+            (
+                AppError::Conflict("Al ingediend".to_string()),
+                StatusCode::CONFLICT,
+                "Al ingediend",
+            ),
+            (
+                AppError::Unprocessable(ErrorKey::IdempotencyKeyMismatch),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorKey::IdempotencyKeyMismatch.nl(),
+            ),
+            (
+                AppError::Db {
+                    message: ErrorKey::SubmitFailed,
+                    error: sqlx::Error::RowNotFound,
+                },
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorKey::SubmitFailed.nl(),
+            ),
+            (
+                AppError::Internal {
+                    message: ErrorKey::DatabaseError,
+                    detail: "connection reset".to_string(),
+                },
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Databasefout",
+            ),
+        ];
+        for (error, status, message) in cases {
+            let (got, _, body) = render(error).await;
+            assert_eq!(got, status);
+            assert_eq!(body["success"], false);
+            assert!(body["data"].is_null());
+            assert_eq!(body["error"], message);
+            assert!(body.get("details").is_none());
+            assert!(body.get("auth_required").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validation_names_the_field() {
+        let (status, _, body) = render(ValidationError::InvalidSlug.into()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], ValidationError::InvalidSlug.to_string());
+        assert_eq!(body["details"][0]["field"], "slug");
+        assert_eq!(body["details"][0]["code"], "invalid_slug");
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_names_the_login() {
+        let (status, _, body) = render(AppError::Unauthorized(
+            AuthScheme::Uploader,
+            ErrorKey::NotAuthenticated,
+        ))
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["auth_required"], "uploader");
+        assert_eq!(body["error"], ErrorKey::NotAuthenticated.nl());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_sets_retry_after() {
+        let (status, retry_after, body) = render(AppError::RateLimited {
+            retry_after_secs: 42,
+            message: ErrorKey::TooManyRequests,
+        })
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(retry_after.as_deref(), Some("42"));
+        assert_eq!(body["error"], ErrorKey::TooManyRequests.nl());
+    }
+
+    #[tokio::test]
+    async fn test_payload_too_large_names_the_limit() {
+        let (status, _, body) = render(AppError::PayloadTooLarge {
+            max_bytes: 50 * 1024 * 1024,
+        })
+        .await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"], "File too large (max 50 MB)");
+        assert_eq!(body["details"][0]["code"], "file_too_large");
+    }
+
+    #[tokio::test]
+    async fn test_bare_query_error_is_a_database_error() {
+        let (status, _, body) = render(sqlx::Error::PoolTimedOut.into()).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["error"], ErrorKey::DatabaseError.nl());
+    }
+}
@@ -0,0 +1,182 @@
+//! Static frontend assets
+//!
+//! Pages, scripts and stylesheets are revalidated on every load so a deploy
+//! shows up at once; thanks to the ETag that usually costs a 304 rather than
+//! the whole file. Fonts and content-hashed files may be cached for long.
+//! Paths without a file extension that match no file get `index.html`, so
+//! client-side routes can be linked to directly.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use std::collections::hash_map::DefaultHasher;
+use std::convert::Infallible;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use tower::{service_fn, ServiceExt};
+use tower_http::services::{ServeDir, ServeFile};
+
+/// Cache-Control for HTML, scripts and stylesheets: always revalidate
+const REVALIDATE: &str = "no-cache";
+
+/// Cache-Control for fonts, which keep their name when they change
+const FONTS: &str = "public, max-age=604800";
+
+/// Cache-Control for files with a content hash in their name
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Service for everything outside `/api`, serving files from `frontend_dir`
+pub fn service(frontend_dir: &str) -> Router {
+    let index = ServeFile::new(Path::new(frontend_dir).join("index.html"));
+    let spa_fallback = service_fn(move |request: Request<Body>| {
+        let index = index.clone();
+        async move {
+            if !is_client_route(request.uri().path()) {
+                return Ok::<_, Infallible>(StatusCode::NOT_FOUND.into_response());
+            }
+            let response = index.oneshot(request).await.unwrap_or_else(|e| match e {});
+            Ok(response.into_response())
+        }
+    });
+
+    Router::new()
+        .fallback_service(ServeDir::new(frontend_dir).fallback(spa_fallback))
+        .layer(middleware::from_fn(cache_headers))
+}
+
+/// Whether a path that matches no file is a page of the frontend itself
+///
+/// Unknown API routes and missing assets keep their 404.
+fn is_client_route(path: &str) -> bool {
+    let last_segment = path.rsplit('/').next().unwrap_or("");
+    path != "/api" && !path.starts_with("/api/") && !last_segment.contains('.')
+}
+
+/// Whether a file name carries a content hash, as in `app.3f9a1c2e.js`
+fn is_content_hashed(path: &str) -> bool {
+    let file = path.rsplit('/').next().unwrap_or("");
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    stem.rsplit(['.', '-'])
+        .next()
+        .filter(|part| *part != stem)
+        .is_some_and(|part| part.len() >= 8 && part.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn cache_control(path: &str, content_type: Option<&HeaderValue>) -> &'static str {
+    let content_type = content_type.and_then(|v| v.to_str().ok()).unwrap_or("");
+    if content_type.starts_with("text/html") {
+        REVALIDATE
+    } else if is_content_hashed(path) {
+        IMMUTABLE
+    } else if content_type.starts_with("font/") || path.ends_with(".woff") {
+        FONTS
+    } else {
+        REVALIDATE
+    }
+}
+
+/// Weak validator from the file's size and modification time
+fn etag(response: &Response) -> Option<HeaderValue> {
+    let headers = response.headers();
+    let last_modified = headers.get(header::LAST_MODIFIED)?;
+    let length = headers.get(header::CONTENT_LENGTH)?;
+
+    let mut hasher = DefaultHasher::new();
+    last_modified.as_bytes().hash(&mut hasher);
+    length.as_bytes().hash(&mut hasher);
+    HeaderValue::from_str(&format!("W/\"{:016x}\"", hasher.finish())).ok()
+}
+
+/// Whether an If-None-Match header matches `etag` (weak comparison)
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let (Ok(if_none_match), Ok(etag)) = (if_none_match.to_str(), etag.to_str()) else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match
+        .split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+/// Add Cache-Control and an ETag, answering a matching If-None-Match with 304
+async fn cache_headers(request: Request<Body>, next: Next) -> Response {
+    let conditional = matches!(*request.method(), Method::GET | Method::HEAD);
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    let path = request.uri().path().to_string();
+    let mut response = next.run(request).await;
+
+    if !matches!(response.status(), StatusCode::OK | StatusCode::NOT_MODIFIED) {
+        return response;
+    }
+    let cache_control = cache_control(&path, response.headers().get(header::CONTENT_TYPE));
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(cache_control),
+    );
+
+    let Some(etag) = etag(&response) else {
+        return response;
+    };
+    if conditional && if_none_match.is_some_and(|value| etag_matches(&value, &etag)) {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        let headers = not_modified.headers_mut();
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        );
+        if let Some(last_modified) = response.headers().get(header::LAST_MODIFIED) {
+            headers.insert(header::LAST_MODIFIED, last_modified.clone());
+        }
+        headers.insert(header::ETAG, etag);
+        return not_modified;
+    }
+    response.headers_mut().insert(header::ETAG, etag);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_routes_exclude_api_and_assets() {
+        assert!(is_client_route("/"));
+        assert!(is_client_route("/inzending/rr-20260101-abcde"));
+        assert!(!is_client_route("/js/missing.js"));
+        assert!(!is_client_route("/api/unknown"));
+        assert!(!is_client_route("/api"));
+    }
+
+    #[test]
+    fn test_cache_control_per_asset_type() {
+        let html = HeaderValue::from_static("text/html; charset=utf-8");
+        let js = HeaderValue::from_static("text/javascript");
+        let font = HeaderValue::from_static("font/woff");
+
+        assert_eq!(cache_control("/index.html", Some(&html)), REVALIDATE);
+        assert_eq!(cache_control("/js/submit.js", Some(&js)), REVALIDATE);
+        assert_eq!(cache_control("/js/app.3f9a1c2e.js", Some(&js)), IMMUTABLE);
+        assert_eq!(cache_control("/js/app-3f9a1c2e7b.js", Some(&js)), IMMUTABLE);
+        assert_eq!(
+            cache_control("/fonts/ROsanswebtextbold.woff", Some(&font)),
+            FONTS
+        );
+        assert_eq!(cache_control("/deadbeef12.js", Some(&js)), REVALIDATE);
+    }
+
+    #[test]
+    fn test_etag_matching_is_weak() {
+        let etag = HeaderValue::from_static("W/\"0123456789abcdef\"");
+        let matches = |value: &'static str| etag_matches(&HeaderValue::from_static(value), &etag);
+
+        assert!(matches("W/\"0123456789abcdef\""));
+        assert!(matches("\"0123456789abcdef\""));
+        assert!(matches("\"other\", W/\"0123456789abcdef\""));
+        assert!(matches("*"));
+        assert!(!matches("W/\"fedcba9876543210\""));
+    }
+}
@@ -0,0 +1,90 @@
+//! Liveness and readiness probes
+//!
+//! `/health` only tells the orchestrator the process is up; `/ready` also
+//! checks the dependencies a request needs, so traffic is held back while the
+//! database is unreachable or the upload volume is read-only.
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde_json::json;
+
+use super::AppState;
+use crate::storage;
+
+/// Liveness probe: the process is running
+pub async fn health() -> &'static str {
+    "ok"
+}
+
+/// Readiness probe: the database answers and the upload directory is writable
+pub async fn ready(State(state): State<AppState>) -> impl IntoResponse {
+    let database = sqlx::query("SELECT 1")
+        .execute(&state.pool)
+        .await
+        .map(|_| ());
+    if let Err(e) = &database {
+        tracing::warn!("Readiness check: database unavailable: {}", e);
+    }
+
+    let upload_dir = storage::check_dir_writable(&state.upload_dir).await;
+    if let Err(e) = &upload_dir {
+        tracing::warn!(
+            "Readiness check: upload directory {:?} not writable: {}",
+            state.upload_dir,
+            e
+        );
+    }
+
+    readiness(database.is_ok(), upload_dir.is_ok())
+}
+
+/// Probe response for the outcome of the individual checks
+fn readiness(database_ok: bool, upload_dir_ok: bool) -> (StatusCode, Json<serde_json::Value>) {
+    let reason = if !database_ok {
+        "database unavailable"
+    } else if !upload_dir_ok {
+        "upload directory not writable"
+    } else {
+        return (StatusCode::OK, Json(json!({ "status": "ready" })));
+    };
+
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "status": "unavailable", "reason": reason })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness_healthy() {
+        let (status, Json(body)) = readiness(true, true);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        assert!(body.get("reason").is_none());
+    }
+
+    #[test]
+    fn test_readiness_reports_failed_check() {
+        let (status, Json(body)) = readiness(false, true);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["reason"], "database unavailable");
+
+        let (status, Json(body)) = readiness(true, false);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["reason"], "upload directory not writable");
+    }
+
+    #[tokio::test]
+    async fn test_check_dir_writable() {
+        let dir = std::env::temp_dir().join(format!("ready-test-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        assert!(storage::check_dir_writable(&dir).await.is_ok());
+        // The probe file is cleaned up again
+        assert!(!dir.join(".write_test").exists());
+        tokio::fs::remove_dir(&dir).await.unwrap();
+
+        assert!(storage::check_dir_writable(&dir).await.is_err());
+    }
+}
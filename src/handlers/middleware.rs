@@ -1,18 +1,121 @@
-//! Middleware for authentication and security headers
+//! Middleware for authentication, security headers and path normalization
 
-use crate::handlers::auth::{extract_session_token, hash_token};
+use crate::config::CspConfig;
+use crate::handlers::auth::{
+    check_rate_limit, csrf_token, extract_session_token, get_client_ip, hash_token, record_attempt,
+    session_cookie,
+};
+use crate::handlers::error::AppError;
 use crate::handlers::AppState;
-use crate::models::AdminUser;
+use crate::i18n::{ErrorKey, Lang};
+use crate::models::{AdminUser, ApiResponse, AuthScheme};
 use axum::{
     body::Body,
     extract::State,
-    http::{header, HeaderValue, Request, StatusCode},
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode, Uri},
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use serde_json::json;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Hourly per-IP limit for one endpoint, enforced by [`rate_limit`]
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    /// Key under which attempts are stored in `rate_limit_attempts`
+    pub endpoint: &'static str,
+    pub max_attempts: i64,
+}
+
+impl RateLimit {
+    pub fn new(endpoint: &'static str, max_attempts: i64) -> Self {
+        Self {
+            endpoint,
+            max_attempts,
+        }
+    }
+}
+
+/// Reject clients that exceeded the endpoint's limit before the handler runs
+///
+/// Apply per route with `from_fn_with_state((state, RateLimit::new(..)), rate_limit)`.
+/// Every request that gets through counts as an attempt, whatever its outcome.
+pub async fn rate_limit(
+    State((state, limit)): State<(AppState, RateLimit)>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let client_ip = get_client_ip(request.headers(), &state.trusted_proxies);
+
+    if let Err(limited) =
+        check_rate_limit(&state, &client_ip, limit.endpoint, limit.max_attempts).await
+    {
+        tracing::warn!(
+            "Rate limit exceeded for {} from {}",
+            limit.endpoint,
+            client_ip
+        );
+        return AppError::rate_limited(limited, ErrorKey::TooManyRequests).into_response();
+    }
+    record_attempt(&state.pool, &client_ip, limit.endpoint).await;
+
+    next.run(request).await
+}
+
+/// New expiry for an admin session that is close to expiring, if it should slide
+///
+/// Sessions are only extended in the last quarter of the idle window, so an
+/// active admin causes one write every few hours instead of one per request.
+/// The new expiry never passes `created_at + max_hours`.
+pub(crate) fn sliding_expiry(
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    idle_hours: u64,
+    max_hours: u64,
+) -> Option<DateTime<Utc>> {
+    let idle = Duration::hours(idle_hours as i64);
+    if expires_at - now > idle / 4 {
+        return None;
+    }
+
+    let extended = (now + idle).min(created_at + Duration::hours(max_hours as i64));
+    (extended > expires_at).then_some(extended)
+}
+
+/// Header carrying the CSRF token on state-changing admin requests
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Whether a request carries the CSRF token of its admin session
+///
+/// Reads never change state and need no token. Sessions created before
+/// tokens were stored are checked against the token derived from the cookie.
+fn csrf_token_valid(
+    method: &Method,
+    headers: &HeaderMap,
+    session: &crate::models::AdminSession,
+    session_token: &str,
+) -> bool {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return true;
+    }
+    let Some(sent) = headers.get(CSRF_HEADER).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let expected = session
+        .csrf_token_hash
+        .clone()
+        .unwrap_or_else(|| hash_token(&csrf_token(session_token)));
+    hash_token(sent) == expected
+}
 
 /// Admin user extracted by middleware, available via Extension<AdminUser>
+///
+/// Requests other than GET, HEAD and OPTIONS must also send the session's
+/// CSRF token in `X-CSRF-Token`, or they are refused with 403.
 pub async fn require_admin(
     State(state): State<AppState>,
     request: Request<Body>,
@@ -26,7 +129,10 @@ pub async fn require_admin(
         None => {
             return (
                 StatusCode::UNAUTHORIZED,
-                axum::Json(json!({"success": false, "error": "Not authenticated"})),
+                axum::Json(ApiResponse::<()>::auth_required(
+                    AuthScheme::Admin,
+                    ErrorKey::NotAuthenticated,
+                )),
             )
                 .into_response();
         }
@@ -48,20 +154,35 @@ pub async fn require_admin(
             // Use generic error message
             return (
                 StatusCode::UNAUTHORIZED,
-                axum::Json(json!({"success": false, "error": "Authentication failed"})),
+                axum::Json(ApiResponse::<()>::auth_required(
+                    AuthScheme::Admin,
+                    ErrorKey::AuthenticationFailed,
+                )),
             )
                 .into_response();
         }
         Err(e) => {
-            tracing::error!("Database error during session validation: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(json!({"success": false, "error": "Authentication failed"})),
-            )
-                .into_response();
+            return AppError::Internal {
+                message: ErrorKey::AuthenticationFailed,
+                detail: format!("database error during session validation: {}", e),
+            }
+            .into_response();
         }
     };
 
+    if !csrf_token_valid(request.method(), request.headers(), &session, &token) {
+        tracing::warn!(
+            "Rejected {} {} without a valid CSRF token",
+            request.method(),
+            request.uri().path()
+        );
+        return (
+            StatusCode::FORBIDDEN,
+            axum::Json(ApiResponse::<()>::error(ErrorKey::InvalidCsrfToken)),
+        )
+            .into_response();
+    }
+
     // Get associated user
     let user = sqlx::query_as::<_, AdminUser>(
         "SELECT * FROM admin_users WHERE id = $1 AND is_active = true",
@@ -76,34 +197,119 @@ pub async fn require_admin(
             // Use generic error to prevent username enumeration
             return (
                 StatusCode::UNAUTHORIZED,
-                axum::Json(json!({"success": false, "error": "Authentication failed"})),
+                axum::Json(ApiResponse::<()>::auth_required(
+                    AuthScheme::Admin,
+                    ErrorKey::AuthenticationFailed,
+                )),
             )
                 .into_response();
         }
         Err(e) => {
-            tracing::error!("Database error fetching admin user: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                axum::Json(json!({"success": false, "error": "Authentication failed"})),
-            )
-                .into_response();
+            return AppError::Internal {
+                message: ErrorKey::AuthenticationFailed,
+                detail: format!("database error fetching admin user: {}", e),
+            }
+            .into_response();
         }
     };
 
+    // Slide the session forward for active admins, up to the absolute maximum
+    let now = Utc::now();
+    let extended = match sliding_expiry(
+        session.created_at,
+        session.expires_at,
+        now,
+        state.session_expiry_hours,
+        state.session_max_hours,
+    ) {
+        Some(expires_at) => {
+            let result = sqlx::query("UPDATE admin_sessions SET expires_at = $1 WHERE id = $2")
+                .bind(expires_at)
+                .bind(session.id)
+                .execute(&state.pool)
+                .await;
+            match result {
+                Ok(_) => Some(expires_at),
+                Err(e) => {
+                    tracing::warn!("Failed to extend admin session: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
     // Insert AdminUser into request extensions
     let mut request = request;
     request.extensions_mut().insert(user);
 
-    next.run(request).await
+    let mut response = next.run(request).await;
+
+    // Refresh the cookie so the browser keeps it as long as the session lives
+    if let Some(expires_at) = extended {
+        let cookie = session_cookie(
+            &token,
+            (expires_at - now).num_seconds(),
+            state.is_production,
+        );
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+
+    response
 }
 
+/// Largest HTML page that gets the CSP nonce added to its tags
+const MAX_NONCED_PAGE_BYTES: usize = 2 * 1024 * 1024;
+
+/// Stands in for the response's CSP nonce in the served HTML pages
+const CSP_NONCE_PLACEHOLDER: &str = "{{CSP_NONCE}}";
+
 /// Security headers middleware
+///
+/// Outside the configured relaxed paths, each response gets a fresh CSP
+/// nonce. HTML pages carry it wherever they have a `{{CSP_NONCE}}` placeholder:
+/// on their script and style elements and in a `<meta property="csp-nonce">`
+/// tag, where scripts that create elements can read it. Without nonce support
+/// the policy allows `'unsafe-inline'` and the placeholders are removed.
 pub async fn security_headers(
     State(state): State<AppState>,
-    request: Request<Body>,
+    mut request: Request<Body>,
     next: Next,
 ) -> Response {
+    let path = request.uri().path();
+    let nonce =
+        (state.csp.nonce && !is_relaxed_path(path, &state.csp.relaxed_paths)).then(new_csp_nonce);
+    if nonce.is_some() && is_page(path) {
+        // A 304 would keep the page with the nonce of an earlier response
+        request.headers_mut().remove(header::IF_NONE_MATCH);
+        request.headers_mut().remove(header::IF_MODIFIED_SINCE);
+    }
+
     let mut response = next.run(request).await;
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    if is_html {
+        let (mut parts, body) = response.into_parts();
+        let page = match axum::body::to_bytes(body, MAX_NONCED_PAGE_BYTES).await {
+            Ok(page) => page,
+            Err(e) => {
+                tracing::error!("Failed to read page for CSP nonce: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+        let page = inject_csp_nonce(&String::from_utf8_lossy(&page), nonce.as_deref());
+        parts.headers.remove(header::CONTENT_LENGTH);
+        if nonce.is_some() {
+            parts.headers.remove(header::ETAG);
+            parts.headers.remove(header::LAST_MODIFIED);
+        }
+        response = Response::from_parts(parts, Body::from(page));
+    }
     let headers = response.headers_mut();
 
     headers.insert("X-Frame-Options", HeaderValue::from_static("DENY"));
@@ -115,12 +321,12 @@ pub async fn security_headers(
         "Referrer-Policy",
         HeaderValue::from_static("strict-origin-when-cross-origin"),
     );
-    headers.insert(
-        "Content-Security-Policy",
-        HeaderValue::from_static(
-            "default-src 'self'; script-src 'self' 'unsafe-inline'; style-src 'self' 'unsafe-inline'; img-src 'self' data:; font-src 'self'; form-action 'self'; base-uri 'self'; frame-ancestors 'none'",
-        ),
-    );
+    // Sources are checked for header-safe characters when the config is read
+    if let Ok(policy) =
+        HeaderValue::from_str(&content_security_policy(&state.csp, nonce.as_deref()))
+    {
+        headers.insert(header::CONTENT_SECURITY_POLICY, policy);
+    }
 
     if state.is_production {
         headers.insert(
@@ -131,3 +337,301 @@ pub async fn security_headers(
 
     response
 }
+
+/// Content-Security-Policy allowing inline code by `nonce`, or by
+/// `'unsafe-inline'` when there is none
+fn content_security_policy(csp: &CspConfig, nonce: Option<&str>) -> String {
+    let inline = nonce.map_or_else(
+        || "'unsafe-inline'".to_string(),
+        |nonce| format!("'nonce-{}'", nonce),
+    );
+    let directive = |name: &str, base: &str, extra: &[String]| {
+        std::iter::once(format!("{} {}", name, base))
+            .chain(extra.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut directives = vec![
+        "default-src 'self'".to_string(),
+        directive("script-src", &format!("'self' {}", inline), &csp.script_src),
+        directive("style-src", &format!("'self' {}", inline), &csp.style_src),
+    ];
+    if nonce.is_some() {
+        // Nonces cannot cover style attributes, which the pages still use
+        directives.push("style-src-attr 'unsafe-inline'".to_string());
+    }
+    directives.extend([
+        directive("img-src", "'self' data:", &csp.img_src),
+        directive("connect-src", "'self'", &csp.connect_src),
+        "font-src 'self'".to_string(),
+        "form-action 'self'".to_string(),
+        "base-uri 'self'".to_string(),
+        "frame-ancestors 'none'".to_string(),
+    ]);
+    directives.join("; ")
+}
+
+fn new_csp_nonce() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    STANDARD.encode(bytes)
+}
+
+/// Fill the nonce placeholders of a page with `nonce`, or drop the `nonce`
+/// attributes holding them when the response has none
+fn inject_csp_nonce(page: &str, nonce: Option<&str>) -> String {
+    match nonce {
+        Some(nonce) => page.replace(CSP_NONCE_PLACEHOLDER, nonce),
+        None => page.replace(&format!(" nonce=\"{}\"", CSP_NONCE_PLACEHOLDER), ""),
+    }
+}
+
+/// Whether `path` is at or below one of `prefixes`
+fn is_relaxed_path(path: &str, prefixes: &[String]) -> bool {
+    prefixes.iter().any(|prefix| {
+        let prefix = prefix.trim_end_matches('/');
+        path == prefix || path.starts_with(&format!("{}/", prefix))
+    })
+}
+
+/// Whether `path` may be an HTML page of the frontend
+fn is_page(path: &str) -> bool {
+    let last_segment = path.rsplit('/').next().unwrap_or("");
+    !path.starts_with("/api/") && (!last_segment.contains('.') || last_segment.ends_with(".html"))
+}
+
+/// Give body-limit rejections the same JSON shape as other errors
+///
+/// Extractors refuse a body over its limit with a plain-text 413 before the
+/// handler runs. This replaces that with [`AppError::PayloadTooLarge`] naming
+/// the configured upload limit; 413s that are already JSON pass through.
+pub async fn payload_too_large(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE || is_json {
+        return response;
+    }
+    AppError::PayloadTooLarge {
+        max_bytes: state.max_upload_size,
+    }
+    .into_response()
+}
+
+/// Header carrying the correlation id of a request
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: Uuid;
+}
+
+/// Correlation id of the request being handled, if any
+///
+/// Only available on the task running the request, not in spawned tasks.
+pub fn current_request_id() -> Option<Uuid> {
+    REQUEST_ID.try_with(|id| *id).ok()
+}
+
+/// Give every request a correlation id for logs, audit entries and support
+///
+/// A valid UUID in an incoming `X-Request-Id` is reused, so a request can be
+/// followed through a proxy; anything else is replaced by a fresh one. The id
+/// is a field on the request span and is echoed in the response.
+pub async fn request_id(request: Request<Body>, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .unwrap_or_else(Uuid::new_v4);
+
+    let span = tracing::info_span!("request", request_id = %id);
+    let mut response = REQUEST_ID
+        .scope(id, next.run(request).instrument(span))
+        .await;
+
+    let mut buf = Uuid::encode_buffer();
+    if let Ok(value) = HeaderValue::from_str(id.hyphenated().encode_lower(&mut buf)) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Largest error body [`localize_errors`] will buffer and rewrite
+const MAX_LOCALIZED_BODY: usize = 64 * 1024;
+
+/// Translate the message of JSON error responses to the client's language
+///
+/// Handlers answer in Dutch; when `Accept-Language` prefers English the
+/// `error` field is replaced by the English text of the same [`ErrorKey`].
+/// Messages that are not in the catalog are left alone.
+pub async fn localize_errors(request: Request<Body>, next: Next) -> Response {
+    let lang = Lang::from_headers(request.headers());
+    let response = next.run(request).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if response.status().as_u16() < 400 || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts
+        .headers
+        .append(header::VARY, HeaderValue::from_static("accept-language"));
+    let bytes = match axum::body::to_bytes(body, MAX_LOCALIZED_BODY).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Could not buffer error response for localization: {}", e);
+            return (parts.status, "").into_response();
+        }
+    };
+
+    let localized = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|mut json| {
+            let key = ErrorKey::from_message(json.get("error")?.as_str()?)?;
+            json["error"] = key.text(lang).into();
+            serde_json::to_vec(&json).ok()
+        });
+    let Some(localized) = localized else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_LANGUAGE,
+        HeaderValue::from_static(lang.code()),
+    );
+    Response::from_parts(parts, Body::from(localized))
+}
+
+/// Strip trailing slashes from API paths so `/api/submissions/` routes like `/api/submissions`
+///
+/// Only `/api/` paths are touched: the static file server relies on the trailing
+/// slash to serve `index.html` for directories and redirects when it is missing.
+pub fn normalize_api_path(mut request: Request<Body>) -> Request<Body> {
+    let path = request.uri().path();
+    if !path.starts_with("/api/") || !path.ends_with('/') {
+        return request;
+    }
+
+    let trimmed = path.trim_end_matches('/');
+    let path_and_query = match request.uri().query() {
+        Some(query) => format!("{}?{}", trimmed, query),
+        None => trimmed.to_string(),
+    };
+
+    let mut parts = request.uri().clone().into_parts();
+    match path_and_query.parse() {
+        Ok(pq) => parts.path_and_query = Some(pq),
+        Err(_) => return request,
+    }
+    if let Ok(uri) = Uri::from_parts(parts) {
+        *request.uri_mut() = uri;
+    }
+
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hours: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap() + Duration::hours(hours)
+    }
+
+    #[test]
+    fn test_csp_without_nonce_allows_inline_code() {
+        let csp = CspConfig {
+            nonce: false,
+            img_src: vec!["https://cdn.example.org".to_string()],
+            ..CspConfig::default()
+        };
+        assert_eq!(
+            content_security_policy(&csp, None),
+            "default-src 'self'; script-src 'self' 'unsafe-inline'; style-src 'self' 'unsafe-inline'; img-src 'self' data: https://cdn.example.org; connect-src 'self'; font-src 'self'; form-action 'self'; base-uri 'self'; frame-ancestors 'none'"
+        );
+
+        let policy = content_security_policy(&csp, Some("abc"));
+        assert!(policy.contains("script-src 'self' 'nonce-abc';"));
+        assert!(policy.contains("style-src 'self' 'nonce-abc';"));
+        assert!(!policy.contains("script-src 'self' 'unsafe-inline'"));
+    }
+
+    #[test]
+    fn test_csp_nonce_fills_only_the_placeholders() {
+        let page = "<head><meta property=\"csp-nonce\" nonce=\"{{CSP_NONCE}}\"></head>\
+            <script nonce=\"{{CSP_NONCE}}\">const tag = '<script>'; // <script</script>\
+            <script nonce=\"fixed\" src=\"/a.js\"></script>";
+        assert_eq!(
+            inject_csp_nonce(page, Some("abc")),
+            "<head><meta property=\"csp-nonce\" nonce=\"abc\"></head>\
+            <script nonce=\"abc\">const tag = '<script>'; // <script</script>\
+            <script nonce=\"fixed\" src=\"/a.js\"></script>"
+        );
+        assert_eq!(
+            inject_csp_nonce(page, None),
+            "<head><meta property=\"csp-nonce\"></head>\
+            <script>const tag = '<script>'; // <script</script>\
+            <script nonce=\"fixed\" src=\"/a.js\"></script>"
+        );
+    }
+
+    #[test]
+    fn test_relaxed_paths_match_whole_segments() {
+        let prefixes = vec!["/admin".to_string()];
+        assert!(is_relaxed_path("/admin", &prefixes));
+        assert!(is_relaxed_path("/admin/", &prefixes));
+        assert!(is_relaxed_path("/admin/submissions.html", &prefixes));
+        assert!(!is_relaxed_path("/administratie", &prefixes));
+        assert!(!is_relaxed_path("/faq.html", &prefixes));
+    }
+
+    #[test]
+    fn test_sliding_expiry_skips_fresh_sessions() {
+        // Created at 0, expires at 8, now at 1: plenty of time left
+        assert_eq!(sliding_expiry(at(0), at(8), at(1), 8, 24), None);
+        // Exactly 2 hours left is within the last quarter
+        assert_eq!(sliding_expiry(at(0), at(8), at(6), 8, 24), Some(at(14)));
+    }
+
+    #[test]
+    fn test_sliding_expiry_extends_by_idle_window() {
+        assert_eq!(sliding_expiry(at(0), at(8), at(7), 8, 24), Some(at(15)));
+        assert_eq!(sliding_expiry(at(0), at(15), at(14), 8, 24), Some(at(22)));
+    }
+
+    #[test]
+    fn test_sliding_expiry_capped_at_absolute_maximum() {
+        assert_eq!(sliding_expiry(at(0), at(22), at(21), 8, 24), Some(at(24)));
+        // Already at the cap: nothing left to extend
+        assert_eq!(sliding_expiry(at(0), at(24), at(23), 8, 24), None);
+    }
+    #[tokio::test]
+    async fn test_request_id_is_scoped_to_the_request_task() {
+        assert_eq!(current_request_id(), None);
+
+        let id = Uuid::new_v4();
+        REQUEST_ID
+            .scope(id, async {
+                assert_eq!(current_request_id(), Some(id));
+                // Spawned work is outside the request and records no id
+                let spawned = tokio::spawn(async { current_request_id() });
+                assert_eq!(spawned.await.unwrap(), None);
+            })
+            .await;
+    }
+}
@@ -1,8 +1,14 @@
 //! HTTP request handlers
 
 pub mod admin;
+pub mod audit;
 pub mod auth;
 pub mod calendar;
+pub mod calendar_import;
+pub mod calendar_recurring;
+pub mod error;
+pub mod frontend;
+pub mod health;
 pub mod middleware;
 pub mod submissions;
 pub mod uploader_auth;
@@ -10,5 +16,7 @@ pub mod uploader_auth;
 pub use admin::*;
 pub use auth::*;
 pub use calendar::*;
+pub use calendar_import::*;
+pub use calendar_recurring::*;
 pub use submissions::*;
 pub use uploader_auth::*;
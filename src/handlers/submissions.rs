@@ -1,74 +1,198 @@
 //! Submission handlers for the applicant portal
 
+use crate::antivirus::{ScanResult, VirusScanner};
+use crate::config::{BusinessHours, CspConfig, UploadLayout};
+use crate::encryption::FileKey;
+use crate::handlers::admin::stream_export_zip;
+use crate::handlers::audit::{self, AuditEntry};
 use crate::handlers::auth::{
-    check_rate_limit_with_max, get_client_ip, record_attempt, MAX_SUBMISSION_ATTEMPTS,
+    check_rate_limit, get_client_ip, record_attempt, LockoutPolicy, RateLimits,
 };
+use crate::handlers::error::AppError;
+use crate::handlers::middleware::current_request_id;
 use crate::handlers::uploader_auth::validate_uploader_session;
+use crate::i18n::ErrorKey;
 use crate::models::*;
+use crate::notify::Notifier;
+use crate::share_links::LinkSigner;
+use crate::storage::{document_key, is_storage_full, submission_prefix, FileStore, StorageError};
+use crate::thumbnails::Thumbnailer;
+use crate::totp::TotpKey;
 use crate::validation::{
     validate_classification_for_upload, validate_create_submission, validate_external_url,
-    validate_file_upload, validate_filename_extensions, validate_slug,
+    validate_file_magic, validate_file_upload, validate_filename_extensions,
+    validate_metadata_update, validate_pdf_structure, validate_slug, ValidationError,
 };
+use crate::webhooks::{self, WebhookEvent, WebhookSender};
+use crate::wetten::LawTitleLookup;
+use crate::workflow::can_withdraw;
 use axum::{
-    extract::{Multipart, Path, Query, State},
-    http::{HeaderMap, StatusCode},
-    response::IntoResponse,
+    extract::{
+        multipart::{Field, MultipartError},
+        Multipart, Path, Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use serde::Deserialize;
-use sqlx::PgPool;
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::fs;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use unicode_normalization::UnicodeNormalization;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
+    /// Local directory; also used to stage uploads before they are stored
     pub upload_dir: PathBuf,
+    /// Storage backend for document files
+    pub store: Arc<dyn FileStore>,
+    /// Grouping of new document files below the storage root
+    pub upload_layout: UploadLayout,
     pub max_upload_size: usize,
     pub is_production: bool,
     /// Trusted proxy IP prefixes for X-Forwarded-For validation
     pub trusted_proxies: Vec<String>,
+    /// Optional upload extension allowlist (lowercase, without dot); empty = disabled
+    pub allowed_extensions: Vec<String>,
+    /// MIME types accepted for uploads (lowercase)
+    pub allowed_mime_types: Vec<String>,
+    /// Maximum number of files in one batch upload request
+    pub max_files_per_upload: usize,
+    /// Maximum length of the sanitized filename in storage keys
+    pub max_filename_length: usize,
+    /// Maximum number of formal law links on one submission
+    pub max_formal_laws_per_submission: i64,
+    /// Reject formal law links to hosts other than wetten.overheid.nl
+    pub strict_formal_law_domain: bool,
+    /// Months a new submission is kept before the retention purge removes it
+    pub retention_months: u32,
+    /// Months added to the retention period per uploader consent
+    pub retention_extension_months: u32,
+    /// Cap on the total retention period in months since creation
+    pub retention_max_months: u32,
+    /// Classification applied per category when an upload omits one
+    pub default_classifications: HashMap<DocumentCategory, DocumentClassification>,
+    /// Email notifier for submitters; `None` when NOTIFY_ENABLED is off
+    pub notifier: Option<Arc<Notifier>>,
+    /// Key for admin TOTP secrets, if two-factor authentication is configured
+    pub totp_key: Option<TotpKey>,
+    /// Idle window of admin sessions in hours
+    pub session_expiry_hours: u64,
+    /// Absolute lifetime of admin sessions in hours
+    pub session_max_hours: u64,
+    /// Lifetime of uploader sessions in hours
+    pub uploader_session_hours: u64,
+    /// When repeated failed logins lock an admin account
+    pub login_lockout: LockoutPolicy,
+    /// Per-IP attempt limits of the login and submission endpoints
+    pub rate_limits: RateLimits,
+    /// Argon2 cost for newly hashed admin passwords
+    pub password_params: argon2::Params,
+    /// Cached title lookup for formal law links
+    pub law_titles: Arc<LawTitleLookup>,
+    /// clamd client for scanning uploads; `None` when CLAMAV_ADDR is unset
+    pub virus_scanner: Option<Arc<VirusScanner>>,
+    /// Reject PDFs that do not parse or have no pages
+    pub strict_pdf_validation: bool,
+    /// Outbound event webhook; `None` when WEBHOOK_URL is unset
+    pub webhooks: Option<Arc<WebhookSender>>,
+    /// Signs and checks document download links
+    pub download_links: Arc<LinkSigner>,
+    /// Key for encrypting document files at rest; `None` stores new uploads as plaintext
+    pub file_key: Option<FileKey>,
+    /// First-page PDF thumbnails; `None` when THUMBNAIL_RENDERER is unset
+    pub thumbnailer: Option<Arc<Thumbnailer>>,
+    /// Weekdays and hours for admin-created slots; `None` when SLOT_BUSINESS_HOURS is unset
+    pub business_hours: Option<BusinessHours>,
+    /// Sources and nonce handling of the Content-Security-Policy
+    pub csp: Arc<CspConfig>,
+    /// Non-secret settings shown to admins
+    pub effective_config: Arc<EffectiveConfig>,
 }
 
 // =============================================================================
 // Submission Endpoints
 // =============================================================================
 
+/// How long an Idempotency-Key replays the submission it created
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i32 = 24;
+
+/// Longest accepted Idempotency-Key, matching the column width
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
 /// Create a new submission
+///
+/// A client may send an `Idempotency-Key` header so that retrying after a
+/// dropped response returns the submission it already created, with 200,
+/// rather than a second draft. The key is tied to the submitter's email, so
+/// it is refused without one, and to the request body, and replays for
+/// [`IDEMPOTENCY_KEY_TTL_HOURS`].
+#[utoipa::path(
+    post,
+    path = "/api/submissions",
+    tag = "submissions",
+    request_body = CreateSubmission,
+    params(("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key that makes retries return the same submission; needs a submitter email")),
+    responses(
+        (status = 201, description = "Submission created", body = ApiResponse<Submission>),
+        (status = 200, description = "Submission created earlier with this Idempotency-Key", body = ApiResponse<Submission>),
+        (status = 400, description = "Invalid input, or an Idempotency-Key without a submitter email", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Idempotency-Key used for a different submission", body = ApiResponse<serde_json::Value>),
+        (status = 422, description = "Idempotency-Key used earlier with a different request body", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many submissions", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+    ),
+)]
 pub async fn create_submission(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(input): Json<CreateSubmission>,
-) -> impl IntoResponse {
-    // Rate limit submission creation
+) -> Result<Response, AppError> {
+    let idempotency_key = idempotency_key(&headers)
+        .map_err(|()| AppError::BadRequest(ErrorKey::InvalidIdempotencyKey.into()))?;
+    let has_email = input
+        .submitter_email
+        .as_deref()
+        .is_some_and(|email| !email.trim().is_empty());
+    if idempotency_key.is_some() && !has_email {
+        return Err(AppError::Validation(FieldError::new(
+            "submitter_email",
+            "required",
+            ErrorKey::IdempotencyKeyNeedsEmail,
+        )));
+    }
+
+    // Rate limit submission creation; replays count too, so keys cannot be
+    // guessed at will
     let client_ip = get_client_ip(&headers, &state.trusted_proxies);
-    if !check_rate_limit_with_max(
-        &state.pool,
+    check_rate_limit(
+        &state,
         &client_ip,
         "create_submission",
-        MAX_SUBMISSION_ATTEMPTS,
+        state.rate_limits.submission,
     )
     .await
-    {
-        return (
-            StatusCode::TOO_MANY_REQUESTS,
-            Json(ApiResponse::<Submission>::error(
-                "Too many submissions. Please try again later.",
-            )),
-        );
-    }
+    .map_err(|limited| AppError::rate_limited(limited, ErrorKey::TooManySubmissions))?;
     record_attempt(&state.pool, &client_ip, "create_submission").await;
 
-    // Validate input
-    if let Err(e) = validate_create_submission(&input) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<Submission>::error(e.to_string())),
-        );
+    let request_hash = request_hash(&input);
+    if let Some(key) = idempotency_key {
+        if let Some(submission) = replay(&state.pool, key, &input, &request_hash).await? {
+            return Ok((StatusCode::OK, Json(ApiResponse::success(submission))).into_response());
+        }
     }
 
+    validate_create_submission(&input)?;
+
     // Generate slug
     let slug: String = sqlx::query_scalar("SELECT generate_submission_slug()")
         .fetch_one(&state.pool)
@@ -82,19 +206,13 @@ pub async fn create_submission(
         });
 
     // Insert submission
-    let result = sqlx::query_as::<_, Submission>(
-        r#"
-        INSERT INTO submissions (slug, submitter_name, submitter_email, organization, organization_department)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING *
-        "#,
+    let result = insert_submission(
+        &state.pool,
+        &slug,
+        &input,
+        state.retention_months,
+        idempotency_key.map(|key| (key, request_hash.as_str())),
     )
-    .bind(&slug)
-    .bind(&input.submitter_name)
-    .bind(&input.submitter_email)
-    .bind(&input.organization)
-    .bind(&input.organization_department)
-    .fetch_one(&state.pool)
     .await;
 
     match result {
@@ -110,180 +228,386 @@ pub async fn create_submission(
             )
             .await;
 
-            (StatusCode::CREATED, Json(ApiResponse::success(submission)))
+            Ok((StatusCode::CREATED, Json(ApiResponse::success(submission))).into_response())
         }
-        Err(e) => {
-            tracing::error!("Failed to create submission: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Failed to create submission")),
-            )
+        // A concurrent request with the same key got there first
+        Err(e) if is_idempotency_key_conflict(&e) => {
+            let key = idempotency_key.unwrap_or_default();
+            match replay(&state.pool, key, &input, &request_hash).await? {
+                Some(submission) => {
+                    Ok((StatusCode::OK, Json(ApiResponse::success(submission))).into_response())
+                }
+                None => Err(AppError::Conflict(ErrorKey::IdempotencyKeyReused.into())),
+            }
+        }
+        Err(e) => Err(AppError::db(ErrorKey::CreateSubmissionFailed)(e)),
+    }
+}
+
+/// The Idempotency-Key header, if sent; `Err` when it is empty or unusable
+fn idempotency_key(headers: &HeaderMap) -> Result<Option<&str>, ()> {
+    let Some(value) = headers.get("idempotency-key") else {
+        return Ok(None);
+    };
+    let key = value.to_str().map_err(|_| ())?.trim();
+    if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+        return Err(());
+    }
+    Ok(Some(key))
+}
+
+/// Hex SHA-256 of the request body as parsed, stored with its Idempotency-Key
+fn request_hash(input: &CreateSubmission) -> String {
+    let body = serde_json::to_vec(input).unwrap_or_default();
+    hex::encode(Sha256::digest(&body))
+}
+
+#[derive(sqlx::FromRow)]
+struct IdempotentSubmission {
+    #[sqlx(flatten)]
+    submission: Submission,
+    idempotency_request_hash: Option<String>,
+}
+
+/// The submission created with `key` within its replay window
+///
+/// Only matches a submission with the same submitter email, so a key from one
+/// applicant cannot be used to fetch another's submission. The key sent again
+/// with a different body is refused with 422.
+async fn replay(
+    pool: &PgPool,
+    key: &str,
+    input: &CreateSubmission,
+    request_hash: &str,
+) -> Result<Option<Submission>, AppError> {
+    let Some(email) = &input.submitter_email else {
+        return Ok(None);
+    };
+    let found = sqlx::query_as::<_, IdempotentSubmission>(
+        r#"
+        SELECT * FROM submissions
+        WHERE idempotency_key = $1
+          AND created_at > NOW() - make_interval(hours => $2)
+          AND submitter_email = $3
+        "#,
+    )
+    .bind(key)
+    .bind(IDEMPOTENCY_KEY_TTL_HOURS)
+    .bind(email)
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::db(ErrorKey::DatabaseError))?;
+
+    match found {
+        Some(found) if found.idempotency_request_hash.as_deref() == Some(request_hash) => {
+            Ok(Some(found.submission))
         }
+        Some(_) => Err(AppError::Unprocessable(ErrorKey::IdempotencyKeyMismatch)),
+        None => Ok(None),
+    }
+}
+
+fn is_idempotency_key_conflict(error: &sqlx::Error) -> bool {
+    error
+        .as_database_error()
+        .and_then(|e| e.constraint())
+        .is_some_and(|constraint| constraint == "idx_submissions_idempotency_key")
+}
+
+/// Release Idempotency-Keys past their replay window
+///
+/// Called from the periodic cleanup task in main.rs.
+pub async fn expire_idempotency_keys(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE submissions SET idempotency_key = NULL, idempotency_request_hash = NULL
+        WHERE idempotency_key IS NOT NULL
+          AND created_at < NOW() - make_interval(hours => $1)
+        "#,
+    )
+    .bind(IDEMPOTENCY_KEY_TTL_HOURS)
+    .execute(pool)
+    .await
+    .map(|result| result.rows_affected())
+}
+
+/// Insert a new draft submission that expires `retention_months` from now
+///
+/// `idempotency` is the Idempotency-Key with the hash of the request body.
+async fn insert_submission(
+    pool: &PgPool,
+    slug: &str,
+    input: &CreateSubmission,
+    retention_months: u32,
+    idempotency: Option<(&str, &str)>,
+) -> Result<Submission, sqlx::Error> {
+    if let Some((key, _)) = idempotency {
+        // An expired key the cleanup has not reached yet is free for reuse
+        sqlx::query(
+            r#"
+            UPDATE submissions SET idempotency_key = NULL, idempotency_request_hash = NULL
+            WHERE idempotency_key = $1 AND created_at < NOW() - make_interval(hours => $2)
+            "#,
+        )
+        .bind(key)
+        .bind(IDEMPOTENCY_KEY_TTL_HOURS)
+        .execute(pool)
+        .await?;
     }
+
+    sqlx::query_as::<_, Submission>(
+        r#"
+        INSERT INTO submissions (
+            slug, submitter_name, submitter_email, organization, organization_department,
+            retention_expiry_date, idempotency_key, idempotency_request_hash
+        )
+        VALUES ($1, $2, $3, $4, $5, NOW() + make_interval(months => $6::int), $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(slug)
+    .bind(&input.submitter_name)
+    .bind(&input.submitter_email)
+    .bind(&input.organization)
+    .bind(&input.organization_department)
+    .bind(i64::from(retention_months))
+    .bind(idempotency.map(|(key, _)| key))
+    .bind(idempotency.map(|(_, hash)| hash))
+    .fetch_one(pool)
+    .await
 }
 
 /// Get submission by slug
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{slug}",
+    tag = "submissions",
+    params(("slug" = String, Path, description = "Submission slug")),
+    responses(
+        (status = 200, description = "Submission with its documents", body = ApiResponse<SubmissionResponse>),
+        (status = 400, description = "Invalid slug", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn get_submission(
     State(state): State<AppState>,
     Path(slug): Path<String>,
-) -> impl IntoResponse {
-    if let Err(e) = validate_slug(&slug) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<SubmissionResponse>::error(e.to_string())),
-        );
-    }
+) -> Result<Json<ApiResponse<SubmissionResponse>>, AppError> {
+    validate_slug(&slug)?;
 
-    // Get submission
     let submission = sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE slug = $1")
         .bind(&slug)
         .fetch_optional(&state.pool)
-        .await;
+        .await?
+        .ok_or(AppError::NotFound(ErrorKey::SubmissionNotFound))?;
 
-    match submission {
-        Ok(Some(submission)) => {
-            // Get documents
-            let documents = sqlx::query_as::<_, Document>(
-                "SELECT * FROM documents WHERE submission_id = $1 ORDER BY created_at",
-            )
-            .bind(submission.id)
-            .fetch_all(&state.pool)
-            .await
-            .unwrap_or_default();
+    let documents = sqlx::query_as::<_, Document>(
+        "SELECT * FROM documents WHERE submission_id = $1 ORDER BY created_at",
+    )
+    .bind(submission.id)
+    .fetch_all(&state.pool)
+    .await
+    .unwrap_or_default();
 
-            let response = SubmissionResponse {
-                id: submission.id,
-                slug: submission.slug,
-                submitter_name: submission.submitter_name,
-                submitter_email: submission.submitter_email,
-                organization: submission.organization,
-                organization_department: submission.organization_department,
-                status: submission.status,
-                notes: submission.notes,
-                created_at: submission.created_at,
-                updated_at: submission.updated_at,
-                submitted_at: submission.submitted_at,
-                retention_expiry_date: submission.retention_expiry_date,
-                documents: documents.into_iter().map(DocumentResponse::from).collect(),
-            };
-
-            (StatusCode::OK, Json(ApiResponse::success(response)))
-        }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Submission not found")),
-        ),
-        Err(e) => {
-            tracing::error!("Database error: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Database error")),
-            )
-        }
-    }
+    let response = SubmissionResponse::new(
+        submission,
+        documents.into_iter().map(DocumentResponse::from).collect(),
+    );
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Check the status of a submission without logging in
+///
+/// Returns only the status and dates, not the submitter or documents, so it
+/// can back a public status page. Rate limited per IP against guessing codes.
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{slug}/status",
+    tag = "submissions",
+    params(("slug" = String, Path, description = "Submission slug")),
+    responses(
+        (status = 200, description = "Status and dates of the submission", body = ApiResponse<SubmissionStatusResponse>),
+        (status = 400, description = "Invalid slug", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many status checks", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn get_submission_status(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> Result<Json<ApiResponse<SubmissionStatusResponse>>, AppError> {
+    validate_slug(&slug)?;
+
+    let status = sqlx::query_as::<_, SubmissionStatusResponse>(
+        r#"
+        SELECT status, created_at, submitted_at, retention_expiry_date,
+            EXISTS (SELECT 1 FROM slot_bookings b WHERE b.submission_id = s.id) AS has_meeting_booked
+        FROM submissions s
+        WHERE slug = $1
+        "#,
+    )
+    .bind(&slug)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::NotFound(ErrorKey::SubmissionNotFound))?;
+
+    Ok(Json(ApiResponse::success(status)))
 }
 
 /// Update submission
+#[utoipa::path(
+    put,
+    path = "/api/submissions/{slug}",
+    tag = "submissions",
+    params(("slug" = String, Path, description = "Submission slug")),
+    request_body = UpdateSubmission,
+    responses(
+        (status = 200, description = "Submission updated", body = ApiResponse<Submission>),
+        (status = 400, description = "Invalid input or submission no longer editable", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn update_submission(
     State(state): State<AppState>,
     Path(slug): Path<String>,
     Json(input): Json<UpdateSubmission>,
-) -> impl IntoResponse {
-    if let Err(e) = validate_slug(&slug) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<Submission>::error(e.to_string())),
-        );
-    }
+) -> Result<Json<ApiResponse<Submission>>, AppError> {
+    validate_slug(&slug)?;
 
     // Check submission exists and is in draft status
-    let existing = sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE slug = $1")
+    let submission = sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE slug = $1")
         .bind(&slug)
         .fetch_optional(&state.pool)
-        .await;
+        .await?
+        .ok_or(AppError::NotFound(ErrorKey::SubmissionNotFound))?;
+    if submission.status != SubmissionStatus::Draft {
+        return Err(AppError::BadRequest(ErrorKey::NotDraft.into()));
+    }
 
-    match existing {
-        Ok(Some(submission)) => {
-            if submission.status != SubmissionStatus::Draft {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(ApiResponse::error(
-                        "Cannot update submission that is not in draft status",
-                    )),
-                );
-            }
+    let updated = sqlx::query_as::<_, Submission>(
+        r#"
+        UPDATE submissions SET
+            submitter_name = COALESCE($1, submitter_name),
+            submitter_email = COALESCE($2, submitter_email),
+            organization = COALESCE($3, organization),
+            organization_department = COALESCE($4, organization_department),
+            notes = COALESCE($5, notes)
+        WHERE slug = $6
+        RETURNING *
+        "#,
+    )
+    .bind(&input.submitter_name)
+    .bind(&input.submitter_email)
+    .bind(&input.organization)
+    .bind(&input.organization_department)
+    .bind(&input.notes)
+    .bind(&slug)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::UpdateSubmissionFailed))?;
 
-            // Build dynamic update query
-            let result = sqlx::query_as::<_, Submission>(
-                r#"
-                UPDATE submissions SET
-                    submitter_name = COALESCE($1, submitter_name),
-                    submitter_email = COALESCE($2, submitter_email),
-                    organization = COALESCE($3, organization),
-                    organization_department = COALESCE($4, organization_department),
-                    notes = COALESCE($5, notes)
-                WHERE slug = $6
-                RETURNING *
-                "#,
-            )
-            .bind(&input.submitter_name)
-            .bind(&input.submitter_email)
-            .bind(&input.organization)
-            .bind(&input.organization_department)
-            .bind(&input.notes)
-            .bind(&slug)
-            .fetch_one(&state.pool)
-            .await;
+    log_audit(
+        &state.pool,
+        "submission_updated",
+        "submission",
+        Some(updated.id),
+        "applicant",
+        None,
+    )
+    .await;
+    Ok(Json(ApiResponse::success(updated)))
+}
 
-            match result {
-                Ok(updated) => {
-                    log_audit(
-                        &state.pool,
-                        "submission_updated",
-                        "submission",
-                        Some(updated.id),
-                        "applicant",
-                        None,
-                    )
-                    .await;
-                    (StatusCode::OK, Json(ApiResponse::success(updated)))
-                }
-                Err(e) => {
-                    tracing::error!("Failed to update submission: {}", e);
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse::error("Failed to update submission")),
-                    )
-                }
-            }
-        }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Submission not found")),
-        ),
-        Err(e) => {
-            tracing::error!("Database error: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Database error")),
-            )
-        }
-    }
+/// Correct submission details as the logged-in uploader
+///
+/// Unlike `update_submission` this also works after submission, but then
+/// only for the email address, department and notes.
+#[utoipa::path(
+    put,
+    path = "/api/submissions/{slug}/metadata",
+    tag = "submissions",
+    params(("slug" = String, Path, description = "Submission slug")),
+    request_body = UpdateSubmission,
+    responses(
+        (status = 200, description = "Submission updated", body = ApiResponse<Submission>),
+        (status = 400, description = "Invalid input or a field locked after submission", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Uploader login required", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("uploader_session" = [])),
+)]
+pub async fn update_submission_metadata(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    Json(input): Json<UpdateSubmission>,
+) -> Result<Json<ApiResponse<Submission>>, AppError> {
+    validate_slug(&slug)?;
+    let submission = uploader_submission(
+        &state,
+        &headers,
+        &slug,
+        ErrorKey::LoginRequiredToEditDetails,
+    )
+    .await?;
+
+    let is_draft = submission.status == SubmissionStatus::Draft;
+    validate_metadata_update(&input, is_draft)?;
+
+    // The status check guards against a submit racing this update
+    let updated = sqlx::query_as::<_, Submission>(
+        r#"
+        UPDATE submissions SET
+            submitter_name = COALESCE($1, submitter_name),
+            submitter_email = COALESCE($2, submitter_email),
+            organization = COALESCE($3, organization),
+            organization_department = COALESCE($4, organization_department),
+            notes = COALESCE($5, notes)
+        WHERE id = $6 AND (status = 'draft' OR ($1 IS NULL AND $3 IS NULL))
+        RETURNING *
+        "#,
+    )
+    .bind(&input.submitter_name)
+    .bind(&input.submitter_email)
+    .bind(&input.organization)
+    .bind(&input.organization_department)
+    .bind(&input.notes)
+    .bind(submission.id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::UpdateSubmissionFailed))?
+    .ok_or_else(|| AppError::BadRequest(ErrorKey::NotDraft.into()))?;
+
+    log_audit(
+        &state.pool,
+        "submission_updated",
+        "submission",
+        Some(updated.id),
+        "uploader",
+        None,
+    )
+    .await;
+    Ok(Json(ApiResponse::success(updated)))
 }
 
 /// Submit a submission (change status from draft to submitted)
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/submit",
+    tag = "submissions",
+    params(("slug" = String, Path, description = "Submission slug")),
+    responses(
+        (status = 200, description = "Submission submitted for review", body = ApiResponse<Submission>),
+        (status = 400, description = "Submission cannot be submitted", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn submit_submission(
     State(state): State<AppState>,
     Path(slug): Path<String>,
-) -> impl IntoResponse {
-    if let Err(e) = validate_slug(&slug) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<Submission>::error(e.to_string())),
-        );
-    }
+) -> Result<Json<ApiResponse<Submission>>, AppError> {
+    validate_slug(&slug)?;
 
-    let result = sqlx::query_as::<_, Submission>(
+    let submission = sqlx::query_as::<_, Submission>(
         r#"
         UPDATE submissions
         SET status = 'submitted', submitted_at = NOW()
@@ -293,311 +617,1358 @@ pub async fn submit_submission(
     )
     .bind(&slug)
     .fetch_optional(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::SubmitFailed))?
+    .ok_or(AppError::NotFound(ErrorKey::NotFoundOrNotDraft))?;
+
+    log_audit(
+        &state.pool,
+        "submission_submitted",
+        "submission",
+        Some(submission.id),
+        "applicant",
+        None,
+    )
+    .await;
+    webhooks::dispatch(
+        &state.pool,
+        state.webhooks.as_ref(),
+        WebhookEvent::SubmissionSubmitted,
+        &submission,
+        None,
+    )
+    .await;
+    Ok(Json(ApiResponse::success(submission)))
+}
+
+/// Withdraw a submitted submission (uploader)
+///
+/// Allowed while the submission is submitted or under review. A booked
+/// meeting slot is released in the same transaction.
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/withdraw",
+    tag = "submissions",
+    params(("slug" = String, Path, description = "Submission slug")),
+    responses(
+        (status = 200, description = "Submission withdrawn", body = ApiResponse<Submission>),
+        (status = 401, description = "Uploader login required", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Submission can no longer be withdrawn", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("uploader_session" = [])),
+)]
+pub async fn withdraw_submission(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<Submission>>, AppError> {
+    validate_slug(&slug)?;
+    let submission =
+        uploader_submission(&state, &headers, &slug, ErrorKey::LoginRequiredToWithdraw).await?;
+
+    let (submission, released_slot) = withdraw(&state.pool, submission.id)
+        .await
+        .map_err(AppError::db(ErrorKey::WithdrawFailed))?
+        .map_err(|_| AppError::Conflict(ErrorKey::NotWithdrawable.into()))?;
+
+    let client_ip = get_client_ip(&headers, &state.trusted_proxies);
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_ip, details, request_id)
+        VALUES ('submission_withdrawn'::audit_action, 'submission', $1, 'uploader', $2, $3, $4)
+        "#,
+    )
+    .bind(submission.id)
+    .bind(&client_ip)
+    .bind(serde_json::json!({ "released_slot": released_slot }))
+    .bind(current_request_id())
+    .execute(&state.pool)
     .await;
 
-    match result {
-        Ok(Some(submission)) => {
-            log_audit(
-                &state.pool,
-                "submission_submitted",
-                "submission",
-                Some(submission.id),
-                "applicant",
-                None,
-            )
-            .await;
-            (StatusCode::OK, Json(ApiResponse::success(submission)))
-        }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error(
-                "Submission not found or not in draft status",
-            )),
-        ),
-        Err(e) => {
-            tracing::error!("Failed to submit: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Failed to submit")),
-            )
-        }
-    }
+    tracing::info!("Submission {} withdrawn by uploader", submission.slug);
+
+    Ok(Json(ApiResponse::success(submission)))
 }
 
-// =============================================================================
-// Document Endpoints
-// =============================================================================
+/// Mark a submission withdrawn and release its booked slot
+///
+/// Returns the current status instead when the submission may no longer be
+/// withdrawn.
+async fn withdraw(
+    pool: &PgPool,
+    submission_id: Uuid,
+) -> Result<Result<(Submission, Option<Uuid>), SubmissionStatus>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let status: SubmissionStatus =
+        sqlx::query_scalar("SELECT status FROM submissions WHERE id = $1 FOR UPDATE")
+            .bind(submission_id)
+            .fetch_one(&mut *tx)
+            .await?;
+    if !can_withdraw(status) {
+        return Ok(Err(status));
+    }
 
-#[derive(Debug, Deserialize)]
-pub struct UploadDocumentQuery {
-    #[serde(default = "default_document_category")]
-    pub category: DocumentCategory,
-    pub classification: DocumentClassification,
-    pub description: Option<String>,
+    let submission = sqlx::query_as::<_, Submission>(
+        "UPDATE submissions SET status = 'withdrawn' WHERE id = $1 RETURNING *",
+    )
+    .bind(submission_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let released_slot: Option<Uuid> =
+        sqlx::query_scalar("DELETE FROM slot_bookings WHERE submission_id = $1 RETURNING slot_id")
+            .bind(submission_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+    tx.commit().await?;
+    Ok(Ok((submission, released_slot)))
 }
 
-fn default_document_category() -> DocumentCategory {
-    DocumentCategory::WorkInstruction
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExtendRetentionRequest {
+    /// Must be `true`: the uploader explicitly agrees to longer retention
+    pub consent: bool,
 }
 
-/// Upload a document
-pub async fn upload_document(
+/// Why a retention extension was refused
+#[derive(Debug, PartialEq)]
+enum RetentionRejection {
+    Expired,
+    AtMaximum,
+}
+
+/// Extend the retention period after explicit uploader consent
+///
+/// Pushes `retention_expiry_date` out by the configured number of months,
+/// capped at the maximum retention counted from creation.
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/extend-retention",
+    tag = "submissions",
+    params(("slug" = String, Path, description = "Submission slug")),
+    request_body = ExtendRetentionRequest,
+    responses(
+        (status = 200, description = "Retention extended", body = ApiResponse<Submission>),
+        (status = 400, description = "Consent not given", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Uploader login required", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Submission expired or already at the maximum retention", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("uploader_session" = [])),
+)]
+pub async fn extend_retention(
     State(state): State<AppState>,
-    headers: HeaderMap,
     Path(slug): Path<String>,
-    Query(query): Query<UploadDocumentQuery>,
-    mut multipart: Multipart,
-) -> impl IntoResponse {
-    tracing::info!(
-        "Upload request received for slug={}, category={:?}, classification={:?}",
-        slug,
-        query.category,
-        query.classification
-    );
+    headers: HeaderMap,
+    Json(input): Json<ExtendRetentionRequest>,
+) -> Result<Json<ApiResponse<Submission>>, AppError> {
+    validate_slug(&slug)?;
+    let submission = uploader_submission(
+        &state,
+        &headers,
+        &slug,
+        ErrorKey::LoginRequiredToExtendRetention,
+    )
+    .await?;
 
-    // Validate slug
-    if let Err(e) = validate_slug(&slug) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<DocumentResponse>::error(e.to_string())),
-        );
+    if !input.consent {
+        return Err(AppError::BadRequest(ErrorKey::ConsentRequired.into()));
     }
 
-    // Check classification - reject restricted documents
-    if let Err(e) = validate_classification_for_upload(query.classification) {
+    let previous_expiry = submission.retention_expiry_date;
+    let submission = store_retention_extension(
+        &state.pool,
+        submission.id,
+        state.retention_extension_months,
+        state.retention_max_months,
+    )
+    .await
+    .map_err(AppError::db(ErrorKey::ExtendRetentionFailed))?
+    .map_err(|rejection| match rejection {
+        RetentionRejection::Expired => AppError::Conflict(ErrorKey::RetentionExpired.into()),
+        RetentionRejection::AtMaximum => AppError::Conflict(ErrorKey::RetentionAtMaximum.into()),
+    })?;
+
+    let client_ip = get_client_ip(&headers, &state.trusted_proxies);
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_ip, details, request_id)
+        VALUES ('retention_extended'::audit_action, 'submission', $1, 'uploader', $2, $3, $4)
+        "#,
+    )
+    .bind(submission.id)
+    .bind(&client_ip)
+    .bind(serde_json::json!({
+        "previous_expiry": previous_expiry,
+        "new_expiry": submission.retention_expiry_date,
+    }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    tracing::info!(
+        "Retention of submission {} extended to {}",
+        submission.slug,
+        submission.retention_expiry_date
+    );
+
+    Ok(Json(ApiResponse::success(submission)))
+}
+
+/// What happened to a submission, newest first, as its uploader may see it
+///
+/// Covers the submission itself, its documents and its bookings. Reviewers
+/// appear only as `reviewer`; their ids, notes and other details are left out.
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{slug}/history",
+    tag = "submissions",
+    params(("slug" = String, Path, description = "Submission slug")),
+    responses(
+        (status = 200, description = "History of the submission", body = ApiResponse<Vec<SubmissionHistoryEntry>>),
+        (status = 401, description = "Uploader login required", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("uploader_session" = [])),
+)]
+pub async fn get_submission_history(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<Vec<SubmissionHistoryEntry>>>, AppError> {
+    validate_slug(&slug)?;
+    let submission = uploader_submission(
+        &state,
+        &headers,
+        &slug,
+        ErrorKey::LoginRequiredToViewHistory,
+    )
+    .await?;
+
+    // Document events point at the document and bookings name the
+    // submission as their actor, so both are matched separately
+    let entries = sqlx::query_as::<_, AuditLogEntry>(
+        r#"
+        SELECT id, action::text AS action, entity_type, entity_id, actor_type,
+               actor_id, actor_ip, details, request_id, created_at
+        FROM audit_log
+        WHERE action::text = ANY($2)
+        AND (
+            (entity_type = 'submission' AND entity_id = $1)
+            OR (entity_type = 'document' AND (
+                entity_id IN (SELECT id FROM documents WHERE submission_id = $1)
+                OR details->>'submission_id' = $1::text
+            ))
+            OR (entity_type = 'calendar_slot' AND actor_type = 'applicant' AND actor_id = $1)
+        )
+        ORDER BY created_at DESC, id
+        "#,
+    )
+    .bind(submission.id)
+    .bind(HISTORY_ACTIONS)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::LoadHistoryFailed))?;
+
+    let history = entries
+        .iter()
+        .filter_map(SubmissionHistoryEntry::from_audit)
+        .collect();
+    Ok(Json(ApiResponse::success(history)))
+}
+
+/// Submission of the uploader session in `headers`, if it is the one at `slug`
+async fn uploader_submission(
+    state: &AppState,
+    headers: &HeaderMap,
+    slug: &str,
+    login_required: ErrorKey,
+) -> Result<Submission, AppError> {
+    match validate_uploader_session(&state.pool, headers).await {
+        Some((submission, _)) if submission.slug == slug => Ok(submission),
+        _ => Err(AppError::Unauthorized(AuthScheme::Uploader, login_required)),
+    }
+}
+
+/// New expiry date for one more extension of `extension_months`
+///
+/// The result never exceeds `max_months` after `created_at`; an extension
+/// that would not move the expiry date at all is refused.
+fn extended_retention(
+    created_at: DateTime<Utc>,
+    expiry: DateTime<Utc>,
+    now: DateTime<Utc>,
+    extension_months: u32,
+    max_months: u32,
+) -> Result<DateTime<Utc>, RetentionRejection> {
+    if expiry <= now {
+        return Err(RetentionRejection::Expired);
+    }
+
+    let cap = created_at
+        .checked_add_months(Months::new(max_months))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+    let extended = expiry
+        .checked_add_months(Months::new(extension_months))
+        .unwrap_or(cap)
+        .min(cap);
+
+    if extended <= expiry {
+        return Err(RetentionRejection::AtMaximum);
+    }
+    Ok(extended)
+}
+
+/// Record consent and move the expiry date of a submission
+async fn store_retention_extension(
+    pool: &PgPool,
+    submission_id: Uuid,
+    extension_months: u32,
+    max_months: u32,
+) -> Result<Result<Submission, RetentionRejection>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let (created_at, expiry): (DateTime<Utc>, DateTime<Utc>) = sqlx::query_as(
+        "SELECT created_at, retention_expiry_date FROM submissions WHERE id = $1 FOR UPDATE",
+    )
+    .bind(submission_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let new_expiry =
+        match extended_retention(created_at, expiry, Utc::now(), extension_months, max_months) {
+            Ok(new_expiry) => new_expiry,
+            Err(rejection) => return Ok(Err(rejection)),
+        };
+
+    let submission = sqlx::query_as::<_, Submission>(
+        r#"
+        UPDATE submissions
+        SET retention_expiry_date = $2, retention_consent_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(submission_id)
+    .bind(new_expiry)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Ok(submission))
+}
+
+// =============================================================================
+// Document Endpoints
+// =============================================================================
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UploadDocumentQuery {
+    #[serde(default = "default_document_category")]
+    pub category: DocumentCategory,
+    /// Falls back to the configured default for the category when omitted
+    pub classification: Option<DocumentClassification>,
+    pub description: Option<String>,
+}
+
+fn default_document_category() -> DocumentCategory {
+    DocumentCategory::WorkInstruction
+}
+
+/// A rejected upload, carrying the HTTP status and message to report
+struct UploadError {
+    status: StatusCode,
+    message: String,
+    auth_required: Option<AuthScheme>,
+    existing_document_id: Option<Uuid>,
+    /// The rejected file, named in batch upload errors
+    filename: Option<String>,
+    detail: Option<FieldError>,
+}
+
+impl UploadError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            auth_required: None,
+            existing_document_id: None,
+            filename: None,
+            detail: None,
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    /// The upload failed validation; the response names the field and reason
+    fn invalid(e: ValidationError) -> Self {
+        Self {
+            detail: Some(e.to_detail()),
+            ..Self::bad_request(e.to_string())
+        }
+    }
+
+    /// The file, or the request carrying it, is over the upload limit
+    fn too_large(max_bytes: usize) -> Self {
+        let e = ValidationError::FileTooLarge {
+            max_mb: max_bytes / (1024 * 1024),
+        };
+        Self {
+            detail: Some(e.to_detail()),
+            ..Self::new(StatusCode::PAYLOAD_TOO_LARGE, e.to_string())
+        }
+    }
+
+    /// Writing `path` failed; a full disk gets a 507 asking to retry later
+    ///
+    /// The OS error is only logged: it means nothing to the applicant.
+    fn write_failed(path: impl std::fmt::Debug, e: &dyn std::fmt::Display, full: bool) -> Self {
+        if full {
+            tracing::error!(
+                "Upload storage is full, uploads fail until space is freed ({:?}: {})",
+                path,
+                e
+            );
+            return Self::new(StatusCode::INSUFFICIENT_STORAGE, ErrorKey::StorageFull);
+        }
+        tracing::error!("Failed to write file {:?}: {}", path, e);
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorKey::StoreDocumentFailed,
+        )
+    }
+
+    /// The file was already uploaded to this submission as `existing_id`
+    fn duplicate(existing_id: Uuid) -> Self {
+        Self {
+            existing_document_id: Some(existing_id),
+            ..Self::new(StatusCode::CONFLICT, ErrorKey::DuplicateUpload)
+        }
+    }
+
+    fn into_response<T>(self) -> (StatusCode, Json<ApiResponse<T>>) {
+        let body = match (self.auth_required, self.existing_document_id, self.detail) {
+            (Some(scheme), _, _) => ApiResponse::auth_required(scheme, self.message),
+            (None, Some(existing_id), _) => {
+                ApiResponse::duplicate_document(existing_id, self.message)
+            }
+            (None, None, Some(detail)) => ApiResponse {
+                details: Some(vec![detail]),
+                ..ApiResponse::error(self.message)
+            },
+            (None, None, None) => ApiResponse::error(self.message),
+        };
+        let body = ApiResponse {
+            filename: self.filename,
+            ..body
+        };
+        (self.status, Json(body))
+    }
+}
+
+/// A file that passed validation and has been written to storage
+struct StoredUpload {
+    doc_id: Uuid,
+    storage_key: String,
+    storage_filename: String,
+    original_filename: String,
+    content_type: String,
+    size: usize,
+    content_sha256: String,
+    /// Stored encrypted with the configured file key
+    encrypted: bool,
+}
+
+/// Upload a document
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/documents",
+    tag = "documents",
+    params(("slug" = String, Path, description = "Submission slug"), UploadDocumentQuery),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Document stored", body = ApiResponse<DocumentResponse>),
+        (status = 400, description = "File rejected", body = ApiResponse<serde_json::Value>),
+        (status = 413, description = "File over the upload limit", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Uploader login required for submitted submissions", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Identical file already uploaded; see existing_document_id", body = ApiResponse<serde_json::Value>),
+        (status = 507, description = "Upload storage is full; try again later", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn upload_document(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+    Query(query): Query<UploadDocumentQuery>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    tracing::info!(
+        "Upload request received for slug={}, category={:?}, classification={:?}",
+        slug,
+        query.category,
+        query.classification
+    );
+
+    let (submission, classification) = match prepare_upload(&state, &headers, &slug, &query).await {
+        Ok(prepared) => prepared,
+        Err(e) => return e.into_response(),
+    };
+
+    // Process multipart upload (single file) with proper error handling
+    let mut field = match multipart.next_field().await {
+        Ok(Some(field)) => field,
+        Ok(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(ErrorKey::NoFileProvided)),
+            );
+        }
+        Err(e) => return multipart_error(e, state.max_upload_size).into_response(),
+    };
+
+    let upload = match store_upload_field(&state, &submission, &mut field).await {
+        Ok(upload) => upload,
+        Err(e) => return e.into_response(),
+    };
+
+    // Store metadata in database
+    let result = insert_documents(
+        &state,
+        submission.id,
+        &query,
+        classification,
+        std::slice::from_ref(&upload),
+    )
+    .await
+    .map(|mut documents| documents.remove(0));
+
+    match result {
+        Ok(doc) => {
+            log_audit(
+                &state.pool,
+                "document_uploaded",
+                "document",
+                Some(doc.id),
+                "applicant",
+                None,
+            )
+            .await;
+            webhooks::dispatch(
+                &state.pool,
+                state.webhooks.as_ref(),
+                WebhookEvent::DocumentUploaded,
+                &submission,
+                Some(&doc),
+            )
+            .await;
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse::success(DocumentResponse::from(doc))),
+            )
+        }
+        Err(e) => {
+            tracing::error!("Failed to store document metadata: {}", e);
+            discard_uploads(&state, std::slice::from_ref(&upload)).await;
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::StoreDocumentFailed)),
+            )
+        }
+    }
+}
+
+/// Upload several documents in one multipart request
+///
+/// All files share the category, classification and description from the
+/// query string. The batch is all-or-nothing: if any file is rejected, files
+/// already stored for this request are removed again and the error's
+/// `filename` names the offending file.
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/documents/batch",
+    tag = "documents",
+    params(("slug" = String, Path, description = "Submission slug"), UploadDocumentQuery),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "All documents stored", body = ApiResponse<Vec<DocumentResponse>>),
+        (status = 400, description = "A file was rejected; nothing was stored", body = ApiResponse<serde_json::Value>),
+        (status = 413, description = "File over the upload limit", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Uploader login required for submitted submissions", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Duplicate file", body = ApiResponse<serde_json::Value>),
+        (status = 507, description = "Upload storage is full; try again later", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn upload_documents(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+    Query(query): Query<UploadDocumentQuery>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    tracing::info!(
+        "Batch upload request received for slug={}, category={:?}, classification={:?}",
+        slug,
+        query.category,
+        query.classification
+    );
+
+    let (submission, classification) = match prepare_upload(&state, &headers, &slug, &query).await {
+        Ok(prepared) => prepared,
+        Err(e) => return e.into_response::<Vec<DocumentResponse>>(),
+    };
+
+    let mut uploads: Vec<StoredUpload> = Vec::new();
+    loop {
+        let mut field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                discard_uploads(&state, &uploads).await;
+                return multipart_error(e, state.max_upload_size).into_response();
+            }
+        };
+
+        // Ignore plain form fields; only file parts are uploads
+        let Some(filename) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+
+        if uploads.len() >= state.max_files_per_upload {
+            discard_uploads(&state, &uploads).await;
+            return UploadError {
+                filename: Some(filename),
+                ..UploadError::bad_request(ErrorKey::TooManyFiles)
+            }
+            .into_response();
+        }
+
+        match store_upload_field(&state, &submission, &mut field).await {
+            Ok(upload) => {
+                let repeated = uploads
+                    .iter()
+                    .any(|u| u.content_sha256 == upload.content_sha256);
+                uploads.push(upload);
+                if repeated {
+                    discard_uploads(&state, &uploads).await;
+                    return UploadError {
+                        filename: Some(filename),
+                        ..UploadError::new(StatusCode::CONFLICT, ErrorKey::DuplicateInUpload)
+                    }
+                    .into_response();
+                }
+            }
+            Err(e) => {
+                discard_uploads(&state, &uploads).await;
+                return UploadError {
+                    filename: Some(filename),
+                    ..e
+                }
+                .into_response();
+            }
+        }
+    }
+
+    if uploads.is_empty() {
         return (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::error(format!(
-                "{}. Documents marked as 'restricted' cannot be uploaded to this portal. \
-                Please only upload documents that may be used with AI tools.",
-                e
-            ))),
+            Json(ApiResponse::error(ErrorKey::NoFileProvided)),
         );
     }
 
+    let documents =
+        match insert_documents(&state, submission.id, &query, classification, &uploads).await {
+            Ok(documents) => documents,
+            Err(e) => {
+                tracing::error!("Failed to store document metadata: {}", e);
+                discard_uploads(&state, &uploads).await;
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(ErrorKey::StoreDocumentsFailed)),
+                );
+            }
+        };
+
+    for doc in &documents {
+        log_audit(
+            &state.pool,
+            "document_uploaded",
+            "document",
+            Some(doc.id),
+            "applicant",
+            None,
+        )
+        .await;
+        webhooks::dispatch(
+            &state.pool,
+            state.webhooks.as_ref(),
+            WebhookEvent::DocumentUploaded,
+            &submission,
+            Some(doc),
+        )
+        .await;
+    }
+
+    (
+        StatusCode::CREATED,
+        Json(ApiResponse::success(
+            documents.into_iter().map(DocumentResponse::from).collect(),
+        )),
+    )
+}
+
+/// Checks shared by single and batch uploads: slug, classification, category
+/// and whether the caller may add documents to the submission
+async fn prepare_upload(
+    state: &AppState,
+    headers: &HeaderMap,
+    slug: &str,
+    query: &UploadDocumentQuery,
+) -> Result<(Submission, DocumentClassification), UploadError> {
+    // Validate slug
+    validate_slug(slug).map_err(UploadError::invalid)?;
+
+    let classification = query
+        .classification
+        .or_else(|| state.default_classifications.get(&query.category).copied())
+        .ok_or_else(|| UploadError::bad_request(ErrorKey::ClassificationRequired))?;
+
+    // Check classification - reject restricted documents
+    if let Err(e) = validate_classification_for_upload(classification) {
+        return Err(UploadError::invalid(e));
+    }
+
     // For formal laws, reject file uploads
     if query.category == DocumentCategory::FormalLaw {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::error(
-                "Formal laws should be added as links, not file uploads. \
-                Use the /api/submissions/{slug}/formal-law endpoint instead.",
-            )),
-        );
+        return Err(UploadError::bad_request(
+            ErrorKey::FormalLawUploadNotAllowed,
+        ));
     }
 
+    let submission = authorize_document_change(state, headers, slug).await?;
+
+    Ok((submission, classification))
+}
+
+/// Load the submission and check that the caller may change its documents
+async fn authorize_document_change(
+    state: &AppState,
+    headers: &HeaderMap,
+    slug: &str,
+) -> Result<Submission, UploadError> {
     // Get submission
-    let submission = match get_submission_by_slug(&state.pool, &slug).await {
-        Some(s) => s,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Submission not found")),
-            )
-        }
-    };
+    let submission = get_submission_by_slug(&state.pool, slug)
+        .await
+        .ok_or_else(|| UploadError::new(StatusCode::NOT_FOUND, ErrorKey::SubmissionNotFound))?;
 
     // Authorization check:
     // - Draft submissions: anyone with the slug can upload (existing behavior)
     // - Non-draft submissions: require valid uploader session for this specific submission
     if submission.status != SubmissionStatus::Draft {
-        match validate_uploader_session(&state.pool, &headers).await {
+        match validate_uploader_session(&state.pool, headers).await {
             Some((session_submission, _)) if session_submission.id == submission.id => {
                 // Valid session for this submission - allow upload
             }
             _ => {
-                return (
-                    StatusCode::UNAUTHORIZED,
-                    Json(ApiResponse::error(
-                        "Inloggen vereist om documenten toe te voegen aan een ingediende inzending.",
-                    )),
-                );
+                return Err(UploadError {
+                    auth_required: Some(AuthScheme::Uploader),
+                    ..UploadError::new(
+                        StatusCode::UNAUTHORIZED,
+                        ErrorKey::LoginRequiredToAddDocuments,
+                    )
+                });
             }
         }
     }
 
-    // Process multipart upload (single file) with proper error handling
-    let field = match multipart.next_field().await {
+    Ok(submission)
+}
+
+/// Replace the file of an existing document, keeping its id and metadata
+///
+/// Category, classification and description stay as they are; the new file
+/// goes through the same validation as a regular upload.
+#[utoipa::path(
+    put,
+    path = "/api/submissions/{slug}/documents/{doc_id}",
+    tag = "documents",
+    params(("slug" = String, Path, description = "Submission slug"), ("doc_id" = Uuid, Path, description = "Document id")),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Document file replaced", body = ApiResponse<DocumentResponse>),
+        (status = 400, description = "File rejected", body = ApiResponse<serde_json::Value>),
+        (status = 413, description = "File over the upload limit", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Uploader login required for submitted submissions", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission or document not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Identical file already uploaded", body = ApiResponse<serde_json::Value>),
+        (status = 507, description = "Upload storage is full; try again later", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn replace_document(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((slug, doc_id)): Path<(String, Uuid)>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    if let Err(e) = validate_slug(&slug) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<DocumentResponse>::error(e.to_string())),
+        );
+    }
+
+    let submission = match authorize_document_change(&state, &headers, &slug).await {
+        Ok(submission) => submission,
+        Err(e) => return e.into_response(),
+    };
+
+    let doc = sqlx::query_as::<_, Document>(
+        "SELECT * FROM documents WHERE id = $1 AND submission_id = $2",
+    )
+    .bind(doc_id)
+    .bind(submission.id)
+    .fetch_optional(&state.pool)
+    .await;
+
+    let old_file_path = match doc {
+        Ok(Some(Document {
+            file_path: Some(file_path),
+            ..
+        })) => file_path,
+        Ok(Some(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(ErrorKey::FormalLawNotReplaceable)),
+            );
+        }
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::DocumentNotFound)),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::DatabaseError)),
+            );
+        }
+    };
+
+    let mut field = match multipart.next_field().await {
         Ok(Some(field)) => field,
         Ok(None) => {
             return (
                 StatusCode::BAD_REQUEST,
-                Json(ApiResponse::error("No file provided")),
+                Json(ApiResponse::error(ErrorKey::NoFileProvided)),
+            );
+        }
+        Err(e) => return multipart_error(e, state.max_upload_size).into_response(),
+    };
+
+    let upload = match store_upload_field(&state, &submission, &mut field).await {
+        Ok(upload) => upload,
+        Err(e) => return e.into_response(),
+    };
+
+    let result = replace_document_file(&state, submission.id, doc_id, &upload).await;
+
+    let doc = match result {
+        Ok(Some(doc)) => doc,
+        Ok(None) => {
+            // Deleted while the new file was being uploaded
+            discard_uploads(&state, std::slice::from_ref(&upload)).await;
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(ErrorKey::DocumentNotFound)),
             );
         }
         Err(e) => {
-            tracing::error!("Multipart parsing error: {}", e);
-            // Provide user-friendly error messages for common issues
-            let error_msg = if e.to_string().contains("length limit") {
-                "File too large. Maximum upload size is 50MB."
-            } else if e.to_string().contains("content-type") {
-                "Invalid upload format. Please use multipart/form-data."
-            } else {
-                "Failed to process upload. Please try again."
-            };
+            tracing::error!("Failed to update document metadata: {}", e);
+            discard_uploads(&state, std::slice::from_ref(&upload)).await;
             return (
-                StatusCode::BAD_REQUEST,
-                Json(ApiResponse::error(format!("{} ({})", error_msg, e))),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::StoreDocumentFailed)),
             );
         }
     };
 
+    // The previous file stays in storage as an older version
+    tracing::info!(
+        "Document {} replaced; previous file kept at {:?}",
+        doc.id,
+        old_file_path
+    );
+
+    log_audit(
+        &state.pool,
+        "document_replaced",
+        "document",
+        Some(doc.id),
+        "applicant",
+        None,
+    )
+    .await;
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(DocumentResponse::from(doc))),
+    )
+}
+
+/// Point a document at a newly stored file and record it as the next version
+async fn replace_document_file(
+    state: &AppState,
+    submission_id: Uuid,
+    doc_id: Uuid,
+    upload: &StoredUpload,
+) -> Result<Option<Document>, sqlx::Error> {
+    let mut tx = state.pool.begin().await?;
+
+    // The row lock from UPDATE serializes concurrent replaces of this document
+    let doc = sqlx::query_as::<_, Document>(
+        r#"
+        UPDATE documents
+        SET filename = $3, original_filename = $4, file_path = $5, file_size = $6,
+            mime_type = $7, content_sha256 = $8, encrypted = $9, updated_at = NOW()
+        WHERE id = $1 AND submission_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(doc_id)
+    .bind(submission_id)
+    .bind(&upload.storage_filename)
+    .bind(&upload.original_filename)
+    .bind(&upload.storage_key)
+    .bind(upload.size as i64)
+    .bind(&upload.content_type)
+    .bind(&upload.content_sha256)
+    .bind(upload.encrypted)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(doc) = doc else {
+        return Ok(None);
+    };
+
+    let latest: Option<i32> =
+        sqlx::query_scalar("SELECT MAX(version_no) FROM document_versions WHERE document_id = $1")
+            .bind(doc.id)
+            .fetch_one(&mut *tx)
+            .await?;
+    insert_version(&mut tx, &doc, next_version_no(latest)).await?;
+
+    tx.commit().await?;
+    Ok(Some(doc))
+}
+
+/// Error for a multipart body that could not be read
+///
+/// Hitting the body limit shows up here as a read error with status 413.
+fn multipart_error(e: MultipartError, max_upload_size: usize) -> UploadError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return UploadError::too_large(max_upload_size);
+    }
+    tracing::error!("Multipart parsing error: {}", e);
+    let key = if e.to_string().contains("content-type") {
+        ErrorKey::InvalidUploadFormat
+    } else {
+        ErrorKey::ProcessUploadFailed
+    };
+    UploadError::bad_request(key)
+}
+
+/// Validate one multipart file field and write it to storage
+async fn store_upload_field(
+    state: &AppState,
+    submission: &Submission,
+    field: &mut Field<'_>,
+) -> Result<StoredUpload, UploadError> {
+    let submission_id = submission.id;
     let original_filename = field.file_name().unwrap_or("unknown").to_string();
     let content_type = field
         .content_type()
         .unwrap_or("application/octet-stream")
         .to_string();
 
-    let data = match field.bytes().await {
-        Ok(d) => d,
-        Err(e) => {
-            tracing::error!("Failed to read file bytes: {}", e);
-            let error_msg = if e.to_string().contains("length limit") {
-                "File too large. Maximum upload size is 50MB."
-            } else if e.to_string().contains("connection") {
-                "Connection interrupted during upload. Please try again."
-            } else {
-                "Failed to read uploaded file. Please try again."
-            };
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(ApiResponse::error(format!("{} ({})", error_msg, e))),
-            );
+    // Validate declared type and filename before touching the disk
+    validate_file_upload(
+        &content_type,
+        0,
+        state.max_upload_size,
+        &state.allowed_mime_types,
+    )
+    .map_err(UploadError::invalid)?;
+
+    // Validate filename doesn't contain dangerous extensions
+    validate_filename_extensions(&original_filename, &state.allowed_extensions)
+        .map_err(UploadError::invalid)?;
+
+    // Stream the upload to a local staging file before handing it to the store
+    let doc_id = Uuid::new_v4();
+    let safe_filename = sanitize_filename(&original_filename, state.max_filename_length);
+    let storage_filename = format!("{}_{}", doc_id, safe_filename);
+    let prefix = submission_prefix(state.upload_layout, submission.id, submission.created_at);
+    let storage_key = document_key(&prefix, &storage_filename);
+    let staging_dir = state.upload_dir.join(STAGING_DIR);
+
+    if let Err(e) = fs::create_dir_all(&staging_dir).await {
+        return Err(UploadError::write_failed(
+            &staging_dir,
+            &e,
+            is_storage_full(&e),
+        ));
+    }
+
+    let file_path = staging_dir.join(doc_id.to_string());
+    let upload = match stream_field_to_file(field, &file_path, state.max_upload_size).await {
+        Ok(upload) => upload,
+        Err(StreamUploadError::TooLarge) => {
+            return Err(UploadError::too_large(state.max_upload_size));
+        }
+        Err(StreamUploadError::Read(e)) => {
+            return Err(multipart_error(e, state.max_upload_size));
+        }
+        Err(StreamUploadError::Write(e)) => {
+            return Err(UploadError::write_failed(
+                &file_path,
+                &e,
+                is_storage_full(&e),
+            ));
         }
     };
 
-    // Validate file
-    if let Err(e) = validate_file_upload(&content_type, data.len(), state.max_upload_size) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::error(e.to_string())),
-        );
+    // Validate content matches the declared type (Content-Type is client-controlled)
+    if let Err(e) = validate_file_magic(&upload.head, &content_type) {
+        tracing::warn!("Rejected upload for submission {}: {}", submission_id, e);
+        remove_partial_file(&file_path).await;
+        return Err(UploadError::invalid(e));
     }
 
-    // Validate filename doesn't contain dangerous extensions
-    if let Err(e) = validate_filename_extensions(&original_filename) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::error(e.to_string())),
-        );
+    if content_type == "application/pdf" {
+        check_staged_pdf(state, submission_id, &file_path).await?;
     }
 
-    // Create storage path
-    let doc_id = Uuid::new_v4();
-    let safe_filename = sanitize_filename(&original_filename);
-    let storage_filename = format!("{}_{}", doc_id, safe_filename);
-    let submission_dir = state.upload_dir.join(&slug);
-
-    // Create directory with detailed error logging
-    if let Err(e) = fs::create_dir_all(&submission_dir).await {
-        tracing::error!(
-            "Failed to create upload directory {:?}: {} (kind: {:?})",
-            submission_dir,
-            e,
-            e.kind()
-        );
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(format!(
-                "Failed to create storage directory: {} ({:?})",
-                e,
-                e.kind()
-            ))),
-        );
+    scan_staged_upload(state, submission_id, &file_path, &original_filename).await?;
+
+    // Don't store a second copy of a file already on this submission
+    let existing: Result<Option<Uuid>, sqlx::Error> = sqlx::query_scalar(
+        "SELECT id FROM documents WHERE submission_id = $1 AND content_sha256 = $2",
+    )
+    .bind(submission_id)
+    .bind(&upload.sha256)
+    .fetch_optional(&state.pool)
+    .await;
+    match existing {
+        Ok(None) => {}
+        Ok(Some(existing_id)) => {
+            remove_partial_file(&file_path).await;
+            return Err(UploadError::duplicate(existing_id));
+        }
+        Err(e) => {
+            tracing::error!("Failed to check for duplicate upload: {}", e);
+            remove_partial_file(&file_path).await;
+            return Err(UploadError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorKey::StoreDocumentFailed,
+            ));
+        }
     }
 
-    // Write file - verify path stays within upload directory
-    let file_path = submission_dir.join(&storage_filename);
-    if !file_path.starts_with(&state.upload_dir) {
-        tracing::error!(
-            "Path traversal attempt detected: {:?} escapes {:?}",
-            file_path,
-            state.upload_dir
-        );
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::error("Invalid filename")),
-        );
+    // Checks above need the plaintext, so encrypt only now
+    let encrypted = encrypt_staged_upload(state, &file_path).await?;
+
+    // Move the staged file into storage
+    let stored = state.store.put_file(&storage_key, &file_path).await;
+    remove_partial_file(&file_path).await;
+    match stored {
+        Ok(()) => {}
+        Err(StorageError::InvalidKey(key)) => {
+            tracing::error!("Rejected storage key {:?}", key);
+            return Err(UploadError::bad_request(ErrorKey::InvalidFilename));
+        }
+        Err(e) => {
+            return Err(UploadError::write_failed(
+                &storage_key,
+                &e,
+                e.is_storage_full(),
+            ));
+        }
     }
 
-    if let Err(e) = fs::write(&file_path, &data).await {
-        tracing::error!(
-            "Failed to write file {:?}: {} (kind: {:?})",
-            file_path,
-            e,
-            e.kind()
-        );
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(format!(
-                "Failed to write file: {} ({:?})",
-                e,
-                e.kind()
-            ))),
-        );
+    Ok(StoredUpload {
+        doc_id,
+        storage_key,
+        storage_filename,
+        original_filename,
+        content_type,
+        size: upload.size,
+        content_sha256: upload.sha256,
+        encrypted,
+    })
+}
+
+/// Reject a staged PDF that does not parse or has no pages
+///
+/// Only runs with STRICT_PDF_VALIDATION. A panic in the parser counts as a
+/// corrupt file.
+async fn check_staged_pdf(
+    state: &AppState,
+    submission_id: Uuid,
+    file_path: &std::path::Path,
+) -> Result<(), UploadError> {
+    if !state.strict_pdf_validation {
+        return Ok(());
     }
 
-    // Store metadata in database
-    let result = sqlx::query_as::<_, Document>(
+    let data = match fs::read(file_path).await {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::error!("Failed to read staged upload {:?}: {}", file_path, e);
+            remove_partial_file(file_path).await;
+            return Err(UploadError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorKey::ReadUploadFailed,
+            ));
+        }
+    };
+    let checked = tokio::task::spawn_blocking(move || validate_pdf_structure(&data))
+        .await
+        .unwrap_or(Err(ValidationError::CorruptFile));
+
+    if let Err(e) = checked {
+        tracing::warn!("Rejected upload for submission {}: {}", submission_id, e);
+        remove_partial_file(file_path).await;
+        return Err(UploadError::invalid(e));
+    }
+    Ok(())
+}
+
+/// Encrypt a staged upload in place when a file key is configured
+///
+/// The ciphertext is streamed into a file next to the staged one, which it
+/// then replaces. Returns whether the staged file is now encrypted.
+async fn encrypt_staged_upload(
+    state: &AppState,
+    file_path: &std::path::Path,
+) -> Result<bool, UploadError> {
+    let Some(key) = &state.file_key else {
+        return Ok(false);
+    };
+
+    let mut encrypted_path = file_path.as_os_str().to_owned();
+    encrypted_path.push(".enc");
+    let encrypted_path = PathBuf::from(encrypted_path);
+    let result = async {
+        let plaintext = fs::File::open(file_path).await?;
+        let out = BufWriter::new(fs::File::create(&encrypted_path).await?);
+        key.encrypt_to(plaintext, out).await?;
+        fs::rename(&encrypted_path, file_path).await
+    }
+    .await;
+
+    match result {
+        Ok(()) => Ok(true),
+        Err(e) => {
+            remove_partial_file(&encrypted_path).await;
+            remove_partial_file(file_path).await;
+            Err(UploadError::write_failed(
+                file_path,
+                &e,
+                is_storage_full(&e),
+            ))
+        }
+    }
+}
+
+/// Reject a staged upload that clamd flags as malware
+///
+/// The staged file is removed and the rejection audited. Without a scanner,
+/// or when clamd cannot be reached, the upload is let through with a warning.
+async fn scan_staged_upload(
+    state: &AppState,
+    submission_id: Uuid,
+    file_path: &std::path::Path,
+    original_filename: &str,
+) -> Result<(), UploadError> {
+    let Some(scanner) = &state.virus_scanner else {
+        return Ok(());
+    };
+
+    let signature = match scanner.scan_file(file_path).await {
+        Ok(ScanResult::Clean) => return Ok(()),
+        Ok(ScanResult::Infected(signature)) => signature,
+        Err(e) => {
+            tracing::warn!(
+                "Virus scan unavailable, storing upload for submission {} unscanned: {}",
+                submission_id,
+                e
+            );
+            return Ok(());
+        }
+    };
+
+    tracing::warn!(
+        "Rejected upload {:?} for submission {}: {} found",
+        original_filename,
+        submission_id,
+        signature
+    );
+    remove_partial_file(file_path).await;
+
+    let details = serde_json::json!({
+        "filename": original_filename,
+        "signature": signature,
+    });
+    if let Err(e) = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, details, request_id)
+        VALUES ('upload_rejected_malware'::audit_action, 'submission', $1, 'applicant', $2, $3)
+        "#,
+    )
+    .bind(submission_id)
+    .bind(details)
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await
+    {
+        tracing::error!("Failed to audit malware rejection: {}", e);
+    }
+
+    Err(UploadError::bad_request(ErrorKey::MalwareRejected))
+}
+
+/// Insert a document row together with its first version
+async fn insert_document(
+    conn: &mut PgConnection,
+    submission_id: Uuid,
+    query: &UploadDocumentQuery,
+    classification: DocumentClassification,
+    upload: &StoredUpload,
+) -> Result<Document, sqlx::Error> {
+    let doc = sqlx::query_as::<_, Document>(
         r#"
         INSERT INTO documents (
             id, submission_id, category, classification,
-            filename, original_filename, file_path, file_size, mime_type, description
+            filename, original_filename, file_path, file_size, mime_type, description,
+            content_sha256, encrypted
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         RETURNING *
         "#,
     )
-    .bind(doc_id)
-    .bind(submission.id)
+    .bind(upload.doc_id)
+    .bind(submission_id)
     .bind(query.category)
-    .bind(query.classification)
-    .bind(&storage_filename)
-    .bind(&original_filename)
-    .bind(file_path.to_string_lossy().to_string())
-    .bind(data.len() as i64)
-    .bind(&content_type)
+    .bind(classification)
+    .bind(&upload.storage_filename)
+    .bind(&upload.original_filename)
+    .bind(&upload.storage_key)
+    .bind(upload.size as i64)
+    .bind(&upload.content_type)
     .bind(&query.description)
-    .fetch_one(&state.pool)
-    .await;
+    .bind(&upload.content_sha256)
+    .bind(upload.encrypted)
+    .fetch_one(&mut *conn)
+    .await?;
 
-    match result {
-        Ok(doc) => {
-            log_audit(
-                &state.pool,
-                "document_uploaded",
-                "document",
-                Some(doc.id),
-                "applicant",
-                None,
-            )
-            .await;
-            (
-                StatusCode::CREATED,
-                Json(ApiResponse::success(DocumentResponse::from(doc))),
-            )
-        }
-        Err(e) => {
-            tracing::error!("Failed to store document metadata: {}", e);
-            // Clean up file - log if cleanup fails
-            if let Err(cleanup_err) = fs::remove_file(&file_path).await {
-                tracing::warn!(
-                    "Failed to clean up orphaned file {:?}: {}",
-                    file_path,
-                    cleanup_err
-                );
-            }
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(
-                    "Failed to store document. Please try again.",
-                )),
-            )
+    insert_version(conn, &doc, next_version_no(None)).await?;
+    Ok(doc)
+}
+
+/// Record the current file of `doc` as version `version_no`
+async fn insert_version(
+    conn: &mut PgConnection,
+    doc: &Document,
+    version_no: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO document_versions (
+            document_id, version_no, file_path, file_size, mime_type,
+            original_filename, content_sha256, encrypted
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(doc.id)
+    .bind(version_no)
+    .bind(&doc.file_path)
+    .bind(doc.file_size)
+    .bind(&doc.mime_type)
+    .bind(&doc.original_filename)
+    .bind(&doc.content_sha256)
+    .bind(doc.encrypted)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Version number following the latest stored version, if any
+fn next_version_no(latest: Option<i32>) -> i32 {
+    latest.unwrap_or(0) + 1
+}
+
+/// Insert metadata for a batch of stored files in one transaction
+async fn insert_documents(
+    state: &AppState,
+    submission_id: Uuid,
+    query: &UploadDocumentQuery,
+    classification: DocumentClassification,
+    uploads: &[StoredUpload],
+) -> Result<Vec<Document>, sqlx::Error> {
+    let mut tx = state.pool.begin().await?;
+    let mut documents = Vec::with_capacity(uploads.len());
+    for upload in uploads {
+        documents
+            .push(insert_document(&mut tx, submission_id, query, classification, upload).await?);
+    }
+    tx.commit().await?;
+    Ok(documents)
+}
+
+/// Remove files stored for a request that is being rolled back
+async fn discard_uploads(state: &AppState, uploads: &[StoredUpload]) {
+    for upload in uploads {
+        // Clean up file - log if cleanup fails
+        if let Err(e) = state.store.delete(&upload.storage_key).await {
+            tracing::warn!(
+                "Failed to clean up orphaned file {:?}: {}",
+                upload.storage_key,
+                e
+            );
         }
     }
 }
 
+/// Refuse another formal law once a submission holds `max` of them
+fn check_formal_law_cap(existing: i64, max: i64) -> Result<(), ErrorKey> {
+    if existing >= max {
+        return Err(ErrorKey::FormalLawLimitReached);
+    }
+    Ok(())
+}
+
 /// Add a formal law link
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{slug}/formal-law",
+    tag = "documents",
+    params(("slug" = String, Path, description = "Submission slug")),
+    request_body = CreateFormalLaw,
+    responses(
+        (status = 201, description = "Formal law link added", body = ApiResponse<DocumentResponse>),
+        (status = 400, description = "Invalid URL or too many formal laws", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Uploader login required for submitted submissions", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many requests", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+    ),
+)]
 pub async fn add_formal_law(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -608,15 +1979,15 @@ pub async fn add_formal_law(
     if let Err(e) = validate_slug(&slug) {
         return (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<DocumentResponse>::error(e.to_string())),
+            Json(ApiResponse::<DocumentResponse>::invalid(e.to_detail())),
         );
     }
 
     // Validate URL
-    if let Err(e) = validate_external_url(&input.external_url) {
+    if let Err(e) = validate_external_url(&input.external_url, state.strict_formal_law_domain) {
         return (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::error(e.to_string())),
+            Json(ApiResponse::invalid(e.to_detail())),
         );
     }
 
@@ -626,7 +1997,7 @@ pub async fn add_formal_law(
         None => {
             return (
                 StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Submission not found")),
+                Json(ApiResponse::error(ErrorKey::SubmissionNotFound)),
             )
         }
     };
@@ -642,14 +2013,35 @@ pub async fn add_formal_law(
             _ => {
                 return (
                     StatusCode::UNAUTHORIZED,
-                    Json(ApiResponse::error(
-                        "Inloggen vereist om documenten toe te voegen aan een ingediende inzending.",
+                    Json(ApiResponse::auth_required(
+                        AuthScheme::Uploader,
+                        ErrorKey::LoginRequiredToAddDocuments,
                     )),
                 );
             }
         }
     }
 
+    let existing: i64 = match sqlx::query_scalar(
+        "SELECT COUNT(*) FROM documents WHERE submission_id = $1 AND category = 'formal_law'",
+    )
+    .bind(submission.id)
+    .fetch_one(&state.pool)
+    .await
+    {
+        Ok(count) => count,
+        Err(e) => {
+            tracing::error!("Failed to count formal laws: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(ErrorKey::AddFormalLawFailed)),
+            );
+        }
+    };
+    if let Err(key) = check_formal_law_cap(existing, state.max_formal_laws_per_submission) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(key)));
+    }
+
     // Formal laws are always public
     let result = sqlx::query_as::<_, Document>(
         r#"
@@ -679,6 +2071,14 @@ pub async fn add_formal_law(
                 None,
             )
             .await;
+            webhooks::dispatch(
+                &state.pool,
+                state.webhooks.as_ref(),
+                WebhookEvent::DocumentUploaded,
+                &submission,
+                Some(&doc),
+            )
+            .await;
             (
                 StatusCode::CREATED,
                 Json(ApiResponse::success(DocumentResponse::from(doc))),
@@ -688,53 +2088,105 @@ pub async fn add_formal_law(
             tracing::error!("Failed to add formal law: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Failed to add formal law")),
+                Json(ApiResponse::error(ErrorKey::AddFormalLawFailed)),
             )
         }
     }
 }
 
-/// Delete a document
-pub async fn delete_document(
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FormalLawLookupQuery {
+    /// Link to a law on wetten.overheid.nl
+    pub url: String,
+}
+
+/// Look up the official title of a law on wetten.overheid.nl
+///
+/// Only the BWB identifier is taken from the link; the page is fetched from
+/// wetten.overheid.nl itself and titles are cached per identifier.
+#[utoipa::path(
+    get,
+    path = "/api/formal-law/lookup",
+    tag = "documents",
+    params(FormalLawLookupQuery),
+    responses(
+        (status = 200, description = "Suggested title", body = ApiResponse<FormalLawLookupResponse>),
+        (status = 400, description = "Not a wetten.overheid.nl link with a BWB identifier", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many requests", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+        (status = 502, description = "Title unavailable", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn lookup_formal_law_title(
     State(state): State<AppState>,
-    headers: HeaderMap,
-    Path((slug, doc_id)): Path<(String, Uuid)>,
+    Query(query): Query<FormalLawLookupQuery>,
 ) -> impl IntoResponse {
-    if let Err(e) = validate_slug(&slug) {
+    if let Err(e) = validate_external_url(&query.url, state.strict_formal_law_domain) {
         return (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(e.to_string())),
+            Json(ApiResponse::error(e.to_string())),
         );
     }
+    let Some(bwb_id) = crate::wetten::bwb_id(&query.url) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(ErrorKey::NotWettenUrl)),
+        );
+    };
 
-    // Get submission and verify ownership
-    let submission = match get_submission_by_slug(&state.pool, &slug).await {
-        Some(s) => s,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(ApiResponse::error("Submission not found")),
+    match state.law_titles.title(&bwb_id).await {
+        Ok(title) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(FormalLawLookupResponse {
+                bwb_id,
+                title,
+            })),
+        ),
+        Err(e) => {
+            tracing::warn!("Title lookup for {} failed: {}", bwb_id, e);
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(ApiResponse::error(ErrorKey::TitleUnavailable)),
             )
         }
-    };
+    }
+}
+
+/// Delete a document
+#[utoipa::path(
+    delete,
+    path = "/api/submissions/{slug}/documents/{doc_id}",
+    tag = "documents",
+    params(("slug" = String, Path, description = "Submission slug"), ("doc_id" = Uuid, Path, description = "Document id")),
+    responses(
+        (status = 200, description = "Document deleted", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Uploader login required for submitted submissions", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission or document not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn delete_document(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((slug, doc_id)): Path<(String, Uuid)>,
+) -> Result<Json<ApiResponse<()>>, AppError> {
+    validate_slug(&slug)?;
+
+    // Get submission and verify ownership
+    let submission = get_submission_by_slug(&state.pool, &slug)
+        .await
+        .ok_or(AppError::NotFound(ErrorKey::SubmissionNotFound))?;
 
     // Authorization check:
     // - Draft submissions: anyone with the slug can delete (existing behavior)
     // - Non-draft submissions: require valid uploader session for this specific submission
     if submission.status != SubmissionStatus::Draft {
-        match validate_uploader_session(&state.pool, &headers).await {
-            Some((session_submission, _)) if session_submission.id == submission.id => {
-                // Valid session for this submission - allow deletion
-            }
-            _ => {
-                return (
-                    StatusCode::UNAUTHORIZED,
-                    Json(ApiResponse::error(
-                        "Inloggen vereist om documenten te verwijderen van een ingediende inzending.",
-                    )),
-                );
-            }
-        }
+        uploader_submission(
+            &state,
+            &headers,
+            &slug,
+            ErrorKey::LoginRequiredToDeleteDocuments,
+        )
+        .await?;
     }
 
     // Get document
@@ -744,45 +2196,222 @@ pub async fn delete_document(
     .bind(doc_id)
     .bind(submission.id)
     .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::NotFound(ErrorKey::DocumentNotFound))?;
+
+    // Delete the current file and those of earlier versions
+    let mut file_paths: Vec<String> =
+        sqlx::query_scalar("SELECT file_path FROM document_versions WHERE document_id = $1")
+            .bind(doc_id)
+            .fetch_all(&state.pool)
+            .await
+            .unwrap_or_default();
+    file_paths.extend(doc.file_path);
+    file_paths.sort();
+    file_paths.dedup();
+    for file_path in &file_paths {
+        if let Err(e) = state.store.delete(file_path).await {
+            tracing::warn!("Failed to delete file {:?}: {}", file_path, e);
+        }
+    }
+
+    // Delete from database
+    let _ = sqlx::query("DELETE FROM documents WHERE id = $1")
+        .bind(doc_id)
+        .execute(&state.pool)
+        .await;
+
+    log_audit(
+        &state.pool,
+        "document_deleted",
+        "document",
+        Some(doc_id),
+        "applicant",
+        None,
+    )
     .await;
 
-    match doc {
-        Ok(Some(doc)) => {
-            // Delete file if exists
-            if let Some(ref file_path) = doc.file_path {
-                let _ = fs::remove_file(file_path).await;
-            }
+    Ok(Json(ApiResponse::success(())))
+}
 
-            // Delete from database
-            let _ = sqlx::query("DELETE FROM documents WHERE id = $1")
-                .bind(doc_id)
-                .execute(&state.pool)
-                .await;
+/// Change a document's classification as the logged-in uploader
+///
+/// Only `public` and `claude_allowed` can be swapped for each other. A move
+/// to or from `restricted` is refused like a restricted upload, so this can
+/// never be used to turn a restricted document into one that may be shared.
+#[utoipa::path(
+    put,
+    path = "/api/submissions/{slug}/documents/{doc_id}/classification",
+    tag = "documents",
+    params(("slug" = String, Path, description = "Submission slug"), ("doc_id" = Uuid, Path, description = "Document id")),
+    request_body = ChangeClassification,
+    responses(
+        (status = 200, description = "Classification changed", body = ApiResponse<DocumentResponse>),
+        (status = 400, description = "Restricted documents cannot be reclassified", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Uploader login required", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Document not found", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("uploader_session" = [])),
+)]
+pub async fn change_document_classification(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((slug, doc_id)): Path<(String, Uuid)>,
+    Json(input): Json<ChangeClassification>,
+) -> Result<Json<ApiResponse<DocumentResponse>>, AppError> {
+    validate_slug(&slug)?;
+    let submission = uploader_submission(
+        &state,
+        &headers,
+        &slug,
+        ErrorKey::LoginRequiredToChangeClassification,
+    )
+    .await?;
 
-            log_audit(
-                &state.pool,
-                "document_deleted",
-                "document",
-                Some(doc_id),
-                "applicant",
-                None,
-            )
-            .await;
+    let doc = sqlx::query_as::<_, Document>(
+        "SELECT * FROM documents WHERE id = $1 AND submission_id = $2",
+    )
+    .bind(doc_id)
+    .bind(submission.id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::NotFound(ErrorKey::DocumentNotFound))?;
 
-            (StatusCode::OK, Json(ApiResponse::success(())))
-        }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error("Document not found")),
-        ),
-        Err(e) => {
-            tracing::error!("Database error: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error("Database error")),
-            )
-        }
+    validate_classification_for_upload(doc.classification)?;
+    validate_classification_for_upload(input.classification)?;
+
+    if doc.classification == input.classification {
+        return Ok(Json(ApiResponse::success(doc.into())));
+    }
+
+    // Matching the old classification keeps a concurrent change from being
+    // overwritten unnoticed
+    let updated = sqlx::query_as::<_, Document>(
+        "UPDATE documents SET classification = $1 WHERE id = $2 AND classification = $3 RETURNING *",
+    )
+    .bind(input.classification)
+    .bind(doc_id)
+    .bind(doc.classification)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(AppError::db(ErrorKey::ChangeClassificationFailed))?
+    .ok_or_else(|| AppError::Conflict(ErrorKey::ChangeClassificationFailed.into()))?;
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, details, request_id)
+        VALUES ('classification_changed'::audit_action, 'document', $1, 'uploader', $2, $3)
+        "#,
+    )
+    .bind(doc_id)
+    .bind(serde_json::json!({
+        "old_classification": doc.classification,
+        "new_classification": updated.classification,
+    }))
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    Ok(Json(ApiResponse::success(updated.into())))
+}
+
+/// A formal law link listed in the manifest of a documents archive
+#[derive(Debug, Serialize)]
+struct FormalLawLink<'a> {
+    url: &'a str,
+    title: Option<&'a str>,
+    description: Option<&'a str>,
+}
+
+/// Manifest of a documents archive: the formal law links, which have no file
+fn links_manifest(documents: &[Document]) -> String {
+    let links: Vec<FormalLawLink> = documents
+        .iter()
+        .filter_map(|doc| {
+            Some(FormalLawLink {
+                url: doc.external_url.as_deref()?,
+                title: doc.external_title.as_deref(),
+                description: doc.description.as_deref(),
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&serde_json::json!({ "formal_laws": links })).unwrap_or_default()
+}
+
+/// Download all documents of a submission as a ZIP
+///
+/// The archive holds every uploaded file under `files/` and a `links.json`
+/// listing the formal law links. Like deleting documents, this needs an
+/// uploader session once the submission is no longer a draft.
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{slug}/documents.zip",
+    tag = "documents",
+    params(("slug" = String, Path, description = "Submission slug")),
+    responses(
+        (status = 200, description = "ZIP with the uploaded files and a manifest of formal law links", body = Vec<u8>, content_type = "application/zip"),
+        (status = 401, description = "Uploader login required for submitted submissions", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Submission not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn download_documents_zip(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+) -> Result<Response, AppError> {
+    validate_slug(&slug)?;
+
+    let submission = get_submission_by_slug(&state.pool, &slug)
+        .await
+        .ok_or(AppError::NotFound(ErrorKey::SubmissionNotFound))?;
+    if submission.status != SubmissionStatus::Draft {
+        uploader_submission(
+            &state,
+            &headers,
+            &slug,
+            ErrorKey::LoginRequiredToDownloadDocuments,
+        )
+        .await?;
     }
+
+    let documents = sqlx::query_as::<_, Document>(
+        "SELECT * FROM documents WHERE submission_id = $1 ORDER BY created_at",
+    )
+    .bind(submission.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let manifest = links_manifest(&documents);
+    let body = stream_export_zip(&state, "links.json", manifest, documents, submission.id);
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/zip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.zip\"", submission.slug),
+        )
+        .body(body)
+        .unwrap())
+}
+
+// =============================================================================
+// Public Config Endpoint
+// =============================================================================
+
+/// Get settings the frontend needs, such as default classifications
+#[utoipa::path(
+    get,
+    path = "/api/config",
+    tag = "submissions",
+    responses(
+        (status = 200, description = "Frontend settings", body = ApiResponse<PublicConfigResponse>),
+    ),
+)]
+pub async fn get_public_config(State(state): State<AppState>) -> impl IntoResponse {
+    Json(ApiResponse::success(PublicConfigResponse {
+        default_classifications: state.default_classifications.clone(),
+    }))
 }
 
 // =============================================================================
@@ -790,7 +2419,15 @@ pub async fn delete_document(
 // =============================================================================
 
 /// Get FAQ content
-pub async fn get_faq() -> impl IntoResponse {
+#[utoipa::path(
+    get,
+    path = "/api/faq",
+    tag = "submissions",
+    responses(
+        (status = 200, description = "Frequently asked questions", body = ApiResponse<Vec<FaqItem>>),
+    ),
+)]
+pub async fn get_faq(State(state): State<AppState>) -> impl IntoResponse {
     let faq_items = vec![
         FaqItem {
             question: "Levert RegelRecht kant en klare regelsets?".to_string(),
@@ -832,11 +2469,13 @@ pub async fn get_faq() -> impl IntoResponse {
         },
         FaqItem {
             question: "Hoe lang worden mijn gegevens bewaard?".to_string(),
-            answer: "Uw gegevens worden bewaard tot 12 maanden na indiening. De exacte \
+            answer: format!(
+                "Uw gegevens worden bewaard tot {} maanden na indiening. De exacte \
                 vervaldatum is zichtbaar bij het opvragen van uw inzendingsstatus. Na afloop \
                 worden de gegevens verwijderd, tenzij u toestemming geeft voor langer bewaren. \
-                Zie onze privacyverklaring voor meer details."
-                .to_string(),
+                Zie onze privacyverklaring voor meer details.",
+                state.retention_months
+            ),
         },
     ];
 
@@ -856,12 +2495,17 @@ async fn get_submission_by_slug(pool: &PgPool, slug: &str) -> Option<Submission>
         .flatten()
 }
 
-fn sanitize_filename(filename: &str) -> String {
+/// Reduce an uploaded filename to a safe basename of at most `max_len` characters
+///
+/// The name is NFC-normalized first, so a precomposed and a decomposed accent
+/// end up the same. Over-long names lose the end of their stem, never all of
+/// their extension.
+fn sanitize_filename(filename: &str, max_len: usize) -> String {
     // Extract only the basename (strip any directory components)
     let basename = filename.rsplit(['/', '\\']).next().unwrap_or(filename);
 
     let sanitized: String = basename
-        .chars()
+        .nfc()
         .map(|c| {
             if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
                 c
@@ -880,7 +2524,108 @@ fn sanitize_filename(filename: &str) -> String {
     if sanitized.is_empty() {
         "upload".to_string()
     } else {
-        sanitized.to_string()
+        truncate_filename(sanitized, max_len)
+    }
+}
+
+/// Shorten an ASCII filename to `max_len`, keeping (part of) its extension
+fn truncate_filename(name: &str, max_len: usize) -> String {
+    if name.len() <= max_len {
+        return name.to_string();
+    }
+    let Some((stem, extension)) = name.rsplit_once('.').filter(|(_, ext)| !ext.is_empty()) else {
+        return name[..max_len].to_string();
+    };
+
+    // The extension gets at most half the room, the stem the rest
+    let extension = &extension[..extension.len().min(max_len / 2)];
+    let stem = stem[..stem.len().min(max_len - extension.len() - 1)].trim_end_matches(['.', '_']);
+    let stem = if stem.is_empty() { "upload" } else { stem };
+    format!("{}.{}", stem, extension)
+}
+
+/// Directory below `upload_dir` where uploads are streamed before being stored
+pub(crate) const STAGING_DIR: &str = ".staging";
+
+/// Number of leading bytes kept in memory for the magic-byte check
+const MAGIC_HEAD_LEN: usize = 16;
+
+/// A multipart field that has been written to disk
+struct StreamedUpload {
+    /// Total number of bytes written
+    size: usize,
+    /// First `MAGIC_HEAD_LEN` bytes of the file, used for content sniffing
+    head: Vec<u8>,
+    /// Hex SHA-256 of the whole file
+    sha256: String,
+}
+
+#[derive(Debug)]
+enum StreamUploadError {
+    TooLarge,
+    Read(MultipartError),
+    Write(std::io::Error),
+}
+
+/// Stream a multipart field to `path` chunk by chunk
+///
+/// The upload is never held in memory as a whole: only the first few bytes are
+/// kept for the magic-byte check. `max_size` is enforced while reading, and on
+/// any error the partially written file is removed.
+async fn stream_field_to_file(
+    field: &mut Field<'_>,
+    path: &std::path::Path,
+    max_size: usize,
+) -> Result<StreamedUpload, StreamUploadError> {
+    let result = write_field_chunks(field, path, max_size).await;
+    if result.is_err() {
+        remove_partial_file(path).await;
+    }
+    result
+}
+
+async fn write_field_chunks(
+    field: &mut Field<'_>,
+    path: &std::path::Path,
+    max_size: usize,
+) -> Result<StreamedUpload, StreamUploadError> {
+    let file = fs::File::create(path)
+        .await
+        .map_err(StreamUploadError::Write)?;
+    let mut writer = BufWriter::new(file);
+    let mut size = 0usize;
+    let mut head = Vec::with_capacity(MAGIC_HEAD_LEN);
+    let mut hasher = Sha256::new();
+
+    while let Some(chunk) = field.chunk().await.map_err(StreamUploadError::Read)? {
+        size += chunk.len();
+        if size > max_size {
+            return Err(StreamUploadError::TooLarge);
+        }
+        if head.len() < MAGIC_HEAD_LEN {
+            let take = (MAGIC_HEAD_LEN - head.len()).min(chunk.len());
+            head.extend_from_slice(&chunk[..take]);
+        }
+        hasher.update(&chunk);
+        writer
+            .write_all(&chunk)
+            .await
+            .map_err(StreamUploadError::Write)?;
+    }
+
+    writer.flush().await.map_err(StreamUploadError::Write)?;
+    Ok(StreamedUpload {
+        size,
+        head,
+        sha256: hex::encode(hasher.finalize()),
+    })
+}
+
+async fn remove_partial_file(path: &std::path::Path) {
+    if let Err(e) = fs::remove_file(path).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove partial upload {:?}: {}", path, e);
+        }
     }
 }
 
@@ -892,17 +2637,360 @@ async fn log_audit(
     actor_type: &str,
     actor_id: Option<Uuid>,
 ) {
-    let _ = sqlx::query(
-        r#"
-        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_id)
-        VALUES ($1::audit_action, $2, $3, $4, $5)
-        "#,
+    audit::record(
+        pool,
+        AuditEntry {
+            action,
+            entity_type,
+            entity_id,
+            actor_type,
+            actor_id,
+            details: None,
+        },
     )
-    .bind(action)
-    .bind(entity_type)
-    .bind(entity_id)
-    .bind(actor_type)
-    .bind(actor_id)
-    .execute(pool)
     .await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        extract::FromRequest,
+        http::{header, Request},
+    };
+
+    async fn multipart_with_file(contents: &[u8]) -> Multipart {
+        let mut body = Vec::new();
+        body.extend_from_slice(
+            b"--BOUNDARY\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"doc.pdf\"\r\n\
+            Content-Type: application/pdf\r\n\r\n",
+        );
+        body.extend_from_slice(contents);
+        body.extend_from_slice(b"\r\n--BOUNDARY--\r\n");
+
+        let request = Request::builder()
+            .header(
+                header::CONTENT_TYPE,
+                "multipart/form-data; boundary=BOUNDARY",
+            )
+            .body(Body::from(body))
+            .unwrap();
+        Multipart::from_request(request, &()).await.unwrap()
+    }
+
+    fn temp_upload_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}_{}", Uuid::new_v4(), name))
+    }
+
+    #[tokio::test]
+    async fn test_stream_field_to_file_writes_upload() {
+        let contents = b"%PDF-1.7 streamed upload body";
+        let mut multipart = multipart_with_file(contents).await;
+        let mut field = multipart.next_field().await.unwrap().unwrap();
+        let path = temp_upload_path("ok.pdf");
+
+        let upload = stream_field_to_file(&mut field, &path, 1024).await.unwrap();
+
+        assert_eq!(upload.size, contents.len());
+        assert_eq!(upload.head, &contents[..MAGIC_HEAD_LEN]);
+        assert_eq!(upload.sha256, hex::encode(Sha256::digest(contents)));
+        assert_eq!(fs::read(&path).await.unwrap(), contents);
+        fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stream_field_to_file_over_limit_leaves_no_file() {
+        let contents = vec![b'x'; 4096];
+        let mut multipart = multipart_with_file(&contents).await;
+        let mut field = multipart.next_field().await.unwrap().unwrap();
+        let path = temp_upload_path("too_large.pdf");
+
+        let result = stream_field_to_file(&mut field, &path, 1024).await;
+
+        assert!(matches!(result, Err(StreamUploadError::TooLarge)));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_sanitize_filename_truncates_long_names_keeping_extension() {
+        let long = format!("{}.pdf", "a".repeat(3000));
+        let sanitized = sanitize_filename(&long, 150);
+        assert_eq!(sanitized.len(), 150);
+        assert!(sanitized.ends_with(".pdf"));
+
+        // An absurd extension is cut too, but never dropped entirely
+        let sanitized = sanitize_filename(&format!("nota.{}", "x".repeat(300)), 20);
+        assert_eq!(sanitized, "nota.xxxxxxxxxx");
+        assert_eq!(sanitize_filename("../../etc/passwd", 150), "passwd");
+    }
+
+    #[test]
+    fn test_sanitize_filename_normalizes_combining_accents() {
+        let precomposed = "Beleidsnotitie r\u{e9}gels.pdf";
+        let decomposed = "Beleidsnotitie re\u{301}gels.pdf";
+        assert_eq!(
+            sanitize_filename(precomposed, 150),
+            sanitize_filename(decomposed, 150)
+        );
+        assert_eq!(
+            sanitize_filename(decomposed, 150),
+            "Beleidsnotitie_r_gels.pdf"
+        );
+    }
+
+    #[test]
+    fn test_formal_law_cap() {
+        assert!(check_formal_law_cap(0, 50).is_ok());
+        assert!(check_formal_law_cap(49, 50).is_ok());
+
+        assert_eq!(
+            check_formal_law_cap(50, 50),
+            Err(ErrorKey::FormalLawLimitReached)
+        );
+        assert!(check_formal_law_cap(51, 50).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_upload_reports_existing_document() {
+        let existing_id = Uuid::new_v4();
+        let (status, Json(body)) =
+            UploadError::duplicate(existing_id).into_response::<DocumentResponse>();
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["existing_document_id"], existing_id.to_string());
+        assert_eq!(json["success"], false);
+    }
+
+    #[tokio::test]
+    async fn test_identical_uploads_hash_equal() {
+        let contents = b"%PDF-1.7 same document";
+        let mut hashes = Vec::new();
+        for name in ["first.pdf", "second.pdf"] {
+            let mut multipart = multipart_with_file(contents).await;
+            let mut field = multipart.next_field().await.unwrap().unwrap();
+            let path = temp_upload_path(name);
+            hashes.push(
+                stream_field_to_file(&mut field, &path, 1024)
+                    .await
+                    .unwrap()
+                    .sha256,
+            );
+            fs::remove_file(&path).await.unwrap();
+        }
+        assert_eq!(hashes[0], hashes[1]);
+        assert_eq!(hashes[0].len(), 64);
+    }
+
+    fn document(original_filename: Option<&str>, file_path: Option<&str>) -> Document {
+        let now = Utc::now();
+        Document {
+            id: Uuid::new_v4(),
+            submission_id: Uuid::new_v4(),
+            category: DocumentCategory::WorkInstruction,
+            classification: DocumentClassification::Public,
+            external_url: None,
+            external_title: None,
+            filename: file_path.map(String::from),
+            original_filename: original_filename.map(String::from),
+            file_path: file_path.map(String::from),
+            file_size: None,
+            mime_type: None,
+            description: None,
+            created_at: now,
+            content_sha256: None,
+            updated_at: now,
+            encrypted: false,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_documents_zip_holds_files_and_links_manifest() {
+        let root = std::env::temp_dir().join(format!("documents-zip-{}", Uuid::new_v4()));
+        let store = crate::storage::FsStore::new(&root);
+        for (key, contents) in [("sub/a.pdf", "eerste"), ("sub/b.pdf", "tweede")] {
+            store.put(key, bytes::Bytes::from(contents)).await.unwrap();
+        }
+        let documents = vec![
+            document(Some("Werkinstructie.pdf"), Some("sub/a.pdf")),
+            document(Some("Werkinstructie.pdf"), Some("sub/b.pdf")),
+            document(Some("Kwijt.pdf"), Some("sub/missing.pdf")),
+            Document {
+                category: DocumentCategory::FormalLaw,
+                external_url: Some("https://wetten.overheid.nl/BWBR0015703".to_string()),
+                external_title: Some("Participatiewet".to_string()),
+                ..document(None, None)
+            },
+        ];
+
+        let manifest = links_manifest(&documents);
+        let handle = tokio::runtime::Handle::current();
+        let data = tokio::task::spawn_blocking(move || {
+            let mut out = Vec::new();
+            crate::handlers::admin::write_export_zip(
+                &handle,
+                &store,
+                None,
+                "links.json",
+                &manifest,
+                &documents,
+                &mut out,
+            )
+            .unwrap();
+            out
+        })
+        .await
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert_eq!(names.len(), 3, "{:?}", names);
+        assert!(names.contains(&"files/Werkinstructie.pdf"));
+        assert!(names.contains(&"files/Werkinstructie (2).pdf"));
+
+        let mut links = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("links.json").unwrap(), &mut links)
+            .unwrap();
+        let links: serde_json::Value = serde_json::from_str(&links).unwrap();
+        assert_eq!(
+            links["formal_laws"],
+            serde_json::json!([{
+                "url": "https://wetten.overheid.nl/BWBR0015703",
+                "title": "Participatiewet",
+                "description": null,
+            }])
+        );
+
+        let _ = fs::remove_dir_all(&root).await;
+    }
+
+    #[test]
+    fn test_two_replaces_yield_three_versions() {
+        // Upload creates version 1, each replace adds the next one
+        let mut versions: Vec<i32> = Vec::new();
+        for _ in 0..3 {
+            versions.push(next_version_no(versions.iter().max().copied()));
+        }
+        assert_eq!(versions, vec![1, 2, 3]);
+    }
+
+    fn at(date: &str) -> DateTime<Utc> {
+        format!("{}T12:00:00Z", date).parse().unwrap()
+    }
+
+    #[test]
+    fn test_extended_retention_adds_months_up_to_cap() {
+        let created = at("2026-01-31");
+        let expiry = at("2027-01-31");
+        let now = at("2026-06-01");
+
+        assert_eq!(
+            extended_retention(created, expiry, now, 12, 36),
+            Ok(at("2028-01-31"))
+        );
+        // Capped at 36 months after creation
+        assert_eq!(
+            extended_retention(created, at("2028-06-01"), now, 12, 36),
+            Ok(at("2029-01-31"))
+        );
+        assert_eq!(
+            extended_retention(created, at("2029-01-31"), now, 12, 36),
+            Err(RetentionRejection::AtMaximum)
+        );
+    }
+
+    #[test]
+    fn test_extended_retention_rejects_expired() {
+        let created = at("2025-01-01");
+        assert_eq!(
+            extended_retention(created, at("2026-01-01"), at("2026-01-02"), 12, 36),
+            Err(RetentionRejection::Expired)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_submission_expires_after_configured_months() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        let input = CreateSubmission {
+            submitter_name: "Jan".to_string(),
+            submitter_email: None,
+            organization: "Gemeente".to_string(),
+            organization_department: None,
+        };
+        for months in [3, 12] {
+            let slug = format!("rr-20260101-{}", &Uuid::new_v4().simple().to_string()[..5]);
+            let submission = insert_submission(&pool, &slug, &input, months, None)
+                .await
+                .unwrap();
+
+            let expected = submission
+                .created_at
+                .checked_add_months(Months::new(months))
+                .unwrap();
+            // The database adds months in its session time zone; allow for a DST shift
+            let drift = (submission.retention_expiry_date - expected).num_minutes();
+            assert!(
+                drift.abs() <= 60,
+                "{} months: off by {} minutes",
+                months,
+                drift
+            );
+
+            sqlx::query("DELETE FROM submissions WHERE id = $1")
+                .bind(submission.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retention_extension_persists_expiry_and_consent() {
+        let Some(pool) = crate::db::test_pool().await else {
+            return;
+        };
+
+        let slug = format!("rr-20260101-{}", &Uuid::new_v4().simple().to_string()[..5]);
+        let before: Submission = sqlx::query_as(
+            r#"
+            INSERT INTO submissions (slug, submitter_name, organization, status)
+            VALUES ($1, 'Jan', 'Gemeente', 'submitted')
+            RETURNING *
+            "#,
+        )
+        .bind(&slug)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(before.retention_consent_at.is_none());
+
+        let returned = store_retention_extension(&pool, before.id, 12, 36)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let stored: Submission = sqlx::query_as("SELECT * FROM submissions WHERE id = $1")
+            .bind(before.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let expected = before
+            .retention_expiry_date
+            .checked_add_months(Months::new(12))
+            .unwrap();
+        assert_eq!(stored.retention_expiry_date, expected);
+        assert_eq!(returned.retention_expiry_date, expected);
+        assert!(stored.retention_consent_at.is_some());
+
+        sqlx::query("DELETE FROM submissions WHERE id = $1")
+            .bind(before.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
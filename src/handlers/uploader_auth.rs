@@ -3,18 +3,21 @@
 //! Allows uploaders to authenticate using their submission slug + email combination
 //! to add documents to their dossier after initial submission.
 
+use crate::handlers::middleware::current_request_id;
+use crate::i18n::ErrorKey;
 use crate::models::*;
+use crate::notify::notify_reference_codes;
+use crate::validation::is_valid_email;
 use axum::{
     extract::State,
     http::{header, HeaderMap, StatusCode},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use rand::RngCore;
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
-use uuid::Uuid;
 
 use super::auth::{check_rate_limit, get_client_ip, record_attempt};
 use super::AppState;
@@ -22,14 +25,25 @@ use super::AppState;
 /// Session cookie name for uploader sessions
 pub const UPLOADER_SESSION_COOKIE: &str = "rr_uploader_session";
 
-/// Session duration in hours
-const UPLOADER_SESSION_HOURS: i64 = 4;
+/// Most reference codes listed in one recovery mail, newest first
+const MAX_RECOVERED_SLUGS: i64 = 20;
 
 // =============================================================================
 // Login Endpoint
 // =============================================================================
 
 /// Uploader login - authenticate with slug + email
+#[utoipa::path(
+    post,
+    path = "/api/uploader/login",
+    tag = "uploader",
+    request_body = UploaderLoginRequest,
+    responses(
+        (status = 200, description = "Logged in; sets the uploader session cookie", body = ApiResponse<UploaderSessionResponse>),
+        (status = 401, description = "Unknown slug and email combination", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many login attempts", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+    ),
+)]
 pub async fn uploader_login(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -37,13 +51,20 @@ pub async fn uploader_login(
 ) -> impl IntoResponse {
     let client_ip = get_client_ip(&headers, &state.trusted_proxies);
 
-    // Check rate limit (10 attempts per hour per IP)
-    if !check_rate_limit(&state.pool, &client_ip, "uploader_login").await {
+    // Check rate limit per IP
+    if let Err(limited) = check_rate_limit(
+        &state,
+        &client_ip,
+        "uploader_login",
+        state.rate_limits.uploader_login,
+    )
+    .await
+    {
         return (
             StatusCode::TOO_MANY_REQUESTS,
-            [(header::SET_COOKIE, "".to_string())],
+            [limited.retry_after()],
             Json(ApiResponse::<UploaderSessionResponse>::error(
-                "Te veel inlogpogingen. Probeer het later opnieuw.",
+                ErrorKey::TooManyLoginAttempts,
             )),
         );
     }
@@ -59,9 +80,7 @@ pub async fn uploader_login(
         return (
             StatusCode::BAD_REQUEST,
             [(header::SET_COOKIE, "".to_string())],
-            Json(ApiResponse::error(
-                "Vul zowel referentiecode als e-mailadres in.",
-            )),
+            Json(ApiResponse::error(ErrorKey::MissingSlugOrEmail)),
         );
     }
 
@@ -85,9 +104,7 @@ pub async fn uploader_login(
             return (
                 StatusCode::UNAUTHORIZED,
                 [(header::SET_COOKIE, "".to_string())],
-                Json(ApiResponse::error(
-                    "Ongeldige referentiecode of e-mailadres.",
-                )),
+                Json(ApiResponse::error(ErrorKey::InvalidSlugOrEmail)),
             );
         }
     };
@@ -97,16 +114,14 @@ pub async fn uploader_login(
         return (
             StatusCode::BAD_REQUEST,
             [(header::SET_COOKIE, "".to_string())],
-            Json(ApiResponse::error(
-                "Deze inzending heeft geen e-mailadres gekoppeld.",
-            )),
+            Json(ApiResponse::error(ErrorKey::NoSubmitterEmail)),
         );
     }
 
     // Generate session token
     let token = generate_session_token();
     let token_hash = hash_token(&token);
-    let expires_at = Utc::now() + Duration::hours(UPLOADER_SESSION_HOURS);
+    let expires_at = Utc::now() + Duration::hours(state.uploader_session_hours as i64);
 
     // Get user agent for audit
     let user_agent = headers
@@ -135,39 +150,24 @@ pub async fn uploader_login(
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
             [(header::SET_COOKIE, "".to_string())],
-            Json(ApiResponse::error("Kon sessie niet aanmaken.")),
+            Json(ApiResponse::error(ErrorKey::CreateSessionFailed)),
         );
     }
 
     // Log audit event
     let _ = sqlx::query(
         r#"
-        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_ip)
-        VALUES ('uploader_login'::audit_action, 'submission', $1, 'uploader', $2)
+        INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_ip, request_id)
+        VALUES ('uploader_login'::audit_action, 'submission', $1, 'uploader', $2, $3)
         "#,
     )
     .bind(submission.id)
     .bind(&client_ip)
+    .bind(current_request_id())
     .execute(&state.pool)
     .await;
 
-    // Get documents for response
-    let documents = sqlx::query_as::<_, Document>(
-        "SELECT * FROM documents WHERE submission_id = $1 ORDER BY created_at",
-    )
-    .bind(submission.id)
-    .fetch_all(&state.pool)
-    .await
-    .unwrap_or_default();
-
-    // Build response (privacy-focused: no name/org)
-    let response = UploaderSessionResponse {
-        submission_id: submission.id,
-        slug: submission.slug,
-        status: submission.status,
-        documents: documents.into_iter().map(DocumentResponse::from).collect(),
-        session_expires_at: expires_at,
-    };
+    let response = uploader_view(&state.pool, submission, expires_at).await;
 
     // Set secure cookie
     let secure_flag = if state.is_production { "; Secure" } else { "" };
@@ -175,7 +175,7 @@ pub async fn uploader_login(
         "{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}{}",
         UPLOADER_SESSION_COOKIE,
         token,
-        UPLOADER_SESSION_HOURS * 3600,
+        state.uploader_session_hours * 3600,
         secure_flag
     );
 
@@ -186,11 +186,115 @@ pub async fn uploader_login(
     )
 }
 
+// =============================================================================
+// Reference Code Recovery
+// =============================================================================
+
+/// Email the reference codes of all submissions made with an address
+///
+/// Always answers the same way, whether or not the address has submissions,
+/// so the endpoint cannot be used to find out who submitted something.
+#[utoipa::path(
+    post,
+    path = "/api/uploader/recover",
+    tag = "uploader",
+    request_body = UploaderRecoverRequest,
+    responses(
+        (status = 200, description = "Request accepted; a mail follows if the address has submissions", body = ApiResponse<serde_json::Value>),
+        (status = 400, description = "Invalid email address", body = ApiResponse<serde_json::Value>),
+        (status = 429, description = "Too many recovery requests", body = ApiResponse<serde_json::Value>, headers(("Retry-After" = u64, description = "Seconds until the client may try again"))),
+        (status = 503, description = "Email notifications are not configured", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn uploader_recover(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(input): Json<UploaderRecoverRequest>,
+) -> Response {
+    let Some(notifier) = &state.notifier else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::<()>::error(ErrorKey::RecoveryUnavailable)),
+        )
+            .into_response();
+    };
+
+    let client_ip = get_client_ip(&headers, &state.trusted_proxies);
+    if let Err(limited) = check_rate_limit(
+        &state,
+        &client_ip,
+        "uploader_recover",
+        state.rate_limits.uploader_login,
+    )
+    .await
+    {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [limited.retry_after()],
+            Json(ApiResponse::<()>::error(ErrorKey::TooManyRequests)),
+        )
+            .into_response();
+    }
+    record_attempt(&state.pool, &client_ip, "uploader_recover").await;
+
+    let email = input.email.trim().to_lowercase();
+    if !is_valid_email(&email) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(ErrorKey::InvalidRecoveryEmail)),
+        )
+            .into_response();
+    }
+
+    let slugs: Result<Vec<String>, sqlx::Error> = sqlx::query_scalar(
+        r#"
+        SELECT slug FROM submissions
+        WHERE LOWER(submitter_email) = $1
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(&email)
+    .bind(MAX_RECOVERED_SLUGS)
+    .fetch_all(&state.pool)
+    .await;
+
+    match slugs {
+        Ok(slugs) if !slugs.is_empty() => {
+            notify_reference_codes(&state.pool, notifier, email, &slugs).await
+        }
+        Ok(_) => {}
+        // Still answer as usual; a different reply would hint at a match
+        Err(e) => tracing::error!("Failed to look up submissions for recovery: {}", e),
+    }
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, entity_type, actor_type, actor_ip, request_id)
+        VALUES ('uploader_recovery_requested'::audit_action, 'uploader', 'uploader', $1, $2)
+        "#,
+    )
+    .bind(&client_ip)
+    .bind(current_request_id())
+    .execute(&state.pool)
+    .await;
+
+    (StatusCode::OK, Json(ApiResponse::success(()))).into_response()
+}
+
 // =============================================================================
 // Logout Endpoint
 // =============================================================================
 
 /// Uploader logout - end session
+#[utoipa::path(
+    post,
+    path = "/api/uploader/logout",
+    tag = "uploader",
+    responses(
+        (status = 200, description = "Logged out", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn uploader_logout(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -221,12 +325,13 @@ pub async fn uploader_logout(
         if let Some(session) = session {
             let _ = sqlx::query(
                 r#"
-                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_ip)
-                VALUES ('uploader_logout'::audit_action, 'submission', $1, 'uploader', $2)
+                INSERT INTO audit_log (action, entity_type, entity_id, actor_type, actor_ip, request_id)
+                VALUES ('uploader_logout'::audit_action, 'submission', $1, 'uploader', $2, $3)
                 "#,
             )
             .bind(session.submission_id)
             .bind(&client_ip)
+            .bind(current_request_id())
             .execute(&state.pool)
             .await;
         }
@@ -251,38 +356,59 @@ pub async fn uploader_logout(
 // =============================================================================
 
 /// Get current uploader session info
+#[utoipa::path(
+    get,
+    path = "/api/uploader/me",
+    tag = "uploader",
+    responses(
+        (status = 200, description = "Current uploader session", body = ApiResponse<UploaderSessionResponse>),
+        (status = 401, description = "Not authenticated", body = ApiResponse<serde_json::Value>),
+    ),
+    security(("uploader_session" = [])),
+)]
 pub async fn get_current_uploader(
     State(state): State<AppState>,
     headers: HeaderMap,
 ) -> impl IntoResponse {
     match validate_uploader_session(&state.pool, &headers).await {
         Some((submission, session)) => {
-            // Get documents
-            let documents = sqlx::query_as::<_, Document>(
-                "SELECT * FROM documents WHERE submission_id = $1 ORDER BY created_at",
-            )
-            .bind(submission.id)
-            .fetch_all(&state.pool)
-            .await
-            .unwrap_or_default();
-
-            let response = UploaderSessionResponse {
-                submission_id: submission.id,
-                slug: submission.slug,
-                status: submission.status,
-                documents: documents.into_iter().map(DocumentResponse::from).collect(),
-                session_expires_at: session.expires_at,
-            };
-
+            let response = uploader_view(&state.pool, submission, session.expires_at).await;
             (StatusCode::OK, Json(ApiResponse::success(response)))
         }
         None => (
             StatusCode::UNAUTHORIZED,
-            Json(ApiResponse::error("Niet ingelogd.")),
+            Json(ApiResponse::auth_required(
+                AuthScheme::Uploader,
+                ErrorKey::NotAuthenticated,
+            )),
         ),
     }
 }
 
+/// What the uploader of `submission` sees: status and documents, no name or organization
+pub(crate) async fn uploader_view(
+    pool: &PgPool,
+    submission: Submission,
+    session_expires_at: DateTime<Utc>,
+) -> UploaderSessionResponse {
+    let documents = sqlx::query_as::<_, Document>(
+        "SELECT * FROM documents WHERE submission_id = $1 ORDER BY created_at",
+    )
+    .bind(submission.id)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    UploaderSessionResponse {
+        submission_id: submission.id,
+        slug: submission.slug,
+        status: submission.status,
+        retention_expiry_date: submission.retention_expiry_date,
+        documents: documents.into_iter().map(DocumentResponse::from).collect(),
+        session_expires_at,
+    }
+}
+
 // =============================================================================
 // Session Validation
 // =============================================================================
@@ -338,18 +464,6 @@ pub async fn validate_uploader_session(
     }
 }
 
-/// Check if an uploader session is valid for a specific submission
-pub async fn validate_uploader_session_for_submission(
-    pool: &PgPool,
-    headers: &HeaderMap,
-    submission_id: Uuid,
-) -> bool {
-    match validate_uploader_session(pool, headers).await {
-        Some((submission, _)) => submission.id == submission_id,
-        None => false,
-    }
-}
-
 // =============================================================================
 // Helper Functions
 // =============================================================================
@@ -0,0 +1,311 @@
+//! Dutch and English error messages for the API
+//!
+//! Handlers report errors as an [`ErrorKey`], which renders in Dutch by
+//! default. The `localize_errors` middleware swaps the message of an error
+//! response for the language the client asks for in `Accept-Language`.
+//! Messages built with `format!` are not in the catalog and are passed on
+//! as they are.
+
+use axum::http::{header, HeaderMap};
+
+/// Language of API messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    Nl,
+    En,
+}
+
+impl Lang {
+    /// Language code for the Content-Language header
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::Nl => "nl",
+            Lang::En => "en",
+        }
+    }
+
+    /// Preferred supported language in an Accept-Language header
+    ///
+    /// Ranges are compared on their primary subtag (`en-GB` counts as `en`);
+    /// the highest quality wins and ties go to the earlier range. Falls back
+    /// to Dutch when nothing matches.
+    pub fn from_accept_language(value: &str) -> Self {
+        let mut best: Option<(Lang, f32)> = None;
+        for range in value.split(',') {
+            let mut parts = range.split(';');
+            let tag = parts.next().unwrap_or("").trim();
+            let quality = parts
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            let primary = tag.split('-').next().unwrap_or("").to_ascii_lowercase();
+            let lang = match primary.as_str() {
+                "nl" => Lang::Nl,
+                "en" => Lang::En,
+                _ => continue,
+            };
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((lang, quality));
+            }
+        }
+        best.map(|(lang, _)| lang).unwrap_or_default()
+    }
+
+    /// Language requested by a client, Dutch when it does not say
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .map(Self::from_accept_language)
+            .unwrap_or_default()
+    }
+}
+
+macro_rules! error_keys {
+    ($($key:ident => $nl:literal, $en:literal;)*) => {
+        /// Error message shown to API clients, with its Dutch and English text
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum ErrorKey {
+            $($key,)*
+        }
+
+        impl ErrorKey {
+            pub const ALL: &'static [ErrorKey] = &[$(ErrorKey::$key,)*];
+
+            pub fn nl(self) -> &'static str {
+                match self {
+                    $(ErrorKey::$key => $nl,)*
+                }
+            }
+
+            pub fn en(self) -> &'static str {
+                match self {
+                    $(ErrorKey::$key => $en,)*
+                }
+            }
+        }
+    };
+}
+
+error_keys! {
+    // General
+    DatabaseError => "Databasefout", "Database error";
+    SubmissionNotFound => "Inzending niet gevonden", "Submission not found";
+    DocumentNotFound => "Document niet gevonden", "Document not found";
+    DocumentVersionNotFound => "Documentversie niet gevonden", "Document version not found";
+    NoFileProvided => "Geen bestand meegestuurd", "No file provided";
+    ProcessUploadFailed => "Kon de upload niet verwerken", "Failed to process upload";
+    InvalidUploadFormat => "Ongeldig uploadformaat, gebruik multipart/form-data", "Invalid upload format, use multipart/form-data";
+    ReadUploadFailed => "Kon het geüploade bestand niet lezen", "Failed to read uploaded file";
+    ReadDocumentFailed => "Kon het documentbestand niet lezen", "Failed to read document file";
+    TooManyRequests => "Te veel verzoeken. Probeer het later opnieuw.", "Too many requests. Please try again later.";
+    TooManyAttempts => "Te veel pogingen. Probeer het later opnieuw.", "Too many attempts. Please try again later.";
+
+    // Admin and uploader authentication
+    NotAuthenticated => "Niet ingelogd.", "Not authenticated";
+    AuthenticationFailed => "Authenticatie mislukt", "Authentication failed";
+    AuthenticationError => "Fout bij het inloggen", "Authentication error";
+    InvalidCsrfToken => "Ongeldig of ontbrekend CSRF-token", "Invalid or missing CSRF token";
+    TooManyLoginAttempts => "Te veel inlogpogingen. Probeer het later opnieuw.", "Too many login attempts. Please try again later.";
+    InvalidCredentials => "Ongeldige gebruikersnaam of wachtwoord", "Invalid username or password";
+    AccountLocked => "Dit account is tijdelijk geblokkeerd na te veel mislukte inlogpogingen. Probeer het later opnieuw.", "This account is temporarily locked after too many failed logins. Please try again later.";
+    CreateSessionFailed => "Kon sessie niet aanmaken.", "Failed to create session";
+    TotpRequired => "Code voor tweestapsverificatie vereist", "Two-factor authentication code required";
+    TotpInvalid => "Ongeldige code voor tweestapsverificatie", "Invalid two-factor authentication code";
+    TotpNotConfigured => "Tweestapsverificatie is niet ingesteld op deze server", "Two-factor authentication is not configured on this server";
+    TotpAlreadyEnabled => "Tweestapsverificatie is al ingeschakeld", "Two-factor authentication is already enabled";
+    StartEnrollmentFailed => "Kon het instellen van tweestapsverificatie niet starten", "Failed to start enrollment";
+    NoEnrollment => "Tweestapsverificatie wordt op dit moment niet ingesteld", "No two-factor enrollment in progress";
+    ConfirmEnrollmentFailed => "Kon tweestapsverificatie niet bevestigen", "Failed to confirm enrollment";
+    EnrollmentChanged => "Het instellen is gewijzigd, begin opnieuw", "Enrollment changed, please start again";
+    CurrentPasswordIncorrect => "Huidig wachtwoord is onjuist", "Current password is incorrect";
+    PasswordUnchanged => "Het nieuwe wachtwoord moet verschillen van het huidige", "New password must differ from the current password";
+    ChangePasswordFailed => "Kon wachtwoord niet wijzigen", "Failed to change password";
+    ListSessionsFailed => "Kon sessies niet ophalen", "Failed to list sessions";
+    SessionNotFound => "Sessie niet gevonden", "Session not found";
+    RevokeSessionFailed => "Kon sessie niet beëindigen", "Failed to revoke session";
+    RevokeSessionsFailed => "Kon sessies niet beëindigen", "Failed to revoke sessions";
+    MissingSlugOrEmail => "Vul zowel referentiecode als e-mailadres in.", "Enter both the reference code and the email address.";
+    InvalidSlugOrEmail => "Ongeldige referentiecode of e-mailadres.", "Invalid reference code or email address.";
+    NoSubmitterEmail => "Deze inzending heeft geen e-mailadres gekoppeld.", "This submission has no email address.";
+    InvalidRecoveryEmail => "Vul een geldig e-mailadres in.", "Enter a valid email address.";
+    RecoveryUnavailable => "Referentiecodes kunnen op deze server niet per e-mail worden verstuurd.", "Reference codes cannot be sent by email on this server.";
+
+    // Submissions
+    TooManySubmissions => "Te veel inzendingen. Probeer het later opnieuw.", "Too many submissions. Please try again later.";
+    CreateSubmissionFailed => "Kon inzending niet aanmaken", "Failed to create submission";
+    InvalidIdempotencyKey => "Ongeldige Idempotency-Key", "Invalid Idempotency-Key header";
+    IdempotencyKeyNeedsEmail => "Een Idempotency-Key kan alleen samen met een e-mailadres worden gebruikt", "An Idempotency-Key can only be used together with a submitter email";
+    IdempotencyKeyReused => "Deze Idempotency-Key is al gebruikt voor een andere inzending", "This Idempotency-Key was already used for a different submission";
+    IdempotencyKeyMismatch => "Deze Idempotency-Key is al gebruikt met andere gegevens", "This Idempotency-Key was already used with a different request body";
+    NotDraft => "Alleen een concept-inzending kan worden gewijzigd", "Cannot update submission that is not in draft status";
+    UpdateSubmissionFailed => "Kon inzending niet bijwerken", "Failed to update submission";
+    NotFoundOrNotDraft => "Inzending niet gevonden of geen concept", "Submission not found or not in draft status";
+    SubmitFailed => "Kon inzending niet indienen", "Failed to submit";
+    LoginRequiredToWithdraw => "Inloggen vereist om deze inzending in te trekken.", "Log in to withdraw this submission.";
+    WithdrawFailed => "Kon inzending niet intrekken", "Failed to withdraw submission";
+    NotWithdrawable => "Een inzending met deze status kan niet meer worden ingetrokken", "A submission with this status can no longer be withdrawn";
+    LoginRequiredToEditDetails => "Inloggen vereist om de gegevens van deze inzending te wijzigen.", "Log in to change the details of this submission.";
+    LoginRequiredToExtendRetention => "Inloggen vereist om de bewaartermijn te verlengen.", "Log in to extend the retention period.";
+    ConsentRequired => "Uitdrukkelijke toestemming is nodig om de bewaartermijn te verlengen", "Explicit consent is required to extend the retention period";
+    RetentionExpired => "De bewaartermijn van deze inzending is al verstreken", "The retention period of this submission has already expired";
+    RetentionAtMaximum => "Deze inzending wordt al zo lang mogelijk bewaard", "This submission is already kept for the longest allowed period";
+    ExtendRetentionFailed => "Kon bewaartermijn niet verlengen", "Failed to extend retention";
+
+    // Documents
+    LoginRequiredToAddDocuments => "Inloggen vereist om documenten toe te voegen aan een ingediende inzending.", "Log in to add documents to a submitted submission.";
+    LoginRequiredToDownloadDocuments => "Inloggen vereist om de documenten van een ingediende inzending te downloaden.", "Log in to download the documents of a submitted submission.";
+    LoginRequiredToViewHistory => "Inloggen vereist om de geschiedenis van de inzending te bekijken.", "Log in to view the history of the submission.";
+    LoginRequiredToDeleteDocuments => "Inloggen vereist om documenten te verwijderen van een ingediende inzending.", "Log in to delete documents from a submitted submission.";
+    LoginRequiredToChangeClassification => "Inloggen vereist om de classificatie van een document te wijzigen.", "Log in to change the classification of a document.";
+    DuplicateUpload => "Dit bestand is al geüpload bij deze inzending", "This file has already been uploaded to this submission";
+    DuplicateInUpload => "Dit bestand heeft dezelfde inhoud als een ander bestand in deze upload", "This file has the same content as another file in this upload";
+    TooManyFiles => "Te veel bestanden in één upload", "Too many files in one upload";
+    StoreDocumentFailed => "Kon document niet opslaan. Probeer het opnieuw.", "Failed to store document. Please try again.";
+    StorageFull => "Er is tijdelijk geen opslagruimte, probeer het later opnieuw.", "Storage space is temporarily unavailable, please try again later.";
+    StoreDocumentsFailed => "Kon documenten niet opslaan. Probeer het opnieuw.", "Failed to store documents. Please try again.";
+    ChangeClassificationFailed => "Kon classificatie niet wijzigen", "Failed to change classification";
+    ClassificationRequired => "Classificatie is verplicht voor deze documentcategorie", "Classification is required for this document category";
+    FormalLawUploadNotAllowed => "Een formele wet wordt als link toegevoegd, niet als bestand", "Formal laws are added as links, not as file uploads";
+    FormalLawNotReplaceable => "Een link naar een formele wet kan niet door een bestand worden vervangen", "Formal law links cannot be replaced with a file";
+    InvalidFilename => "Ongeldige bestandsnaam", "Invalid filename";
+    MalwareRejected => "bestand geweigerd: mogelijk schadelijk", "file rejected: possibly malicious";
+    FormalLawLimitReached => "Deze inzending bevat al het maximale aantal formele wetten", "This submission already holds the maximum number of formal laws";
+    AddFormalLawFailed => "Kon formele wet niet toevoegen", "Failed to add formal law";
+    NotWettenUrl => "De URL moet verwijzen naar een wet op wetten.overheid.nl", "URL must point to a law on wetten.overheid.nl";
+    TitleUnavailable => "Titel niet beschikbaar", "Title unavailable";
+    InvalidDownloadLink => "Ongeldige of verlopen downloadlink", "Invalid or expired download link";
+    ThumbnailsNotConfigured => "Voorbeeldweergave is niet ingesteld op deze server", "Thumbnails are not configured on this server";
+    ThumbnailPdfOnly => "Voorbeeldweergave is alleen beschikbaar voor pdf-bestanden", "Thumbnails are only available for PDF files";
+    ThumbnailFailed => "Kon geen voorbeeldweergave maken", "Failed to render thumbnail";
+
+    // Calendar
+    MeetingAlreadyBooked => "Voor deze inzending is al een afspraak gepland", "This submission already has a meeting booked";
+    SlotUnavailable => "Tijdslot niet beschikbaar of al geboekt", "Slot not available or has already been booked";
+    SlotAlreadyBookedBySubmission => "Dit tijdslot is al geboekt voor deze inzending", "This slot is already booked for this submission";
+    SlotInPast => "Tijdslot ligt in het verleden", "Slot lies in the past";
+    SlotNotFound => "Tijdslot niet gevonden", "Slot not found";
+    SlotCapacityOutOfRange => "De capaciteit van het tijdslot ligt buiten het toegestane bereik", "Slot capacity is outside the allowed range";
+    BookSlotFailed => "Kon tijdslot niet boeken", "Failed to book slot";
+    NoBooking => "Geen afspraak gevonden voor deze inzending", "No booking found for this submission";
+    CancelBookingFailed => "Kon afspraak niet annuleren", "Failed to cancel booking";
+    RescheduleFailed => "Kon afspraak niet verzetten", "Failed to reschedule booking";
+    EndBeforeStart => "Eindtijd moet na de begintijd liggen", "End time must be after start time";
+    CreateSlotFailed => "Kon tijdslot niet aanmaken", "Failed to create slot";
+    CreateSlotsFailed => "Kon tijdsloten niet aanmaken", "Failed to create slots";
+    SlotBooked => "Een geboekt tijdslot kan niet worden verwijderd. Annuleer eerst de afspraak.", "Cannot delete a booked slot. Cancel the booking first.";
+    UnsupportedImportFile => "Onbekend importbestand. Upload een .ics- of .csv-bestand.", "Unsupported import file. Upload an .ics or .csv file.";
+    ImportTooLarge => "Importbestand te groot. De maximale grootte is 1 MB.", "Import file too large. Maximum size is 1MB.";
+    ImportNotUtf8 => "Importbestand moet UTF-8-gecodeerd zijn", "Import file must be UTF-8 encoded";
+    NoSlotsInImport => "Geen tijdsloten gevonden in importbestand", "No slots found in import file";
+    ImportSlotsFailed => "Kon tijdsloten niet importeren", "Failed to import slots";
+
+    // Admin review
+    InvalidCursor => "Ongeldige cursor", "Invalid cursor";
+    InvalidTimezone => "Onbekende tijdzone", "Unknown time zone";
+    CursorWithFullTextSearch => "Paginering met een cursor kan niet samen met zoeken in tekst", "Cursor pagination is not available for full-text search";
+    LoadSubmissionsFailed => "Kon inzendingen niet laden", "Failed to load submissions";
+    LoadCommentsFailed => "Kon opmerkingen niet laden", "Failed to load comments";
+    AddCommentFailed => "Kon opmerking niet toevoegen", "Failed to add comment";
+    CommentRequired => "Schrijf eerst een opmerking", "Write a comment first";
+    CommentTooLong => "De opmerking is te lang", "Comment is too long";
+    LoadSubmissionStatsFailed => "Kon overzicht van de inzending niet laden", "Failed to load submission statistics";
+    StatusChangedConcurrently => "De status van de inzending is intussen gewijzigd, laad de pagina opnieuw", "Submission status was changed concurrently, please reload";
+    UpdateStatusFailed => "Kon status niet bijwerken", "Failed to update status";
+    ChangeRequestMessageRequired => "Beschrijf welke aanvulling nodig is", "Describe the changes you need";
+    ChangeRequestMessageTooLong => "Het verzoek om aanvulling is te lang", "Change request message is too long";
+    InvalidStatusTransition => "Deze statuswijziging is niet toegestaan", "This status change is not allowed";
+    BulkSelectionSize => "Het aantal geselecteerde inzendingen ligt buiten het toegestane bereik", "The number of selected submissions is outside the allowed range";
+    UpdateLegalHoldFailed => "Kon juridische bewaarplicht niet bijwerken", "Failed to update legal hold";
+    RotateSlugFailed => "Kon geen nieuwe referentiecode toekennen", "Failed to assign a new reference code";
+    NotForwardable => "Inzending niet gevonden of niet door te sturen in deze status", "Submission not found or not in a forwardable status";
+    ForwardFailed => "Kon inzending niet doorsturen", "Failed to forward submission";
+    ShareLinkExpiryOutOfRange => "De geldigheidsduur van de gedeelde link ligt buiten het toegestane bereik", "The lifetime of the shared link is outside the allowed range";
+    DeleteSubmissionFailed => "Kon inzending niet verwijderen", "Failed to delete submission";
+    LoadAuditLogFailed => "Kon auditlog niet laden", "Failed to load audit log";
+    LoadHistoryFailed => "Kon geschiedenis niet laden", "Failed to load history";
+    ExportFailed => "Kon inzendingen niet exporteren", "Failed to export submissions";
+
+    // Input validation
+    InvalidEmail => "Ongeldig e-mailadres", "Invalid email format";
+    InvalidUrl => "Ongeldige URL", "Invalid URL format";
+    InvalidSlug => "Ongeldige referentiecode (alleen kleine letters, cijfers en koppeltekens)", "Invalid slug format (must be lowercase alphanumeric with hyphens)";
+    RestrictedDocument => "Documenten met classificatie 'restricted' kunnen niet worden geüpload", "Restricted documents cannot be uploaded";
+    WeakPassword => "Het wachtwoord moet minstens drie van deze soorten tekens bevatten: kleine letters, hoofdletters, cijfers, symbolen", "Password must contain at least three of: lowercase letters, uppercase letters, digits, symbols";
+}
+
+impl ErrorKey {
+    pub fn text(self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::Nl => self.nl(),
+            Lang::En => self.en(),
+        }
+    }
+
+    /// Key of a message in either language
+    pub fn from_message(message: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|key| key.nl() == message || key.en() == message)
+    }
+}
+
+/// Messages default to Dutch; the middleware translates on request
+impl From<ErrorKey> for String {
+    fn from(key: ErrorKey) -> Self {
+        key.nl().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_error_key_renders_per_language() {
+        let key = ErrorKey::SubmissionNotFound;
+        assert_eq!(key.text(Lang::Nl), "Inzending niet gevonden");
+        assert_eq!(key.text(Lang::En), "Submission not found");
+        assert_eq!(String::from(key), "Inzending niet gevonden");
+
+        assert_eq!(
+            ErrorKey::from_message("Submission not found"),
+            Some(ErrorKey::SubmissionNotFound)
+        );
+        assert_eq!(
+            ErrorKey::from_message("Inzending niet gevonden"),
+            Some(ErrorKey::SubmissionNotFound)
+        );
+        assert_eq!(ErrorKey::from_message("Iets anders"), None);
+    }
+
+    #[test]
+    fn test_messages_are_unique_and_translated() {
+        let mut seen = HashSet::new();
+        for key in ErrorKey::ALL {
+            assert_ne!(key.nl(), key.en(), "{:?} is not translated", key);
+            assert!(seen.insert(key.nl()), "duplicate message {:?}", key.nl());
+            assert!(seen.insert(key.en()), "duplicate message {:?}", key.en());
+        }
+    }
+
+    #[test]
+    fn test_accept_language_picks_supported_language() {
+        assert_eq!(Lang::from_accept_language("en"), Lang::En);
+        assert_eq!(Lang::from_accept_language("en-GB,en;q=0.9"), Lang::En);
+        assert_eq!(
+            Lang::from_accept_language("nl-NL,nl;q=0.9,en;q=0.8"),
+            Lang::Nl
+        );
+        assert_eq!(Lang::from_accept_language("de,en;q=0.5,nl;q=0.7"), Lang::Nl);
+        assert_eq!(Lang::from_accept_language("fr-FR,de"), Lang::Nl);
+        assert_eq!(Lang::from_accept_language("en;q=0,fr"), Lang::Nl);
+        assert_eq!(Lang::from_accept_language("*"), Lang::Nl);
+        assert_eq!(Lang::from_accept_language(""), Lang::Nl);
+        assert_eq!(Lang::from_headers(&HeaderMap::new()), Lang::Nl);
+    }
+}
@@ -0,0 +1,322 @@
+//! Persisted queue for outbound emails and webhooks
+//!
+//! Handlers enqueue a job in `outbound_jobs` instead of sending from a spawned
+//! task, so a delivery survives a restart. A worker polls for due jobs and
+//! claims them with `FOR UPDATE SKIP LOCKED`, so several instances never pick
+//! up the same job. A claimed job is leased: should the instance die while
+//! sending, the job becomes due again once the lease runs out. Failed
+//! attempts are retried with exponential backoff until `MAX_ATTEMPTS`, after
+//! which the job is marked failed. Finished jobs hold addresses and message
+//! bodies, so the periodic cleanup removes them after `KEEP_FINISHED_DAYS`.
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::notify::Notifier;
+use crate::webhooks::{WebhookEvent, WebhookSender};
+
+/// How often the worker looks for due jobs
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Jobs claimed per poll
+const BATCH_SIZE: i64 = 20;
+
+/// Attempts before a job is marked failed
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// Pause after the first failed attempt, doubling with every further one
+const BASE_BACKOFF: Duration = Duration::from_secs(30);
+const MAX_BACKOFF: Duration = Duration::from_secs(3600);
+
+/// How long a claimed job is left to its worker before it is due again
+const LEASE: Duration = Duration::from_secs(300);
+
+/// Days done and failed jobs are kept before the cleanup removes them
+pub const KEEP_FINISHED_DAYS: i32 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "outbound_job_kind", rename_all = "snake_case")]
+pub enum JobKind {
+    Email,
+    Webhook,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "outbound_job_status", rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Done,
+    Failed,
+}
+
+/// A delivery waiting in the queue, stored as the job's payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Job {
+    Email {
+        to: String,
+        subject: String,
+        body: String,
+    },
+    /// `body` is the JSON text that is signed and posted as is
+    Webhook { event: WebhookEvent, body: String },
+}
+
+impl Job {
+    fn kind(&self) -> JobKind {
+        match self {
+            Job::Email { .. } => JobKind::Email,
+            Job::Webhook { .. } => JobKind::Webhook,
+        }
+    }
+}
+
+/// Add a job to the queue; the worker picks it up on its next poll
+pub async fn enqueue(pool: &PgPool, job: &Job) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar("INSERT INTO outbound_jobs (kind, payload) VALUES ($1, $2) RETURNING id")
+        .bind(job.kind())
+        .bind(sqlx::types::Json(job))
+        .fetch_one(pool)
+        .await
+}
+
+/// Sends claimed jobs through the configured mail server and webhook
+///
+/// A job for a channel that is not configured (any more) fails like any
+/// other delivery and is given up after its attempts.
+pub struct JobRunner {
+    pub notifier: Option<Arc<Notifier>>,
+    pub webhooks: Option<Arc<WebhookSender>>,
+}
+
+impl JobRunner {
+    async fn run(&self, job: Job) -> Result<(), String> {
+        match job {
+            Job::Email { to, subject, body } => {
+                let notifier = self
+                    .notifier
+                    .as_ref()
+                    .ok_or("email notifications are not configured")?;
+                notifier
+                    .send(&to, subject, body)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            Job::Webhook { body, .. } => {
+                let sender = self.webhooks.as_ref().ok_or("no webhook is configured")?;
+                sender.deliver(body.into_bytes()).await
+            }
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ClaimedJob {
+    id: Uuid,
+    kind: JobKind,
+    payload: serde_json::Value,
+    attempts: i32,
+}
+
+/// Claim the jobs that are due and run them, returning how many were run
+pub async fn run_due(pool: &PgPool, runner: &JobRunner) -> Result<usize, sqlx::Error> {
+    let claimed: Vec<ClaimedJob> = sqlx::query_as(
+        r#"
+        UPDATE outbound_jobs
+        SET attempts = attempts + 1,
+            next_attempt_at = NOW() + make_interval(secs => $2),
+            updated_at = NOW()
+        WHERE id IN (
+            SELECT id FROM outbound_jobs
+            WHERE status = 'pending' AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, kind, payload, attempts
+        "#,
+    )
+    .bind(BATCH_SIZE)
+    .bind(LEASE.as_secs_f64())
+    .fetch_all(pool)
+    .await?;
+
+    let count = claimed.len();
+    for job in claimed {
+        let result = match serde_json::from_value::<Job>(job.payload) {
+            Ok(payload) => runner.run(payload).await,
+            // Retrying cannot fix a payload this build does not understand
+            Err(e) => {
+                mark_failed(pool, job.id, &format!("unreadable payload: {}", e)).await?;
+                continue;
+            }
+        };
+        match result {
+            Ok(()) => {
+                sqlx::query(
+                    "UPDATE outbound_jobs SET status = $2, last_error = NULL, updated_at = NOW() WHERE id = $1",
+                )
+                .bind(job.id)
+                .bind(JobStatus::Done)
+                .execute(pool)
+                .await?;
+                tracing::debug!("Delivered {:?} job {}", job.kind, job.id);
+            }
+            Err(e) if job.attempts >= MAX_ATTEMPTS => {
+                tracing::warn!(
+                    "Giving up on {:?} job {} after {} attempts: {}",
+                    job.kind,
+                    job.id,
+                    job.attempts,
+                    e
+                );
+                mark_failed(pool, job.id, &e).await?;
+            }
+            Err(e) => {
+                tracing::info!(
+                    "{:?} job {} failed (attempt {}): {}",
+                    job.kind,
+                    job.id,
+                    job.attempts,
+                    e
+                );
+                sqlx::query(
+                    r#"
+                    UPDATE outbound_jobs
+                    SET next_attempt_at = NOW() + make_interval(secs => $2),
+                        last_error = $3,
+                        updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(job.id)
+                .bind(backoff(job.attempts).as_secs_f64())
+                .bind(&e)
+                .execute(pool)
+                .await?;
+            }
+        }
+    }
+    Ok(count)
+}
+
+async fn mark_failed(pool: &PgPool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE outbound_jobs SET status = $2, last_error = $3, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(id)
+    .bind(JobStatus::Failed)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Remove done and failed jobs older than `KEEP_FINISHED_DAYS`
+pub async fn prune_finished(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM outbound_jobs
+        WHERE status <> 'pending' AND updated_at < NOW() - make_interval(days => $1)
+        "#,
+    )
+    .bind(KEEP_FINISHED_DAYS)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Pause before the next attempt after `attempts` failed ones
+fn backoff(attempts: i32) -> Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    BASE_BACKOFF.saturating_mul(1 << doublings).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        assert_eq!(backoff(1), Duration::from_secs(30));
+        assert_eq!(backoff(2), Duration::from_secs(60));
+        assert_eq!(backoff(4), Duration::from_secs(240));
+        assert_eq!(backoff(7), Duration::from_secs(1920));
+        assert_eq!(backoff(8), MAX_BACKOFF);
+        assert_eq!(backoff(100), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_failing_job_is_retried_then_marked_failed() {
+        let Some(pool) = db::test_pool().await else {
+            return;
+        };
+        // Without a mail server every email job fails
+        let runner = JobRunner {
+            notifier: None,
+            webhooks: None,
+        };
+        let id = enqueue(
+            &pool,
+            &Job::Email {
+                to: "jan@example.org".to_string(),
+                subject: "Test".to_string(),
+                body: "Test".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        let state = || async {
+            sqlx::query_as::<_, (JobStatus, i32, Option<String>, bool)>(
+                "SELECT status, attempts, last_error, next_attempt_at > NOW() FROM outbound_jobs WHERE id = $1",
+            )
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+        };
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            run_due(&pool, &runner).await.unwrap();
+            let (status, attempts, last_error, rescheduled) = state().await;
+            assert_eq!(attempts, attempt);
+            assert_eq!(
+                last_error.as_deref(),
+                Some("email notifications are not configured")
+            );
+            if attempt < MAX_ATTEMPTS {
+                assert_eq!(status, JobStatus::Pending);
+                assert!(rescheduled, "attempt {} was not backed off", attempt);
+                // Not due yet, so another poll leaves it alone
+                run_due(&pool, &runner).await.unwrap();
+                assert_eq!(state().await.1, attempt);
+                sqlx::query("UPDATE outbound_jobs SET next_attempt_at = NOW() WHERE id = $1")
+                    .bind(id)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            } else {
+                assert_eq!(status, JobStatus::Failed);
+            }
+        }
+
+        // A failed job is not picked up again
+        sqlx::query("UPDATE outbound_jobs SET next_attempt_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        run_due(&pool, &runner).await.unwrap();
+        assert_eq!(state().await.1, MAX_ATTEMPTS);
+
+        sqlx::query("DELETE FROM outbound_jobs WHERE id = $1")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
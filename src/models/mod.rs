@@ -1,15 +1,16 @@
 //! Data models for the application
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 // =============================================================================
 // Enums
 // =============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "submission_status", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum SubmissionStatus {
@@ -20,9 +21,30 @@ pub enum SubmissionStatus {
     Rejected,
     Forwarded,
     Completed,
+    /// Retracted by the uploader before it was forwarded
+    Withdrawn,
+    /// Sent back to the uploader to add or correct documents
+    ChangesRequested,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+impl SubmissionStatus {
+    /// Dutch name shown to applicants
+    pub fn label_nl(&self) -> &'static str {
+        match self {
+            Self::Draft => "concept",
+            Self::Submitted => "ingediend",
+            Self::UnderReview => "in behandeling",
+            Self::Approved => "goedgekeurd",
+            Self::Rejected => "afgewezen",
+            Self::Forwarded => "doorgestuurd",
+            Self::Completed => "afgerond",
+            Self::Withdrawn => "ingetrokken",
+            Self::ChangesRequested => "wijzigingen gevraagd",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "document_category", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum DocumentCategory {
@@ -32,7 +54,7 @@ pub enum DocumentCategory {
     WorkInstruction,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "document_classification", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum DocumentClassification {
@@ -41,11 +63,38 @@ pub enum DocumentClassification {
     Restricted,
 }
 
+impl std::str::FromStr for DocumentCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "formal_law" => Ok(Self::FormalLaw),
+            "circular" => Ok(Self::Circular),
+            "implementation_policy" => Ok(Self::ImplementationPolicy),
+            "work_instruction" => Ok(Self::WorkInstruction),
+            other => Err(format!("unknown document category '{}'", other)),
+        }
+    }
+}
+
+impl std::str::FromStr for DocumentClassification {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "public" => Ok(Self::Public),
+            "claude_allowed" => Ok(Self::ClaudeAllowed),
+            "restricted" => Ok(Self::Restricted),
+            other => Err(format!("unknown document classification '{}'", other)),
+        }
+    }
+}
+
 // =============================================================================
 // Submission
 // =============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Submission {
     pub id: Uuid,
     pub slug: String,
@@ -59,9 +108,16 @@ pub struct Submission {
     pub updated_at: DateTime<Utc>,
     pub submitted_at: Option<DateTime<Utc>>,
     pub retention_expiry_date: DateTime<Utc>,
+    /// When the uploader last agreed to extend `retention_expiry_date`
+    pub retention_consent_at: Option<DateTime<Utc>>,
+    /// Exempts the submission from draft cleanup and the retention purge
+    pub legal_hold: bool,
+    /// Reviewer notes; only ever sent to admins, via `AdminSubmissionResponse`
+    #[serde(skip_serializing)]
+    pub admin_notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateSubmission {
     pub submitter_name: String,
     pub submitter_email: Option<String>,
@@ -69,7 +125,7 @@ pub struct CreateSubmission {
     pub organization_department: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateSubmission {
     pub submitter_name: Option<String>,
     pub submitter_email: Option<String>,
@@ -78,7 +134,7 @@ pub struct UpdateSubmission {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct SubmissionResponse {
     pub id: Uuid,
     pub slug: String,
@@ -92,9 +148,81 @@ pub struct SubmissionResponse {
     pub updated_at: DateTime<Utc>,
     pub submitted_at: Option<DateTime<Utc>>,
     pub retention_expiry_date: DateTime<Utc>,
+    pub retention_consent_at: Option<DateTime<Utc>>,
+    pub legal_hold: bool,
     pub documents: Vec<DocumentResponse>,
 }
 
+impl SubmissionResponse {
+    pub fn new(sub: Submission, documents: Vec<DocumentResponse>) -> Self {
+        Self {
+            id: sub.id,
+            slug: sub.slug,
+            submitter_name: sub.submitter_name,
+            submitter_email: sub.submitter_email,
+            organization: sub.organization,
+            organization_department: sub.organization_department,
+            status: sub.status,
+            notes: sub.notes,
+            created_at: sub.created_at,
+            updated_at: sub.updated_at,
+            submitted_at: sub.submitted_at,
+            retention_expiry_date: sub.retention_expiry_date,
+            retention_consent_at: sub.retention_consent_at,
+            legal_hold: sub.legal_hold,
+            documents,
+        }
+    }
+}
+
+/// What anyone holding the reference code may see of a submission
+///
+/// Deliberately leaves out the submitter, their organisation and the
+/// documents, so a guessed or leaked code reveals no more than the status.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SubmissionStatusResponse {
+    pub status: SubmissionStatus,
+    pub created_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub retention_expiry_date: DateTime<Utc>,
+    pub has_meeting_booked: bool,
+}
+
+/// Submission as reviewers see it, with their internal notes
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminSubmissionResponse {
+    #[serde(flatten)]
+    pub submission: SubmissionResponse,
+    /// Notes written by reviewers; never shown to the applicant
+    pub admin_notes: Option<String>,
+    /// Reviewer comments on the submission; only filled in the submission list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_count: Option<i64>,
+}
+
+impl AdminSubmissionResponse {
+    pub fn new(mut sub: Submission, documents: Vec<DocumentResponse>) -> Self {
+        let admin_notes = sub.admin_notes.take();
+        Self {
+            submission: SubmissionResponse::new(sub, documents),
+            admin_notes,
+            comment_count: None,
+        }
+    }
+}
+
+/// A reviewer's comment in the thread of a submission; never shown to the applicant
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SubmissionComment {
+    pub id: Uuid,
+    /// None once the author's account has been removed
+    pub author_id: Option<Uuid>,
+    /// Display name of the author, or their username when they have none
+    pub author_name: Option<String>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
 // =============================================================================
 // Document
 // =============================================================================
@@ -114,16 +242,38 @@ pub struct Document {
     pub mime_type: Option<String>,
     pub description: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Hex SHA-256 of the stored file; `None` for formal law links
+    pub content_sha256: Option<String>,
+    /// When the file was last replaced; equals `created_at` until then
+    pub updated_at: DateTime<Utc>,
+    /// Whether the stored file is encrypted with FILE_ENCRYPTION_KEY
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateFormalLaw {
     pub external_url: String,
     pub external_title: Option<String>,
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// New classification for an existing document
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChangeClassification {
+    pub classification: DocumentClassification,
+}
+
+/// Suggested `external_title` for a formal law link
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FormalLawLookupResponse {
+    #[schema(example = "BWBR0015703")]
+    pub bwb_id: String,
+    #[schema(example = "Participatiewet")]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct DocumentResponse {
     pub id: Uuid,
     pub category: DocumentCategory,
@@ -135,6 +285,8 @@ pub struct DocumentResponse {
     pub mime_type: Option<String>,
     pub description: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub content_sha256: Option<String>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl From<Document> for DocumentResponse {
@@ -150,6 +302,47 @@ impl From<Document> for DocumentResponse {
             mime_type: doc.mime_type,
             description: doc.description,
             created_at: doc.created_at,
+            content_sha256: doc.content_sha256,
+            updated_at: doc.updated_at,
+        }
+    }
+}
+
+/// A file stored for a document at some point; the newest is the current file
+#[derive(Debug, Clone, FromRow)]
+#[allow(dead_code)]
+pub struct DocumentVersion {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub version_no: i32,
+    pub file_path: String,
+    pub file_size: Option<i64>,
+    pub mime_type: Option<String>,
+    pub original_filename: Option<String>,
+    pub content_sha256: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DocumentVersionResponse {
+    pub version_no: i32,
+    pub filename: Option<String>,
+    pub file_size: Option<i64>,
+    pub mime_type: Option<String>,
+    pub content_sha256: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DocumentVersion> for DocumentVersionResponse {
+    fn from(version: DocumentVersion) -> Self {
+        Self {
+            version_no: version.version_no,
+            filename: version.original_filename,
+            file_size: version.file_size,
+            mime_type: version.mime_type,
+            content_sha256: version.content_sha256,
+            created_at: version.created_at,
         }
     }
 }
@@ -169,9 +362,17 @@ pub struct AdminUser {
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub last_login_at: Option<DateTime<Utc>>,
+    /// Encrypted TOTP secret; login requires a code when set
+    pub totp_secret: Option<String>,
+    /// Encrypted secret awaiting confirmation during enrollment
+    pub totp_pending_secret: Option<String>,
+    /// Consecutive failed logins since the last success or lockout
+    pub failed_login_attempts: i32,
+    /// Logins are refused until this time
+    pub locked_until: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct AdminUserResponse {
     pub id: Uuid,
     pub username: String,
@@ -179,6 +380,11 @@ pub struct AdminUserResponse {
     pub display_name: Option<String>,
     pub is_active: bool,
     pub last_login_at: Option<DateTime<Utc>>,
+    pub totp_enabled: bool,
+    /// Token to send as `X-CSRF-Token` on state-changing admin requests;
+    /// only included by login and `/api/admin/me`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csrf_token: Option<String>,
 }
 
 impl From<AdminUser> for AdminUserResponse {
@@ -190,14 +396,40 @@ impl From<AdminUser> for AdminUserResponse {
             display_name: user.display_name,
             is_active: user.is_active,
             last_login_at: user.last_login_at,
+            totp_enabled: user.totp_secret.is_some(),
+            csrf_token: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
+    /// Authenticator code, required for accounts with two-factor authentication
+    #[serde(default)]
+    pub totp_code: Option<String>,
+}
+
+/// Change the password of the logged-in admin
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Confirm two-factor enrollment with a code from the authenticator app
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct TotpConfirmRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TotpEnrollmentResponse {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// otpauth:// URI to render as a QR code
+    pub otpauth_uri: String,
 }
 
 // =============================================================================
@@ -214,6 +446,46 @@ pub struct AdminSession {
     pub created_at: DateTime<Utc>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    /// Hash of the session's CSRF token; `None` for sessions from before tokens
+    pub csrf_token_hash: Option<String>,
+}
+
+/// Longest user agent shown in the session list
+const SESSION_USER_AGENT_MAX_CHARS: usize = 120;
+
+/// An admin session as listed to its owner; the token hash is never exposed
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminSessionResponse {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// The session making this request
+    pub current: bool,
+}
+
+impl AdminSessionResponse {
+    pub fn new(session: AdminSession, current_token_hash: &str) -> Self {
+        Self {
+            id: session.id,
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent.map(shorten_user_agent),
+            current: session.token_hash == current_token_hash,
+        }
+    }
+}
+
+fn shorten_user_agent(ua: String) -> String {
+    if ua.chars().count() > SESSION_USER_AGENT_MAX_CHARS {
+        let mut short: String = ua.chars().take(SESSION_USER_AGENT_MAX_CHARS).collect();
+        short.push('…');
+        short
+    } else {
+        ua
+    }
 }
 
 // =============================================================================
@@ -225,52 +497,304 @@ pub struct CalendarSlot {
     pub id: Uuid,
     pub slot_start: DateTime<Utc>,
     pub slot_end: DateTime<Utc>,
-    pub is_available: bool,
-    pub booked_by_submission: Option<Uuid>,
+    /// How many submissions can book the slot
+    pub capacity: i32,
     pub created_by: Option<Uuid>,
     pub notes: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Bookings on the slot, counted from `slot_bookings` by the query;
+    /// left at 0 for a slot that was just inserted
+    #[sqlx(default)]
+    pub booked: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl CalendarSlot {
+    pub fn remaining_capacity(&self) -> i64 {
+        (i64::from(self.capacity) - self.booked).max(0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateCalendarSlot {
     pub slot_start: DateTime<Utc>,
     pub slot_end: DateTime<Utc>,
     pub notes: Option<String>,
+    /// How many submissions can book the slot; 1 when omitted
+    pub capacity: Option<i32>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct CalendarSlotResponse {
     pub id: Uuid,
     pub slot_start: DateTime<Utc>,
     pub slot_end: DateTime<Utc>,
     pub is_available: bool,
-    pub booked_by_submission: Option<Uuid>,
+    /// Bookings the slot can still take
+    pub remaining_capacity: i64,
     pub notes: Option<String>,
 }
 
 impl From<CalendarSlot> for CalendarSlotResponse {
     fn from(slot: CalendarSlot) -> Self {
+        let remaining_capacity = slot.remaining_capacity();
         Self {
             id: slot.id,
             slot_start: slot.slot_start,
             slot_end: slot.slot_end,
-            is_available: slot.is_available,
-            booked_by_submission: slot.booked_by_submission,
+            is_available: remaining_capacity > 0,
+            remaining_capacity,
             notes: slot.notes,
         }
     }
 }
 
+/// Bookable slot with its times in the requested time zone as well
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LocalSlotResponse {
+    pub id: Uuid,
+    pub slot_start: DateTime<Utc>,
+    pub slot_end: DateTime<Utc>,
+    /// `slot_start` in the requested time zone, with its offset
+    pub local_start: DateTime<FixedOffset>,
+    pub local_end: DateTime<FixedOffset>,
+    pub is_available: bool,
+    pub remaining_capacity: i64,
+    pub notes: Option<String>,
+}
+
+/// Bookable slots starting on one local date
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AvailableSlotDay {
+    pub date: NaiveDate,
+    pub slots: Vec<LocalSlotResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AvailableSlotsResponse {
+    /// IANA name of the time zone the dates and local times are in
+    pub timezone: String,
+    pub days: Vec<AvailableSlotDay>,
+    /// Pass as `from` to get the slots after this window
+    pub next_from: DateTime<Utc>,
+}
+
+/// Calendar slot as admins see it, with the submissions that booked it
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminCalendarSlotResponse {
+    pub id: Uuid,
+    pub slot_start: DateTime<Utc>,
+    pub slot_end: DateTime<Utc>,
+    pub capacity: i32,
+    pub is_available: bool,
+    pub remaining_capacity: i64,
+    pub notes: Option<String>,
+    /// Empty while the slot is free
+    pub bookings: Vec<SlotBooking>,
+}
+
+/// Submission holding a booking on a slot
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SlotBooking {
+    pub submission_id: Uuid,
+    pub submission_slug: String,
+    pub submitter_name: String,
+    pub organization: String,
+}
+
+// =============================================================================
+// Audit Log
+// =============================================================================
+
+/// Row from `audit_log`; select `action::text AS action` to read the enum
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<Uuid>,
+    pub actor_type: String,
+    pub actor_id: Option<Uuid>,
+    pub actor_ip: Option<String>,
+    pub details: Option<serde_json::Value>,
+    pub request_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub id: Uuid,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<Uuid>,
+    pub actor_type: String,
+    pub actor_id: Option<Uuid>,
+    pub actor_ip: Option<String>,
+    pub details: Option<serde_json::Value>,
+    /// X-Request-Id of the request that caused the entry
+    pub request_id: Option<Uuid>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<AuditLogEntry> for AuditLogResponse {
+    fn from(entry: AuditLogEntry) -> Self {
+        Self {
+            id: entry.id,
+            action: entry.action,
+            entity_type: entry.entity_type,
+            entity_id: entry.entity_id,
+            actor_type: entry.actor_type,
+            actor_id: entry.actor_id,
+            actor_ip: entry.actor_ip,
+            details: entry.details,
+            request_id: entry.request_id,
+            timestamp: entry.created_at,
+        }
+    }
+}
+
+/// Audit actions shown to the uploader in the submission history
+pub const HISTORY_ACTIONS: &[&str] = &[
+    "submission_created",
+    "submission_updated",
+    "submission_submitted",
+    "submission_status_changed",
+    "changes_requested",
+    "submission_withdrawn",
+    "retention_extended",
+    "slug_rotated",
+    "uploader_login",
+    "uploader_view_accessed",
+    "document_uploaded",
+    "document_replaced",
+    "classification_changed",
+    "document_downloaded",
+    "slot_booked",
+    "slot_cancelled",
+    "slot_rescheduled",
+];
+
+/// One event in the history an uploader sees of their submission
+///
+/// Leaves out who did it beyond their role, and any details such as
+/// reviewer notes or IP addresses.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SubmissionHistoryEntry {
+    pub action: String,
+    /// Dutch description of the event
+    pub label: String,
+    /// `applicant`, `reviewer` or `system`
+    pub actor: &'static str,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl SubmissionHistoryEntry {
+    /// The uploader's view of an audit entry, if it is one they get to see
+    pub fn from_audit(entry: &AuditLogEntry) -> Option<Self> {
+        let label = match entry.action.as_str() {
+            "submission_created" => "Inzending aangemaakt".to_string(),
+            "submission_updated" => "Gegevens gewijzigd".to_string(),
+            "submission_submitted" => "Inzending ingediend".to_string(),
+            "submission_status_changed" => {
+                let status = entry
+                    .details
+                    .as_ref()
+                    .and_then(|d| d.get("new_status"))
+                    .and_then(|s| serde_json::from_value::<SubmissionStatus>(s.clone()).ok());
+                match status {
+                    Some(status) => format!("Status gewijzigd naar '{}'", status.label_nl()),
+                    None => "Status gewijzigd".to_string(),
+                }
+            }
+            "changes_requested" => "Wijzigingen gevraagd".to_string(),
+            "submission_withdrawn" => "Inzending ingetrokken".to_string(),
+            "retention_extended" => "Bewaartermijn verlengd".to_string(),
+            "slug_rotated" => "Referentiecode vervangen".to_string(),
+            "uploader_login" => "Ingelogd met referentiecode".to_string(),
+            "uploader_view_accessed" => "Inzending bekeken door een beoordelaar".to_string(),
+            "document_uploaded" => "Document toegevoegd".to_string(),
+            "document_replaced" => "Document vervangen".to_string(),
+            "classification_changed" => "Classificatie van document gewijzigd".to_string(),
+            "document_downloaded" => "Document bekeken door een beoordelaar".to_string(),
+            "slot_booked" => "Afspraak gemaakt".to_string(),
+            "slot_cancelled" => "Afspraak geannuleerd".to_string(),
+            "slot_rescheduled" => "Afspraak verzet".to_string(),
+            _ => return None,
+        };
+        let actor = match entry.actor_type.as_str() {
+            "admin" => "reviewer",
+            "applicant" | "uploader" => "applicant",
+            _ => "system",
+        };
+        Some(Self {
+            action: entry.action.clone(),
+            label,
+            actor,
+            timestamp: entry.created_at,
+        })
+    }
+}
+
 // =============================================================================
 // API Responses
 // =============================================================================
 
-#[derive(Debug, Serialize)]
+/// The kind of session a 401 response asks the client to log in for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthScheme {
+    Admin,
+    Uploader,
+    /// Credentials were accepted; an authenticator code is still needed
+    Totp,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Set on 401 responses so clients can route to the right login
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_required: Option<AuthScheme>,
+    /// Set on 409 responses for an upload identical to an existing document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub existing_document_id: Option<Uuid>,
+    /// Set on batch upload errors, naming the file that was rejected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Set on bulk status errors, naming the submission that blocked the update
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission_id: Option<Uuid>,
+    /// Set on 400 responses for input that failed validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Vec<FieldError>>,
+}
+
+/// One validation failure, so a form can highlight the offending field
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    /// Stable reason such as `too_long` or `invalid_email`
+    pub code: String,
+    pub message: String,
+    /// Smallest allowed value or count, for errors about a limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<i64>,
+    /// Largest allowed value, count or length, for errors about a limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<i64>,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+            min: None,
+            max: None,
+        }
+    }
 }
 
 impl<T> ApiResponse<T> {
@@ -279,6 +803,11 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            auth_required: None,
+            existing_document_id: None,
+            filename: None,
+            details: None,
+            submission_id: None,
         }
     }
 
@@ -287,24 +816,69 @@ impl<T> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message.into()),
+            auth_required: None,
+            existing_document_id: None,
+            filename: None,
+            details: None,
+            submission_id: None,
+        }
+    }
+
+    /// Error for a request that needs an admin or uploader session
+    pub fn auth_required(scheme: AuthScheme, message: impl Into<String>) -> Self {
+        Self {
+            auth_required: Some(scheme),
+            ..Self::error(message)
+        }
+    }
+
+    /// Error for input that failed validation, keeping `error` for older clients
+    pub fn invalid(detail: FieldError) -> Self {
+        Self {
+            details: Some(vec![detail.clone()]),
+            ..Self::error(detail.message)
+        }
+    }
+
+    /// Error for an upload whose content matches an existing document
+    pub fn duplicate_document(existing_id: Uuid, message: impl Into<String>) -> Self {
+        Self {
+            existing_document_id: Some(existing_id),
+            ..Self::error(message)
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PaginatedResponse<T> {
     pub items: Vec<T>,
     pub total: i64,
     pub page: i64,
     pub per_page: i64,
     pub total_pages: i64,
+    /// Opaque cursor for the next page, if there is one; only set by
+    /// endpoints that support keyset pagination
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+// =============================================================================
+// Public Config
+// =============================================================================
+
+/// Settings the frontend needs to render its forms
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PublicConfigResponse {
+    /// Classification pre-selected (and applied when omitted) per category
+    pub default_classifications:
+        std::collections::HashMap<DocumentCategory, DocumentClassification>,
 }
 
 // =============================================================================
 // FAQ
 // =============================================================================
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct FaqItem {
     pub question: String,
     pub answer: String,
@@ -327,29 +901,80 @@ pub struct UploaderSession {
     pub user_agent: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// An uploader session as listed to admins; the token hash is never exposed
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UploaderSessionSummary {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl From<UploaderSession> for UploaderSessionSummary {
+    fn from(session: UploaderSession) -> Self {
+        Self {
+            id: session.id,
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent.map(shorten_user_agent),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct UploaderLoginRequest {
     pub slug: String,
     pub email: String,
 }
 
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UploaderRecoverRequest {
+    /// Address given with the submissions whose reference codes were lost
+    pub email: String,
+}
+
 /// Response for uploader session - excludes sensitive submitter info
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct UploaderSessionResponse {
     pub submission_id: Uuid,
     pub slug: String,
     pub status: SubmissionStatus,
+    /// When the submission and its documents are purged
+    pub retention_expiry_date: DateTime<Utc>,
     pub documents: Vec<DocumentResponse>,
     pub session_expires_at: DateTime<Utc>,
 }
 
-/// Minimal submission info for uploader dashboard (privacy-focused)
-#[derive(Debug, Clone, Serialize)]
-pub struct UploaderSubmissionResponse {
-    pub id: Uuid,
-    pub slug: String,
-    pub status: SubmissionStatus,
-    pub created_at: DateTime<Utc>,
-    pub submitted_at: Option<DateTime<Utc>>,
-    pub documents: Vec<DocumentResponse>,
+/// Settings the server runs with, as shown to admins
+///
+/// Only settings that are safe to show: the database URL, passwords and keys
+/// are left out, and for secrets only whether they are set is reported.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectiveConfig {
+    /// `development` or `production`
+    pub environment: String,
+    /// Which variables the database URL came from: `database_url`,
+    /// `database_server_full` or `components`
+    pub database_url_source: String,
+    pub max_upload_size: usize,
+    pub max_files_per_upload: usize,
+    /// `fs` or `s3`
+    pub storage_backend: String,
+    pub upload_dir: String,
+    /// `flat` or `date`
+    pub upload_layout: String,
+    pub cors_origins: Vec<String>,
+    pub trusted_proxies: Vec<String>,
+    pub behind_proxy: bool,
+    pub session_expiry_hours: u64,
+    pub session_max_hours: u64,
+    pub uploader_session_hours: u64,
+    pub retention_months: u32,
+    pub notify_enabled: bool,
+    pub virus_scanning_enabled: bool,
+    pub webhook_configured: bool,
+    pub file_encryption_enabled: bool,
+    pub totp_enabled: bool,
 }
@@ -0,0 +1,354 @@
+//! Email notifications to submitters
+//!
+//! Submitters who left an email address are told when an admin moves their
+//! submission to a final or forwarded status, or asks them for changes, and
+//! can have their reference codes mailed to them.
+//! Mails are queued as outbound jobs and sent by the job worker, which retries
+//! them with backoff, so a slow or unreachable mail server never affects the
+//! admin's request.
+
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::config::{SmtpConfig, SmtpTls};
+use crate::jobs::{self, Job};
+use crate::models::{Submission, SubmissionStatus};
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotifyError {
+    #[error("Invalid address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+    #[error("Failed to build message: {0}")]
+    Message(#[from] lettre::error::Error),
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+}
+
+/// Sends notification emails over SMTP
+pub struct Notifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    /// Portal address for login links, without trailing slash
+    public_url: Option<String>,
+}
+
+impl Notifier {
+    pub fn from_config(config: &SmtpConfig) -> Result<Self, NotifyError> {
+        let mut builder = match config.tls {
+            SmtpTls::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+        }
+        .port(config.port);
+
+        if let (Some(user), Some(password)) = (&config.user, &config.password) {
+            builder = builder.credentials(Credentials::new(user.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from.parse()?,
+            public_url: config.public_url.clone(),
+        })
+    }
+
+    pub(crate) async fn send(
+        &self,
+        to: &str,
+        subject: String,
+        body: String,
+    ) -> Result<(), NotifyError> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse()?)
+            .subject(subject)
+            .body(body)?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
+
+/// Notify the submitter of a status change, if there is anyone to notify
+///
+/// The mail only names the new status; reviewer notes stay internal.
+/// The mail is queued; the job worker sends it.
+pub async fn notify_status_change(
+    pool: &PgPool,
+    notifier: Option<&Arc<Notifier>>,
+    submission: &Submission,
+) {
+    if notifier.is_none() {
+        return;
+    }
+    let Some(to) = submission.submitter_email.clone() else {
+        return;
+    };
+    let Some((subject, body)) = status_change_message(submission) else {
+        return;
+    };
+    queue_mail(pool, to, subject, body, submission.id).await;
+}
+
+/// Ask the submitter to add or correct documents, linking to the login page
+///
+/// The mail is queued; the job worker sends it.
+pub async fn notify_changes_requested(
+    pool: &PgPool,
+    notifier: Option<&Arc<Notifier>>,
+    submission: &Submission,
+    message: &str,
+) {
+    let Some(notifier) = notifier else {
+        return;
+    };
+    let Some(to) = submission.submitter_email.clone() else {
+        return;
+    };
+    let (subject, body) =
+        changes_requested_message(submission, message, notifier.public_url.as_deref());
+    queue_mail(pool, to, subject, body, submission.id).await;
+}
+
+/// Mail the reference codes of the submissions made with `to`
+///
+/// The mail is queued; the job worker sends it.
+pub async fn notify_reference_codes(
+    pool: &PgPool,
+    notifier: &Arc<Notifier>,
+    to: String,
+    slugs: &[String],
+) {
+    let (subject, body) = reference_codes_message(slugs, notifier.public_url.as_deref());
+    match jobs::enqueue(pool, &Job::Email { to, subject, body }).await {
+        Ok(_) => tracing::info!("Queued reference code recovery mail"),
+        Err(e) => tracing::warn!("Failed to queue reference code recovery mail: {}", e),
+    }
+}
+
+async fn queue_mail(
+    pool: &PgPool,
+    to: String,
+    subject: String,
+    body: String,
+    submission_id: uuid::Uuid,
+) {
+    match jobs::enqueue(pool, &Job::Email { to, subject, body }).await {
+        Ok(_) => tracing::info!(
+            "Queued status notification for submission {}",
+            submission_id
+        ),
+        Err(e) => tracing::warn!(
+            "Failed to queue status notification for submission {}: {}",
+            submission_id,
+            e
+        ),
+    }
+}
+
+/// Dutch label for statuses that trigger a notification
+fn status_label(status: SubmissionStatus) -> Option<&'static str> {
+    match status {
+        SubmissionStatus::Approved => Some("goedgekeurd"),
+        SubmissionStatus::Rejected => Some("afgewezen"),
+        SubmissionStatus::Forwarded => Some("doorgestuurd"),
+        SubmissionStatus::Completed => Some("afgerond"),
+        // Withdrawal is done by the submitter, who needs no email about it;
+        // a change request gets its own message
+        SubmissionStatus::Draft
+        | SubmissionStatus::Submitted
+        | SubmissionStatus::UnderReview
+        | SubmissionStatus::Withdrawn
+        | SubmissionStatus::ChangesRequested => None,
+    }
+}
+
+/// Subject and body of the status change email, or `None` if the new status
+/// does not warrant one
+fn status_change_message(submission: &Submission) -> Option<(String, String)> {
+    let label = status_label(submission.status)?;
+
+    let subject = format!("Uw inzending {} is {}", submission.slug, label);
+
+    let mut body = format!(
+        "Beste {},\n\n\
+        De status van uw inzending {} bij het RegelRecht Upload Portal is gewijzigd naar: {}.\n",
+        submission.submitter_name, submission.slug, label
+    );
+    body.push_str(
+        "\nU kunt uw inzending bekijken door in te loggen met uw inzendingscode en e-mailadres.\n\n\
+        Met vriendelijke groet,\n\
+        Team RegelRecht\n",
+    );
+
+    Some((subject, body))
+}
+
+/// Subject and body of the email asking the submitter for changes
+fn changes_requested_message(
+    submission: &Submission,
+    message: &str,
+    public_url: Option<&str>,
+) -> (String, String) {
+    let subject = format!("Aanvulling gevraagd voor uw inzending {}", submission.slug);
+
+    let mut body = format!(
+        "Beste {},\n\n\
+        Bij de beoordeling van uw inzending {} bij het RegelRecht Upload Portal \
+        is om een aanvulling gevraagd:\n\n{}\n\n\
+        Log in met uw inzendingscode en e-mailadres om documenten toe te voegen",
+        submission.submitter_name,
+        submission.slug,
+        message.trim()
+    );
+    match public_url {
+        Some(url) => body.push_str(&format!(
+            ":\n{}/uploader-login.html?slug={}\n",
+            url, submission.slug
+        )),
+        None => body.push_str(".\n"),
+    }
+    body.push_str(
+        "\nMet vriendelijke groet,\n\
+        Team RegelRecht\n",
+    );
+
+    (subject, body)
+}
+
+/// Subject and body of the email listing someone's reference codes
+fn reference_codes_message(slugs: &[String], public_url: Option<&str>) -> (String, String) {
+    let subject = "Uw referentiecodes bij het RegelRecht Upload Portal".to_string();
+
+    let mut body = "Beste indiener,\n\n\
+        U heeft gevraagd om de referentiecodes van uw inzendingen bij het \
+        RegelRecht Upload Portal. Bij dit e-mailadres horen:\n\n"
+        .to_string();
+    for slug in slugs {
+        match public_url {
+            Some(url) => body.push_str(&format!(
+                "- {} ({}/uploader-login.html?slug={})\n",
+                slug, url, slug
+            )),
+            None => body.push_str(&format!("- {}\n", slug)),
+        }
+    }
+    body.push_str(
+        "\nLog in met een referentiecode en dit e-mailadres om documenten toe te voegen.\n\
+        Heeft u hier niet om gevraagd? Dan kunt u deze e-mail negeren.\n\n\
+        Met vriendelijke groet,\n\
+        Team RegelRecht\n",
+    );
+
+    (subject, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn submission(status: SubmissionStatus) -> Submission {
+        Submission {
+            id: Uuid::new_v4(),
+            slug: "rr-20260101-abcde".to_string(),
+            submitter_name: "Jan Jansen".to_string(),
+            submitter_email: Some("jan@example.nl".to_string()),
+            organization: "Gemeente".to_string(),
+            organization_department: None,
+            status,
+            notes: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            submitted_at: None,
+            retention_expiry_date: Utc::now(),
+            retention_consent_at: None,
+            legal_hold: false,
+            admin_notes: None,
+        }
+    }
+
+    #[test]
+    fn test_status_change_message_contents() {
+        let submission = Submission {
+            admin_notes: Some("Interne afweging, niet voor de indiener".to_string()),
+            ..submission(SubmissionStatus::Approved)
+        };
+        let (subject, body) = status_change_message(&submission).unwrap();
+
+        assert_eq!(subject, "Uw inzending rr-20260101-abcde is goedgekeurd");
+        assert!(body.starts_with("Beste Jan Jansen,"));
+        assert!(body.contains("rr-20260101-abcde"));
+        assert!(!body.contains("Interne afweging"));
+    }
+
+    #[test]
+    fn test_status_change_message_only_for_notifying_statuses() {
+        for status in [
+            SubmissionStatus::Approved,
+            SubmissionStatus::Rejected,
+            SubmissionStatus::Forwarded,
+            SubmissionStatus::Completed,
+        ] {
+            assert!(status_change_message(&submission(status)).is_some());
+        }
+        for status in [
+            SubmissionStatus::Draft,
+            SubmissionStatus::Submitted,
+            SubmissionStatus::UnderReview,
+            SubmissionStatus::Withdrawn,
+            SubmissionStatus::ChangesRequested,
+        ] {
+            assert!(status_change_message(&submission(status)).is_none());
+        }
+    }
+
+    #[test]
+    fn test_changes_requested_message_links_to_login() {
+        let submission = submission(SubmissionStatus::ChangesRequested);
+        let (subject, body) = changes_requested_message(
+            &submission,
+            "  Graag ook de werkinstructie toevoegen.\n",
+            Some("https://upload.regelrecht.nl"),
+        );
+
+        assert_eq!(
+            subject,
+            "Aanvulling gevraagd voor uw inzending rr-20260101-abcde"
+        );
+        assert!(body.contains("\n\nGraag ook de werkinstructie toevoegen.\n\n"));
+        assert!(body
+            .contains("https://upload.regelrecht.nl/uploader-login.html?slug=rr-20260101-abcde"));
+
+        let (_, body) = changes_requested_message(&submission, "Graag aanvullen.", None);
+        assert!(body.contains("om documenten toe te voegen.\n"));
+        assert!(!body.contains("http"));
+    }
+
+    #[test]
+    fn test_reference_codes_message_lists_every_slug() {
+        let slugs = [
+            "rr-20260101-abcde".to_string(),
+            "rr-20260202-fghij".to_string(),
+        ];
+        let (subject, body) = reference_codes_message(&slugs, Some("https://upload.regelrecht.nl"));
+
+        assert_eq!(
+            subject,
+            "Uw referentiecodes bij het RegelRecht Upload Portal"
+        );
+        assert!(body.contains(
+            "- rr-20260101-abcde (https://upload.regelrecht.nl/uploader-login.html?slug=rr-20260101-abcde)\n"
+        ));
+        assert!(body.contains("- rr-20260202-fghij ("));
+
+        let (_, body) = reference_codes_message(&slugs, None);
+        assert!(body.contains("- rr-20260101-abcde\n- rr-20260202-fghij\n"));
+    }
+}
@@ -0,0 +1,125 @@
+//! OpenAPI description of the HTTP API, served at `/api/openapi.json`
+//!
+//! Paths are collected from the `#[utoipa::path]` annotations on the handlers;
+//! schemas referenced there are picked up automatically.
+
+use crate::handlers;
+use crate::handlers::auth::SESSION_COOKIE;
+use crate::handlers::uploader_auth::UPLOADER_SESSION_COOKIE;
+use axum::Json;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "RegelRecht Upload Portal API",
+        description = "Submissions, documents and meeting scheduling for the RegelRecht upload portal"
+    ),
+    paths(
+        handlers::create_submission,
+        handlers::get_submission,
+        handlers::get_submission_status,
+        handlers::update_submission,
+        handlers::update_submission_metadata,
+        handlers::submit_submission,
+        handlers::withdraw_submission,
+        handlers::extend_retention,
+        handlers::get_submission_history,
+        handlers::upload_document,
+        handlers::upload_documents,
+        handlers::add_formal_law,
+        handlers::lookup_formal_law_title,
+        handlers::replace_document,
+        handlers::delete_document,
+        handlers::change_document_classification,
+        handlers::download_documents_zip,
+        handlers::get_public_config,
+        handlers::get_faq,
+        handlers::get_available_slots,
+        handlers::book_slot,
+        handlers::cancel_booking,
+        handlers::reschedule_booking,
+        handlers::get_booking_ics,
+        handlers::list_slots_admin,
+        handlers::create_slots,
+        handlers::delete_slot,
+        handlers::admin_login,
+        handlers::admin_logout,
+        handlers::get_current_admin,
+        handlers::change_password,
+        handlers::list_admin_sessions,
+        handlers::revoke_admin_session,
+        handlers::revoke_other_admin_sessions,
+        handlers::enroll_totp,
+        handlers::confirm_totp,
+        handlers::uploader_login,
+        handlers::uploader_logout,
+        handlers::uploader_recover,
+        handlers::get_current_uploader,
+        handlers::list_submissions,
+        handlers::export_submissions_csv,
+        handlers::list_expiring_submissions,
+        handlers::get_submission_admin,
+        handlers::delete_submission,
+        handlers::update_submission_status,
+        handlers::bulk_update_submission_status,
+        handlers::request_changes,
+        handlers::forward_submission,
+        handlers::set_legal_hold,
+        handlers::rotate_slug,
+        handlers::list_uploader_sessions,
+        handlers::revoke_uploader_sessions,
+        handlers::get_uploader_view,
+        handlers::list_comments,
+        handlers::add_comment,
+        handlers::get_submission_stats,
+        handlers::export_submission_json,
+        handlers::export_submission_files,
+        handlers::export_all_submissions_jsonl,
+        handlers::list_document_versions,
+        handlers::download_document_version,
+        handlers::download_document,
+        handlers::create_share_link,
+        handlers::get_document_thumbnail,
+        handlers::download_shared_document,
+        handlers::get_dashboard_stats,
+        handlers::get_effective_config,
+        handlers::list_audit_log,
+        handlers::import_slots,
+        handlers::create_recurring_slots,
+    ),
+    modifiers(&SessionCookies),
+    tags(
+        (name = "submissions", description = "Applicant submissions"),
+        (name = "documents", description = "Documents and formal law links of a submission"),
+        (name = "calendar", description = "Meeting slots and bookings"),
+        (name = "auth", description = "Admin authentication"),
+        (name = "uploader", description = "Uploader self-service sessions"),
+        (name = "admin", description = "Submission review (admin only)"),
+        (name = "admin-calendar", description = "Meeting slot management (admin only)")
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the session cookies referenced by the `security` of the paths
+struct SessionCookies;
+
+impl Modify for SessionCookies {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "admin_session",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(SESSION_COOKIE))),
+        );
+        components.add_security_scheme(
+            "uploader_session",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(UPLOADER_SESSION_COOKIE))),
+        );
+    }
+}
+
+/// Serve the OpenAPI document
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
@@ -0,0 +1,212 @@
+//! Signed, expiring download links for single documents
+//!
+//! Reviewers can hand a colleague one document without sharing a session. A
+//! link token carries the document id and an expiry time, signed with
+//! HMAC-SHA256 under a server secret, so the public download endpoint can
+//! check it without any session or stored link. Without
+//! `DOWNLOAD_LINK_SECRET` a random secret is generated at startup and links
+//! stop working when the server restarts.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Shortest accepted DOWNLOAD_LINK_SECRET
+const MIN_SECRET_LEN: usize = 32;
+
+/// Document id (16 bytes) followed by the expiry as big-endian Unix seconds
+const PAYLOAD_LEN: usize = 16 + 8;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum LinkError {
+    #[error("Malformed download link")]
+    Malformed,
+    #[error("Invalid download link signature")]
+    BadSignature,
+    #[error("Download link has expired")]
+    Expired,
+}
+
+/// Secret for signing download links
+#[derive(Clone)]
+pub struct LinkSecret(Vec<u8>);
+
+// Keep the secret out of logs
+impl std::fmt::Debug for LinkSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LinkSecret(<redacted>)")
+    }
+}
+
+impl FromStr for LinkSecret {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() < MIN_SECRET_LEN {
+            return Err(format!("must be at least {} characters", MIN_SECRET_LEN));
+        }
+        Ok(Self(s.as_bytes().to_vec()))
+    }
+}
+
+impl LinkSecret {
+    /// Random secret for when none is configured
+    pub fn generate() -> Self {
+        let mut key = vec![0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut key);
+        Self(key)
+    }
+}
+
+/// Creates and verifies download link tokens
+pub struct LinkSigner {
+    secret: LinkSecret,
+    /// Lifetime of a link when the admin does not ask for another
+    pub default_ttl: Duration,
+}
+
+impl LinkSigner {
+    pub fn new(secret: LinkSecret, default_ttl: Duration) -> Self {
+        Self {
+            secret,
+            default_ttl,
+        }
+    }
+
+    fn mac(&self, payload: &[u8]) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret.0).expect("HMAC accepts any key length");
+        mac.update(payload);
+        mac
+    }
+
+    /// Token granting access to `document_id` until `expires_at`
+    pub fn sign(&self, document_id: Uuid, expires_at: DateTime<Utc>) -> String {
+        let mut payload = Vec::with_capacity(PAYLOAD_LEN);
+        payload.extend_from_slice(document_id.as_bytes());
+        payload.extend_from_slice(&expires_at.timestamp().to_be_bytes());
+        let signature = self.mac(&payload).finalize().into_bytes();
+        format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(&payload),
+            URL_SAFE_NO_PAD.encode(signature)
+        )
+    }
+
+    /// Document id of a token that is correctly signed and not expired at `now`
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> Result<Uuid, LinkError> {
+        let (payload, signature) = token.split_once('.').ok_or(LinkError::Malformed)?;
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| LinkError::Malformed)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| LinkError::Malformed)?;
+        if payload.len() != PAYLOAD_LEN {
+            return Err(LinkError::Malformed);
+        }
+        self.mac(&payload)
+            .verify_slice(&signature)
+            .map_err(|_| LinkError::BadSignature)?;
+
+        let (id, expiry) = payload.split_at(16);
+        let expiry = i64::from_be_bytes(expiry.try_into().unwrap());
+        let expires_at = Utc
+            .timestamp_opt(expiry, 0)
+            .single()
+            .ok_or(LinkError::Malformed)?;
+        if now >= expires_at {
+            return Err(LinkError::Expired);
+        }
+        Ok(Uuid::from_slice(id).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link_signer(secret: &str) -> LinkSigner {
+        LinkSigner::new(secret.parse().unwrap(), Duration::hours(24))
+    }
+
+    const SECRET: &str = "een-lang-genoeg-geheim-voor-de-tests";
+
+    #[test]
+    fn test_token_round_trip() {
+        let signer = link_signer(SECRET);
+        let id = Uuid::new_v4();
+        let token = signer.sign(id, Utc::now() + Duration::hours(1));
+
+        assert_eq!(signer.verify(&token, Utc::now()), Ok(id));
+    }
+
+    #[test]
+    fn test_tampered_token_is_rejected() {
+        let signer = link_signer(SECRET);
+        let token = signer.sign(Uuid::new_v4(), Utc::now() + Duration::hours(1));
+        let (payload, signature) = token.split_once('.').unwrap();
+
+        // Another document id under the original signature
+        let mut bytes = URL_SAFE_NO_PAD.decode(payload).unwrap();
+        bytes[0] ^= 1;
+        let forged = format!("{}.{}", URL_SAFE_NO_PAD.encode(&bytes), signature);
+        assert_eq!(
+            signer.verify(&forged, Utc::now()),
+            Err(LinkError::BadSignature)
+        );
+
+        // A later expiry under the original signature
+        let mut bytes = URL_SAFE_NO_PAD.decode(payload).unwrap();
+        bytes[PAYLOAD_LEN - 3] = bytes[PAYLOAD_LEN - 3].wrapping_add(1);
+        let extended = format!("{}.{}", URL_SAFE_NO_PAD.encode(&bytes), signature);
+        assert_eq!(
+            signer.verify(&extended, Utc::now()),
+            Err(LinkError::BadSignature)
+        );
+
+        // Signed with a different secret
+        let other = link_signer("een-ander-geheim-van-voldoende-lengte");
+        assert_eq!(
+            other.verify(&token, Utc::now()),
+            Err(LinkError::BadSignature)
+        );
+
+        assert_eq!(
+            signer.verify("geen-token", Utc::now()),
+            Err(LinkError::Malformed)
+        );
+        assert_eq!(
+            signer.verify(&format!("{}.", payload), Utc::now()),
+            Err(LinkError::BadSignature)
+        );
+    }
+
+    #[test]
+    fn test_expired_token_is_rejected() {
+        let signer = link_signer(SECRET);
+        let id = Uuid::new_v4();
+        let expires_at = Utc::now() + Duration::hours(1);
+        let token = signer.sign(id, expires_at);
+
+        assert_eq!(
+            signer.verify(&token, expires_at - Duration::seconds(1)),
+            Ok(id)
+        );
+        assert_eq!(signer.verify(&token, expires_at), Err(LinkError::Expired));
+        assert_eq!(
+            signer.verify(&token, expires_at + Duration::days(1)),
+            Err(LinkError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_short_secret_is_refused() {
+        assert!("te-kort".parse::<LinkSecret>().is_err());
+    }
+}
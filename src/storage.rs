@@ -0,0 +1,478 @@
+//! File storage backends for uploaded documents
+//!
+//! Documents are addressed by a storage key of the form
+//! `<submission id>/<document id>_<filename>`, which is what `documents.file_path`
+//! holds. With `UPLOAD_LAYOUT=date` the key is prefixed by the year and month
+//! the submission was created (`YYYY/MM/<submission id>/...`). The filesystem
+//! backend resolves keys below `UPLOAD_DIR`; the S3 backend uses them as object
+//! keys in the configured bucket.
+
+use async_trait::async_trait;
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    primitives::ByteStream,
+    Client,
+};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, BufReader};
+
+use crate::config::{Config, S3Config, StorageBackend, UploadLayout};
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("File not found: {0}")]
+    NotFound(String),
+    #[error("Invalid storage key: {0}")]
+    InvalidKey(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Object storage error: {0}")]
+    S3(String),
+}
+
+impl StorageError {
+    /// Whether the store failed because its disk or quota is full
+    pub fn is_storage_full(&self) -> bool {
+        matches!(self, Self::Io(e) if is_storage_full(e))
+    }
+}
+
+/// `ENOSPC`, the same on Linux and macOS
+const ENOSPC: i32 = 28;
+
+/// Whether a write failed because the disk is full
+///
+/// Some platforms and filesystems report `ENOSPC` with a generic error kind,
+/// so the OS error code is checked as well.
+pub fn is_storage_full(e: &std::io::Error) -> bool {
+    e.kind() == std::io::ErrorKind::StorageFull || e.raw_os_error() == Some(ENOSPC)
+}
+
+/// Storage for uploaded document files
+#[async_trait]
+pub trait FileStore: Send + Sync {
+    /// Store `data` under `key`, replacing any existing file
+    #[allow(dead_code)]
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), StorageError>;
+
+    /// Store the contents of a local file under `key`
+    ///
+    /// The local file may be moved into place; callers should treat it as
+    /// consumed and remove it afterwards if it still exists.
+    async fn put_file(&self, key: &str, path: &Path) -> Result<(), StorageError>;
+
+    /// Read the file stored under `key`
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError>;
+
+    /// Open the file stored under `key` for reading without loading it whole
+    async fn open(&self, key: &str) -> Result<FileReader, StorageError>;
+
+    /// Delete the file stored under `key`; a missing file is not an error
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Delete every file whose key starts with `<prefix>/`
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), StorageError>;
+}
+
+/// Streaming reader over a stored file
+pub type FileReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Build the storage backend selected by `STORAGE_BACKEND`
+pub fn from_config(config: &Config) -> Result<Arc<dyn FileStore>, StorageError> {
+    match config.storage_backend {
+        StorageBackend::Fs => Ok(Arc::new(FsStore::new(&config.upload_dir))),
+        StorageBackend::S3 => {
+            let s3 = config.s3.as_ref().ok_or_else(|| {
+                StorageError::S3("STORAGE_BACKEND=s3 requires S3 configuration".to_string())
+            })?;
+            Ok(Arc::new(S3Store::new(s3)))
+        }
+    }
+}
+
+/// Key prefix holding the files of a submission under `layout`
+pub fn submission_prefix(
+    layout: UploadLayout,
+    submission_id: uuid::Uuid,
+    created_at: DateTime<Utc>,
+) -> String {
+    match layout {
+        UploadLayout::Flat => submission_id.to_string(),
+        UploadLayout::Date => format!("{}/{}", created_at.format("%Y/%m"), submission_id),
+    }
+}
+
+/// Storage key for a document file below its submission's prefix
+pub fn document_key(prefix: &str, storage_filename: &str) -> String {
+    format!("{}/{}", prefix, storage_filename)
+}
+
+/// Delete the files of a submission, whichever layout they were stored under
+///
+/// Changing `UPLOAD_LAYOUT` does not move files already stored, so the
+/// prefixes of both layouts are cleared.
+pub async fn delete_submission_files(
+    store: &dyn FileStore,
+    submission_id: uuid::Uuid,
+    created_at: DateTime<Utc>,
+) -> Result<(), StorageError> {
+    for layout in [UploadLayout::Flat, UploadLayout::Date] {
+        store
+            .delete_prefix(&submission_prefix(layout, submission_id, created_at))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Check that a directory accepts writes by creating and removing a probe file
+pub async fn check_dir_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(".write_test");
+    tokio::fs::write(&probe, b"test").await?;
+    let _ = tokio::fs::remove_file(&probe).await;
+    Ok(())
+}
+
+/// Reject keys that could escape the storage root
+fn validate_key(key: &str) -> Result<(), StorageError> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && !key.contains('\\')
+        && key
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != "..");
+
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+// =============================================================================
+// Filesystem
+// =============================================================================
+
+/// Stores files below a local directory (the `UPLOAD_DIR` volume)
+pub struct FsStore {
+    root: PathBuf,
+}
+
+impl FsStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    async fn create_parent(path: &Path) -> Result<(), StorageError> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Remove what a failed write left behind, such as a copy cut off by a full disk
+async fn remove_partial(path: &Path) {
+    if let Err(e) = tokio::fs::remove_file(path).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove partial file {:?}: {}", path, e);
+        }
+    }
+}
+
+#[async_trait]
+impl FileStore for FsStore {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        Self::create_parent(&path).await?;
+        if let Err(e) = tokio::fs::write(&path, &data).await {
+            remove_partial(&path).await;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    async fn put_file(&self, key: &str, source: &Path) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        Self::create_parent(&path).await?;
+        // Rename when staged on the same filesystem, copy otherwise
+        if tokio::fs::rename(source, &path).await.is_err() {
+            if let Err(e) = tokio::fs::copy(source, &path).await {
+                remove_partial(&path).await;
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        let path = self.path(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(Bytes::from(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn open(&self, key: &str) -> Result<FileReader, StorageError> {
+        let path = self.path(key)?;
+        match tokio::fs::File::open(&path).await {
+            Ok(file) => Ok(Box::new(BufReader::new(file))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), StorageError> {
+        let path = self.path(prefix)?;
+        match tokio::fs::remove_dir_all(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+// =============================================================================
+// S3 / MinIO
+// =============================================================================
+
+/// Stores files as objects in an S3-compatible bucket
+pub struct S3Store {
+    client: Client,
+    bucket: String,
+}
+
+impl S3Store {
+    pub fn new(config: &S3Config) -> Self {
+        let credentials = Credentials::new(
+            &config.access_key_id,
+            &config.secret_access_key,
+            None,
+            None,
+            "regelrecht-upload",
+        );
+
+        let mut builder = aws_sdk_s3::Config::builder()
+            .behavior_version_latest()
+            .region(Region::new(config.region.clone()))
+            .credentials_provider(credentials)
+            .force_path_style(config.force_path_style);
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder.endpoint_url(endpoint);
+        }
+
+        Self {
+            client: Client::from_conf(builder.build()),
+            bucket: config.bucket.clone(),
+        }
+    }
+
+    /// Body of the object stored under `key`
+    async fn get_object(&self, key: &str) -> Result<ByteStream, StorageError> {
+        validate_key(key)?;
+        match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(output) => Ok(output.body),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => {
+                Err(StorageError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(s3_error(e)),
+        }
+    }
+}
+
+fn s3_error(e: impl std::error::Error) -> StorageError {
+    // The SDK's Display is terse; the debug chain includes the service message
+    StorageError::S3(format!("{:?}", e))
+}
+
+#[async_trait]
+impl FileStore for S3Store {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), StorageError> {
+        validate_key(key)?;
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(())
+    }
+
+    async fn put_file(&self, key: &str, source: &Path) -> Result<(), StorageError> {
+        validate_key(key)?;
+        let body = ByteStream::from_path(source)
+            .await
+            .map_err(|e| StorageError::S3(e.to_string()))?;
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(body)
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        let data = self
+            .get_object(key)
+            .await?
+            .collect()
+            .await
+            .map_err(|e| StorageError::S3(e.to_string()))?;
+        Ok(data.into_bytes())
+    }
+
+    async fn open(&self, key: &str) -> Result<FileReader, StorageError> {
+        Ok(Box::new(self.get_object(key).await?.into_async_read()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        validate_key(key)?;
+        // S3 treats deleting a missing object as success
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(())
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), StorageError> {
+        validate_key(prefix)?;
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(format!("{}/", prefix))
+            .into_paginator()
+            .send();
+
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(s3_error)?;
+            for object in page.contents() {
+                if let Some(key) = object.key() {
+                    self.client
+                        .delete_object()
+                        .bucket(&self.bucket)
+                        .key(key)
+                        .send()
+                        .await
+                        .map_err(s3_error)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("0b6c/doc_report.pdf").is_ok());
+        assert!(validate_key("2024/03/0b6c/doc_report.pdf").is_ok());
+
+        assert!(validate_key("").is_err());
+        assert!(validate_key("/etc/passwd").is_err());
+        assert!(validate_key("../secret").is_err());
+        assert!(validate_key("a/../../b").is_err());
+        assert!(validate_key("a//b").is_err());
+        assert!(validate_key("a\\b").is_err());
+        assert!(validate_key("2024/../../b").is_err());
+    }
+
+    #[test]
+    fn test_is_storage_full() {
+        assert!(is_storage_full(&std::io::Error::from_raw_os_error(ENOSPC)));
+        assert!(is_storage_full(&std::io::ErrorKind::StorageFull.into()));
+        assert!(!is_storage_full(
+            &std::io::ErrorKind::PermissionDenied.into()
+        ));
+        assert!(StorageError::Io(std::io::Error::from_raw_os_error(ENOSPC)).is_storage_full());
+        assert!(!StorageError::NotFound("a".to_string()).is_storage_full());
+    }
+
+    #[test]
+    fn test_submission_prefix_per_layout() {
+        let id = uuid::Uuid::nil();
+        let created_at = "2024-03-31T23:30:00Z".parse().unwrap();
+        assert_eq!(
+            submission_prefix(UploadLayout::Flat, id, created_at),
+            id.to_string()
+        );
+        assert_eq!(
+            submission_prefix(UploadLayout::Date, id, created_at),
+            format!("2024/03/{}", id)
+        );
+        assert_eq!(
+            document_key(
+                &submission_prefix(UploadLayout::Date, id, created_at),
+                "a.pdf"
+            ),
+            format!("2024/03/{}/a.pdf", id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fs_store_round_trip() {
+        let root = std::env::temp_dir().join(format!("fs_store_{}", uuid::Uuid::new_v4()));
+        let store = FsStore::new(&root);
+
+        store
+            .put("sub/one.txt", Bytes::from_static(b"one"))
+            .await
+            .unwrap();
+        assert_eq!(store.get("sub/one.txt").await.unwrap(), "one");
+
+        let staged = root.join("staged");
+        tokio::fs::write(&staged, b"two").await.unwrap();
+        store.put_file("sub/two.txt", &staged).await.unwrap();
+        assert_eq!(store.get("sub/two.txt").await.unwrap(), "two");
+
+        store.delete("sub/one.txt").await.unwrap();
+        store.delete("sub/one.txt").await.unwrap();
+        assert!(matches!(
+            store.get("sub/one.txt").await,
+            Err(StorageError::NotFound(_))
+        ));
+
+        store.delete_prefix("sub").await.unwrap();
+        assert!(!root.join("sub").exists());
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+//! First-page thumbnails of PDF documents
+//!
+//! Rendering is left to an external program named in `THUMBNAIL_RENDERER`,
+//! such as MuPDF's `mutool draw`, so the server does not link a PDF
+//! rasterizer itself. The command is split on whitespace and `{input}`,
+//! `{output}` and `{size}` are substituted in its arguments: the program must
+//! write a PNG of the first page of `{input}` to `{output}`, fitting within
+//! `{size}` pixels. Thumbnails are cached below `UPLOAD_DIR/.thumbnails`, one
+//! file per document, and rendered again once the document's file is replaced.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::fs;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::encryption::FileKey;
+
+/// Directory below UPLOAD_DIR holding cached thumbnails
+pub const CACHE_DIR: &str = ".thumbnails";
+
+/// Default and largest accepted THUMBNAIL_SIZE, in pixels
+pub const DEFAULT_SIZE: u32 = 320;
+pub const MAX_SIZE: u32 = 1024;
+
+/// Time allowed for rendering one thumbnail
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest PNG accepted from the renderer
+const MAX_THUMBNAIL_BYTES: u64 = 4 * 1024 * 1024;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ThumbnailError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("renderer did not finish in time")]
+    Timeout,
+    #[error("renderer failed: {0}")]
+    Renderer(String),
+    #[error("failed to encrypt thumbnail")]
+    Encrypt,
+}
+
+/// Renders first-page thumbnails and keeps them on disk
+pub struct Thumbnailer {
+    command: Vec<String>,
+    cache_dir: PathBuf,
+    size: u32,
+    /// Cached thumbnails are encrypted like the documents they show
+    file_key: Option<FileKey>,
+}
+
+impl Thumbnailer {
+    pub fn new(
+        command: &str,
+        cache_dir: impl Into<PathBuf>,
+        size: u32,
+        file_key: Option<FileKey>,
+    ) -> Self {
+        Self {
+            command: command.split_whitespace().map(str::to_string).collect(),
+            cache_dir: cache_dir.into(),
+            size,
+            file_key,
+        }
+    }
+
+    /// Cached thumbnail of a document, unless its file changed after caching
+    pub async fn cached(&self, doc_id: Uuid, updated_at: DateTime<Utc>) -> Option<Bytes> {
+        let path = self.cache_path(doc_id);
+        let cached_at: DateTime<Utc> = fs::metadata(&path).await.ok()?.modified().ok()?.into();
+        if cached_at < updated_at {
+            return None;
+        }
+        let data = fs::read(&path).await.ok()?;
+        let png = match &self.file_key {
+            Some(key) => key.decrypt(&data).ok()?,
+            None => data,
+        };
+        png.starts_with(PNG_SIGNATURE).then(|| Bytes::from(png))
+    }
+
+    /// Render the first page of `pdf` and cache the result
+    pub async fn render(&self, doc_id: Uuid, pdf: &[u8]) -> Result<Bytes, ThumbnailError> {
+        fs::create_dir_all(&self.cache_dir).await?;
+        let work_dir = self.cache_dir.join(format!(".render-{}", Uuid::new_v4()));
+        fs::create_dir(&work_dir).await?;
+        let rendered = self.run_renderer(&work_dir, pdf).await;
+        let _ = fs::remove_dir_all(&work_dir).await;
+        let png = rendered?;
+
+        let stored = match &self.file_key {
+            Some(key) => key.encrypt(&png).map_err(|_| ThumbnailError::Encrypt)?,
+            None => png.to_vec(),
+        };
+        // Write aside and rename, so a concurrent request never reads half a file
+        let partial = self.cache_dir.join(format!(".{}.partial", Uuid::new_v4()));
+        fs::write(&partial, stored).await?;
+        if let Err(e) = fs::rename(&partial, self.cache_path(doc_id)).await {
+            let _ = fs::remove_file(&partial).await;
+            return Err(e.into());
+        }
+        Ok(png)
+    }
+
+    /// Remove cached thumbnails of documents that no longer exist
+    pub async fn prune(&self, pool: &PgPool) -> Result<usize, ThumbnailError> {
+        let mut entries = match fs::read_dir(&self.cache_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut cached = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let doc_id = name
+                .to_str()
+                .and_then(|name| name.strip_suffix(".png"))
+                .and_then(|stem| stem.parse::<Uuid>().ok());
+            if let Some(doc_id) = doc_id {
+                cached.push(doc_id);
+            }
+        }
+        if cached.is_empty() {
+            return Ok(0);
+        }
+
+        let existing: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM documents WHERE id = ANY($1)")
+            .bind(&cached)
+            .fetch_all(pool)
+            .await?;
+        let mut removed = 0;
+        for doc_id in cached.into_iter().filter(|id| !existing.contains(id)) {
+            match fs::remove_file(self.cache_path(doc_id)).await {
+                Ok(()) => removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(removed)
+    }
+
+    fn cache_path(&self, doc_id: Uuid) -> PathBuf {
+        self.cache_dir.join(format!("{}.png", doc_id))
+    }
+
+    async fn run_renderer(&self, work_dir: &Path, pdf: &[u8]) -> Result<Bytes, ThumbnailError> {
+        let input = work_dir.join("input.pdf");
+        let output = work_dir.join("output.png");
+        fs::write(&input, pdf).await?;
+
+        let args = substitute(&self.command, &input, &output, self.size);
+        let Some((program, args)) = args.split_first() else {
+            return Err(ThumbnailError::Renderer("no renderer command".to_string()));
+        };
+        let child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let result = tokio::time::timeout(RENDER_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| ThumbnailError::Timeout)??;
+        if !result.status.success() {
+            return Err(ThumbnailError::Renderer(format!(
+                "{}: {}",
+                result.status,
+                String::from_utf8_lossy(&result.stderr).trim()
+            )));
+        }
+
+        let written = fs::metadata(&output)
+            .await
+            .map_err(|_| ThumbnailError::Renderer("no output written".to_string()))?;
+        if written.len() > MAX_THUMBNAIL_BYTES {
+            return Err(ThumbnailError::Renderer(format!(
+                "output of {} bytes is too large",
+                written.len()
+            )));
+        }
+        let png = fs::read(&output).await?;
+        if !png.starts_with(PNG_SIGNATURE) {
+            return Err(ThumbnailError::Renderer("output is not a PNG".to_string()));
+        }
+        Ok(Bytes::from(png))
+    }
+}
+
+/// Renderer arguments with the placeholders filled in
+fn substitute(command: &[String], input: &Path, output: &Path, size: u32) -> Vec<String> {
+    let size = size.to_string();
+    command
+        .iter()
+        .map(|arg| {
+            arg.replace("{input}", &input.to_string_lossy())
+                .replace("{output}", &output.to_string_lossy())
+                .replace("{size}", &size)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_fills_placeholders() {
+        let command: Vec<String> = "mutool draw -o {output} -w {size} -h {size} {input} 1"
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        let args = substitute(
+            &command,
+            Path::new("/tmp/in.pdf"),
+            Path::new("/tmp/out.png"),
+            320,
+        );
+        assert_eq!(
+            args,
+            [
+                "mutool",
+                "draw",
+                "-o",
+                "/tmp/out.png",
+                "-w",
+                "320",
+                "-h",
+                "320",
+                "/tmp/in.pdf",
+                "1"
+            ]
+        );
+    }
+}
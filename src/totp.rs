@@ -0,0 +1,203 @@
+//! TOTP two-factor authentication for admin accounts
+//!
+//! Secrets are standard RFC 6238 (SHA-1, 6 digits, 30 second steps) so any
+//! authenticator app can enroll from the otpauth URI. They are stored in
+//! `admin_users` encrypted with AES-256-GCM under `TOTP_ENCRYPTION_KEY`, so a
+//! database dump alone is not enough to generate codes.
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use std::str::FromStr;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+/// Issuer shown in authenticator apps
+const ISSUER: &str = "RegelRecht Upload";
+
+/// Accept codes from one step before or after the current one
+const SKEW_STEPS: u8 = 1;
+
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TotpError {
+    #[error("Invalid TOTP secret: {0}")]
+    Secret(String),
+    #[error("Failed to encrypt TOTP secret")]
+    Encrypt,
+    #[error("Failed to decrypt TOTP secret")]
+    Decrypt,
+}
+
+/// Key used to encrypt TOTP secrets at rest
+#[derive(Clone)]
+pub struct TotpKey([u8; 32]);
+
+// Keep the key out of logs
+impl std::fmt::Debug for TotpKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TotpKey(<redacted>)")
+    }
+}
+
+impl FromStr for TotpKey {
+    type Err = String;
+
+    /// Parse a key given as 64 hex characters
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s.trim()).map_err(|e| e.to_string())?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| "expected 32 bytes (64 hex characters)".to_string())?;
+        Ok(Self(key))
+    }
+}
+
+impl TotpKey {
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new((&self.0).into())
+    }
+
+    /// Encrypt a base32 secret as hex(nonce || ciphertext)
+    pub fn encrypt(&self, secret: &str) -> Result<String, TotpError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(&nonce, secret.as_bytes())
+            .map_err(|_| TotpError::Encrypt)?;
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&ciphertext);
+        Ok(hex::encode(out))
+    }
+
+    /// Decrypt a secret produced by [`TotpKey::encrypt`]
+    pub fn decrypt(&self, stored: &str) -> Result<String, TotpError> {
+        let bytes = hex::decode(stored).map_err(|_| TotpError::Decrypt)?;
+        if bytes.len() <= NONCE_LEN {
+            return Err(TotpError::Decrypt);
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| TotpError::Decrypt)?;
+        String::from_utf8(plaintext).map_err(|_| TotpError::Decrypt)
+    }
+}
+
+/// Generate a new random secret, base32 encoded
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+fn totp(secret: &str, account: &str) -> Result<TOTP, TotpError> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| TotpError::Secret(e.to_string()))?;
+    // The otpauth label uses ':' as the issuer separator
+    let account = account.replace(':', "_");
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        SKEW_STEPS,
+        30,
+        bytes,
+        Some(ISSUER.to_string()),
+        account,
+    )
+    .map_err(|e| TotpError::Secret(e.to_string()))
+}
+
+/// otpauth:// URI for enrolling `secret` in an authenticator app
+pub fn otpauth_uri(secret: &str, account: &str) -> Result<String, TotpError> {
+    Ok(totp(secret, account)?.get_url())
+}
+
+/// Check a 6-digit code against `secret` at `unix_time`, allowing one step of
+/// clock skew either way
+pub fn verify_code(secret: &str, code: &str, unix_time: u64) -> Result<bool, TotpError> {
+    let code = code.trim();
+    if code.len() != 6 || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return Ok(false);
+    }
+    Ok(totp(secret, "")?.check(code, unix_time))
+}
+
+/// Current time for [`verify_code`]
+pub fn now() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 test secret "12345678901234567890"
+    const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    fn code_at(time: u64) -> String {
+        totp(SECRET, "admin").unwrap().generate(time)
+    }
+
+    #[test]
+    fn test_verify_code_rfc_vector() {
+        // RFC 6238 appendix B, truncated to 6 digits
+        assert_eq!(code_at(59), "287082");
+        assert!(verify_code(SECRET, "287082", 59).unwrap());
+        assert!(verify_code(SECRET, " 287082 ", 59).unwrap());
+    }
+
+    #[test]
+    fn test_verify_code_clock_skew() {
+        let now = 1_700_000_000;
+        let current = code_at(now);
+        let previous = code_at(now - 30);
+        let next = code_at(now + 30);
+
+        assert!(verify_code(SECRET, &current, now).unwrap());
+        assert!(verify_code(SECRET, &previous, now).unwrap());
+        assert!(verify_code(SECRET, &next, now).unwrap());
+
+        assert!(!verify_code(SECRET, &code_at(now - 60), now).unwrap());
+        assert!(!verify_code(SECRET, &code_at(now + 60), now).unwrap());
+    }
+
+    #[test]
+    fn test_verify_code_rejects_malformed() {
+        assert!(!verify_code(SECRET, "", 59).unwrap());
+        assert!(!verify_code(SECRET, "28708", 59).unwrap());
+        assert!(!verify_code(SECRET, "2870822", 59).unwrap());
+        assert!(!verify_code(SECRET, "28708a", 59).unwrap());
+    }
+
+    #[test]
+    fn test_secret_encryption_round_trip() {
+        let key: TotpKey = "00".repeat(32).parse().unwrap();
+        let secret = generate_secret();
+
+        let stored = key.encrypt(&secret).unwrap();
+        assert!(!stored.contains(&secret));
+        assert_eq!(key.decrypt(&stored).unwrap(), secret);
+
+        let other: TotpKey = "11".repeat(32).parse().unwrap();
+        assert!(other.decrypt(&stored).is_err());
+        assert!(key.decrypt("abcd").is_err());
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = otpauth_uri(SECRET, "ad:min").unwrap();
+        assert!(uri.starts_with("otpauth://totp/"));
+        assert!(uri.contains(&format!("secret={}", SECRET)));
+        assert!(uri.contains("issuer=RegelRecht"));
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert!("00".repeat(32).parse::<TotpKey>().is_ok());
+        assert!("00".repeat(16).parse::<TotpKey>().is_err());
+        assert!("zz".repeat(32).parse::<TotpKey>().is_err());
+    }
+}
@@ -1,7 +1,8 @@
 //! Input validation module
 
-use crate::models::{CreateSubmission, DocumentClassification};
+use crate::models::{CreateSubmission, DocumentClassification, FieldError, UpdateSubmission};
 use thiserror::Error;
+use url::Url;
 
 #[derive(Debug, Error)]
 #[allow(dead_code)]
@@ -15,6 +16,9 @@ pub enum ValidationError {
     #[error("Field '{field}' is too short (min {min} characters)")]
     TooShort { field: String, min: usize },
 
+    #[error("Field '{field}' can no longer be changed after submission")]
+    Locked { field: String },
+
     #[error("Invalid email format")]
     InvalidEmail,
 
@@ -32,8 +36,50 @@ pub enum ValidationError {
 
     #[error("File too large (max {max_mb} MB)")]
     FileTooLarge { max_mb: usize },
+
+    #[error("File content does not match declared type {declared} (detected {detected})")]
+    MimeMismatch { declared: String, detected: String },
+
+    #[error("File is corrupt or has no pages")]
+    CorruptFile,
+
+    #[error(
+        "Password must contain at least three of: lowercase letters, uppercase letters, digits, symbols"
+    )]
+    WeakPassword,
+}
+
+impl ValidationError {
+    /// The field and a stable code for this error, for clients to act on
+    ///
+    /// Errors that do not name a field are attributed to the input they are
+    /// checked against: the submitter email, the formal law URL or the file.
+    pub fn to_detail(&self) -> FieldError {
+        let (field, code) = match self {
+            Self::Required { field } => (field.as_str(), "required"),
+            Self::TooLong { field, .. } => (field.as_str(), "too_long"),
+            Self::TooShort { field, .. } => (field.as_str(), "too_short"),
+            Self::Locked { field } => (field.as_str(), "locked"),
+            Self::InvalidEmail => ("submitter_email", "invalid_email"),
+            Self::InvalidUrl => ("external_url", "invalid_url"),
+            Self::InvalidSlug => ("slug", "invalid_slug"),
+            Self::RestrictedDocument => ("classification", "restricted_document"),
+            Self::InvalidFileType { .. } => ("file", "invalid_file_type"),
+            Self::FileTooLarge { .. } => ("file", "file_too_large"),
+            Self::MimeMismatch { .. } => ("file", "mime_mismatch"),
+            Self::CorruptFile => ("file", "corrupt_file"),
+            Self::WeakPassword => ("password", "weak_password"),
+        };
+        FieldError::new(field, code, self.to_string())
+    }
 }
 
+/// Minimum length for admin passwords
+pub const MIN_PASSWORD_LENGTH: usize = 12;
+
+/// Upper bound to keep Argon2 hashing cheap for absurd inputs
+const MAX_PASSWORD_LENGTH: usize = 128;
+
 /// Validate a submission creation request
 pub fn validate_create_submission(input: &CreateSubmission) -> Result<(), ValidationError> {
     // Submitter name
@@ -82,8 +128,103 @@ pub fn validate_create_submission(input: &CreateSubmission) -> Result<(), Valida
     Ok(())
 }
 
+/// Validate an uploader's change to submission metadata
+///
+/// Same rules as for a new submission. Once submitted, `submitter_name` and
+/// `organization` record who made the submission and can no longer change.
+pub fn validate_metadata_update(
+    input: &UpdateSubmission,
+    is_draft: bool,
+) -> Result<(), ValidationError> {
+    for (field, value) in [
+        ("submitter_name", &input.submitter_name),
+        ("organization", &input.organization),
+    ] {
+        let Some(value) = value else {
+            continue;
+        };
+        if !is_draft {
+            return Err(ValidationError::Locked {
+                field: field.to_string(),
+            });
+        }
+        if value.trim().is_empty() {
+            return Err(ValidationError::Required {
+                field: field.to_string(),
+            });
+        }
+        if value.len() > 255 {
+            return Err(ValidationError::TooLong {
+                field: field.to_string(),
+                max: 255,
+            });
+        }
+    }
+
+    if let Some(ref email) = input.submitter_email {
+        if !email.is_empty() && !is_valid_email(email) {
+            return Err(ValidationError::InvalidEmail);
+        }
+    }
+
+    if let Some(ref dept) = input.organization_department {
+        if dept.len() > 255 {
+            return Err(ValidationError::TooLong {
+                field: "organization_department".to_string(),
+                max: 255,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate a new admin password against the password policy
+///
+/// Requires at least `MIN_PASSWORD_LENGTH` characters and three of the four
+/// character classes (lowercase, uppercase, digits, symbols).
+pub fn validate_password(password: &str) -> Result<(), ValidationError> {
+    let length = password.chars().count();
+    if length < MIN_PASSWORD_LENGTH {
+        return Err(ValidationError::TooShort {
+            field: "new_password".to_string(),
+            min: MIN_PASSWORD_LENGTH,
+        });
+    }
+    if length > MAX_PASSWORD_LENGTH {
+        return Err(ValidationError::TooLong {
+            field: "new_password".to_string(),
+            max: MAX_PASSWORD_LENGTH,
+        });
+    }
+
+    let classes = [
+        password.chars().any(|c| c.is_lowercase()),
+        password.chars().any(|c| c.is_uppercase()),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ];
+    if classes.iter().filter(|&&present| present).count() < 3 {
+        return Err(ValidationError::WeakPassword);
+    }
+
+    Ok(())
+}
+
+/// Hosts that formal law links are expected to point to
+const FORMAL_LAW_HOSTS: [&str; 2] = ["wetten.overheid.nl", "www.wetten.overheid.nl"];
+
+/// Whether a parsed URL points to wetten.overheid.nl
+pub fn is_formal_law_host(url: &Url) -> bool {
+    url.host_str()
+        .is_some_and(|host| FORMAL_LAW_HOSTS.contains(&host))
+}
+
 /// Validate an external URL (for wetten.overheid.nl)
-pub fn validate_external_url(url: &str) -> Result<(), ValidationError> {
+///
+/// With `strict_domain` set, links to any other host are rejected; otherwise
+/// they are only logged.
+pub fn validate_external_url(url: &str, strict_domain: bool) -> Result<(), ValidationError> {
     if url.trim().is_empty() {
         return Err(ValidationError::Required {
             field: "external_url".to_string(),
@@ -91,13 +232,16 @@ pub fn validate_external_url(url: &str) -> Result<(), ValidationError> {
     }
 
     // Must be a valid URL
-    if !url.starts_with("https://") && !url.starts_with("http://") {
+    let parsed = Url::parse(url).map_err(|_| ValidationError::InvalidUrl)?;
+    if parsed.scheme() != "https" && parsed.scheme() != "http" {
         return Err(ValidationError::InvalidUrl);
     }
 
     // Should be from wetten.overheid.nl for formal laws
-    if !url.contains("wetten.overheid.nl") {
-        // Allow for now but could restrict in the future
+    if !is_formal_law_host(&parsed) {
+        if strict_domain {
+            return Err(ValidationError::InvalidUrl);
+        }
         tracing::warn!("External URL is not from wetten.overheid.nl: {}", url);
     }
 
@@ -139,11 +283,50 @@ pub fn validate_classification_for_upload(
     Ok(())
 }
 
-/// Validate uploaded file
+/// MIME types accepted for uploads when ALLOWED_MIME_TYPES is unset
+pub const DEFAULT_ALLOWED_MIME_TYPES: &[&str] = &[
+    "application/pdf",
+    "application/msword",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.ms-excel",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.ms-powerpoint",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.oasis.opendocument.text",
+    "application/vnd.oasis.opendocument.spreadsheet",
+    "application/vnd.oasis.opendocument.presentation",
+    "application/rtf",
+    "text/plain",
+    "text/markdown",
+    "text/csv",
+];
+
+/// Whether browsers could run scripts in a file of this type when it is
+/// opened from our origin, as with HTML, XML (including SVG) and JavaScript
+pub fn is_dangerous_mime_type(mime_type: &str) -> bool {
+    let essence = mime_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    matches!(
+        essence.as_str(),
+        "text/html" | "application/xhtml+xml" | "text/xml" | "application/xml"
+    ) || essence.ends_with("+xml")
+        || essence.contains("javascript")
+        || essence.contains("ecmascript")
+}
+
+/// Validate uploaded file against the size limit and the MIME type allowlist
+///
+/// HTML, XML and script types are refused even when allowed, to prevent XSS
+/// via stored files.
 pub fn validate_file_upload(
     mime_type: &str,
     file_size: usize,
     max_size_bytes: usize,
+    allowed_types: &[String],
 ) -> Result<(), ValidationError> {
     // Check file size
     if file_size > max_size_bytes {
@@ -152,23 +335,7 @@ pub fn validate_file_upload(
         });
     }
 
-    // Allowed MIME types (no HTML/XML to prevent XSS via stored files)
-    let allowed_types = [
-        "application/pdf",
-        "application/msword",
-        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
-        "application/vnd.ms-excel",
-        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
-        "application/vnd.ms-powerpoint",
-        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
-        "application/vnd.oasis.opendocument.text",
-        "application/rtf",
-        "text/plain",
-        "text/markdown",
-        "text/csv",
-    ];
-
-    if !allowed_types.contains(&mime_type) {
+    if is_dangerous_mime_type(mime_type) || !allowed_types.iter().any(|t| t == mime_type) {
         return Err(ValidationError::InvalidFileType {
             mime_type: mime_type.to_string(),
         });
@@ -177,6 +344,94 @@ pub fn validate_file_upload(
     Ok(())
 }
 
+/// Leading bytes of the binary formats we accept, with the type they identify
+const MAGIC_SIGNATURES: &[(&[u8], &str)] = &[
+    (b"%PDF-", "application/pdf"),
+    // OOXML (docx/xlsx/pptx) and OpenDocument files are ZIP containers
+    (b"PK\x03\x04", "application/zip"),
+    // Legacy Office formats (doc/xls/ppt) use the OLE2 compound file format
+    (
+        &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1],
+        "application/x-ole-storage",
+    ),
+    (b"{\\rtf", "application/rtf"),
+    // Executables, only detected to report a meaningful mismatch
+    (b"MZ", "application/x-msdownload"),
+    (b"\x7fELF", "application/x-elf"),
+];
+
+/// Detect the file type from its leading bytes
+fn detect_magic_type(data: &[u8]) -> Option<&'static str> {
+    MAGIC_SIGNATURES
+        .iter()
+        .find(|(magic, _)| data.starts_with(magic))
+        .map(|(_, detected)| *detected)
+}
+
+/// Check that `data` is UTF-8, tolerating a multi-byte character cut off at the end
+fn is_utf8_text(data: &[u8]) -> bool {
+    match std::str::from_utf8(data) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Validate uploaded file content against its declared MIME type
+///
+/// The Content-Type of a multipart field is chosen by the client, so we sniff
+/// the leading bytes to make sure e.g. an executable can't be uploaded while
+/// claiming to be a PDF. Text formats have no signature and must be UTF-8.
+pub fn validate_file_magic(data: &[u8], declared_mime: &str) -> Result<(), ValidationError> {
+    let expected = match declared_mime {
+        "application/pdf" => "application/pdf",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        | "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        | "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+        | "application/vnd.oasis.opendocument.text"
+        | "application/vnd.oasis.opendocument.spreadsheet"
+        | "application/vnd.oasis.opendocument.presentation" => "application/zip",
+        "application/msword" | "application/vnd.ms-excel" | "application/vnd.ms-powerpoint" => {
+            "application/x-ole-storage"
+        }
+        "application/rtf" => "application/rtf",
+        "text/plain" | "text/markdown" | "text/csv" => {
+            if is_utf8_text(data) {
+                return Ok(());
+            }
+            return Err(ValidationError::MimeMismatch {
+                declared: declared_mime.to_string(),
+                detected: detect_magic_type(data)
+                    .unwrap_or("application/octet-stream")
+                    .to_string(),
+            });
+        }
+        // No known signature for this type
+        _ => return Ok(()),
+    };
+
+    match detect_magic_type(data) {
+        Some(detected) if detected == expected => Ok(()),
+        detected => Err(ValidationError::MimeMismatch {
+            declared: declared_mime.to_string(),
+            detected: detected.unwrap_or("application/octet-stream").to_string(),
+        }),
+    }
+}
+
+/// Check that a PDF has a readable trailer and cross-reference table and at least one page
+///
+/// Parses the whole document, so call it off the async runtime for large files.
+pub fn validate_pdf_structure(data: &[u8]) -> Result<(), ValidationError> {
+    match lopdf::Document::load_mem(data) {
+        Ok(doc) if doc.page_iter().next().is_some() => Ok(()),
+        Ok(_) => Err(ValidationError::CorruptFile),
+        Err(e) => {
+            tracing::debug!("PDF failed to parse: {}", e);
+            Err(ValidationError::CorruptFile)
+        }
+    }
+}
+
 /// Dangerous file extensions that could be executed if misconfigured
 const DANGEROUS_EXTENSIONS: &[&str] = &[
     // Server-side scripting
@@ -233,7 +488,15 @@ const DANGEROUS_EXTENSIONS: &[&str] = &[
 ///
 /// We check for dangerous extensions at the end of the filename
 /// and for double extensions (e.g., "malware.php.pdf")
-pub fn validate_filename_extensions(filename: &str) -> Result<(), ValidationError> {
+///
+/// When `allowed_extensions` is non-empty it acts as the primary gate: the
+/// filename must have an extension and it must be in the list. The dangerous
+/// extension blacklist is always checked first as a backstop, so a
+/// misconfigured allowlist containing e.g. "php" still rejects "shell.php".
+pub fn validate_filename_extensions(
+    filename: &str,
+    allowed_extensions: &[String],
+) -> Result<(), ValidationError> {
     let lower = filename.to_lowercase();
 
     for ext in DANGEROUS_EXTENSIONS {
@@ -252,19 +515,131 @@ pub fn validate_filename_extensions(filename: &str) -> Result<(), ValidationErro
         }
     }
 
+    if !allowed_extensions.is_empty() {
+        let extension = lower
+            .rsplit_once('.')
+            .map(|(_, ext)| ext)
+            .filter(|ext| !ext.is_empty());
+        match extension {
+            Some(ext) if allowed_extensions.iter().any(|allowed| allowed == ext) => {}
+            Some(ext) => {
+                return Err(ValidationError::InvalidFileType {
+                    mime_type: format!("extension .{} is not allowed", ext),
+                });
+            }
+            None => {
+                return Err(ValidationError::InvalidFileType {
+                    mime_type: "filename has no extension".to_string(),
+                });
+            }
+        }
+    }
     Ok(())
 }
 
-/// Simple email validation
-fn is_valid_email(email: &str) -> bool {
-    // Basic check: contains @ and at least one .
-    let parts: Vec<&str> = email.split('@').collect();
-    if parts.len() != 2 {
+/// Longest address that fits an SMTP forward-path
+const MAX_EMAIL_LENGTH: usize = 254;
+const MAX_LOCAL_PART_LENGTH: usize = 64;
+const MAX_DOMAIN_LENGTH: usize = 253;
+const MAX_DOMAIN_LABEL_LENGTH: usize = 63;
+
+/// Email address validation
+///
+/// Accepts dot-atom local parts (including plus-tags and non-ASCII letters)
+/// and quoted local parts, followed by a domain of at least two labels with
+/// an alphabetic top-level domain. No DNS lookups are made.
+pub fn is_valid_email(email: &str) -> bool {
+    if email.len() > MAX_EMAIL_LENGTH {
+        return false;
+    }
+    // A quoted local part may itself contain '@'
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+
+    let valid = is_valid_local_part(local) && is_valid_email_domain(domain);
+    #[cfg(feature = "mx-check")]
+    let valid = valid && has_mx_ready_domain(domain);
+    valid
+}
+
+fn is_valid_local_part(local: &str) -> bool {
+    if local.is_empty() || local.len() > MAX_LOCAL_PART_LENGTH {
+        return false;
+    }
+
+    if let Some(quoted) = local
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                // The guard consumes the escaped character
+                '\\' if chars.next().is_none() => return false,
+                '"' => return false,
+                c if c.is_control() => return false,
+                _ => {}
+            }
+        }
+        return true;
+    }
+
+    local.split('.').all(|atom| {
+        !atom.is_empty()
+            && atom
+                .chars()
+                .all(|c| c.is_alphanumeric() || "!#$%&'*+/=?^_`{|}~-".contains(c))
+    })
+}
+
+fn is_valid_email_domain(domain: &str) -> bool {
+    if domain.len() > MAX_DOMAIN_LENGTH {
         return false;
     }
-    let (local, domain) = (parts[0], parts[1]);
 
-    !local.is_empty() && !domain.is_empty() && domain.contains('.') && domain.len() > 2
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return false;
+    }
+    let labels_valid = labels.iter().all(|label| {
+        !label.is_empty()
+            && label.len() <= MAX_DOMAIN_LABEL_LENGTH
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+    });
+
+    // The TLD is alphabetic, or punycode for internationalized TLDs
+    let tld = labels[labels.len() - 1];
+    let tld_valid = tld.chars().count() >= 2
+        && (tld.chars().all(char::is_alphabetic) || tld.to_ascii_lowercase().starts_with("xn--"));
+
+    labels_valid && tld_valid
+}
+
+/// Top-level and second-level names reserved by RFC 2606 and RFC 6761
+#[cfg(feature = "mx-check")]
+const NON_ROUTABLE_DOMAINS: [&str; 8] = [
+    "test",
+    "example",
+    "invalid",
+    "localhost",
+    "local",
+    "example.com",
+    "example.net",
+    "example.org",
+];
+
+/// Whether the domain could have mail exchangers at all
+///
+/// A format check only: reserved names are rejected without a DNS lookup.
+#[cfg(feature = "mx-check")]
+fn has_mx_ready_domain(domain: &str) -> bool {
+    let domain = domain.to_lowercase();
+    !NON_ROUTABLE_DOMAINS
+        .iter()
+        .any(|reserved| domain == *reserved || domain.ends_with(&format!(".{}", reserved)))
 }
 
 #[cfg(test)]
@@ -273,11 +648,72 @@ mod tests {
 
     #[test]
     fn test_email_validation() {
-        assert!(is_valid_email("test@example.com"));
+        assert!(is_valid_email("jan@gemeente.nl"));
         assert!(is_valid_email("user.name@domain.nl"));
         assert!(!is_valid_email("invalid"));
         assert!(!is_valid_email("@domain.com"));
         assert!(!is_valid_email("user@"));
+        assert!(!is_valid_email("a@b@domain.nl"));
+        assert!(!is_valid_email("user..name@domain.nl"));
+        assert!(!is_valid_email(".user@domain.nl"));
+        assert!(!is_valid_email("user name@domain.nl"));
+    }
+
+    #[test]
+    fn test_email_domain_structure() {
+        assert!(is_valid_email("user@sub.domain.nl"));
+        assert!(!is_valid_email("user@localhost"));
+        assert!(!is_valid_email("user@domain"));
+        assert!(!is_valid_email("user@domain.n"));
+        assert!(!is_valid_email("user@domain.123"));
+        assert!(!is_valid_email("user@domain..nl"));
+        assert!(!is_valid_email("user@-domain.nl"));
+        assert!(!is_valid_email("user@domain-.nl"));
+        assert!(!is_valid_email("user@dom_ain.nl"));
+        assert!(is_valid_email("user@xn--mnchen-3ya.xn--p1ai"));
+    }
+
+    #[test]
+    fn test_email_plus_addressing_and_quoted_local_parts() {
+        assert!(is_valid_email("user+tag@sub.domain.nl"));
+        assert!(is_valid_email("user+tag+more@domain.nl"));
+        assert!(is_valid_email("\"jan de vries\"@domain.nl"));
+        assert!(is_valid_email("\"a@b\"@domain.nl"));
+        assert!(is_valid_email("\"quote\\\"inside\"@domain.nl"));
+        assert!(!is_valid_email("\"unterminated@domain.nl"));
+        assert!(!is_valid_email("\"bad\"quote\"@domain.nl"));
+    }
+
+    #[test]
+    fn test_email_internationalized_local_parts() {
+        assert!(is_valid_email("jürgen@domain.de"));
+        assert!(is_valid_email("chloé.dupont@domain.fr"));
+        assert!(is_valid_email("用户@例子.广告"));
+    }
+
+    #[test]
+    fn test_email_length_limits() {
+        let local = "a".repeat(64);
+        assert!(is_valid_email(&format!("{}@domain.nl", local)));
+        assert!(!is_valid_email(&format!("a{}@domain.nl", local)));
+
+        let label = "a".repeat(63);
+        assert!(is_valid_email(&format!("user@{}.nl", label)));
+        assert!(!is_valid_email(&format!("user@a{}.nl", label)));
+
+        let domain = format!("{}.nl", [label.as_str(); 4].join("."));
+        assert!(domain.len() > MAX_DOMAIN_LENGTH);
+        assert!(!is_valid_email(&format!("user@{}", domain)));
+    }
+
+    #[cfg(feature = "mx-check")]
+    #[test]
+    fn test_email_mx_ready_domain() {
+        assert!(is_valid_email("jan@gemeente.nl"));
+        assert!(!is_valid_email("jan@example.com"));
+        assert!(!is_valid_email("jan@mail.example.org"));
+        assert!(!is_valid_email("jan@server.test"));
+        assert!(!is_valid_email("jan@printer.local"));
     }
 
     #[test]
@@ -293,7 +729,7 @@ mod tests {
     fn test_validate_create_submission_valid() {
         let input = CreateSubmission {
             submitter_name: "Jan de Vries".to_string(),
-            submitter_email: Some("jan@example.com".to_string()),
+            submitter_email: Some("jan@gemeente.nl".to_string()),
             organization: "Gemeente Amsterdam".to_string(),
             organization_department: Some("ICT".to_string()),
         };
@@ -342,6 +778,103 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_validation_detail_names_field_and_code() {
+        let too_long_org = CreateSubmission {
+            submitter_name: "Jan".to_string(),
+            submitter_email: None,
+            organization: "x".repeat(256),
+            organization_department: None,
+        };
+        let detail = validate_create_submission(&too_long_org)
+            .unwrap_err()
+            .to_detail();
+        assert_eq!(detail.field, "organization");
+        assert_eq!(detail.code, "too_long");
+
+        let invalid_email = CreateSubmission {
+            submitter_email: Some("not-an-email".to_string()),
+            organization: "Org".to_string(),
+            ..too_long_org
+        };
+        let detail = validate_create_submission(&invalid_email)
+            .unwrap_err()
+            .to_detail();
+        assert_eq!(detail.field, "submitter_email");
+        assert_eq!(detail.code, "invalid_email");
+
+        // The flat message stays for clients that only read `error`
+        let body = serde_json::to_value(crate::models::ApiResponse::<()>::invalid(detail)).unwrap();
+        assert_eq!(body["error"], "Invalid email format");
+        assert_eq!(
+            body["details"],
+            serde_json::json!([{
+                "field": "submitter_email",
+                "code": "invalid_email",
+                "message": "Invalid email format",
+            }])
+        );
+    }
+
+    fn metadata_update() -> UpdateSubmission {
+        UpdateSubmission {
+            submitter_name: None,
+            submitter_email: None,
+            organization: None,
+            organization_department: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_metadata_update_after_submission() {
+        let allowed = UpdateSubmission {
+            submitter_email: Some("jan@example.org".to_string()),
+            organization_department: Some("Directie Wetgeving".to_string()),
+            notes: Some("Tikfout in e-mailadres hersteld".to_string()),
+            ..metadata_update()
+        };
+        assert!(validate_metadata_update(&allowed, false).is_ok());
+
+        for locked in [
+            UpdateSubmission {
+                submitter_name: Some("Jan".to_string()),
+                ..metadata_update()
+            },
+            UpdateSubmission {
+                organization: Some("Andere Org".to_string()),
+                ..metadata_update()
+            },
+        ] {
+            assert!(validate_metadata_update(&locked, true).is_ok());
+            assert!(matches!(
+                validate_metadata_update(&locked, false),
+                Err(ValidationError::Locked { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_validate_metadata_update_rules() {
+        let bad_email = UpdateSubmission {
+            submitter_email: Some("not-an-email".to_string()),
+            ..metadata_update()
+        };
+        assert!(matches!(
+            validate_metadata_update(&bad_email, false),
+            Err(ValidationError::InvalidEmail)
+        ));
+
+        let blank_name = UpdateSubmission {
+            submitter_name: Some("  ".to_string()),
+            ..metadata_update()
+        };
+        assert!(matches!(
+            validate_metadata_update(&blank_name, true),
+            Err(ValidationError::Required { .. })
+        ));
+    }
+
     #[test]
     fn test_validate_classification_public() {
         assert!(validate_classification_for_upload(DocumentClassification::Public).is_ok());
@@ -360,15 +893,47 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_validate_password_policy() {
+        assert!(validate_password("Correct-Horse-42").is_ok());
+        assert!(validate_password("lowercase and 123").is_ok());
+
+        assert!(matches!(
+            validate_password("Sh0rt!"),
+            Err(ValidationError::TooShort { .. })
+        ));
+        assert!(matches!(
+            validate_password(&"Aa1!".repeat(40)),
+            Err(ValidationError::TooLong { .. })
+        ));
+        assert!(matches!(
+            validate_password("alllowercaseletters"),
+            Err(ValidationError::WeakPassword)
+        ));
+        assert!(matches!(
+            validate_password("lowercase123456"),
+            Err(ValidationError::WeakPassword)
+        ));
+    }
+
     #[test]
     fn test_validate_external_url_valid() {
-        assert!(validate_external_url("https://wetten.overheid.nl/BWBR0001840/2024-01-01").is_ok());
+        for strict in [false, true] {
+            assert!(validate_external_url(
+                "https://wetten.overheid.nl/BWBR0001840/2024-01-01",
+                strict
+            )
+            .is_ok());
+            assert!(
+                validate_external_url("https://WETTEN.overheid.nl/BWBR0001840", strict).is_ok()
+            );
+        }
     }
 
     #[test]
     fn test_validate_external_url_empty() {
         assert!(matches!(
-            validate_external_url("  "),
+            validate_external_url("  ", false),
             Err(ValidationError::Required { .. })
         ));
     }
@@ -376,20 +941,69 @@ mod tests {
     #[test]
     fn test_validate_external_url_no_protocol() {
         assert!(matches!(
-            validate_external_url("wetten.overheid.nl/test"),
+            validate_external_url("wetten.overheid.nl/test", false),
+            Err(ValidationError::InvalidUrl)
+        ));
+        assert!(matches!(
+            validate_external_url("ftp://wetten.overheid.nl/test", false),
             Err(ValidationError::InvalidUrl)
         ));
     }
 
+    #[test]
+    fn test_validate_external_url_strict_domain() {
+        let other = "https://lokaleregelgeving.overheid.nl/CVDR1234";
+        assert!(validate_external_url(other, false).is_ok());
+        assert!(matches!(
+            validate_external_url(other, true),
+            Err(ValidationError::InvalidUrl)
+        ));
+
+        // The host is parsed, so mentioning the domain elsewhere is not enough
+        for spoofed in [
+            "https://evil.com/?x=wetten.overheid.nl",
+            "https://wetten.overheid.nl.evil.com/BWBR0001840",
+            "https://wetten.overheid.nl@evil.com/BWBR0001840",
+            "https://evil.com/wetten.overheid.nl/BWBR0001840",
+        ] {
+            assert!(
+                matches!(
+                    validate_external_url(spoofed, true),
+                    Err(ValidationError::InvalidUrl)
+                ),
+                "{}",
+                spoofed
+            );
+        }
+    }
+
+    fn default_mime_types() -> Vec<String> {
+        DEFAULT_ALLOWED_MIME_TYPES
+            .iter()
+            .map(|t| t.to_string())
+            .collect()
+    }
+
     #[test]
     fn test_validate_file_upload_valid_pdf() {
-        assert!(validate_file_upload("application/pdf", 1024, 50 * 1024 * 1024).is_ok());
+        assert!(validate_file_upload(
+            "application/pdf",
+            1024,
+            50 * 1024 * 1024,
+            &default_mime_types()
+        )
+        .is_ok());
     }
 
     #[test]
     fn test_validate_file_upload_too_large() {
         assert!(matches!(
-            validate_file_upload("application/pdf", 100 * 1024 * 1024, 50 * 1024 * 1024),
+            validate_file_upload(
+                "application/pdf",
+                100 * 1024 * 1024,
+                50 * 1024 * 1024,
+                &default_mime_types()
+            ),
             Err(ValidationError::FileTooLarge { .. })
         ));
     }
@@ -397,32 +1011,202 @@ mod tests {
     #[test]
     fn test_validate_file_upload_invalid_type() {
         assert!(matches!(
-            validate_file_upload("application/zip", 1024, 50 * 1024 * 1024),
+            validate_file_upload(
+                "application/zip",
+                1024,
+                50 * 1024 * 1024,
+                &default_mime_types()
+            ),
+            Err(ValidationError::InvalidFileType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_file_upload_configured_types() {
+        let png = "image/png";
+        let mut allowed = default_mime_types();
+        assert!(validate_file_upload(png, 1024, 50 * 1024 * 1024, &allowed).is_err());
+        allowed.push(png.to_string());
+        assert!(validate_file_upload(png, 1024, 50 * 1024 * 1024, &allowed).is_ok());
+
+        // Allowing HTML by mistake does not let it through
+        allowed.push("text/html".to_string());
+        assert!(matches!(
+            validate_file_upload("text/html", 1024, 50 * 1024 * 1024, &allowed),
             Err(ValidationError::InvalidFileType { .. })
         ));
     }
 
+    #[test]
+    fn test_validate_file_upload_opendocument_types() {
+        for mime_type in [
+            "application/vnd.oasis.opendocument.text",
+            "application/vnd.oasis.opendocument.spreadsheet",
+            "application/vnd.oasis.opendocument.presentation",
+        ] {
+            assert!(
+                validate_file_upload(mime_type, 1024, 50 * 1024 * 1024, &default_mime_types())
+                    .is_ok(),
+                "{} is refused",
+                mime_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_dangerous_mime_types() {
+        assert!(is_dangerous_mime_type("text/html"));
+        assert!(is_dangerous_mime_type("Text/HTML; charset=utf-8"));
+        assert!(is_dangerous_mime_type("image/svg+xml"));
+        assert!(is_dangerous_mime_type("application/xml"));
+        assert!(is_dangerous_mime_type("text/javascript"));
+        assert!(!is_dangerous_mime_type("application/pdf"));
+        assert!(!is_dangerous_mime_type(
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        ));
+    }
+
+    #[test]
+    fn test_validate_file_magic_pdf() {
+        assert!(validate_file_magic(b"%PDF-1.7\n%\xE2\xE3", "application/pdf").is_ok());
+    }
+
+    const SINGLE_PAGE_PDF: &[u8] = include_bytes!("../../tests/fixtures/single_page.pdf");
+
+    #[test]
+    fn test_validate_pdf_structure_single_page() {
+        assert!(validate_pdf_structure(SINGLE_PAGE_PDF).is_ok());
+    }
+
+    #[test]
+    fn test_validate_pdf_structure_truncated() {
+        let truncated = &SINGLE_PAGE_PDF[..SINGLE_PAGE_PDF.len() / 2];
+        // Still passes the signature check, the trailer is what is missing
+        assert!(validate_file_magic(truncated, "application/pdf").is_ok());
+        assert!(matches!(
+            validate_pdf_structure(truncated),
+            Err(ValidationError::CorruptFile)
+        ));
+    }
+
+    #[test]
+    fn test_validate_pdf_structure_without_pages() {
+        // Same length, so the xref offsets stay valid
+        let (kids, no_kids) = (b"/Kids [3 0 R] /Count 1", b"/Kids [     ] /Count 0");
+        let at = SINGLE_PAGE_PDF
+            .windows(kids.len())
+            .position(|w| w == kids)
+            .unwrap();
+        let mut empty = SINGLE_PAGE_PDF.to_vec();
+        empty[at..at + kids.len()].copy_from_slice(no_kids);
+        assert!(matches!(
+            validate_pdf_structure(&empty),
+            Err(ValidationError::CorruptFile)
+        ));
+    }
+
+    #[test]
+    fn test_validate_file_magic_executable_disguised_as_pdf() {
+        // PE header of a Windows executable renamed to .pdf
+        let pe = b"MZ\x90\x00\x03\x00\x00\x00\x04\x00\x00\x00\xff\xff\x00\x00";
+        match validate_file_magic(pe, "application/pdf") {
+            Err(ValidationError::MimeMismatch { declared, detected }) => {
+                assert_eq!(declared, "application/pdf");
+                assert_eq!(detected, "application/x-msdownload");
+            }
+            other => panic!("expected MimeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_file_magic_zip_based_formats() {
+        let zip = b"PK\x03\x04\x14\x00\x06\x00";
+        assert!(validate_file_magic(
+            zip,
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        )
+        .is_ok());
+        assert!(validate_file_magic(zip, "application/vnd.oasis.opendocument.text").is_ok());
+        assert!(validate_file_magic(zip, "application/vnd.oasis.opendocument.spreadsheet").is_ok());
+        assert!(
+            validate_file_magic(zip, "application/vnd.oasis.opendocument.presentation").is_ok()
+        );
+        let ole = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+        assert!(
+            validate_file_magic(&ole, "application/vnd.oasis.opendocument.spreadsheet").is_err()
+        );
+        assert!(validate_file_magic(zip, "application/pdf").is_err());
+    }
+
+    #[test]
+    fn test_validate_file_magic_legacy_office_and_rtf() {
+        let ole = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1, 0x00];
+        assert!(validate_file_magic(&ole, "application/msword").is_ok());
+        assert!(validate_file_magic(b"{\\rtf1\\ansi", "application/rtf").is_ok());
+        assert!(validate_file_magic(b"plain words", "application/rtf").is_err());
+    }
+
+    #[test]
+    fn test_validate_file_magic_text_requires_utf8() {
+        assert!(
+            validate_file_magic("naam,organisatie\nJosé,Gemeente".as_bytes(), "text/csv").is_ok()
+        );
+        assert!(validate_file_magic(b"# Titel", "text/markdown").is_ok());
+        assert!(matches!(
+            validate_file_magic(b"\x7fELF\x02\x01\x01\x00\xff\xfe", "text/plain"),
+            Err(ValidationError::MimeMismatch { .. })
+        ));
+    }
+
     #[test]
     fn test_validate_filename_extensions_safe() {
-        assert!(validate_filename_extensions("document.pdf").is_ok());
-        assert!(validate_filename_extensions("report.docx").is_ok());
-        assert!(validate_filename_extensions("notes.txt").is_ok());
-        assert!(validate_filename_extensions("readme.md").is_ok());
+        assert!(validate_filename_extensions("document.pdf", &[]).is_ok());
+        assert!(validate_filename_extensions("report.docx", &[]).is_ok());
+        assert!(validate_filename_extensions("notes.txt", &[]).is_ok());
+        assert!(validate_filename_extensions("readme.md", &[]).is_ok());
+        assert!(validate_filename_extensions("begroting.ods", &[]).is_ok());
+        assert!(validate_filename_extensions("presentatie.odp", &[]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_filename_extensions_allowlist() {
+        let allowed: Vec<String> = ["pdf", "docx", "md"]
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+
+        // Allowed (case-insensitive)
+        assert!(validate_filename_extensions("beleid.pdf", &allowed).is_ok());
+        assert!(validate_filename_extensions("Werkinstructie.DOCX", &allowed).is_ok());
+
+        // Disallowed extension
+        assert!(validate_filename_extensions("data.xlsx", &allowed).is_err());
+
+        // Missing extension
+        assert!(validate_filename_extensions("README", &allowed).is_err());
+        assert!(validate_filename_extensions("trailing.", &allowed).is_err());
+    }
+
+    #[test]
+    fn test_validate_filename_extensions_blacklist_precedes_allowlist() {
+        let allowed = vec!["php".to_string(), "pdf".to_string()];
+        assert!(validate_filename_extensions("shell.php", &allowed).is_err());
+        assert!(validate_filename_extensions("document.php.pdf", &allowed).is_err());
     }
 
     #[test]
     fn test_validate_filename_extensions_dangerous() {
         // Direct dangerous extensions
-        assert!(validate_filename_extensions("script.php").is_err());
-        assert!(validate_filename_extensions("shell.sh").is_err());
-        assert!(validate_filename_extensions("malware.exe").is_err());
+        assert!(validate_filename_extensions("script.php", &[]).is_err());
+        assert!(validate_filename_extensions("shell.sh", &[]).is_err());
+        assert!(validate_filename_extensions("malware.exe", &[]).is_err());
 
         // Double extensions (hidden dangerous extension)
-        assert!(validate_filename_extensions("document.php.pdf").is_err());
-        assert!(validate_filename_extensions("image.exe.jpg").is_err());
+        assert!(validate_filename_extensions("document.php.pdf", &[]).is_err());
+        assert!(validate_filename_extensions("image.exe.jpg", &[]).is_err());
 
         // Case insensitive
-        assert!(validate_filename_extensions("SCRIPT.PHP").is_err());
-        assert!(validate_filename_extensions("Shell.SH").is_err());
+        assert!(validate_filename_extensions("SCRIPT.PHP", &[]).is_err());
+        assert!(validate_filename_extensions("Shell.SH", &[]).is_err());
     }
 }
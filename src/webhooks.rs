@@ -0,0 +1,281 @@
+//! Outbound webhooks for the RegelRecht processing team
+//!
+//! Key submission events are POSTed as JSON to a configured URL so external
+//! tooling does not have to poll the admin API. Each body is signed with
+//! HMAC-SHA256 using a shared secret, sent as `X-RR-Signature: sha256=<hex>`.
+//! Events are queued as outbound jobs; the job worker delivers them with a
+//! timeout and retries failures with backoff. Payloads carry the submission slug, organization and
+//! timestamps, never submitter names, emails or file names.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::WebhookConfig;
+use crate::jobs::{self, Job};
+use crate::models::{Document, DocumentCategory, Submission, SubmissionStatus};
+
+/// Header carrying the body signature
+pub const SIGNATURE_HEADER: &str = "X-RR-Signature";
+
+/// Time allowed for one delivery attempt
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    SubmissionSubmitted,
+    SubmissionForwarded,
+    DocumentUploaded,
+}
+
+impl WebhookEvent {
+    /// Event for an admin status change, if that status is reported
+    pub fn for_status(status: SubmissionStatus) -> Option<Self> {
+        match status {
+            SubmissionStatus::Submitted => Some(Self::SubmissionSubmitted),
+            SubmissionStatus::Forwarded => Some(Self::SubmissionForwarded),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SubmissionPayload<'a> {
+    slug: &'a str,
+    organization: &'a str,
+    status: SubmissionStatus,
+    created_at: DateTime<Utc>,
+    submitted_at: Option<DateTime<Utc>>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct DocumentPayload {
+    id: Uuid,
+    category: DocumentCategory,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct Payload<'a> {
+    event: WebhookEvent,
+    occurred_at: DateTime<Utc>,
+    submission: SubmissionPayload<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document: Option<DocumentPayload>,
+}
+
+/// Delivers signed event payloads to the configured webhook URL
+pub struct WebhookSender {
+    client: reqwest::Client,
+    url: String,
+    secret: String,
+}
+
+impl WebhookSender {
+    pub fn from_config(config: &WebhookConfig) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        Ok(Self {
+            client,
+            url: config.url.clone(),
+            secret: config.secret.clone(),
+        })
+    }
+
+    pub(crate) async fn deliver(&self, body: Vec<u8>) -> Result<(), String> {
+        let signature = sign(&self.secret, &body);
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("status {}", response.status()))
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC of `body` under `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn payload_body(
+    event: WebhookEvent,
+    submission: &Submission,
+    document: Option<&Document>,
+) -> serde_json::Result<String> {
+    serde_json::to_string(&Payload {
+        event,
+        occurred_at: Utc::now(),
+        submission: SubmissionPayload {
+            slug: &submission.slug,
+            organization: &submission.organization,
+            status: submission.status,
+            created_at: submission.created_at,
+            submitted_at: submission.submitted_at,
+            updated_at: submission.updated_at,
+        },
+        document: document.map(|doc| DocumentPayload {
+            id: doc.id,
+            category: doc.category,
+            created_at: doc.created_at,
+        }),
+    })
+}
+
+/// Queue an event for the webhook, if one is configured
+///
+/// The job worker delivers it and retries failed deliveries.
+pub async fn dispatch(
+    pool: &PgPool,
+    sender: Option<&Arc<WebhookSender>>,
+    event: WebhookEvent,
+    submission: &Submission,
+    document: Option<&Document>,
+) {
+    if sender.is_none() {
+        return;
+    }
+    let body = match payload_body(event, submission, document) {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to serialize webhook payload: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = jobs::enqueue(pool, &Job::Webhook { event, body }).await {
+        tracing::warn!(
+            "Failed to queue {:?} webhook for {}: {}",
+            event,
+            submission.slug,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Bytes, http::HeaderMap};
+    use tokio::sync::mpsc;
+
+    fn submission() -> Submission {
+        Submission {
+            id: Uuid::new_v4(),
+            slug: "rr-20260101-abcde".to_string(),
+            submitter_name: "Jan Jansen".to_string(),
+            submitter_email: Some("jan@gemeente.nl".to_string()),
+            organization: "Gemeente Utrecht".to_string(),
+            organization_department: Some("Werk en Inkomen".to_string()),
+            status: SubmissionStatus::Forwarded,
+            notes: Some("Interne notitie".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            submitted_at: Some(Utc::now()),
+            retention_expiry_date: Utc::now(),
+            retention_consent_at: None,
+            legal_hold: false,
+            admin_notes: None,
+        }
+    }
+
+    fn verify(secret: &str, body: &[u8], signature: &str) -> bool {
+        let Some(hex_mac) = signature.strip_prefix("sha256=") else {
+            return false;
+        };
+        let Ok(expected) = hex::decode(hex_mac) else {
+            return false;
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        mac.verify_slice(&expected).is_ok()
+    }
+
+    #[test]
+    fn test_signature_verifies_with_shared_secret() {
+        let body = payload_body(WebhookEvent::SubmissionForwarded, &submission(), None)
+            .unwrap()
+            .into_bytes();
+        let signature = sign("gedeeld-geheim", &body);
+
+        assert!(verify("gedeeld-geheim", &body, &signature));
+        assert!(!verify("ander-geheim", &body, &signature));
+
+        let mut tampered = body.clone();
+        tampered[0] ^= 1;
+        assert!(!verify("gedeeld-geheim", &tampered, &signature));
+    }
+
+    #[test]
+    fn test_payload_leaves_out_personal_data() {
+        let body = payload_body(WebhookEvent::SubmissionForwarded, &submission(), None)
+            .unwrap()
+            .into_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["event"], "submission_forwarded");
+        assert_eq!(json["submission"]["slug"], "rr-20260101-abcde");
+        assert_eq!(json["submission"]["organization"], "Gemeente Utrecht");
+        assert!(json.get("document").is_none());
+
+        let text = String::from_utf8(body).unwrap();
+        for personal in [
+            "Jan Jansen",
+            "jan@gemeente.nl",
+            "Werk en Inkomen",
+            "Interne notitie",
+        ] {
+            assert!(!text.contains(personal), "payload contains {:?}", personal);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deliver_posts_signed_payload() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |headers: HeaderMap, body: Bytes| async move {
+                let signature = headers[SIGNATURE_HEADER].to_str().unwrap().to_string();
+                tx.send((signature, body)).unwrap();
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let sender = WebhookSender::from_config(&WebhookConfig {
+            url: format!("http://{}/hook", addr),
+            secret: "gedeeld-geheim".to_string(),
+        })
+        .unwrap();
+        let body = payload_body(WebhookEvent::SubmissionSubmitted, &submission(), None).unwrap();
+        sender.deliver(body.into_bytes()).await.unwrap();
+
+        let (signature, body) = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(verify("gedeeld-geheim", &body, &signature));
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["event"], "submission_submitted");
+    }
+}
@@ -0,0 +1,291 @@
+//! Title lookup for laws on wetten.overheid.nl
+//!
+//! Uploaders linking a formal law get its official title suggested instead of
+//! typing it by hand. Pages are only ever fetched for a BWB identifier taken
+//! from the link, never from the link itself, so the lookup cannot be used to
+//! make the server request arbitrary URLs. Titles are cached per identifier.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use crate::config::Config;
+use crate::validation::is_formal_law_host;
+
+/// Upstream pages are read up to this size; the title is near the top
+const MAX_PAGE_BYTES: usize = 2 * 1024 * 1024;
+
+/// Time allowed for one upstream request, including reading the body
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum LookupError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Upstream returned status {0}")]
+    Status(reqwest::StatusCode),
+    #[error("No title found in page")]
+    NoTitle,
+}
+
+struct CachedTitle {
+    title: String,
+    fetched_at: Instant,
+}
+
+/// Fetches and caches law titles from wetten.overheid.nl
+pub struct LawTitleLookup {
+    client: reqwest::Client,
+    base_url: String,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, CachedTitle>>,
+}
+
+impl LawTitleLookup {
+    pub fn from_config(config: &Config) -> Result<Self, LookupError> {
+        Self::new(
+            &config.wetten_base_url,
+            Duration::from_secs(config.formal_law_title_cache_hours * 3600),
+        )
+    }
+
+    pub fn new(base_url: &str, ttl: Duration) -> Result<Self, LookupError> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::limited(5))
+            .build()?;
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Official title of the law with this BWB identifier
+    pub async fn title(&self, bwb_id: &str) -> Result<String, LookupError> {
+        if let Some(title) = self.cached(bwb_id) {
+            return Ok(title);
+        }
+
+        let title = self.fetch(bwb_id).await?;
+
+        let mut cache = self.cache.lock().unwrap();
+        let ttl = self.ttl;
+        cache.retain(|_, entry| entry.fetched_at.elapsed() < ttl);
+        cache.insert(
+            bwb_id.to_string(),
+            CachedTitle {
+                title: title.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(title)
+    }
+
+    fn cached(&self, bwb_id: &str) -> Option<String> {
+        let cache = self.cache.lock().unwrap();
+        cache
+            .get(bwb_id)
+            .filter(|entry| entry.fetched_at.elapsed() < self.ttl)
+            .map(|entry| entry.title.clone())
+    }
+
+    async fn fetch(&self, bwb_id: &str) -> Result<String, LookupError> {
+        let mut response = self
+            .client
+            .get(format!("{}/{}", self.base_url, bwb_id))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(LookupError::Status(response.status()));
+        }
+
+        let mut page = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            page.extend_from_slice(&chunk);
+            if page.len() >= MAX_PAGE_BYTES {
+                page.truncate(MAX_PAGE_BYTES);
+                break;
+            }
+        }
+
+        extract_title(&String::from_utf8_lossy(&page)).ok_or(LookupError::NoTitle)
+    }
+}
+
+/// BWB identifier (e.g. `BWBR0015703`) of a wetten.overheid.nl link
+///
+/// Returns `None` for links to other hosts or without an identifier as the
+/// first path segment.
+pub fn bwb_id(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    if !matches!(url.scheme(), "https" | "http") || !is_formal_law_host(&url) {
+        return None;
+    }
+
+    let id = url.path_segments()?.next()?.to_ascii_uppercase();
+    let valid = id.len() == 11
+        && (id.starts_with("BWBR") || id.starts_with("BWBV"))
+        && id[4..].bytes().all(|b| b.is_ascii_digit());
+    valid.then_some(id)
+}
+
+/// Law title from a wetten.overheid.nl page
+///
+/// The page title reads `wetten.nl - Regeling - <title> - <BWB id>`; the
+/// site prefix, document type and identifier are stripped.
+fn extract_title(html: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets valid for the original
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title>")?;
+
+    let text = decode_entities(&html[start..end]);
+    let mut title = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if let Some(rest) = title.strip_prefix("wetten.nl - ") {
+        // Drop the document type ("Regeling", "Wet", ...) as well
+        title = rest.split_once(" - ").map_or(rest, |(_, t)| t).to_string();
+    }
+    if let Some((rest, id)) = title.rsplit_once(" - ") {
+        if id.starts_with("BWB") {
+            title = rest.to_string();
+        }
+    }
+
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Decode the HTML entities that show up in law titles
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest.find(';').and_then(|semi| {
+            let entity = &rest[1..semi];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, semi + 1))
+        });
+
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Trimmed copy of https://wetten.overheid.nl/BWBR0015703
+    const PARTICIPATIEWET_PAGE: &str =
+        include_str!("../tests/fixtures/wetten_participatiewet.html");
+
+    #[test]
+    fn test_bwb_id_only_from_wetten_links() {
+        assert_eq!(
+            bwb_id("https://wetten.overheid.nl/BWBR0015703/2024-01-01").as_deref(),
+            Some("BWBR0015703")
+        );
+        assert_eq!(
+            bwb_id("https://wetten.overheid.nl/bwbr0015703#Hoofdstuk1").as_deref(),
+            Some("BWBR0015703")
+        );
+        assert_eq!(bwb_id("https://wetten.overheid.nl/zoeken"), None);
+        assert_eq!(
+            bwb_id("https://wetten.overheid.nl.example.com/BWBR0015703"),
+            None
+        );
+        assert_eq!(bwb_id("https://example.com/BWBR0015703"), None);
+        assert_eq!(bwb_id("ftp://wetten.overheid.nl/BWBR0015703"), None);
+    }
+
+    #[test]
+    fn test_extract_title_from_recorded_page() {
+        assert_eq!(
+            extract_title(PARTICIPATIEWET_PAGE).as_deref(),
+            Some("Participatiewet")
+        );
+        assert_eq!(
+            extract_title("<title>wetten.nl - Regeling - Wet op de zorg &amp; het onderwijs - BWBR0000001</title>")
+                .as_deref(),
+            Some("Wet op de zorg & het onderwijs")
+        );
+        assert_eq!(extract_title("<html><body>Geen titel</body></html>"), None);
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("Caf&#233; &#x26; co"), "Café & co");
+        assert_eq!(decode_entities("a & b &unknown;"), "a & b &unknown;");
+    }
+
+    #[tokio::test]
+    async fn test_title_is_cached_per_bwb_id() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new().route(
+            "/:id",
+            axum::routing::get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { axum::response::Html(PARTICIPATIEWET_PAGE) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let lookup =
+            LawTitleLookup::new(&format!("http://{}/", addr), Duration::from_secs(60)).unwrap();
+        assert_eq!(
+            lookup.title("BWBR0015703").await.unwrap(),
+            "Participatiewet"
+        );
+        assert_eq!(
+            lookup.title("BWBR0015703").await.unwrap(),
+            "Participatiewet"
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        lookup.title("BWBR0000001").await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        // An expired entry is fetched again
+        let lookup = LawTitleLookup::new(&format!("http://{}", addr), Duration::ZERO).unwrap();
+        lookup.title("BWBR0015703").await.unwrap();
+        lookup.title("BWBR0015703").await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 4);
+    }
+}
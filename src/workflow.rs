@@ -0,0 +1,136 @@
+//! Submission status workflow
+//!
+//! Encodes which status changes are allowed, so admins cannot move a
+//! submission backwards or skip review steps.
+//!
+//! ```text
+//! draft -> submitted -> under_review -> approved -> forwarded -> completed
+//!              |             |            |
+//!              |             v            +-----------------------> completed
+//!              +--------> rejected
+//!                            |
+//!                            +--> under_review (reopened)
+//! ```
+//!
+//! `submitted` and `under_review` may also be forwarded directly, matching the
+//! forward endpoint.
+//!
+//! A reviewer who needs more from the uploader moves a `submitted` or
+//! `under_review` submission to `changes_requested`; review resumes from
+//! there with `under_review`, or ends with `rejected`.
+//!
+//! Uploaders may withdraw their own submission while it is `submitted`,
+//! `under_review` or `changes_requested`; `withdrawn` is final and not
+//! reachable through the admin status endpoint.
+
+use crate::models::SubmissionStatus;
+
+/// Whether a submission may move from `from` to `to`
+///
+/// Keeping the same status is allowed, so notes can be updated without a
+/// status change.
+pub fn is_valid_transition(from: SubmissionStatus, to: SubmissionStatus) -> bool {
+    use SubmissionStatus::*;
+
+    from == to
+        || matches!(
+            (from, to),
+            (Draft, Submitted)
+                | (Submitted, UnderReview)
+                | (Submitted, Rejected)
+                | (Submitted, Forwarded)
+                | (Submitted, ChangesRequested)
+                | (UnderReview, Approved)
+                | (UnderReview, Rejected)
+                | (UnderReview, Forwarded)
+                | (UnderReview, ChangesRequested)
+                | (ChangesRequested, UnderReview)
+                | (ChangesRequested, Rejected)
+                | (Approved, Forwarded)
+                | (Approved, Completed)
+                | (Rejected, UnderReview)
+                | (Forwarded, Completed)
+        )
+}
+
+/// Whether the uploader may still withdraw a submission in `status`
+pub fn can_withdraw(status: SubmissionStatus) -> bool {
+    matches!(
+        status,
+        SubmissionStatus::Submitted
+            | SubmissionStatus::UnderReview
+            | SubmissionStatus::ChangesRequested
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SubmissionStatus::*;
+
+    const ALL: [SubmissionStatus; 9] = [
+        Draft,
+        Submitted,
+        UnderReview,
+        Approved,
+        Rejected,
+        Forwarded,
+        Completed,
+        Withdrawn,
+        ChangesRequested,
+    ];
+
+    #[test]
+    fn test_transition_table() {
+        // (from, allowed targets other than staying put)
+        let table: [(SubmissionStatus, &[SubmissionStatus]); 9] = [
+            (Draft, &[Submitted]),
+            (
+                Submitted,
+                &[UnderReview, Rejected, Forwarded, ChangesRequested],
+            ),
+            (
+                UnderReview,
+                &[Approved, Rejected, Forwarded, ChangesRequested],
+            ),
+            (Approved, &[Forwarded, Completed]),
+            (Rejected, &[UnderReview]),
+            (Forwarded, &[Completed]),
+            (Completed, &[]),
+            (Withdrawn, &[]),
+            (ChangesRequested, &[UnderReview, Rejected]),
+        ];
+
+        for (from, allowed) in table {
+            for to in ALL {
+                let expected = from == to || allowed.contains(&to);
+                assert_eq!(
+                    is_valid_transition(from, to),
+                    expected,
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_workflow_shortcuts() {
+        assert!(!is_valid_transition(Draft, Completed));
+        assert!(!is_valid_transition(Completed, Draft));
+        assert!(!is_valid_transition(Submitted, Approved));
+        assert!(!is_valid_transition(Approved, Draft));
+    }
+
+    #[test]
+    fn test_withdrawal_only_before_forwarding() {
+        assert!(can_withdraw(Submitted));
+        assert!(can_withdraw(UnderReview));
+        assert!(can_withdraw(ChangesRequested));
+
+        for status in [Draft, Approved, Rejected, Forwarded, Completed, Withdrawn] {
+            assert!(!can_withdraw(status), "{:?}", status);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Harness for tests that drive the server binary over HTTP
+//!
+//! Each test gets its own server process on a free local port, backed by the
+//! scratch database in `TEST_DATABASE_URL` and a fresh upload directory. When
+//! the variable is not set the helpers return `None` and the test is skipped,
+//! like the database tests inside the crate.
+
+use reqwest::header::{HeaderMap, HeaderValue};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Time allowed for the server to migrate the database and start listening
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A running server and a connection to its database
+pub struct TestApp {
+    /// Base URL, such as `http://127.0.0.1:41234`
+    pub address: String,
+    pub pool: PgPool,
+    /// Client sending a per-app `X-Real-IP`, so rate limits are not shared between tests
+    pub client: reqwest::Client,
+    server: Child,
+    upload_dir: PathBuf,
+}
+
+impl TestApp {
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.address, path)
+    }
+
+    async fn wait_until_ready(&mut self) {
+        let started = Instant::now();
+        loop {
+            if let Ok(response) = self.client.get(self.url("/ready")).send().await {
+                if response.status().is_success() {
+                    return;
+                }
+            }
+            if let Some(status) = self.server.try_wait().unwrap() {
+                panic!("server exited during startup: {}", status);
+            }
+            assert!(
+                started.elapsed() < STARTUP_TIMEOUT,
+                "server did not become ready within {:?}",
+                STARTUP_TIMEOUT
+            );
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = self.server.kill();
+        let _ = self.server.wait();
+        let _ = std::fs::remove_dir_all(&self.upload_dir);
+    }
+}
+
+/// Start the server against `TEST_DATABASE_URL`, or `None` when it is not set
+pub async fn spawn_app() -> Option<TestApp> {
+    let database_url = std::env::var("TEST_DATABASE_URL").ok()?;
+
+    // Ask the OS for a free port, then hand it to the server
+    let port = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("no free local port")
+        .port();
+    let upload_dir = std::env::temp_dir().join(format!("regelrecht-it-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&upload_dir).unwrap();
+
+    let server = Command::new(env!("CARGO_BIN_EXE_regelrecht-upload"))
+        .env_clear()
+        .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+        .env("DATABASE_URL", &database_url)
+        .env("ENVIRONMENT", "development")
+        .env("HOST", "127.0.0.1")
+        .env("PORT", port.to_string())
+        .env("UPLOAD_DIR", &upload_dir)
+        .env(
+            "FRONTEND_DIR",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/frontend"),
+        )
+        .env("RUST_LOG", "regelrecht_upload=warn")
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("failed to start server binary");
+
+    let mut headers = HeaderMap::new();
+    let client_ip = format!("it-{}", Uuid::new_v4().simple());
+    headers.insert("x-real-ip", HeaderValue::from_str(&client_ip).unwrap());
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&database_url)
+        .await
+        .expect("cannot connect to TEST_DATABASE_URL");
+
+    let mut app = TestApp {
+        address: format!("http://127.0.0.1:{}", port),
+        pool,
+        client,
+        server,
+        upload_dir,
+    };
+    app.wait_until_ready().await;
+    Some(app)
+}
@@ -0,0 +1,110 @@
+//! End-to-end flow of an applicant against a running server
+
+mod common;
+
+use reqwest::multipart::{Form, Part};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_create_upload_submit_book() {
+    let Some(app) = common::spawn_app().await else {
+        return;
+    };
+
+    // Create a draft
+    let response = app
+        .client
+        .post(app.url("/api/submissions"))
+        .json(&json!({
+            "submitter_name": "Integratietest",
+            "submitter_email": "integratie@example.org",
+            "organization": "Gemeente Teststad",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: Value = response.json().await.unwrap();
+    let submission = &body["data"];
+    let slug = submission["slug"].as_str().unwrap().to_string();
+    let id: Uuid = submission["id"].as_str().unwrap().parse().unwrap();
+    assert_eq!(submission["status"], "draft");
+    assert!(submission["retention_expiry_date"].is_string());
+
+    // Upload a document while it is still a draft
+    let pdf = std::fs::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/single_page.pdf"
+    ))
+    .unwrap();
+    let file = Part::bytes(pdf)
+        .file_name("omzendbrief.pdf")
+        .mime_str("application/pdf")
+        .unwrap();
+    let response = app
+        .client
+        .post(app.url(&format!(
+            "/api/submissions/{}/documents?category=circular&classification=public",
+            slug
+        )))
+        .multipart(Form::new().part("file", file))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Submit
+    let response = app
+        .client
+        .post(app.url(&format!("/api/submissions/{}/submit", slug)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"]["status"], "submitted");
+
+    // Book a meeting in a slot an admin opened
+    let slot_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO calendar_slots (slot_start, slot_end)
+         VALUES (NOW() + INTERVAL '3 days', NOW() + INTERVAL '3 days 1 hour') RETURNING id",
+    )
+    .fetch_one(&app.pool)
+    .await
+    .unwrap();
+    let response = app
+        .client
+        .post(app.url(&format!("/api/submissions/{}/book-slot", slug)))
+        .json(&json!({ "slot_id": slot_id }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Everything landed in the database
+    let (status, documents): (String, i64) = sqlx::query_as(
+        "SELECT s.status::text, (SELECT COUNT(*) FROM documents d WHERE d.submission_id = s.id)
+         FROM submissions s WHERE s.id = $1",
+    )
+    .bind(id)
+    .fetch_one(&app.pool)
+    .await
+    .unwrap();
+    assert_eq!(status, "submitted");
+    assert_eq!(documents, 1);
+    let booked: Option<Uuid> =
+        sqlx::query_scalar("SELECT slot_id FROM slot_bookings WHERE submission_id = $1")
+            .bind(id)
+            .fetch_optional(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(booked, Some(slot_id));
+
+    sqlx::query("DELETE FROM calendar_slots WHERE id = $1")
+        .bind(slot_id)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+}